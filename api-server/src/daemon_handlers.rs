@@ -0,0 +1,142 @@
+//! Background sync daemon inside the api-server (`/api/daemon/*`).
+//!
+//! Ticks share `wallet_sync_lock` with `/api/sync`, so a manual sync and the daemon
+//! never scan at the same time. Events go to the process-wide wallet event bus.
+
+use axum::{http::StatusCode, response::Json as ResponseJson, Json};
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+use std::time::Duration;
+use tokio::sync::{oneshot, Mutex};
+
+use crate::handlers::{error_response, load_wallet_with_password, wallet_sync_lock};
+
+struct RunningDaemon {
+    stop: oneshot::Sender<()>,
+    status: std::sync::Arc<Mutex<DaemonStatus>>,
+}
+
+static DAEMON: OnceLock<Mutex<Option<RunningDaemon>>> = OnceLock::new();
+
+fn daemon_slot() -> &'static Mutex<Option<RunningDaemon>> {
+    DAEMON.get_or_init(|| Mutex::new(None))
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DaemonStatus {
+    pub running: bool,
+    pub interval_secs: u64,
+    pub run_scheduled_payments: bool,
+    pub started_at: Option<String>,
+    pub last_tick_at: Option<String>,
+    pub last_tick: Option<nozy::DaemonTick>,
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StartDaemonRequest {
+    pub password: Option<String>,
+    pub zebra_url: Option<String>,
+    pub interval_secs: Option<u64>,
+    /// Also send due scheduled payments each tick; off unless asked for.
+    #[serde(default)]
+    pub run_scheduled_payments: bool,
+}
+
+/// POST `/api/daemon/start`
+pub async fn start_daemon(
+    Json(payload): Json<StartDaemonRequest>,
+) -> Result<ResponseJson<DaemonStatus>, (StatusCode, ResponseJson<serde_json::Value>)> {
+    let interval_secs = payload
+        .interval_secs
+        .unwrap_or(nozy::DEFAULT_DAEMON_INTERVAL_SECS);
+    if interval_secs == 0 {
+        return Err(error_response(
+            StatusCode::BAD_REQUEST,
+            "interval_secs must be at least 1",
+        ));
+    }
+
+    let mut slot = daemon_slot().lock().await;
+    if let Some(running) = slot.as_ref() {
+        if !running.stop.is_closed() {
+            return Err(error_response(
+                StatusCode::CONFLICT,
+                "Daemon is already running; stop it first",
+            ));
+        }
+    }
+
+    let (wallet, _storage) = load_wallet_with_password(payload.password)
+        .await
+        .map_err(|e| error_response(StatusCode::UNAUTHORIZED, e))?;
+    let options = nozy::DaemonOptions {
+        interval: Duration::from_secs(interval_secs),
+        zebra_url: payload.zebra_url,
+        run_scheduled_payments: payload.run_scheduled_payments,
+    };
+    let status = std::sync::Arc::new(Mutex::new(DaemonStatus {
+        running: true,
+        interval_secs,
+        run_scheduled_payments: options.run_scheduled_payments,
+        started_at: Some(chrono::Utc::now().to_rfc3339()),
+        ..DaemonStatus::default()
+    }));
+    let (stop_tx, mut stop_rx) = oneshot::channel();
+    let mut daemon = nozy::WalletDaemon::new(wallet, options);
+    let task_status = status.clone();
+    tokio::spawn(async move {
+        loop {
+            let result = {
+                let _sync_guard = wallet_sync_lock().lock().await;
+                daemon.tick().await
+            };
+            {
+                let mut s = task_status.lock().await;
+                s.last_tick_at = Some(chrono::Utc::now().to_rfc3339());
+                match result {
+                    Ok(tick) => {
+                        s.last_tick = Some(tick);
+                        s.last_error = None;
+                    }
+                    Err(e) => {
+                        tracing::warn!(error = %e, "daemon tick failed");
+                        s.last_error = Some(e.to_string());
+                    }
+                }
+            }
+            tokio::select! {
+                _ = &mut stop_rx => break,
+                _ = tokio::time::sleep(daemon.options().interval) => {}
+            }
+        }
+        task_status.lock().await.running = false;
+        tracing::info!("wallet daemon stopped");
+    });
+    tracing::info!(interval_secs, "wallet daemon started");
+
+    let snapshot = status.lock().await.clone();
+    *slot = Some(RunningDaemon {
+        stop: stop_tx,
+        status,
+    });
+    Ok(ResponseJson(snapshot))
+}
+
+/// POST `/api/daemon/stop`
+pub async fn stop_daemon() -> ResponseJson<serde_json::Value> {
+    let stopped = match daemon_slot().lock().await.take() {
+        Some(running) => running.stop.send(()).is_ok(),
+        None => false,
+    };
+    ResponseJson(serde_json::json!({ "stopped": stopped }))
+}
+
+/// GET `/api/daemon/status`
+pub async fn get_daemon_status() -> ResponseJson<DaemonStatus> {
+    let status = match daemon_slot().lock().await.as_ref() {
+        Some(running) => running.status.lock().await.clone(),
+        None => DaemonStatus::default(),
+    };
+    ResponseJson(status)
+}
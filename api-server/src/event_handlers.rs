@@ -0,0 +1,73 @@
+//! `/api/events`: Server-Sent Events stream of wallet events.
+//!
+//! Streams whatever the wallet event bus publishes (daemon ticks, `/api/sync`, invoice
+//! changes). Sits behind `api_key_auth` and rate limiting like every other route; browser
+//! clients need `fetch` streaming since `EventSource` cannot send the API key header.
+
+use axum::{
+    extract::Query,
+    response::sse::{Event, KeepAlive, Sse},
+};
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::convert::Infallible;
+use tokio_stream::wrappers::{errors::BroadcastStreamRecvError, BroadcastStream};
+use tokio_stream::{Stream, StreamExt};
+
+#[derive(Debug, Deserialize)]
+pub struct EventsQuery {
+    /// Comma-separated event types (e.g. `note_received,tx_confirmed`); all when omitted.
+    pub types: Option<String>,
+}
+
+fn parse_types(types: Option<&str>) -> Option<HashSet<String>> {
+    let set: HashSet<String> = types?
+        .split(',')
+        .map(|t| t.trim().to_ascii_lowercase())
+        .filter(|t| !t.is_empty())
+        .collect();
+    (!set.is_empty()).then_some(set)
+}
+
+/// GET `/api/events`
+pub async fn stream_events(
+    Query(params): Query<EventsQuery>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let wanted = parse_types(params.types.as_deref());
+    let stream = BroadcastStream::new(nozy::subscribe_wallet_events()).filter_map(move |item| {
+        let event = match item {
+            Ok(event) => event,
+            Err(BroadcastStreamRecvError::Lagged(missed)) => {
+                return Some(Ok(Event::default()
+                    .event("lagged")
+                    .data(serde_json::json!({ "missed": missed }).to_string())));
+            }
+        };
+        if wanted.as_ref().is_some_and(|w| !w.contains(event.kind())) {
+            return None;
+        }
+        match Event::default().event(event.kind()).json_data(&event) {
+            Ok(sse) => Some(Ok(sse)),
+            Err(e) => {
+                tracing::warn!(error = %e, "could not encode wallet event");
+                None
+            }
+        }
+    });
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn type_filter_is_normalized() {
+        let set = parse_types(Some(" Note_Received, ,tx_confirmed")).unwrap();
+        assert!(set.contains("note_received"));
+        assert!(set.contains("tx_confirmed"));
+        assert_eq!(set.len(), 2);
+        assert!(parse_types(Some(" , ")).is_none());
+        assert!(parse_types(None).is_none());
+    }
+}
@@ -10,7 +10,7 @@ use tokio::sync::Mutex;
 
 static WALLET_SYNC_LOCK: OnceLock<Mutex<()>> = OnceLock::new();
 
-fn wallet_sync_lock() -> &'static Mutex<()> {
+pub(crate) fn wallet_sync_lock() -> &'static Mutex<()> {
     WALLET_SYNC_LOCK.get_or_init(|| Mutex::new(()))
 }
 
@@ -130,6 +130,12 @@ pub struct SyncResponse {
     pub last_scan_height: u32,
     pub chain_tip: u32,
     pub already_synced: bool,
+    /// Sent transactions rebuilt from chain via OVK decryption during this sync.
+    #[serde(default)]
+    pub recovered_sent_transactions: usize,
+    /// Merchant invoices whose status changed during this sync.
+    #[serde(default)]
+    pub invoices_updated: usize,
     pub message: String,
 }
 
@@ -143,6 +149,9 @@ pub struct SendTransactionRequest {
     pub zebra_url: Option<String>,
     #[serde(default)]
     pub priority: bool,
+    /// Coin control: only spend from these notes (nullifier hex). Empty = automatic.
+    #[serde(default)]
+    pub note_ids: Vec<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -438,6 +447,101 @@ pub async fn get_balance(
     Ok(ResponseJson(balance_response_from_snapshot(snapshot)))
 }
 
+#[derive(Debug, Deserialize)]
+pub struct FiatQuery {
+    /// ISO 4217 code; defaults to `fiat.display_currency` from config.
+    pub currency: Option<String>,
+    /// Historical day (`YYYY-MM-DD`); spot price when omitted.
+    pub at: Option<String>,
+}
+
+fn fiat_error(e: nozy::NozyError) -> (StatusCode, ResponseJson<serde_json::Value>) {
+    match e {
+        nozy::NozyError::InvalidInput(_) => {
+            error_response_with_code(StatusCode::BAD_REQUEST, e.to_string(), "invalid_input")
+        }
+        nozy::NozyError::NetworkError(_) => {
+            error_response_with_code(StatusCode::BAD_GATEWAY, e.to_string(), "price_unavailable")
+        }
+        _ => error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
+pub async fn get_price(
+    Query(params): Query<FiatQuery>,
+) -> Result<ResponseJson<serde_json::Value>, (StatusCode, ResponseJson<serde_json::Value>)> {
+    use nozy::price_source::normalize_currency_code;
+
+    let config = nozy::load_config();
+    let currency = normalize_currency_code(
+        params
+            .currency
+            .as_deref()
+            .unwrap_or(&config.fiat.display_currency),
+    )
+    .map_err(fiat_error)?;
+    let Some(source) = nozy::configured_price_source(&config).map_err(fiat_error)? else {
+        return Err(error_response_with_code(
+            StatusCode::NOT_FOUND,
+            "No price source configured (fiat.source = none)",
+            "price_source_disabled",
+        ));
+    };
+    let price = match params.at.as_deref() {
+        Some(day) => {
+            let at = chrono::NaiveDate::parse_from_str(day, "%Y-%m-%d")
+                .ok()
+                .and_then(|d| d.and_hms_opt(0, 0, 0))
+                .map(|d| d.and_utc())
+                .ok_or_else(|| {
+                    error_response_with_code(
+                        StatusCode::BAD_REQUEST,
+                        format!("Invalid date '{day}' (YYYY-MM-DD)"),
+                        "invalid_input",
+                    )
+                })?;
+            source.historical_price(&currency, at).await
+        }
+        None => source.spot_price(&currency).await,
+    }
+    .map_err(fiat_error)?;
+
+    Ok(ResponseJson(serde_json::json!({
+        "currency": currency,
+        "source": source.name(),
+        "price": price,
+    })))
+}
+
+pub async fn get_fiat_balance(
+    Query(params): Query<FiatQuery>,
+) -> Result<ResponseJson<serde_json::Value>, (StatusCode, ResponseJson<serde_json::Value>)> {
+    let config = nozy::load_config();
+    let currency = nozy::price_source::normalize_currency_code(
+        params
+            .currency
+            .as_deref()
+            .unwrap_or(&config.fiat.display_currency),
+    )
+    .map_err(fiat_error)?;
+    let snapshot = nozy::wallet_balance_snapshot().map_err(|e| {
+        error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to read wallet balance: {e}"),
+        )
+    })?;
+    let fiat = nozy::fiat_value_at_spot(&config, Some(&currency), snapshot.available_zatoshis)
+        .await
+        .map_err(fiat_error)?;
+
+    Ok(ResponseJson(serde_json::json!({
+        "currency": currency,
+        "available_zatoshis": snapshot.available_zatoshis,
+        "available_zec": zats_to_zec(snapshot.available_zatoshis),
+        "fiat": fiat,
+    })))
+}
+
 pub async fn sync_wallet(
     Json(payload): Json<SyncRequest>,
 ) -> Result<ResponseJson<SyncResponse>, (StatusCode, ResponseJson<serde_json::Value>)> {
@@ -465,8 +569,15 @@ pub async fn sync_wallet(
         ..WalletSyncOptions::api_default()
     };
 
+    // The scanner publishes `scan_progress` events on `/api/events` while this runs.
     match sync_wallet_notes(&wallet, options).await {
         Ok(result) => {
+            nozy::publish_wallet_event(nozy::WalletEvent::SyncCompleted {
+                chain_tip: result.chain_tip,
+                blocks_scanned: result.blocks_scanned,
+                new_notes: result.new_notes_in_scan,
+                balance_zatoshis: result.balance_zatoshis,
+            });
             let balance_zec = result.balance_zatoshis as f64 / 100_000_000.0;
             let message = if result.already_synced {
                 format!(
@@ -494,10 +605,15 @@ pub async fn sync_wallet(
                 last_scan_height: result.last_scan_height,
                 chain_tip: result.chain_tip,
                 already_synced: result.already_synced,
+                recovered_sent_transactions: result.recovered_sent_transactions,
+                invoices_updated: result.invoices_updated,
                 message,
             }))
         }
         Err(e) => {
+            nozy::publish_wallet_event(nozy::WalletEvent::SyncFailed {
+                error: e.to_string(),
+            });
             let status = StatusCode::from_u16(e.api_status_code())
                 .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
             Err((status, ResponseJson(e.to_api_json())))
@@ -632,6 +748,17 @@ pub async fn send_transaction(
             ));
         }
     };
+    let spendable_notes =
+        match nozy::restrict_to_note_ids(spendable_notes, &payload.request.note_ids) {
+            Ok(notes) => notes,
+            Err(e) => {
+                return Ok(ResponseJson(SendTransactionResponse {
+                    success: false,
+                    txid: None,
+                    message: e.to_string(),
+                }));
+            }
+        };
 
     let zebra_client = ZebraClient::from_config_with_url(&config, Some(&zebra_url));
 
@@ -721,6 +848,160 @@ pub async fn send_transaction(
     }))
 }
 
+/// POST `/api/transaction/propose` — select notes, change, fee and expiry without proving.
+pub async fn propose_transaction(
+    Json(payload): Json<SendTransactionRequestWrapper>,
+) -> Result<ResponseJson<serde_json::Value>, (StatusCode, ResponseJson<serde_json::Value>)> {
+    use nozy::cli_helpers::{is_zebra_unavailable_error, scan_notes_for_sending};
+    use nozy::{
+        estimate_orchard_send_fee_zatoshis, load_config, ZcashTransactionBuilder, ZebraClient,
+    };
+
+    let config = load_config();
+    let zebra_url = payload
+        .request
+        .zebra_url
+        .clone()
+        .unwrap_or_else(|| config.zebra_url.clone());
+
+    let (wallet, _storage) = load_wallet_with_password(payload.password)
+        .await
+        .map_err(|e| error_response(StatusCode::UNAUTHORIZED, e))?;
+
+    nozy::input_validation::validate_zcash_address(&payload.request.recipient).map_err(|e| {
+        error_response(
+            StatusCode::BAD_REQUEST,
+            format!("Invalid recipient address: {e}"),
+        )
+    })?;
+
+    let amount_zatoshis = nozy::input_validation::resolve_send_amount_zatoshis(
+        payload.request.amount_zatoshis,
+        payload.request.amount,
+    )
+    .map_err(|e| error_response(StatusCode::BAD_REQUEST, e.to_string()))?;
+
+    let memo_bytes_opt = payload
+        .request
+        .memo
+        .as_ref()
+        .map(|m| m.trim().as_bytes().to_vec())
+        .filter(|b| !b.is_empty());
+
+    let pilot = nozy::PilotSendOptions::for_send();
+    let fee_zatoshis =
+        estimate_orchard_send_fee_zatoshis(memo_bytes_opt.as_deref(), pilot.priority);
+
+    let spendable_notes = scan_notes_for_sending(&wallet, &zebra_url)
+        .await
+        .map_err(|e| {
+            let msg = e.to_string();
+            if is_zebra_unavailable_error(&msg) {
+                error_response_with_code(
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    format!("Zebra node unavailable during note scan: {msg}"),
+                    "ZEBRA_UNAVAILABLE",
+                )
+            } else {
+                error_response(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    format!("Failed to scan notes: {msg}"),
+                )
+            }
+        })?;
+    let spendable_notes = nozy::restrict_to_note_ids(spendable_notes, &payload.request.note_ids)
+        .map_err(|e| {
+            error_response_with_code(StatusCode::BAD_REQUEST, e.to_string(), "invalid_note_id")
+        })?;
+
+    let zebra_client = ZebraClient::from_config_with_url(&config, Some(&zebra_url));
+    let proposal = ZcashTransactionBuilder::new()
+        .propose_send_transaction(
+            &zebra_client,
+            &spendable_notes,
+            &payload.request.recipient,
+            amount_zatoshis,
+            fee_zatoshis,
+            memo_bytes_opt.as_deref(),
+            pilot,
+        )
+        .await
+        .map_err(|e| error_response(StatusCode::BAD_REQUEST, e.to_string()))?;
+
+    Ok(ResponseJson(serde_json::json!({ "proposal": proposal })))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExecuteProposalRequest {
+    pub proposal: nozy::SendProposal,
+    pub zebra_url: Option<String>,
+    pub password: Option<String>,
+}
+
+/// POST `/api/transaction/execute-proposal` — prove, sign and broadcast a confirmed proposal.
+pub async fn execute_proposal(
+    Json(payload): Json<ExecuteProposalRequest>,
+) -> Result<ResponseJson<SendTransactionResponse>, (StatusCode, ResponseJson<serde_json::Value>)> {
+    use nozy::cli_helpers::scan_notes_for_sending;
+    use nozy::{load_config, ZcashTransactionBuilder, ZebraClient};
+
+    let config = load_config();
+    let zebra_url = payload
+        .zebra_url
+        .unwrap_or_else(|| config.zebra_url.clone());
+
+    payload
+        .proposal
+        .validate()
+        .map_err(|e| error_response(StatusCode::BAD_REQUEST, e.to_string()))?;
+
+    let (wallet, _storage) = load_wallet_with_password(payload.password)
+        .await
+        .map_err(|e| error_response(StatusCode::UNAUTHORIZED, e))?;
+
+    let spendable_notes = scan_notes_for_sending(&wallet, &zebra_url)
+        .await
+        .map_err(|e| {
+            error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to scan notes: {e}"),
+            )
+        })?;
+
+    let zebra_client = ZebraClient::from_config_with_url(&config, Some(&zebra_url));
+    let mut tx_builder = ZcashTransactionBuilder::new();
+    tx_builder.set_zebra_url(&zebra_url);
+    tx_builder.enable_mainnet_broadcast();
+    nozy::warm_orchard_proving_key();
+
+    let transaction = match tx_builder
+        .execute_proposal(&zebra_client, &spendable_notes, &payload.proposal)
+        .await
+    {
+        Ok(tx) => tx,
+        Err(e) => {
+            return Ok(ResponseJson(SendTransactionResponse {
+                success: false,
+                txid: None,
+                message: e.to_string(),
+            }));
+        }
+    };
+
+    if let Err(e) = nozy::send_proposal::record_executed_proposal(&payload.proposal, &transaction) {
+        tracing::warn!(
+            "Failed to record executed proposal {}: {e}",
+            transaction.txid
+        );
+    }
+
+    Ok(ResponseJson(SendTransactionResponse {
+        success: true,
+        txid: Some(transaction.txid.clone()),
+        message: format!("Transaction sent successfully! TXID: {}", transaction.txid),
+    }))
+}
+
 pub async fn estimate_fee(
 ) -> Result<ResponseJson<serde_json::Value>, (StatusCode, ResponseJson<serde_json::Value>)> {
     let fee_zatoshis = nozy::estimate_orchard_send_fee_zatoshis(None, true);
@@ -1031,6 +1312,10 @@ pub struct TransactionQueryParams {
     pub min_amount: Option<f64>,
     pub max_amount: Option<f64>,
     pub recipient: Option<String>,
+    /// Attach historical fiat values in this currency (best-effort).
+    pub fiat: Option<String>,
+    /// Match label, tag (`#tag`) or note on the transaction or its notes.
+    pub q: Option<String>,
 }
 
 pub async fn get_transaction_history(
@@ -1060,10 +1345,30 @@ pub async fn get_transaction_history(
     let zebra_client = ZebraClient::from_config(&config);
     let mut views = views;
     nozy::transaction_history::enrich_block_times_for_views(&mut views, &zebra_client).await;
+    if let Some(currency) = params.fiat.as_deref() {
+        if let Err(e) = nozy::apply_configured_fiat_values(&mut views, currency).await {
+            tracing::warn!(error = %e, "Fiat valuation skipped for history");
+        }
+    }
+    let labels = match nozy::LabelStore::new() {
+        Ok(store) => Some(store),
+        Err(e) => {
+            tracing::warn!(error = %e, "Labels unavailable for history");
+            None
+        }
+    };
+    if let Some(store) = &labels {
+        store.annotate_views(&mut views);
+    }
 
     let filtered: Vec<_> = views
         .iter()
         .filter(|tx| {
+            if let Some(ref q) = params.q {
+                if !labels.as_ref().is_some_and(|l| l.view_matches(tx, q)) {
+                    return false;
+                }
+            }
             if let Some(ref status) = params.status {
                 if format!("{:?}", tx.status).to_lowercase() != status.to_lowercase() {
                     return false;
@@ -1102,6 +1407,310 @@ pub async fn get_transaction_history(
     }))
 }
 
+#[derive(Debug, Deserialize)]
+pub struct LabelSearchQuery {
+    pub q: Option<String>,
+}
+
+fn label_error(e: nozy::NozyError) -> (StatusCode, ResponseJson<serde_json::Value>) {
+    match e {
+        nozy::NozyError::InvalidInput(_) => {
+            error_response_with_code(StatusCode::BAD_REQUEST, e.to_string(), "invalid_input")
+        }
+        _ => error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
+pub async fn search_labels(
+    Query(params): Query<LabelSearchQuery>,
+) -> Result<ResponseJson<serde_json::Value>, (StatusCode, ResponseJson<serde_json::Value>)> {
+    let store = nozy::LabelStore::new().map_err(label_error)?;
+    let matches = store.search(params.q.as_deref().unwrap_or(""));
+    Ok(ResponseJson(serde_json::json!({
+        "total": matches.len(),
+        "labels": matches,
+    })))
+}
+
+pub async fn update_label(
+    Path((target, id)): Path<(String, String)>,
+    Json(payload): Json<nozy::AnnotationUpdate>,
+) -> Result<ResponseJson<serde_json::Value>, (StatusCode, ResponseJson<serde_json::Value>)> {
+    let target: nozy::LabelTarget = target.parse().map_err(label_error)?;
+    let mut store = nozy::LabelStore::new().map_err(label_error)?;
+    let annotation = store.update(target, &id, payload).map_err(label_error)?;
+    Ok(ResponseJson(serde_json::json!({
+        "target": target,
+        "id": id,
+        "annotation": annotation,
+    })))
+}
+
+pub async fn remove_label(
+    Path((target, id)): Path<(String, String)>,
+) -> Result<ResponseJson<serde_json::Value>, (StatusCode, ResponseJson<serde_json::Value>)> {
+    let target: nozy::LabelTarget = target.parse().map_err(label_error)?;
+    let mut store = nozy::LabelStore::new().map_err(label_error)?;
+    if !store.remove(target, &id).map_err(label_error)? {
+        return Err(error_response_with_code(
+            StatusCode::NOT_FOUND,
+            format!("No label for {id}"),
+            "not_found",
+        ));
+    }
+    Ok(ResponseJson(serde_json::json!({ "success": true })))
+}
+
+fn schedule_error(e: nozy::NozyError) -> (StatusCode, ResponseJson<serde_json::Value>) {
+    match e {
+        nozy::NozyError::InvalidInput(_) | nozy::NozyError::InvalidOperation(_) => {
+            error_response_with_code(StatusCode::BAD_REQUEST, e.to_string(), "invalid_input")
+        }
+        _ => error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
+pub async fn list_scheduled_payments(
+) -> Result<ResponseJson<nozy::PaymentSchedule>, (StatusCode, ResponseJson<serde_json::Value>)> {
+    nozy::load_payment_schedule()
+        .map(ResponseJson)
+        .map_err(schedule_error)
+}
+
+pub async fn add_scheduled_payment(
+    Json(payload): Json<nozy::NewScheduledPayment>,
+) -> Result<ResponseJson<nozy::ScheduledPayment>, (StatusCode, ResponseJson<serde_json::Value>)> {
+    nozy::add_scheduled_payment(payload)
+        .map(ResponseJson)
+        .map_err(schedule_error)
+}
+
+pub async fn remove_scheduled_payment(
+    Path(id): Path<String>,
+) -> Result<ResponseJson<serde_json::Value>, (StatusCode, ResponseJson<serde_json::Value>)> {
+    nozy::remove_scheduled_payment(&id).map_err(schedule_error)?;
+    Ok(ResponseJson(serde_json::json!({ "success": true })))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ScheduleEnableRequest {
+    pub enabled: bool,
+}
+
+pub async fn set_scheduled_payment_enabled(
+    Path(id): Path<String>,
+    Json(payload): Json<ScheduleEnableRequest>,
+) -> Result<ResponseJson<serde_json::Value>, (StatusCode, ResponseJson<serde_json::Value>)> {
+    nozy::set_scheduled_payment_enabled(&id, payload.enabled).map_err(schedule_error)?;
+    Ok(ResponseJson(serde_json::json!({
+        "success": true,
+        "id": id,
+        "enabled": payload.enabled,
+    })))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ScheduleRunRequest {
+    pub password: Option<String>,
+    pub zebra_url: Option<String>,
+}
+
+pub async fn run_scheduled_payments(
+    Json(payload): Json<ScheduleRunRequest>,
+) -> Result<ResponseJson<serde_json::Value>, (StatusCode, ResponseJson<serde_json::Value>)> {
+    let zebra_url = payload
+        .zebra_url
+        .unwrap_or_else(|| nozy::load_config().zebra_url);
+    let (wallet, _storage) = load_wallet_with_password(payload.password)
+        .await
+        .map_err(|e| error_response(StatusCode::UNAUTHORIZED, e))?;
+    let runs = nozy::run_due_payments(&wallet, &zebra_url)
+        .await
+        .map_err(schedule_error)?;
+    for run in runs.iter().filter(|r| r.is_alert()) {
+        tracing::warn!(payment_id = %run.payment_id, outcome = ?run.outcome, "scheduled payment not sent");
+    }
+    let alerts = runs.iter().filter(|r| r.is_alert()).count();
+    Ok(ResponseJson(serde_json::json!({
+        "runs": runs,
+        "alerts": alerts,
+    })))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct HistoryExportQuery {
+    /// `csv` (default), `jsonl` or `ofx`.
+    pub format: Option<String>,
+    /// First day to include (`YYYY-MM-DD`, UTC).
+    pub from: Option<String>,
+    /// Last day to include (`YYYY-MM-DD`, UTC).
+    pub to: Option<String>,
+    /// Wallet profile id; defaults to the active profile.
+    pub account: Option<String>,
+    /// Add historical fiat values in this currency (uses the configured price source).
+    pub fiat: Option<String>,
+}
+
+pub async fn export_transaction_history(
+    Query(params): Query<HistoryExportQuery>,
+) -> Result<axum::response::Response, (StatusCode, ResponseJson<serde_json::Value>)> {
+    use axum::http::header;
+    use axum::response::IntoResponse;
+    use nozy::{load_config, HistoryExportFilter, HistoryExportFormat, ZebraClient};
+
+    let format: HistoryExportFormat =
+        params
+            .format
+            .as_deref()
+            .unwrap_or("csv")
+            .parse()
+            .map_err(|e: nozy::NozyError| {
+                error_response_with_code(StatusCode::BAD_REQUEST, e.to_string(), "invalid_format")
+            })?;
+    let filter =
+        HistoryExportFilter::parse(params.from.as_deref(), params.to.as_deref(), params.account)
+            .map_err(|e| {
+                error_response_with_code(StatusCode::BAD_REQUEST, e.to_string(), "invalid_filter")
+            })?;
+
+    let config = load_config();
+    let zebra_client = ZebraClient::from_config(&config);
+    let body = nozy::export_wallet_history(&zebra_client, &filter, format, params.fiat.as_deref())
+        .await
+        .map_err(|e| match e {
+            nozy::NozyError::InvalidInput(_) => {
+                error_response_with_code(StatusCode::BAD_REQUEST, e.to_string(), "invalid_filter")
+            }
+            _ => error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to export transaction history: {e}"),
+            ),
+        })?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, format.content_type().to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", format.file_name()),
+            ),
+        ],
+        body,
+    )
+        .into_response())
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CostBasisReportQuery {
+    pub year: i32,
+    /// `fifo` (default), `lifo` or `specific-id`.
+    pub method: Option<String>,
+    pub currency: Option<String>,
+    /// `json` (default) or `csv`.
+    pub format: Option<String>,
+}
+
+pub async fn get_cost_basis_report(
+    Query(params): Query<CostBasisReportQuery>,
+) -> Result<axum::response::Response, (StatusCode, ResponseJson<serde_json::Value>)> {
+    use axum::http::header;
+    use axum::response::IntoResponse;
+    use nozy::{load_config, CostBasisMethod, ZebraClient};
+
+    let method: CostBasisMethod =
+        params
+            .method
+            .as_deref()
+            .unwrap_or("fifo")
+            .parse()
+            .map_err(|e: nozy::NozyError| {
+                error_response_with_code(StatusCode::BAD_REQUEST, e.to_string(), "invalid_method")
+            })?;
+    let config = load_config();
+    let zebra_client = ZebraClient::from_config(&config);
+    let report = nozy::wallet_cost_basis_report(
+        &zebra_client,
+        params.year,
+        method,
+        params.currency.as_deref(),
+    )
+    .await
+    .map_err(|e| match e {
+        nozy::NozyError::InvalidInput(_) => {
+            error_response_with_code(StatusCode::BAD_REQUEST, e.to_string(), "invalid_input")
+        }
+        _ => error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to build cost-basis report: {e}"),
+        ),
+    })?;
+
+    match params.format.as_deref().unwrap_or("json") {
+        "json" => Ok(ResponseJson(report).into_response()),
+        "csv" => Ok((
+            [
+                (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!("attachment; filename=\"nozy-gains-{}.csv\"", report.year),
+                ),
+            ],
+            nozy::render_cost_basis_csv(&report),
+        )
+            .into_response()),
+        other => Err(error_response_with_code(
+            StatusCode::BAD_REQUEST,
+            format!("Unknown format '{other}' (expected json or csv)"),
+            "invalid_format",
+        )),
+    }
+}
+
+fn cost_basis_overrides(
+) -> Result<nozy::CostBasisOverrides, (StatusCode, ResponseJson<serde_json::Value>)> {
+    nozy::CostBasisOverrides::new().map_err(|e| {
+        error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to load cost-basis overrides: {e}"),
+        )
+    })
+}
+
+pub async fn list_cost_basis_overrides(
+) -> Result<ResponseJson<serde_json::Value>, (StatusCode, ResponseJson<serde_json::Value>)> {
+    let overrides = cost_basis_overrides()?.list();
+    Ok(ResponseJson(serde_json::json!({ "overrides": overrides })))
+}
+
+pub async fn set_cost_basis_override(
+    Json(payload): Json<nozy::CostBasisOverride>,
+) -> Result<ResponseJson<serde_json::Value>, (StatusCode, ResponseJson<serde_json::Value>)> {
+    let mut overrides = cost_basis_overrides()?;
+    overrides.set(payload).map_err(|e| match e {
+        nozy::NozyError::InvalidInput(_) => {
+            error_response_with_code(StatusCode::BAD_REQUEST, e.to_string(), "invalid_input")
+        }
+        _ => error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    })?;
+    Ok(ResponseJson(serde_json::json!({ "success": true })))
+}
+
+pub async fn remove_cost_basis_override(
+    Path(note_id): Path<String>,
+) -> Result<ResponseJson<serde_json::Value>, (StatusCode, ResponseJson<serde_json::Value>)> {
+    let removed = cost_basis_overrides()?
+        .remove(&note_id)
+        .map_err(|e| error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    if !removed {
+        return Err(error_response_with_code(
+            StatusCode::NOT_FOUND,
+            format!("No cost-basis override for {note_id}"),
+            "not_found",
+        ));
+    }
+    Ok(ResponseJson(serde_json::json!({ "success": true })))
+}
+
 pub async fn get_transaction(
     Path(txid): Path<String>,
 ) -> Result<ResponseJson<serde_json::Value>, (StatusCode, ResponseJson<serde_json::Value>)> {
@@ -1258,6 +1867,8 @@ pub struct AddressBookEntry {
     pub last_used: Option<String>,
     pub usage_count: u32,
     pub notes: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub merchant_key: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -1265,6 +1876,9 @@ pub struct AddAddressRequest {
     pub name: String,
     pub address: String,
     pub notes: Option<String>,
+    /// Hex key the contact signs payment requests with.
+    #[serde(default)]
+    pub merchant_key: Option<String>,
 }
 
 pub async fn list_address_book(
@@ -1290,6 +1904,7 @@ pub async fn list_address_book(
             last_used: e.last_used.map(|d| d.to_rfc3339()),
             usage_count: e.usage_count,
             notes: e.notes.clone(),
+            merchant_key: e.merchant_key.clone(),
         })
         .collect();
 
@@ -1324,6 +1939,11 @@ pub async fn add_address_book_entry(
                 })),
             )
         })?;
+    if payload.merchant_key.is_some() {
+        address_book
+            .set_merchant_key(&payload.name, payload.merchant_key.clone())
+            .map_err(|e| error_response(StatusCode::BAD_REQUEST, e.to_string()))?;
+    }
 
     Ok(ResponseJson(serde_json::json!({
         "success": true,
@@ -1394,12 +2014,98 @@ pub async fn search_address_book(
             last_used: e.last_used.map(|d| d.to_rfc3339()),
             usage_count: e.usage_count,
             notes: e.notes.clone(),
+            merchant_key: e.merchant_key.clone(),
         })
         .collect();
 
     Ok(ResponseJson(entries))
 }
 
+#[derive(Debug, Deserialize)]
+pub struct VerifyPaymentRequestBody {
+    pub uri: String,
+}
+
+/// POST `/api/payment-request/verify` — check a `zcash:` URI's merchant signature and expiry.
+pub async fn verify_payment_request(
+    Json(body): Json<VerifyPaymentRequestBody>,
+) -> Result<ResponseJson<serde_json::Value>, (StatusCode, ResponseJson<serde_json::Value>)> {
+    let contacts = nozy::AddressBook::new()
+        .map(|book| book.list_addresses())
+        .unwrap_or_default();
+    let (request, check) = nozy::verify_payment_uri(&body.uri, &contacts).map_err(|e| {
+        error_response_with_code(StatusCode::BAD_REQUEST, e.to_string(), "invalid_uri")
+    })?;
+    Ok(ResponseJson(serde_json::json!({
+        "address": request.address,
+        "amount_zec": request.amount_zec,
+        "memo": request.memo,
+        "expires": request.expires,
+        "authenticity": check.authenticity,
+        "expired": check.expired,
+        "acceptable": check.is_acceptable(false),
+        "unknown_signer": check.signed_by_unknown_key(),
+        "summary": check.summary(),
+    })))
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ExportDisclosureRequest {
+    /// Output index; defaults to the first non-change output.
+    #[serde(default)]
+    pub output: Option<usize>,
+    #[serde(default)]
+    pub password: Option<String>,
+}
+
+/// POST `/api/transaction/{txid}/disclosure` — payment disclosure for one output of a send.
+pub async fn export_payment_disclosure(
+    Path(txid): Path<String>,
+    Json(body): Json<ExportDisclosureRequest>,
+) -> Result<
+    ResponseJson<nozy::payment_disclosure::PaymentDisclosure>,
+    (StatusCode, ResponseJson<serde_json::Value>),
+> {
+    let config = nozy::load_config();
+    let (wallet, _storage) = load_wallet_with_password(body.password)
+        .await
+        .map_err(|e| error_response(StatusCode::UNAUTHORIZED, e))?;
+    let network = if config.network == "testnet" {
+        zcash_protocol::consensus::NetworkType::Test
+    } else {
+        zcash_protocol::consensus::NetworkType::Main
+    };
+    let disclosure = nozy::payment_disclosure::export_payment_disclosure(
+        &wallet,
+        &nozy::ZebraClient::from_config(&config),
+        &txid,
+        body.output,
+        network,
+    )
+    .await
+    .map_err(|e| error_response_with_code(StatusCode::BAD_REQUEST, e.to_string(), "disclosure"))?;
+    Ok(ResponseJson(disclosure))
+}
+
+/// POST `/api/disclosure/verify` — check a payment disclosure against the chain.
+pub async fn verify_payment_disclosure(
+    Json(disclosure): Json<nozy::payment_disclosure::PaymentDisclosure>,
+) -> Result<
+    ResponseJson<nozy::payment_disclosure::VerifiedPayment>,
+    (StatusCode, ResponseJson<serde_json::Value>),
+> {
+    let config = nozy::load_config();
+    let verified = nozy::payment_disclosure::verify_payment_disclosure_with_zebra(
+        &nozy::ZebraClient::from_config(&config),
+        &disclosure,
+    )
+    .await
+    .map_err(|e| {
+        error_response_with_code(StatusCode::BAD_REQUEST, e.to_string(), "invalid_disclosure")
+    })?;
+    Ok(ResponseJson(verified))
+}
+
 #[derive(Debug, Serialize)]
 pub struct WalletStatusResponse {
     /// Legacy alias for confirmed shielded balance (`confirmed_zec`).
@@ -1785,11 +2491,14 @@ pub async fn get_notes(
         .iter()
         .map(|n| {
             serde_json::json!({
+                "id": hex::encode(&n.nullifier_bytes),
                 "value": n.value,
                 "value_zec": n.value as f64 / 100_000_000.0,
                 "block_height": n.block_height,
                 "txid": n.txid,
                 "spent": n.spent,
+                "frozen": n.frozen,
+                "pool": n.pool,
                 "memo": String::from_utf8(n.memo.clone()).unwrap_or_default()
             })
         })
@@ -1802,3 +2511,41 @@ pub async fn get_notes(
         total_balance_zec,
     }))
 }
+
+#[derive(Debug, Deserialize)]
+pub struct NoteFreezeRequest {
+    /// Note ids (nullifier hex) as returned by `/api/notes`.
+    pub ids: Vec<String>,
+}
+
+async fn set_notes_frozen_response(
+    ids: Vec<String>,
+    frozen: bool,
+) -> Result<ResponseJson<serde_json::Value>, (StatusCode, ResponseJson<serde_json::Value>)> {
+    let changed = nozy::set_notes_frozen(&ids, frozen).map_err(|e| match e {
+        nozy::NozyError::InvalidInput(_) => {
+            error_response_with_code(StatusCode::BAD_REQUEST, e.to_string(), "invalid_note_id")
+        }
+        _ => error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to update notes: {e}"),
+        ),
+    })?;
+    Ok(ResponseJson(serde_json::json!({
+        "success": true,
+        "changed": changed,
+        "frozen": frozen,
+    })))
+}
+
+pub async fn freeze_notes(
+    Json(payload): Json<NoteFreezeRequest>,
+) -> Result<ResponseJson<serde_json::Value>, (StatusCode, ResponseJson<serde_json::Value>)> {
+    set_notes_frozen_response(payload.ids, true).await
+}
+
+pub async fn unfreeze_notes(
+    Json(payload): Json<NoteFreezeRequest>,
+) -> Result<ResponseJson<serde_json::Value>, (StatusCode, ResponseJson<serde_json::Value>)> {
+    set_notes_frozen_response(payload.ids, false).await
+}
@@ -2,8 +2,8 @@
 
 use axum::{
     extract::{Path, Query},
-    http::StatusCode,
-    response::Json as ResponseJson,
+    http::{header, StatusCode},
+    response::{IntoResponse, Json as ResponseJson, Response},
     Json,
 };
 use serde::Deserialize;
@@ -23,6 +23,18 @@ pub struct CreateInvoiceBody {
     pub memo: Option<String>,
     #[serde(default)]
     pub ttl_minutes: Option<i64>,
+    /// Overrides the global `merchant_webhooks.url` for this invoice.
+    #[serde(default)]
+    pub webhook_url: Option<String>,
+    /// Overrides `merchant_invoices.tolerance_zatoshis` for this invoice.
+    #[serde(default)]
+    pub tolerance_zatoshis: Option<u64>,
+    /// Overrides `merchant_invoices.confirmations` for this invoice.
+    #[serde(default)]
+    pub confirmations: Option<u32>,
+    /// Sign the payment request; defaults to `merchant_invoices.sign_payment_requests`.
+    #[serde(default)]
+    pub sign: Option<bool>,
     #[serde(default)]
     pub password: Option<String>,
 }
@@ -68,6 +80,13 @@ pub async fn create_invoice(
     let payment_address = wallet
         .generate_orchard_address(1, diversifier, net)
         .map_err(|e| error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let sign = body
+        .sign
+        .unwrap_or(config.merchant_invoices.sign_payment_requests);
+    let signer = sign
+        .then(|| nozy::PaymentRequestSigner::from_wallet(&wallet, 1))
+        .transpose()
+        .map_err(|e| error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
     let invoice =
         nozy::merchant_invoices::create_invoice(nozy::merchant_invoices::CreateInvoiceParams {
@@ -79,8 +98,18 @@ pub async fn create_invoice(
             ttl_minutes: body.ttl_minutes,
             payment_address,
             diversifier_index: diversifier,
+            webhook_url: body.webhook_url,
+            tolerance_zatoshis: body.tolerance_zatoshis,
+            confirmations: body.confirmations,
+            locked_rate: None,
+            signer,
         })
         .map_err(|e| error_response(StatusCode::BAD_REQUEST, e.to_string()))?;
+    nozy::publish_wallet_event(nozy::WalletEvent::InvoiceStatusChanged {
+        invoice_id: invoice.invoice_id.clone(),
+        status: invoice.status.clone(),
+    });
+    spawn_webhook_delivery();
 
     Ok(ResponseJson(
         serde_json::to_value(invoice).unwrap_or_default(),
@@ -127,20 +156,255 @@ pub async fn get_invoice_qr(
             )
         })?
         .ok_or_else(|| error_response(StatusCode::NOT_FOUND, format!("Invoice {id} not found")))?;
+    // Partially paid invoices show a QR for the remaining amount.
+    let zcash_uri = inv
+        .outstanding_payment_uri()
+        .map_err(|e| error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
     Ok(ResponseJson(serde_json::json!({
         "invoice_id": inv.invoice_id,
-        "zcash_uri": inv.zcash_uri,
+        "zcash_uri": zcash_uri,
         "payment_address": inv.payment_address,
         "amount_zec": inv.amount_zec,
+        "outstanding_zatoshis": inv.outstanding_zatoshis,
         "status": inv.status,
     })))
 }
 
+#[derive(Debug, Deserialize)]
+pub struct QrPngQuery {
+    /// Pixels per QR module (default 8).
+    #[serde(default)]
+    pub scale: Option<u32>,
+}
+
+/// GET `/api/business/invoices/{id}/qr.png` — the outstanding-amount URI as a PNG QR code.
+pub async fn get_invoice_qr_png(
+    Path(id): Path<String>,
+    Query(q): Query<QrPngQuery>,
+) -> Result<Response, (StatusCode, ResponseJson<serde_json::Value>)> {
+    let inv = nozy::merchant_invoices::get_invoice(&id)
+        .map_err(|e| {
+            error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Invoice store error: {e}"),
+            )
+        })?
+        .ok_or_else(|| error_response(StatusCode::NOT_FOUND, format!("Invoice {id} not found")))?;
+    let png = inv
+        .outstanding_payment_uri()
+        .and_then(|uri| nozy::pos::render_qr_png(&uri, q.scale.unwrap_or(8)))
+        .map_err(|e| error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(([(header::CONTENT_TYPE, "image/png".to_string())], png).into_response())
+}
+
+/// GET `/api/business/invoices/{id}/refund` — refund guidance for an overpaid invoice.
+pub async fn get_invoice_refund(
+    Path(id): Path<String>,
+) -> Result<ResponseJson<serde_json::Value>, (StatusCode, ResponseJson<serde_json::Value>)> {
+    let inv = nozy::merchant_invoices::get_invoice(&id)
+        .map_err(|e| {
+            error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Invoice store error: {e}"),
+            )
+        })?
+        .ok_or_else(|| error_response(StatusCode::NOT_FOUND, format!("Invoice {id} not found")))?;
+    let guidance = nozy::merchant_invoices::refund_guidance(&inv).ok_or_else(|| {
+        error_response(
+            StatusCode::BAD_REQUEST,
+            format!("Invoice {id} is not overpaid (status {:?})", inv.status),
+        )
+    })?;
+    Ok(ResponseJson(
+        serde_json::to_value(guidance).unwrap_or_default(),
+    ))
+}
+
 /// POST `/api/business/invoices/{id}/cancel`
 pub async fn cancel_invoice(
     Path(id): Path<String>,
 ) -> Result<ResponseJson<serde_json::Value>, (StatusCode, ResponseJson<serde_json::Value>)> {
     let inv = nozy::merchant_invoices::cancel_invoice(&id)
         .map_err(|e| error_response(StatusCode::BAD_REQUEST, e.to_string()))?;
+    nozy::publish_wallet_event(nozy::WalletEvent::InvoiceStatusChanged {
+        invoice_id: inv.invoice_id.clone(),
+        status: inv.status.clone(),
+    });
+    spawn_webhook_delivery();
     Ok(ResponseJson(serde_json::to_value(inv).unwrap_or_default()))
 }
+
+#[derive(Debug, Deserialize)]
+pub struct PosSaleBody {
+    pub amount_fiat: f64,
+    /// Defaults to `fiat.display_currency`.
+    #[serde(default)]
+    pub currency: Option<String>,
+    #[serde(default)]
+    pub product_name: Option<String>,
+    #[serde(default)]
+    pub memo: Option<String>,
+    #[serde(default)]
+    pub ttl_minutes: Option<i64>,
+    /// Cashier-entered ZEC price; otherwise the configured price source is used.
+    #[serde(default)]
+    pub price_per_zec: Option<f64>,
+    #[serde(default)]
+    pub password: Option<String>,
+}
+
+/// POST `/api/business/pos/sale` — invoice for a fiat amount at a locked rate.
+pub async fn create_pos_sale(
+    Json(body): Json<PosSaleBody>,
+) -> Result<ResponseJson<serde_json::Value>, (StatusCode, ResponseJson<serde_json::Value>)> {
+    let config = nozy::load_config();
+    let (wallet, _storage) = load_wallet_with_password(body.password)
+        .await
+        .map_err(|e| error_response(StatusCode::UNAUTHORIZED, e))?;
+
+    let request = nozy::pos::PosSaleRequest {
+        amount_fiat: body.amount_fiat,
+        currency: body.currency,
+        product_name: body.product_name,
+        memo: body.memo,
+        ttl_minutes: body.ttl_minutes,
+        price_per_zec: body.price_per_zec,
+    };
+    let invoice = nozy::pos::create_pos_sale(&wallet, &config, request)
+        .await
+        .map_err(|e| error_response(StatusCode::BAD_REQUEST, e.to_string()))?;
+    nozy::publish_wallet_event(nozy::WalletEvent::InvoiceStatusChanged {
+        invoice_id: invoice.invoice_id.clone(),
+        status: invoice.status.clone(),
+    });
+    spawn_webhook_delivery();
+
+    let zcash_uri = invoice
+        .outstanding_payment_uri()
+        .map_err(|e| error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let qr_text = nozy::pos::render_qr_terminal(&zcash_uri)
+        .map_err(|e| error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(ResponseJson(serde_json::json!({
+        "invoice": invoice,
+        "zcash_uri": zcash_uri,
+        "qr_text": qr_text,
+        "qr_png": format!("/api/business/invoices/{}/qr.png", invoice.invoice_id),
+    })))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PosCheckBody {
+    /// Treat a mempool payment as final.
+    #[serde(default)]
+    pub zero_conf: bool,
+    #[serde(default)]
+    pub password: Option<String>,
+}
+
+/// POST `/api/business/pos/{id}/check` — one mempool + sync pass for a POS screen to poll.
+pub async fn check_pos_sale(
+    Path(id): Path<String>,
+    Json(body): Json<PosCheckBody>,
+) -> Result<ResponseJson<serde_json::Value>, (StatusCode, ResponseJson<serde_json::Value>)> {
+    let before = nozy::merchant_invoices::get_invoice(&id)
+        .map_err(|e| {
+            error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Invoice store error: {e}"),
+            )
+        })?
+        .ok_or_else(|| error_response(StatusCode::NOT_FOUND, format!("Invoice {id} not found")))?;
+
+    let config = nozy::load_config();
+    let (wallet, _storage) = load_wallet_with_password(body.password)
+        .await
+        .map_err(|e| error_response(StatusCode::UNAUTHORIZED, e))?;
+    let mut watcher = nozy::pos::PosWatcher::new(&wallet, &config, &id, None)
+        .map_err(|e| error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let inv = watcher
+        .poll()
+        .await
+        .map_err(|e| error_response(StatusCode::SERVICE_UNAVAILABLE, e.to_string()))?;
+
+    if inv.status != before.status {
+        nozy::publish_wallet_event(nozy::WalletEvent::InvoiceStatusChanged {
+            invoice_id: inv.invoice_id.clone(),
+            status: inv.status.clone(),
+        });
+        spawn_webhook_delivery();
+    }
+    Ok(ResponseJson(serde_json::json!({
+        "finished": nozy::pos::pos_sale_finished(&inv, body.zero_conf),
+        "status_line": nozy::pos::pos_status_line(&inv),
+        "invoice": inv,
+    })))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MerchantKeyBody {
+    #[serde(default)]
+    pub password: Option<String>,
+}
+
+/// POST `/api/business/merchant-key` — key payers save to recognise signed invoices.
+pub async fn get_merchant_key(
+    Json(body): Json<MerchantKeyBody>,
+) -> Result<ResponseJson<serde_json::Value>, (StatusCode, ResponseJson<serde_json::Value>)> {
+    let (wallet, _storage) = load_wallet_with_password(body.password)
+        .await
+        .map_err(|e| error_response(StatusCode::UNAUTHORIZED, e))?;
+    let signer = nozy::PaymentRequestSigner::from_wallet(&wallet, 1)
+        .map_err(|e| error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(ResponseJson(
+        serde_json::json!({ "merchant_key": signer.merchant_key() }),
+    ))
+}
+
+/// Send queued webhooks now instead of waiting for the daemon.
+fn spawn_webhook_delivery() {
+    tokio::spawn(async {
+        if let Err(e) = nozy::merchant_webhooks::deliver_due_webhooks().await {
+            tracing::warn!(error = %e, "webhook delivery failed");
+        }
+    });
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeliveriesQuery {
+    pub invoice_id: Option<String>,
+    #[serde(default = "default_limit")]
+    pub limit: usize,
+}
+
+/// GET `/api/business/webhooks/deliveries`
+pub async fn list_webhook_deliveries(
+    Query(q): Query<DeliveriesQuery>,
+) -> Result<ResponseJson<serde_json::Value>, (StatusCode, ResponseJson<serde_json::Value>)> {
+    let deliveries =
+        nozy::merchant_webhooks::list_webhook_deliveries(q.invoice_id.as_deref(), q.limit)
+            .map_err(|e| error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(ResponseJson(
+        serde_json::json!({ "deliveries": deliveries }),
+    ))
+}
+
+/// POST `/api/business/webhooks/deliveries/{id}/retry`
+pub async fn retry_webhook_delivery(
+    Path(id): Path<String>,
+) -> Result<ResponseJson<serde_json::Value>, (StatusCode, ResponseJson<serde_json::Value>)> {
+    let delivery = nozy::merchant_webhooks::retry_webhook_delivery(&id)
+        .map_err(|e| error_response(StatusCode::NOT_FOUND, e.to_string()))?;
+    spawn_webhook_delivery();
+    Ok(ResponseJson(
+        serde_json::to_value(delivery).unwrap_or_default(),
+    ))
+}
+
+/// POST `/api/business/webhooks/deliver` — send due deliveries and report the attempts.
+pub async fn deliver_webhooks(
+) -> Result<ResponseJson<serde_json::Value>, (StatusCode, ResponseJson<serde_json::Value>)> {
+    let attempted = nozy::merchant_webhooks::deliver_due_webhooks()
+        .await
+        .map_err(|e| error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(ResponseJson(serde_json::json!({ "attempted": attempted })))
+}
@@ -82,7 +82,7 @@ fn migration_schedule_tip(
     }
 }
 
-async fn chain_context(
+pub(crate) async fn chain_context(
 ) -> Result<(nozy::WalletConfig, u32, bool, u32), (StatusCode, ResponseJson<serde_json::Value>)> {
     let config = load_config();
     let is_testnet = config.network.eq_ignore_ascii_case("testnet");
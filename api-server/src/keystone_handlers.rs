@@ -1,4 +1,4 @@
-use axum::{http::StatusCode, response::Json as ResponseJson, Json};
+use axum::{extract::Path, http::StatusCode, response::Json as ResponseJson, Json};
 use serde::{Deserialize, Serialize};
 use zcash_protocol::consensus::NetworkType;
 
@@ -25,22 +25,25 @@ pub struct KeystoneStatusResponse {
     pub device_label: Option<String>,
     pub has_ufvk: bool,
     pub pending_send: bool,
+    /// Live entries in the hardware-signing queue.
+    pub pending_requests: usize,
     /// Wallet network from config (`mainnet` or `testnet`).
     pub network: String,
 }
 
 pub async fn keystone_status(
 ) -> Result<ResponseJson<KeystoneStatusResponse>, (StatusCode, ResponseJson<serde_json::Value>)> {
-    use nozy::{load_config, load_pending_send};
+    use nozy::{load_config, load_signing_queue};
 
     let config = load_config();
-    let pending_send = load_pending_send().ok().flatten().is_some();
+    let pending_requests = load_signing_queue().map(|q| q.requests.len()).unwrap_or(0);
 
     Ok(ResponseJson(KeystoneStatusResponse {
         enabled: config.keystone.enabled,
         device_label: config.keystone.device_label.clone(),
         has_ufvk: config.keystone.ufvk.as_ref().is_some_and(|s| !s.is_empty()),
-        pending_send,
+        pending_send: pending_requests > 0,
+        pending_requests,
         network: config.network.clone(),
     }))
 }
@@ -165,6 +168,8 @@ fn default_true() -> bool {
 pub struct KeystonePrepareResponse {
     pub success: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub summary: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub action_count: Option<u32>,
@@ -183,16 +188,17 @@ pub async fn keystone_prepare_send(
 ) -> Result<ResponseJson<KeystonePrepareResponse>, (StatusCode, ResponseJson<serde_json::Value>)> {
     use nozy::cli_helpers::{is_zebra_unavailable_error, scan_notes_for_sending};
     use nozy::{
-        build_keystone_send_pczt, encode_pczt_ur_frames, estimate_transaction_fee_for_send,
-        load_config, prepared_send_from_build, save_pending_send, PilotSendOptions, ZebraClient,
-        ZebraJsonRpcOrchardWitnessProvider, DEFAULT_UR_FRAGMENT_SIZE, PILOT_EXPIRY_DELTA_BLOCKS,
-        UR_TYPE_ZCASH_PCZT,
+        build_keystone_send_pczt, encode_pczt_ur_frames, enqueue_signing_request,
+        estimate_transaction_fee_for_send, load_config, prepared_send_from_build, PilotSendOptions,
+        ZebraClient, ZebraJsonRpcOrchardWitnessProvider, DEFAULT_UR_FRAGMENT_SIZE,
+        PILOT_EXPIRY_DELTA_BLOCKS, UR_TYPE_ZCASH_PCZT,
     };
 
     let config = load_config();
     if config.network == "testnet" {
         return Ok(ResponseJson(KeystonePrepareResponse {
             success: false,
+            request_id: None,
             message: Some(
                 "Keystone is configured for Zcash mainnet only. Set wallet network to mainnet in config."
                     .to_string(),
@@ -208,6 +214,7 @@ pub async fn keystone_prepare_send(
     if !payload.recipient.starts_with("u1") || payload.recipient.len() < 78 {
         return Ok(ResponseJson(KeystonePrepareResponse {
             success: false,
+            request_id: None,
             message: Some(
                 "Invalid recipient. Use a mainnet Orchard unified address (u1…).".to_string(),
             ),
@@ -222,6 +229,7 @@ pub async fn keystone_prepare_send(
     if !validate_amount(payload.amount.unwrap_or(0.0)) && payload.amount_zatoshis.is_none() {
         return Ok(ResponseJson(KeystonePrepareResponse {
             success: false,
+            request_id: None,
             message: Some("Invalid amount. Must be greater than 0.".to_string()),
             summary: None,
             action_count: None,
@@ -239,6 +247,7 @@ pub async fn keystone_prepare_send(
         Err(e) => {
             return Ok(ResponseJson(KeystonePrepareResponse {
                 success: false,
+                request_id: None,
                 message: Some(e.to_string()),
                 summary: None,
                 action_count: None,
@@ -294,7 +303,6 @@ pub async fn keystone_prepare_send(
     let build = build_keystone_send_pczt(
         &zebra_client,
         &witness_provider,
-        &wallet,
         &config.keystone,
         &spendable_notes,
         &payload.recipient,
@@ -312,14 +320,18 @@ pub async fn keystone_prepare_send(
         )
     })?;
 
-    let prepared =
-        prepared_send_from_build(&payload.recipient, amount_zatoshis, fee_zatoshis, &build);
-    save_pending_send(&prepared).map_err(|e| {
+    let request = enqueue_signing_request(prepared_send_from_build(
+        &payload.recipient,
+        amount_zatoshis,
+        &build,
+    ))
+    .map_err(|e| {
         error_response(
             StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Failed to save pending send: {e}"),
+            format!("Failed to queue signing request: {e}"),
         )
     })?;
+    let prepared = &request.send;
 
     let ur_frames =
         encode_pczt_ur_frames(&build.pczt_bytes, DEFAULT_UR_FRAGMENT_SIZE).map_err(|e| {
@@ -331,6 +343,7 @@ pub async fn keystone_prepare_send(
 
     Ok(ResponseJson(KeystonePrepareResponse {
         success: true,
+        request_id: Some(request.id.clone()),
         summary: Some(prepared.summary.clone()),
         action_count: Some(prepared.action_count),
         pczt_hex: Some(prepared.pczt_hex.clone()),
@@ -344,6 +357,8 @@ pub async fn keystone_prepare_send(
 pub struct KeystoneCompleteSendRequest {
     pub pczt_hex: Option<String>,
     pub ur_frames: Option<Vec<String>>,
+    /// Use the frames saved through `/api/keystone/queue/{id}/scan`.
+    pub request_id: Option<String>,
     #[serde(default = "default_true")]
     pub broadcast: bool,
     pub zebra_url: Option<String>,
@@ -356,42 +371,51 @@ pub struct KeystoneCompleteSendResponse {
     pub txid: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub broadcast: Option<bool>,
+    /// Set when the broadcast succeeded but recording it locally failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub warning: Option<String>,
+}
+
+fn signed_pczt_bytes(
+    pczt_hex: Option<String>,
+    ur_frames: Option<Vec<String>>,
+) -> Result<Vec<u8>, (StatusCode, ResponseJson<serde_json::Value>)> {
+    if let Some(frames) = ur_frames.filter(|f| !f.is_empty()) {
+        nozy::decode_pczt_ur_frames(&frames).map_err(|e| {
+            error_response(
+                StatusCode::BAD_REQUEST,
+                format!("Failed to decode UR frames: {e}"),
+            )
+        })
+    } else if let Some(hex_str) = pczt_hex.filter(|s| !s.trim().is_empty()) {
+        hex::decode(hex_str.trim())
+            .map_err(|e| error_response(StatusCode::BAD_REQUEST, format!("Invalid PCZT hex: {e}")))
+    } else {
+        Err(error_response(
+            StatusCode::BAD_REQUEST,
+            "Provide pczt_hex or ur_frames from Keystone",
+        ))
+    }
 }
 
 pub async fn keystone_complete_send(
     Json(payload): Json<KeystoneCompleteSendRequest>,
 ) -> Result<ResponseJson<KeystoneCompleteSendResponse>, (StatusCode, ResponseJson<serde_json::Value>)>
 {
-    use nozy::cli_helpers::scan_notes_for_sending;
-    use nozy::transaction_history::{SentTransactionRecord, SentTransactionStorage};
-    use nozy::{
-        clear_pending_send, decode_pczt_ur_frames, extract_signed_tx_from_pczt_bytes, load_config,
-        load_pending_send, mark_wallet_notes_spent_from_spendables, select_single_spend_note,
-        ZebraClient,
-    };
-    use nozy::{NOZY_WALLET_PRIORITY_FEE, PILOT_EXPIRY_DELTA_BLOCKS};
+    use nozy::cold_signing::record_signed_pczt_broadcast;
+    use nozy::{extract_signed_tx_from_pczt_bytes, load_config, ZebraClient};
 
     let config = load_config();
     if config.network == "testnet" {
         return Err(keystone_mainnet_error());
     }
 
-    let pczt_bytes = if let Some(frames) = payload.ur_frames.filter(|f| !f.is_empty()) {
-        decode_pczt_ur_frames(&frames).map_err(|e| {
-            error_response(
-                StatusCode::BAD_REQUEST,
-                format!("Failed to decode UR frames: {e}"),
-            )
-        })?
-    } else if let Some(hex_str) = payload.pczt_hex.filter(|s| !s.trim().is_empty()) {
-        hex::decode(hex_str.trim()).map_err(|e| {
-            error_response(StatusCode::BAD_REQUEST, format!("Invalid PCZT hex: {e}"))
-        })?
-    } else {
-        return Err(error_response(
-            StatusCode::BAD_REQUEST,
-            "Provide pczt_hex or ur_frames from Keystone",
-        ));
+    let pczt_bytes = match payload.request_id {
+        Some(id) if payload.pczt_hex.is_none() && payload.ur_frames.is_none() => {
+            nozy::scanned_signed_pczt(&id)
+                .map_err(|e| error_response(StatusCode::BAD_REQUEST, e.to_string()))?
+        }
+        _ => signed_pczt_bytes(payload.pczt_hex, payload.ur_frames)?,
     };
 
     let extracted = extract_signed_tx_from_pczt_bytes(&pczt_bytes).map_err(|e| {
@@ -402,11 +426,11 @@ pub async fn keystone_complete_send(
     })?;
 
     if !payload.broadcast {
-        let _ = clear_pending_send();
         return Ok(ResponseJson(KeystoneCompleteSendResponse {
             success: true,
             txid: Some(extracted.txid.clone()),
             broadcast: Some(false),
+            warning: None,
         }));
     }
 
@@ -425,49 +449,219 @@ pub async fn keystone_complete_send(
             )
         })?;
 
-    let pending = load_pending_send().ok().flatten();
-    if let Some(prepared) = pending {
-        if let Ok((wallet, _)) = load_wallet_with_password(None).await {
-            if let Ok(spendable_notes) = scan_notes_for_sending(&wallet, &zebra_url).await {
-                if let Ok(spent_note) = select_single_spend_note(
-                    &spendable_notes,
-                    prepared.amount_zatoshis,
-                    prepared.fee_zatoshis,
-                ) {
-                    let _ = mark_wallet_notes_spent_from_spendables(
-                        std::slice::from_ref(spent_note),
-                        Some(&txid),
-                    );
-                    if let Ok(tx_storage) = SentTransactionStorage::new() {
-                        let spent_note_ids =
-                            vec![hex::encode(spent_note.orchard_note.nullifier.to_bytes())];
-                        let mut tx_record = SentTransactionRecord::new_pilot(
-                            txid.clone(),
-                            prepared.recipient.clone(),
-                            prepared.amount_zatoshis,
-                            prepared.fee_zatoshis,
-                            None,
-                            spent_note_ids,
-                            NOZY_WALLET_PRIORITY_FEE,
-                            zebra_client
-                                .get_best_block_height()
-                                .await
-                                .unwrap_or(0)
-                                .saturating_add(PILOT_EXPIRY_DELTA_BLOCKS),
-                        );
-                        tx_record.mark_broadcast();
-                        let _ = tx_storage.save_transaction(tx_record);
-                    }
-                }
-            }
-        }
-    }
-
-    let _ = clear_pending_send();
+    // The transaction is on the network now, so bookkeeping problems are only reported.
+    let warning = record_signed_pczt_broadcast(&pczt_bytes, &txid)
+        .err()
+        .map(|e| format!("Broadcast succeeded but could not record it locally: {e}"));
 
     Ok(ResponseJson(KeystoneCompleteSendResponse {
         success: true,
         txid: Some(txid),
         broadcast: Some(true),
+        warning,
+    }))
+}
+
+#[derive(Debug, Serialize)]
+pub struct KeystoneQueueResponse {
+    pub requests: Vec<nozy::SigningRequest>,
+    /// Requests dropped by this call because their expiry height passed.
+    pub expired: Vec<String>,
+}
+
+fn queue_error(e: nozy::NozyError) -> (StatusCode, ResponseJson<serde_json::Value>) {
+    match e {
+        nozy::NozyError::InvalidInput(_) | nozy::NozyError::InvalidOperation(_) => {
+            error_response(StatusCode::BAD_REQUEST, e.to_string())
+        }
+        _ => error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
+/// Pending hardware-signing requests; expired ones are pruned when the node is reachable.
+pub async fn keystone_queue(
+) -> Result<ResponseJson<KeystoneQueueResponse>, (StatusCode, ResponseJson<serde_json::Value>)> {
+    use nozy::{load_config, load_signing_queue, prune_expired_signing_requests, ZebraClient};
+
+    let config = load_config();
+    let expired = match ZebraClient::from_config(&config).get_block_count().await {
+        Ok(chain_tip) => prune_expired_signing_requests(chain_tip).map_err(queue_error)?,
+        Err(_) => Vec::new(),
+    };
+    let queue = load_signing_queue().map_err(queue_error)?;
+    Ok(ResponseJson(KeystoneQueueResponse {
+        requests: queue.requests,
+        expired: expired.into_iter().map(|r| r.id).collect(),
     }))
 }
+
+pub async fn keystone_cancel_request(
+    Path(id): Path<String>,
+) -> Result<ResponseJson<serde_json::Value>, (StatusCode, ResponseJson<serde_json::Value>)> {
+    let cancelled = nozy::cancel_signing_request(&id).map_err(queue_error)?;
+    Ok(ResponseJson(serde_json::json!({
+        "success": true,
+        "id": cancelled.id,
+        "released_notes": cancelled.locked_notes.len(),
+    })))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct KeystoneScanFramesRequest {
+    pub frames: Vec<String>,
+}
+
+pub async fn keystone_scan_frames(
+    Path(id): Path<String>,
+    Json(payload): Json<KeystoneScanFramesRequest>,
+) -> Result<ResponseJson<nozy::UrScanProgress>, (StatusCode, ResponseJson<serde_json::Value>)> {
+    nozy::record_scanned_frames(&id, &payload.frames)
+        .map(ResponseJson)
+        .map_err(queue_error)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct KeystonePrepareMigrationRequest {
+    /// Build the ZIP 318 note split instead of the next scheduled crossing.
+    #[serde(default)]
+    pub split: bool,
+    pub password: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct KeystonePrepareMigrationResponse {
+    pub migration: nozy::ironwood::KeystoneMigrationPczt,
+    pub ur_frames: Vec<String>,
+    pub ur_type: String,
+}
+
+/// Build a ZIP 318 split or turnstile crossing PCZT for Keystone to sign.
+pub async fn keystone_prepare_migration(
+    Json(payload): Json<KeystonePrepareMigrationRequest>,
+) -> Result<
+    ResponseJson<KeystonePrepareMigrationResponse>,
+    (StatusCode, ResponseJson<serde_json::Value>),
+> {
+    use nozy::ironwood::{prepare_keystone_migration_pczt, prepare_keystone_note_split_pczt};
+    use nozy::{encode_pczt_ur_frames, scan_notes_for_sending, DEFAULT_UR_FRAGMENT_SIZE};
+
+    let (config, _chain_tip, ironwood_active, _tip) =
+        crate::ironwood_handlers::chain_context().await?;
+    if config.network == "testnet" {
+        return Err(keystone_mainnet_error());
+    }
+    let (wallet, _storage) = load_wallet_with_password(payload.password)
+        .await
+        .map_err(|e| error_response(StatusCode::UNAUTHORIZED, e))?;
+    let spendable_notes = scan_notes_for_sending(&wallet, &config.zebra_url)
+        .await
+        .map_err(|e| {
+            error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to scan notes: {e}"),
+            )
+        })?;
+
+    let migration = if payload.split {
+        prepare_keystone_note_split_pczt(
+            &config.zebra_url,
+            ironwood_active,
+            &wallet,
+            &config.keystone,
+            &spendable_notes,
+        )
+        .await
+    } else {
+        prepare_keystone_migration_pczt(
+            &config.zebra_url,
+            ironwood_active,
+            &wallet,
+            &config.keystone,
+            &spendable_notes,
+        )
+        .await
+    }
+    .map_err(|e| error_response(StatusCode::BAD_REQUEST, e.to_string()))?;
+
+    let pczt_bytes = hex::decode(&migration.pczt_hex)
+        .map_err(|e| error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let ur_frames = encode_pczt_ur_frames(&pczt_bytes, DEFAULT_UR_FRAGMENT_SIZE).map_err(|e| {
+        error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to encode UR frames: {e}"),
+        )
+    })?;
+    Ok(ResponseJson(KeystonePrepareMigrationResponse {
+        migration,
+        ur_frames,
+        ur_type: nozy::UR_TYPE_ZCASH_PCZT.to_string(),
+    }))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct KeystoneCompleteMigrationRequest {
+    pub pczt_hex: Option<String>,
+    pub ur_frames: Option<Vec<String>>,
+}
+
+/// Broadcast a signed split, or store a signed crossing as presigned in the schedule.
+pub async fn keystone_complete_migration(
+    Json(payload): Json<KeystoneCompleteMigrationRequest>,
+) -> Result<
+    ResponseJson<nozy::ironwood::KeystoneMigrationCompletion>,
+    (StatusCode, ResponseJson<serde_json::Value>),
+> {
+    let config = nozy::load_config();
+    if config.network == "testnet" {
+        return Err(keystone_mainnet_error());
+    }
+    let pczt_bytes = signed_pczt_bytes(payload.pczt_hex, payload.ur_frames)?;
+    nozy::ironwood::complete_keystone_migration_pczt(&config.zebra_url, &pczt_bytes)
+        .await
+        .map(ResponseJson)
+        .map_err(|e| error_response(StatusCode::BAD_REQUEST, e.to_string()))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PcztInspectRequest {
+    pub pczt_hex: Option<String>,
+    pub ur_frames: Option<Vec<String>>,
+    pub password: Option<String>,
+}
+
+/// Decode a signed or unsigned PCZT and check it against `pczt_policy`; never signs.
+pub async fn pczt_inspect(
+    Json(payload): Json<PcztInspectRequest>,
+) -> Result<
+    ResponseJson<nozy::cold_signing::PcztInspection>,
+    (StatusCode, ResponseJson<serde_json::Value>),
+> {
+    use nozy::cold_signing::{inspect_pczt_for_wallet, read_pczt_transport};
+    use nozy::load_config;
+
+    let transport = if let Some(frames) = payload.ur_frames.filter(|f| !f.is_empty()) {
+        frames.join("\n")
+    } else if let Some(hex_str) = payload.pczt_hex.filter(|s| !s.trim().is_empty()) {
+        hex_str
+    } else {
+        return Err(error_response(
+            StatusCode::BAD_REQUEST,
+            "Provide pczt_hex or ur_frames",
+        ));
+    };
+    let pczt_bytes = read_pczt_transport(transport.as_bytes())
+        .map_err(|e| error_response(StatusCode::BAD_REQUEST, e.to_string()))?;
+
+    let (wallet, _storage) = load_wallet_with_password(payload.password)
+        .await
+        .map_err(|e| error_response(StatusCode::UNAUTHORIZED, e))?;
+    let config = load_config();
+    let inspection = inspect_pczt_for_wallet(
+        &pczt_bytes,
+        &wallet,
+        network_from_config(&config),
+        &config.pczt_policy,
+    )
+    .map_err(|e| error_response(StatusCode::BAD_REQUEST, e.to_string()))?;
+    Ok(ResponseJson(inspection))
+}
@@ -10,11 +10,14 @@ use tower_http::cors::{AllowOrigin, CorsLayer};
 use tower_http::trace::TraceLayer;
 use tracing::{info, warn};
 
+mod daemon_handlers;
+mod event_handlers;
 mod handlers;
 mod invoice_handlers;
 mod ironwood_handlers;
 mod keystone_handlers;
 mod lwd_handlers;
+mod message_handlers;
 mod middleware;
 mod profile_handlers;
 mod sapling_handlers;
@@ -139,6 +142,10 @@ async fn main() -> anyhow::Result<()> {
         .route("/api/zns/link", post(zns_handlers::link_zns_name))
         .route("/api/zns/link", delete(zns_handlers::unlink_zns_name))
         .route("/api/zns/reverse", get(zns_handlers::reverse_zns_lookup))
+        .route("/api/zns/register", post(zns_handlers::register_zns_name))
+        .route("/api/zns/update", post(zns_handlers::update_zns_name))
+        .route("/api/zns/renew", post(zns_handlers::renew_zns_name))
+        .route("/api/zns/actions", get(zns_handlers::list_zns_actions))
         .route("/api/profile", get(profile_handlers::get_profile))
         .route("/api/profile", post(profile_handlers::update_profile))
         .route(
@@ -157,21 +164,87 @@ async fn main() -> anyhow::Result<()> {
             "/api/business/invoices/{id}/qr",
             get(invoice_handlers::get_invoice_qr),
         )
+        .route(
+            "/api/business/invoices/{id}/qr.png",
+            get(invoice_handlers::get_invoice_qr_png),
+        )
+        .route(
+            "/api/business/invoices/{id}/refund",
+            get(invoice_handlers::get_invoice_refund),
+        )
         .route(
             "/api/business/invoices/{id}/cancel",
             post(invoice_handlers::cancel_invoice),
         )
+        .route(
+            "/api/business/pos/sale",
+            post(invoice_handlers::create_pos_sale),
+        )
+        .route(
+            "/api/business/pos/{id}/check",
+            post(invoice_handlers::check_pos_sale),
+        )
+        .route(
+            "/api/business/merchant-key",
+            post(invoice_handlers::get_merchant_key),
+        )
+        .route(
+            "/api/business/webhooks/deliveries",
+            get(invoice_handlers::list_webhook_deliveries),
+        )
+        .route(
+            "/api/business/webhooks/deliveries/{id}/retry",
+            post(invoice_handlers::retry_webhook_delivery),
+        )
+        .route(
+            "/api/business/webhooks/deliver",
+            post(invoice_handlers::deliver_webhooks),
+        )
         .route("/api/pilot/metrics", get(handlers::get_pilot_metrics))
         .route("/api/address/generate", post(handlers::generate_address))
         .route("/api/balance", get(handlers::get_balance))
+        .route("/api/balance/fiat", get(handlers::get_fiat_balance))
+        .route("/api/price", get(handlers::get_price))
         .route("/api/sync", post(handlers::sync_wallet))
         .route("/api/transaction/send", post(handlers::send_transaction))
+        .route(
+            "/api/transaction/propose",
+            post(handlers::propose_transaction),
+        )
+        .route(
+            "/api/transaction/execute-proposal",
+            post(handlers::execute_proposal),
+        )
         .route("/api/transaction/fee-estimate", get(handlers::estimate_fee))
         .route(
             "/api/transaction/history",
             get(handlers::get_transaction_history),
         )
+        .route(
+            "/api/transaction/history/export",
+            get(handlers::export_transaction_history),
+        )
         .route("/api/transaction/{txid}", get(handlers::get_transaction))
+        .route(
+            "/api/transaction/{txid}/disclosure",
+            post(handlers::export_payment_disclosure),
+        )
+        .route(
+            "/api/disclosure/verify",
+            post(handlers::verify_payment_disclosure),
+        )
+        .route(
+            "/api/cost-basis/report",
+            get(handlers::get_cost_basis_report),
+        )
+        .route(
+            "/api/cost-basis/overrides",
+            get(handlers::list_cost_basis_overrides).post(handlers::set_cost_basis_override),
+        )
+        .route(
+            "/api/cost-basis/overrides/{note_id}",
+            delete(handlers::remove_cost_basis_override),
+        )
         .route(
             "/api/transaction/check-confirmations",
             post(handlers::check_transaction_confirmations),
@@ -180,17 +253,53 @@ async fn main() -> anyhow::Result<()> {
             "/api/transaction/speed-up",
             post(handlers::speed_up_transaction),
         )
+        .route("/api/labels", get(handlers::search_labels))
+        .route(
+            "/api/labels/{target}/{id}",
+            post(handlers::update_label).delete(handlers::remove_label),
+        )
+        .route(
+            "/api/schedule",
+            get(handlers::list_scheduled_payments).post(handlers::add_scheduled_payment),
+        )
+        .route("/api/schedule/run", post(handlers::run_scheduled_payments))
+        .route("/api/daemon/start", post(daemon_handlers::start_daemon))
+        .route("/api/daemon/stop", post(daemon_handlers::stop_daemon))
+        .route(
+            "/api/daemon/status",
+            get(daemon_handlers::get_daemon_status),
+        )
+        .route("/api/events", get(event_handlers::stream_events))
+        .route(
+            "/api/schedule/{id}",
+            delete(handlers::remove_scheduled_payment),
+        )
+        .route(
+            "/api/schedule/{id}/enabled",
+            post(handlers::set_scheduled_payment_enabled),
+        )
         .route("/api/address-book", get(handlers::list_address_book))
         .route("/api/address-book", post(handlers::add_address_book_entry))
         .route(
             "/api/address-book/{name}",
             delete(handlers::remove_address_book_entry),
         )
+        .route("/api/message/sign", post(message_handlers::sign_message))
+        .route(
+            "/api/message/verify",
+            post(message_handlers::verify_message),
+        )
+        .route(
+            "/api/payment-request/verify",
+            post(handlers::verify_payment_request),
+        )
         .route(
             "/api/address-book/search",
             get(handlers::search_address_book),
         )
         .route("/api/notes", get(handlers::get_notes))
+        .route("/api/notes/freeze", post(handlers::freeze_notes))
+        .route("/api/notes/unfreeze", post(handlers::unfreeze_notes))
         .route("/api/config", get(handlers::get_config))
         .route("/api/config/zebra-url", post(handlers::set_zebra_url))
         .route("/api/config/theme", post(handlers::set_theme))
@@ -243,6 +352,27 @@ async fn main() -> anyhow::Result<()> {
             "/api/keystone/complete-send",
             post(keystone_handlers::keystone_complete_send),
         )
+        .route(
+            "/api/keystone/queue",
+            get(keystone_handlers::keystone_queue),
+        )
+        .route(
+            "/api/keystone/queue/{id}/cancel",
+            post(keystone_handlers::keystone_cancel_request),
+        )
+        .route(
+            "/api/keystone/queue/{id}/scan",
+            post(keystone_handlers::keystone_scan_frames),
+        )
+        .route(
+            "/api/keystone/prepare-migration",
+            post(keystone_handlers::keystone_prepare_migration),
+        )
+        .route(
+            "/api/keystone/complete-migration",
+            post(keystone_handlers::keystone_complete_migration),
+        )
+        .route("/api/pczt/inspect", post(keystone_handlers::pczt_inspect))
         .route("/health", get(health_check))
         .layer(axum::middleware::from_fn(
             move |req: axum::extract::Request, next: axum::middleware::Next| {
@@ -0,0 +1,129 @@
+//! Address-bound message signing (`nozy-sm-v2`, ZIP-304 for Sapling) and verification.
+
+use axum::{extract::Json, http::StatusCode, response::Json as ResponseJson};
+use serde::{Deserialize, Serialize};
+
+use crate::handlers::{error_response, error_response_with_code, load_wallet_with_password};
+
+#[derive(Debug, Deserialize)]
+pub struct SignMessageRequest {
+    pub message: String,
+    /// ZIP-32 account; defaults to the active profile's account.
+    #[serde(default)]
+    pub account: Option<u32>,
+    /// Address to sign for; defaults to the account's first address.
+    #[serde(default)]
+    pub address: Option<String>,
+    /// Sign a Sapling address with ZIP-304 instead.
+    #[serde(default)]
+    pub sapling: bool,
+    /// Required for `nozy-sm-v2`, whose signature contains the account's full viewing key.
+    #[serde(default)]
+    pub reveal_viewing_key: bool,
+    #[serde(default)]
+    pub password: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SignMessageResponse {
+    pub address: String,
+    pub signature: String,
+    /// [`nozy::signed_message::VIEWING_KEY_WARNING`] for `nozy-sm-v2` signatures.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub warning: Option<&'static str>,
+}
+
+/// POST `/api/message/sign` — a `nozy-sm-v2` signature reveals the account's full viewing
+/// key, so it needs `reveal_viewing_key: true`; ZIP-304 (`sapling: true`) reveals none.
+pub async fn sign_message(
+    Json(body): Json<SignMessageRequest>,
+) -> Result<ResponseJson<SignMessageResponse>, (StatusCode, ResponseJson<serde_json::Value>)> {
+    if !body.sapling && !body.reveal_viewing_key {
+        return Err(error_response_with_code(
+            StatusCode::BAD_REQUEST,
+            format!(
+                "{} Set reveal_viewing_key to sign anyway, or sapling for ZIP-304.",
+                nozy::signed_message::VIEWING_KEY_WARNING
+            ),
+            "viewing_key_opt_in_required",
+        ));
+    }
+    let config = nozy::load_config();
+    let (wallet, _storage) = load_wallet_with_password(body.password)
+        .await
+        .map_err(|e| error_response(StatusCode::UNAUTHORIZED, e))?;
+
+    let account = body.account.unwrap_or(config.active_orchard_account());
+    let network = if config.network == "testnet" {
+        zcash_protocol::consensus::NetworkType::Test
+    } else {
+        zcash_protocol::consensus::NetworkType::Main
+    };
+    let address = match body.address {
+        Some(address) => address,
+        None if body.sapling => wallet
+            .generate_sapling_payment_address(account, 0, network)
+            .map_err(|e| error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?,
+        None => wallet
+            .generate_orchard_address(account, 0, network)
+            .map_err(|e| error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?,
+    };
+
+    let seed = wallet.get_mnemonic_object().to_seed("");
+    let signature = if body.sapling {
+        // Building the Spend proof takes a few seconds; keep it off the async workers.
+        let (address, message) = (address.clone(), body.message);
+        tokio::task::spawn_blocking(move || {
+            nozy::zip304::sign_with_seed(&seed, account, &address, &message)
+        })
+        .await
+        .map_err(|e| error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    } else {
+        nozy::signed_message::sign_for_address(
+            &seed,
+            account,
+            &address,
+            &body.message,
+            body.reveal_viewing_key,
+        )
+    }
+    .map_err(|e| error_response(StatusCode::BAD_REQUEST, e.to_string()))?;
+    Ok(ResponseJson(SignMessageResponse {
+        address,
+        signature,
+        warning: (!body.sapling).then_some(nozy::signed_message::VIEWING_KEY_WARNING),
+    }))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct VerifyMessageRequest {
+    pub message: String,
+    pub signature: String,
+    /// Claimed signer; required for `nozy-sm-v2` and ZIP-304.
+    #[serde(default)]
+    pub address: Option<String>,
+}
+
+/// POST `/api/message/verify`
+pub async fn verify_message(
+    Json(body): Json<VerifyMessageRequest>,
+) -> Result<ResponseJson<serde_json::Value>, (StatusCode, ResponseJson<serde_json::Value>)> {
+    let signature = body.signature.trim().to_string();
+    let (message, address) = (body.message, body.address.clone());
+    let valid = tokio::task::spawn_blocking(move || {
+        nozy::signed_message::verify_any(&signature, &message, address.as_deref())
+    })
+    .await
+    .map_err(|e| error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .map_err(|e| {
+        error_response_with_code(StatusCode::BAD_REQUEST, e.to_string(), "invalid_input")
+    })?;
+    let signature = body.signature.trim();
+    let address_bound = signature.starts_with(nozy::signed_message::SCHEME_ID_V2)
+        || signature.starts_with(nozy::zip304::SIGNATURE_PREFIX);
+    Ok(ResponseJson(serde_json::json!({
+        "valid": valid,
+        "address_bound": address_bound,
+        "address": body.address.filter(|_| address_bound),
+    })))
+}
@@ -34,6 +34,8 @@ pub struct ZnsRegistration {
     pub nonce: Option<u64>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub last_action: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pubkey: Option<String>,
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -118,6 +120,7 @@ async fn resolve_name_inner(
         height: r.height,
         nonce: r.nonce,
         last_action: r.last_action,
+        pubkey: r.pubkey,
     });
 
     let response = ResolveResponse {
@@ -358,6 +361,104 @@ pub async fn reverse_zns_lookup(
     })))
 }
 
+#[derive(Debug, Deserialize)]
+pub struct ZnsActionRequest {
+    pub name: String,
+    /// Target UA; defaults to the Business UA. Ignored for renewals.
+    #[serde(default)]
+    pub address: Option<String>,
+    /// Return the signed memo and cost without sending.
+    #[serde(default)]
+    pub dry_run: bool,
+    pub password: Option<String>,
+    pub zebra_url: Option<String>,
+}
+
+fn zns_action_error(e: nozy::NozyError) -> (StatusCode, ResponseJson<serde_json::Value>) {
+    match e {
+        nozy::NozyError::InvalidInput(_) | nozy::NozyError::InvalidOperation(_) => {
+            error_response(StatusCode::BAD_REQUEST, e.to_string())
+        }
+        nozy::NozyError::InsufficientFunds(_) => {
+            error_response(StatusCode::PAYMENT_REQUIRED, e.to_string())
+        }
+        nozy::NozyError::NetworkError(_) => error_response(StatusCode::BAD_GATEWAY, e.to_string()),
+        _ => error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
+async fn run_zns_action(
+    kind: nozy::zns_registration::ZnsActionKind,
+    payload: ZnsActionRequest,
+) -> Result<ResponseJson<serde_json::Value>, (StatusCode, ResponseJson<serde_json::Value>)> {
+    let config = nozy::load_config();
+    let network = if config.network == "testnet" {
+        zcash_protocol::consensus::NetworkType::Test
+    } else {
+        zcash_protocol::consensus::NetworkType::Main
+    };
+    let indexer =
+        nozy::zns::ZnsIndexer::for_network(Some(&config.network)).map_err(zns_action_error)?;
+    let (wallet, _storage) = crate::handlers::load_wallet_with_password(payload.password)
+        .await
+        .map_err(|e| error_response(StatusCode::UNAUTHORIZED, e))?;
+    let prepared = nozy::zns_registration::prepare_zns_action(
+        &wallet,
+        &indexer,
+        kind,
+        &payload.name,
+        payload.address.as_deref(),
+        network,
+    )
+    .await
+    .map_err(zns_action_error)?;
+    if payload.dry_run {
+        return Ok(ResponseJson(serde_json::json!({ "prepared": prepared })));
+    }
+    let zebra_url = payload.zebra_url.unwrap_or(config.zebra_url);
+    let action = nozy::zns_registration::submit_zns_action(&wallet, &zebra_url, &prepared)
+        .await
+        .map_err(zns_action_error)?;
+    Ok(ResponseJson(serde_json::json!({
+        "prepared": prepared,
+        "action": action,
+    })))
+}
+
+/// POST `/api/zns/register` — claim a free name from this wallet.
+pub async fn register_zns_name(
+    Json(payload): Json<ZnsActionRequest>,
+) -> Result<ResponseJson<serde_json::Value>, (StatusCode, ResponseJson<serde_json::Value>)> {
+    run_zns_action(nozy::zns_registration::ZnsActionKind::Claim, payload).await
+}
+
+/// POST `/api/zns/update` — point a name this wallet owns at another UA.
+pub async fn update_zns_name(
+    Json(payload): Json<ZnsActionRequest>,
+) -> Result<ResponseJson<serde_json::Value>, (StatusCode, ResponseJson<serde_json::Value>)> {
+    run_zns_action(nozy::zns_registration::ZnsActionKind::Update, payload).await
+}
+
+/// POST `/api/zns/renew` — re-send the current UA to keep the name active.
+pub async fn renew_zns_name(
+    Json(payload): Json<ZnsActionRequest>,
+) -> Result<ResponseJson<serde_json::Value>, (StatusCode, ResponseJson<serde_json::Value>)> {
+    run_zns_action(nozy::zns_registration::ZnsActionKind::Renew, payload).await
+}
+
+/// GET `/api/zns/actions` — submitted actions, re-checked against the chain and indexer.
+pub async fn list_zns_actions(
+) -> Result<ResponseJson<serde_json::Value>, (StatusCode, ResponseJson<serde_json::Value>)> {
+    let config = nozy::load_config();
+    let indexer =
+        nozy::zns::ZnsIndexer::for_network(Some(&config.network)).map_err(zns_action_error)?;
+    let zebra_client = nozy::ZebraClient::from_config(&config);
+    let actions = nozy::zns_registration::refresh_zns_actions(&zebra_client, &indexer)
+        .await
+        .map_err(zns_action_error)?;
+    Ok(ResponseJson(serde_json::json!({ "actions": actions })))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1894,8 +1894,30 @@ chrome.runtime.onMessage.addListener((msg, _sender, sendResponse) => {
           return;
         case "wallet_sign_message":
           if (!session.unlocked || !session.mnemonic) throw new Error("Wallet is locked");
+          // An account or address asks for an address-bound nozy-sm-v2 signature, which
+          // contains the account's viewing key and needs revealViewingKey.
+          if (params.address != null || params.account != null) {
+            sendResponse(
+              ok({
+                signature: wasm.sign_message(
+                  session.mnemonic,
+                  params.message || "",
+                  params.account ?? undefined,
+                  params.address ?? undefined,
+                  params.revealViewingKey === true
+                ),
+                warning: wasm.signed_message_warning()
+              })
+            );
+            return;
+          }
           sendResponse(ok(wasm.sign_message(session.mnemonic, params.message || "")));
           return;
+        case "wallet_verify_message":
+          sendResponse(
+            ok(wasm.verify_message(params.address ?? undefined, params.message || "", params.signature || ""))
+          );
+          return;
         case "wallet_get_pending_approvals":
           sendResponse(ok(Array.from(pendingApprovals.values())));
           return;
@@ -141,10 +141,19 @@ export const extensionApi = {
       method: "wallet_generate_address",
       params: { account, index }
     }),
-  walletSignMessage: (message: string) =>
-    sendMessage<string>({
+  /** With `account` or `address` the result is `{ signature, warning }` (nozy-sm-v2). */
+  walletSignMessage: (
+    message: string,
+    signer?: { account?: number; address?: string; revealViewingKey?: boolean }
+  ) =>
+    sendMessage<string | { signature: string; warning: string }>({
       method: "wallet_sign_message",
-      params: { message }
+      params: { message, ...signer }
+    }),
+  walletVerifyMessage: (message: string, signature: string, address?: string) =>
+    sendMessage<boolean>({
+      method: "wallet_verify_message",
+      params: { message, signature, address }
     }),
   walletGetPendingApprovals: () =>
     sendMessage<PendingApproval[]>({ method: "wallet_get_pending_approvals" }),
@@ -275,15 +275,46 @@ pub fn get_nu5_activation_height() -> u32 {
         .unwrap_or(0)
 }
 
+/// Sign `message`. With `account` or `address`, signs as the owner of that address
+/// (`nozy-sm-v2`, defaulting to the account's first address); otherwise returns the legacy
+/// seed digest used for mobile pairing.
+///
+/// A `nozy-sm-v2` signature reveals the account's full viewing key, so it needs
+/// `reveal_viewing_key`; [`signed_message_warning`] is the text to show the user.
 #[wasm_bindgen]
-pub fn sign_message(mnemonic_str: &str, message: &str) -> Result<String, JsError> {
+pub fn sign_message(
+    mnemonic_str: &str,
+    message: &str,
+    account: Option<u32>,
+    address: Option<String>,
+    reveal_viewing_key: Option<bool>,
+) -> Result<String, JsError> {
     use nozy::hd_wallet::HDWallet;
     use sha2::{Sha256, Digest};
+    use zcash_protocol::consensus::NetworkType;
 
     let wallet = HDWallet::from_mnemonic(mnemonic_str)
         .map_err(|e| JsError::new(&format!("Wallet creation failed: {}", e)))?;
-
     let seed_bytes = wallet.get_mnemonic_object().to_seed("");
+
+    let address = address.filter(|a| !a.trim().is_empty());
+    if account.is_some() || address.is_some() {
+        let account = account.unwrap_or(0);
+        let address = match address {
+            Some(address) => address,
+            None => wallet.generate_orchard_address(account, 0, NetworkType::Main)
+                .map_err(|e| JsError::new(&format!("Address generation failed: {}", e)))?,
+        };
+        return nozy::signed_message::sign_for_address(
+            &seed_bytes,
+            account,
+            &address,
+            message,
+            reveal_viewing_key.unwrap_or(false),
+        )
+        .map_err(|e| JsError::new(&format!("Signing failed: {}", e)));
+    }
+
     let mut hasher = Sha256::new();
     hasher.update(&seed_bytes);
     hasher.update(message.as_bytes());
@@ -292,6 +323,23 @@ pub fn sign_message(mnemonic_str: &str, message: &str) -> Result<String, JsError
     Ok(hex::encode(signature))
 }
 
+/// Warning to show before asking for `reveal_viewing_key` in [`sign_message`].
+#[wasm_bindgen]
+pub fn signed_message_warning() -> String {
+    nozy::signed_message::VIEWING_KEY_WARNING.to_string()
+}
+
+/// Verify a `nozy-sm-v1` / `nozy-sm-v2` signature (`address` is required for v2).
+#[wasm_bindgen]
+pub fn verify_message(
+    address: Option<String>,
+    message: &str,
+    signature: &str,
+) -> Result<bool, JsError> {
+    nozy::signed_message::verify_any(signature.trim(), message, address.as_deref())
+        .map_err(|e| JsError::new(&format!("Verification failed: {}", e)))
+}
+
 #[wasm_bindgen]
 pub fn scan_orchard_actions(
     mnemonic_str: &str,
@@ -4,7 +4,6 @@ use nozy::{
     build_keystone_send_pczt, estimate_transaction_fee_for_send, extract_signed_tx_from_pczt_bytes,
     load_config, mark_wallet_notes_spent_from_spendables,
     orchard_spending_key_from_wallet, prepared_send_from_build, scan_notes_for_sending,
-    select_single_spend_note,
     sign_pczt_orchard_spends, transaction_history::{SentTransactionRecord, SentTransactionStorage},
     KeystonePreparedSend, KeystoneWalletConfig, PilotSendOptions, NOZY_WALLET_PRIORITY_FEE,
     PILOT_EXPIRY_DELTA_BLOCKS, ZebraClient, ZebraJsonRpcOrchardWitnessProvider,
@@ -22,6 +21,28 @@ fn network_from_config() -> NetworkType {
     }
 }
 
+/// Spendable notes of the active profile. FROST and watch-only profiles scan with their
+/// viewing key, so no seed is loaded.
+async fn cosign_spendable_notes(
+    password: Option<&str>,
+    zebra_url: &str,
+) -> Result<Vec<nozy::SpendableNote>, TauriError> {
+    if nozy::active_profile_kind() == nozy::WalletProfileKind::Seed {
+        let wallet = load_session_wallet(password).await?;
+        return scan_notes_for_sending(&wallet, zebra_url)
+            .await
+            .map_err(|e| TauriError::from(e.to_string()));
+    }
+    nozy::notes_vault::unlock_notes_vault(password.unwrap_or_default())
+        .map_err(|e| TauriError::from(e.to_string()))?;
+    let fvk = nozy::watch_only::active_profile_viewing_key()
+        .map_err(|e| TauriError::from(e.to_string()))?
+        .ok_or_else(|| TauriError::from("The active profile has no viewing key".to_string()))?;
+    nozy::scan_notes_for_viewing_key(&fvk, zebra_url)
+        .await
+        .map_err(|e| TauriError::from(e.to_string()))
+}
+
 #[derive(Debug, Deserialize)]
 pub struct PrepareCosignRequest {
     pub recipient: String,
@@ -93,10 +114,7 @@ pub async fn prepare_cosign_request(
     let zebra_url = request
         .zebra_url
         .unwrap_or_else(|| config.zebra_url.clone());
-    let wallet = load_session_wallet(request.password.as_deref()).await?;
-    let spendable_notes = scan_notes_for_sending(&wallet, &zebra_url)
-        .await
-        .map_err(|e| TauriError::from(e.to_string()))?;
+    let spendable_notes = cosign_spendable_notes(request.password.as_deref(), &zebra_url).await?;
 
     let zebra_client = ZebraClient::from_config(&config);
     let pilot = PilotSendOptions {
@@ -117,7 +135,6 @@ pub async fn prepare_cosign_request(
     let build = build_keystone_send_pczt(
         &zebra_client,
         &witness_provider,
-        &wallet,
         &keystone,
         &spendable_notes,
         &recipient,
@@ -130,7 +147,7 @@ pub async fn prepare_cosign_request(
     .await
     .map_err(|e| TauriError::from(e.to_string()))?;
 
-    let prepared = prepared_send_from_build(&recipient, amount_zatoshis, fee_zatoshis, &build);
+    let prepared = prepared_send_from_build(&recipient, amount_zatoshis, &build);
     let ur_frames =
         nozy::encode_pczt_ur_frames(&build.pczt_bytes, nozy::DEFAULT_UR_FRAGMENT_SIZE)
             .map_err(|e| TauriError::from(e.to_string()))?;
@@ -148,6 +165,14 @@ pub async fn sign_cosign_request(
     let pczt_bytes = hex::decode(request.pczt_hex.trim())
         .map_err(|e| TauriError::from(format!("Invalid PCZT hex: {e}")))?;
     let wallet = load_session_wallet(request.password.as_deref()).await?;
+    nozy::cold_signing::inspect_pczt_for_wallet(
+        &pczt_bytes,
+        &wallet,
+        network_from_config(),
+        &load_config().pczt_policy,
+    )
+    .and_then(|inspection| inspection.enforce_policy())
+    .map_err(|e| TauriError::from(e.to_string()))?;
     let spending_key = orchard_spending_key_from_wallet(&wallet)
         .map_err(|e| TauriError::from(e.to_string()))?;
     let signed = sign_pczt_orchard_spends(&pczt_bytes, &spending_key)
@@ -178,10 +203,7 @@ pub async fn complete_cosign_send(
         .await
         .map_err(|e| TauriError::from(e.to_string()))?;
 
-    let wallet = load_session_wallet(request.password.as_deref()).await?;
-    let spendable_notes = scan_notes_for_sending(&wallet, &zebra_url)
-        .await
-        .map_err(|e| TauriError::from(e.to_string()))?;
+    let spendable_notes = cosign_spendable_notes(request.password.as_deref(), &zebra_url).await?;
     let amount_zatoshis = nozy::input_validation::resolve_send_amount_zatoshis(
         request.amount_zatoshis,
         request.amount,
@@ -201,11 +223,19 @@ pub async fn complete_cosign_send(
     let chain_tip = zebra_client.get_best_block_height().await.unwrap_or(0);
     let expiry_height = chain_tip.saturating_add(PILOT_EXPIRY_DELTA_BLOCKS);
 
-    if let Ok(spent_note) = select_single_spend_note(&spendable_notes, amount_zatoshis, fee_zatoshis)
-    {
-        let _ = mark_wallet_notes_spent_from_spendables(std::slice::from_ref(spent_note), Some(&txid));
+    let spend_nullifiers = nozy::cold_signing::pczt_spend_nullifiers(&pczt_bytes)
+        .map_err(|e| TauriError::from(e.to_string()))?;
+    let spent_notes: Vec<_> = spendable_notes
+        .into_iter()
+        .filter(|n| spend_nullifiers.contains(&n.orchard_note.nullifier.to_bytes()))
+        .collect();
+    if !spent_notes.is_empty() {
+        let _ = mark_wallet_notes_spent_from_spendables(&spent_notes, Some(&txid));
         if let Ok(tx_storage) = SentTransactionStorage::new() {
-            let spent_note_ids = vec![hex::encode(spent_note.orchard_note.nullifier.to_bytes())];
+            let spent_note_ids = spent_notes
+                .iter()
+                .map(|n| hex::encode(n.orchard_note.nullifier.to_bytes()))
+                .collect();
             let memo_bytes = request
                 .memo
                 .as_ref()
@@ -232,3 +262,121 @@ pub async fn complete_cosign_send(
         message: format!("Co-signed transaction broadcast successfully! TXID: {txid}"),
     })
 }
+
+#[derive(Debug, Serialize)]
+pub struct FrostShareInfo {
+    pub identifier: u16,
+    pub min_signers: u16,
+    pub max_signers: u16,
+    pub network: String,
+    pub group_address: String,
+    pub group_ufvk: String,
+}
+
+/// Load the FROST share of the active profile; the notes vault password encrypts it.
+fn load_frost_share(
+    password: Option<&str>,
+) -> Result<nozy::frost_multisig::FrostShare, TauriError> {
+    nozy::notes_vault::unlock_notes_vault(password.unwrap_or_default())
+        .map_err(|e| TauriError::from(e.to_string()))?;
+    nozy::frost_multisig::load_share().map_err(|e| TauriError::from(e.to_string()))
+}
+
+fn frost_message<T: serde::de::DeserializeOwned>(message: &str) -> Result<T, TauriError> {
+    nozy::frost_multisig::read_frost_message(message).map_err(|e| TauriError::from(e.to_string()))
+}
+
+fn frost_json<T: Serialize>(message: &T) -> Result<String, TauriError> {
+    serde_json::to_string(message).map_err(|e| TauriError::from(e.to_string()))
+}
+
+#[command]
+pub async fn frost_share_info(password: Option<String>) -> Result<FrostShareInfo, TauriError> {
+    let share = load_frost_share(password.as_deref())?;
+    Ok(FrostShareInfo {
+        identifier: share.identifier,
+        min_signers: share.min_signers,
+        max_signers: share.max_signers,
+        network: share.network.clone(),
+        group_address: share
+            .group_address()
+            .map_err(|e| TauriError::from(e.to_string()))?,
+        group_ufvk: share
+            .group_ufvk()
+            .map_err(|e| TauriError::from(e.to_string()))?,
+    })
+}
+
+/// Round 1: commit to nonces for the group spends of a PCZT. Returns the commitments JSON.
+#[command]
+pub async fn frost_commit(
+    pczt_hex: String,
+    password: Option<String>,
+) -> Result<String, TauriError> {
+    let pczt_bytes = hex::decode(pczt_hex.trim())
+        .map_err(|e| TauriError::from(format!("Invalid PCZT hex: {e}")))?;
+    let share = load_frost_share(password.as_deref())?;
+    let commitments = nozy::frost_multisig::commit_to_pczt(&share, &pczt_bytes)
+        .map_err(|e| TauriError::from(e.to_string()))?;
+    frost_json(&commitments)
+}
+
+/// Coordinator: bundle the PCZT with `t` commitments into a signing request JSON.
+#[command]
+pub async fn frost_build_request(
+    pczt_hex: String,
+    commitments: Vec<String>,
+    password: Option<String>,
+) -> Result<String, TauriError> {
+    let pczt_bytes = hex::decode(pczt_hex.trim())
+        .map_err(|e| TauriError::from(format!("Invalid PCZT hex: {e}")))?;
+    let commitments = commitments
+        .iter()
+        .map(|c| frost_message(c))
+        .collect::<Result<Vec<_>, _>>()?;
+    let share = load_frost_share(password.as_deref())?;
+    let request = nozy::frost_multisig::build_signing_request(&share, &pczt_bytes, commitments)
+        .map_err(|e| TauriError::from(e.to_string()))?;
+    frost_json(&request)
+}
+
+/// Round 2: sign a request the user has reviewed. Returns the signature shares JSON.
+#[command]
+pub async fn frost_sign(request: String, password: Option<String>) -> Result<String, TauriError> {
+    let request: nozy::frost_multisig::FrostSigningRequest = frost_message(&request)?;
+    let share = load_frost_share(password.as_deref())?;
+    nozy::frost_multisig::request_pczt(&request)
+        .and_then(|pczt_bytes| {
+            nozy::cold_signing::inspect_pczt(
+                &pczt_bytes,
+                &share.group_fvk()?,
+                share.network_type(),
+                &load_config().pczt_policy,
+            )
+        })
+        .and_then(|inspection| inspection.enforce_policy())
+        .map_err(|e| TauriError::from(e.to_string()))?;
+    let shares = nozy::frost_multisig::sign_request(&share, &request)
+        .map_err(|e| TauriError::from(e.to_string()))?;
+    frost_json(&shares)
+}
+
+/// Coordinator: aggregate signature shares; the signed PCZT goes to `complete_cosign_send`.
+#[command]
+pub async fn frost_aggregate(
+    request: String,
+    shares: Vec<String>,
+    password: Option<String>,
+) -> Result<SignCosignResponse, TauriError> {
+    let request: nozy::frost_multisig::FrostSigningRequest = frost_message(&request)?;
+    let shares = shares
+        .iter()
+        .map(|s| frost_message(s))
+        .collect::<Result<Vec<_>, _>>()?;
+    let share = load_frost_share(password.as_deref())?;
+    let signed = nozy::frost_multisig::aggregate_signatures(&share, &request, &shares)
+        .map_err(|e| TauriError::from(e.to_string()))?;
+    Ok(SignCosignResponse {
+        pczt_hex: hex::encode(signed),
+    })
+}
@@ -28,7 +28,7 @@ fn migration_schedule_tip(
     }
 }
 
-async fn chain_context() -> Result<(nozy::WalletConfig, u32, bool, u32), TauriError> {
+pub(super) async fn chain_context() -> Result<(nozy::WalletConfig, u32, bool, u32), TauriError> {
     let config = load_config();
     let is_testnet = config.network.eq_ignore_ascii_case("testnet");
     let zebra = ZebraClient::from_config(&config);
@@ -1,16 +1,13 @@
 use crate::error::TauriError;
 use crate::session::load_session_wallet;
 use nozy::{
-    build_keystone_send_pczt, clear_pending_send, decode_pczt_ur_frames,
-    encode_pczt_ur_frames, estimate_transaction_fee_for_send, export_ufvk_from_wallet,
-    extract_signed_tx_from_pczt_bytes, load_config, load_pending_send,
-    mark_wallet_notes_spent_from_spendables, prepared_send_from_build,
-    save_config, save_pending_send, scan_notes_for_sending, select_single_spend_note,
-    validate_ufvk, ZebraClient, ZebraJsonRpcOrchardWitnessProvider, DEFAULT_UR_FRAGMENT_SIZE,
-    KeystonePreparedSend, PilotSendOptions, NOZY_WALLET_PRIORITY_FEE, PILOT_EXPIRY_DELTA_BLOCKS,
-    UR_TYPE_ZCASH_PCZT,
+    build_keystone_send_pczt, decode_pczt_ur_frames, encode_pczt_ur_frames,
+    enqueue_signing_request, estimate_transaction_fee_for_send, export_ufvk_from_wallet,
+    extract_signed_tx_from_pczt_bytes, load_config, load_signing_queue, prepared_send_from_build,
+    save_config, scan_notes_for_sending, validate_ufvk, SigningRequest, UrScanProgress, ZebraClient,
+    ZebraJsonRpcOrchardWitnessProvider, DEFAULT_UR_FRAGMENT_SIZE, PilotSendOptions,
+    PILOT_EXPIRY_DELTA_BLOCKS, UR_TYPE_ZCASH_PCZT,
 };
-use nozy::transaction_history::{SentTransactionRecord, SentTransactionStorage};
 use serde::{Deserialize, Serialize};
 use tauri::command;
 use zcash_protocol::consensus::NetworkType;
@@ -41,6 +38,8 @@ pub struct KeystoneStatusResponse {
     pub device_label: Option<String>,
     pub has_ufvk: bool,
     pub pending_send: bool,
+    /// Live entries in the hardware-signing queue.
+    pub pending_requests: usize,
     /// Wallet network from config (`mainnet` or `testnet`).
     pub network: String,
 }
@@ -48,9 +47,10 @@ pub struct KeystoneStatusResponse {
 #[command]
 pub async fn get_keystone_status() -> Result<KeystoneStatusResponse, TauriError> {
     let config = load_config();
-    let pending_send = load_pending_send()
+    let pending_requests = load_signing_queue()
         .map_err(|e| TauriError::from(e.to_string()))?
-        .is_some();
+        .requests
+        .len();
 
     Ok(KeystoneStatusResponse {
         enabled: config.keystone.enabled,
@@ -60,7 +60,8 @@ pub async fn get_keystone_status() -> Result<KeystoneStatusResponse, TauriError>
             .ufvk
             .as_ref()
             .is_some_and(|s| !s.is_empty()),
-        pending_send,
+        pending_send: pending_requests > 0,
+        pending_requests,
         network: config.network.clone(),
     })
 }
@@ -154,6 +155,7 @@ fn default_true() -> bool {
 #[derive(Debug, Serialize)]
 pub struct KeystonePrepareResponse {
     pub success: bool,
+    pub request_id: Option<String>,
     pub summary: Option<String>,
     pub action_count: Option<u32>,
     pub pczt_hex: Option<String>,
@@ -169,6 +171,7 @@ pub async fn keystone_prepare_send(
     if let Err(e) = ensure_keystone_mainnet() {
         return Ok(KeystonePrepareResponse {
             success: false,
+            request_id: None,
             message: Some(e.message),
             summary: None,
             action_count: None,
@@ -180,6 +183,7 @@ pub async fn keystone_prepare_send(
     if !request.recipient.starts_with("u1") || request.recipient.len() < 78 {
         return Ok(KeystonePrepareResponse {
             success: false,
+            request_id: None,
             message: Some(
                 "Invalid recipient. Use a mainnet Orchard unified address (u1…).".to_string(),
             ),
@@ -198,6 +202,7 @@ pub async fn keystone_prepare_send(
         Err(e) => {
             return Ok(KeystonePrepareResponse {
                 success: false,
+                request_id: None,
                 message: Some(e.to_string()),
                 summary: None,
                 action_count: None,
@@ -235,7 +240,6 @@ pub async fn keystone_prepare_send(
     let build = build_keystone_send_pczt(
         &zebra_client,
         &witness_provider,
-        &wallet,
         &config.keystone,
         &spendable_notes,
         &request.recipient,
@@ -248,19 +252,20 @@ pub async fn keystone_prepare_send(
     .await
     .map_err(|e| TauriError::from(e.to_string()))?;
 
-    let prepared = prepared_send_from_build(
+    let queued = enqueue_signing_request(prepared_send_from_build(
         &request.recipient,
         amount_zatoshis,
-        fee_zatoshis,
         &build,
-    );
-    save_pending_send(&prepared).map_err(|e| TauriError::from(e.to_string()))?;
+    ))
+    .map_err(|e| TauriError::from(e.to_string()))?;
+    let prepared = &queued.send;
     let ur_frames =
         encode_pczt_ur_frames(&build.pczt_bytes, DEFAULT_UR_FRAGMENT_SIZE)
             .map_err(|e| TauriError::from(e.to_string()))?;
 
     Ok(KeystonePrepareResponse {
         success: true,
+        request_id: Some(queued.id.clone()),
         summary: Some(prepared.summary.clone()),
         action_count: Some(prepared.action_count),
         pczt_hex: Some(prepared.pczt_hex.clone()),
@@ -274,6 +279,8 @@ pub async fn keystone_prepare_send(
 pub struct KeystoneCompleteSendRequest {
     pub pczt_hex: Option<String>,
     pub ur_frames: Option<Vec<String>>,
+    /// Use the frames saved by `keystone_scan_frames`.
+    pub request_id: Option<String>,
     #[serde(default = "default_true")]
     pub broadcast: bool,
     pub zebra_url: Option<String>,
@@ -284,6 +291,24 @@ pub struct KeystoneCompleteSendResponse {
     pub success: bool,
     pub txid: Option<String>,
     pub broadcast: Option<bool>,
+    /// Set when the broadcast succeeded but recording it locally failed.
+    pub warning: Option<String>,
+}
+
+fn signed_pczt_bytes(
+    pczt_hex: Option<String>,
+    ur_frames: Option<Vec<String>>,
+) -> Result<Vec<u8>, TauriError> {
+    if let Some(frames) = ur_frames.filter(|f| !f.is_empty()) {
+        decode_pczt_ur_frames(&frames).map_err(|e| TauriError::from(e.to_string()))
+    } else if let Some(hex_str) = pczt_hex.filter(|s| !s.trim().is_empty()) {
+        hex::decode(hex_str.trim())
+            .map_err(|e| TauriError::from(format!("Invalid PCZT hex: {e}")))
+    } else {
+        Err(TauriError::from(
+            "Provide pczt_hex or ur_frames from Keystone".to_string(),
+        ))
+    }
 }
 
 #[command]
@@ -291,26 +316,22 @@ pub async fn keystone_complete_send(
     request: KeystoneCompleteSendRequest,
 ) -> Result<KeystoneCompleteSendResponse, TauriError> {
     ensure_keystone_mainnet()?;
-    let pczt_bytes = if let Some(frames) = request.ur_frames.filter(|f| !f.is_empty()) {
-        decode_pczt_ur_frames(&frames).map_err(|e| TauriError::from(e.to_string()))?
-    } else if let Some(hex_str) = request.pczt_hex.filter(|s| !s.trim().is_empty()) {
-        hex::decode(hex_str.trim())
-            .map_err(|e| TauriError::from(format!("Invalid PCZT hex: {e}")))?
-    } else {
-        return Err(TauriError::from(
-            "Provide pczt_hex or ur_frames from Keystone".to_string(),
-        ));
+    let pczt_bytes = match request.request_id {
+        Some(id) if request.pczt_hex.is_none() && request.ur_frames.is_none() => {
+            nozy::scanned_signed_pczt(&id).map_err(TauriError::from)?
+        }
+        _ => signed_pczt_bytes(request.pczt_hex, request.ur_frames)?,
     };
 
     let extracted = extract_signed_tx_from_pczt_bytes(&pczt_bytes)
         .map_err(|e| TauriError::from(e.to_string()))?;
 
     if !request.broadcast {
-        let _ = clear_pending_send();
         return Ok(KeystoneCompleteSendResponse {
             success: true,
             txid: Some(extracted.txid.clone()),
             broadcast: Some(false),
+            warning: None,
         });
     }
 
@@ -318,57 +339,132 @@ pub async fn keystone_complete_send(
     let zebra_url = request
         .zebra_url
         .unwrap_or_else(|| config.zebra_url.clone());
-    let zebra_client = ZebraClient::from_config(&config);
+    let zebra_client = ZebraClient::from_config_with_url(&config, Some(&zebra_url));
     let raw_hex = hex::encode(&extracted.raw_transaction);
     let txid = zebra_client
         .broadcast_transaction(&raw_hex)
         .await
         .map_err(|e| TauriError::from(e.to_string()))?;
 
-    let pending: Option<KeystonePreparedSend> = load_pending_send()
-        .map_err(|e| TauriError::from(e.to_string()))?;
-    if let Some(prepared) = pending {
-        if let Ok(wallet) = load_session_wallet(None).await {
-            if let Ok(spendable_notes) = scan_notes_for_sending(&wallet, &zebra_url).await {
-                if let Ok(spent_note) = select_single_spend_note(
-                    &spendable_notes,
-                    prepared.amount_zatoshis,
-                    prepared.fee_zatoshis,
-                ) {
-                    let _ = mark_wallet_notes_spent_from_spendables(
-                        std::slice::from_ref(spent_note),
-                        Some(&txid),
-                    );
-                    if let Ok(tx_storage) = SentTransactionStorage::new() {
-                        let spent_note_ids =
-                            vec![hex::encode(spent_note.orchard_note.nullifier.to_bytes())];
-                        let mut tx_record = SentTransactionRecord::new_pilot(
-                            txid.clone(),
-                            prepared.recipient.clone(),
-                            prepared.amount_zatoshis,
-                            prepared.fee_zatoshis,
-                            None,
-                            spent_note_ids,
-                            NOZY_WALLET_PRIORITY_FEE,
-                            zebra_client
-                                .get_best_block_height()
-                                .await
-                                .unwrap_or(0)
-                                .saturating_add(PILOT_EXPIRY_DELTA_BLOCKS),
-                        );
-                        tx_record.mark_broadcast();
-                        let _ = tx_storage.save_transaction(tx_record);
-                    }
-                }
-            }
-        }
-    }
-
-    let _ = clear_pending_send();
+    // The transaction is on the network now, so bookkeeping problems are only reported.
+    let warning = nozy::cold_signing::record_signed_pczt_broadcast(&pczt_bytes, &txid)
+        .err()
+        .map(|e| format!("Broadcast succeeded but could not record it locally: {e}"));
 
     Ok(KeystoneCompleteSendResponse {
         success: true,
         txid: Some(txid),
         broadcast: Some(true),
+        warning,
+    })
+}
+
+#[derive(Debug, Deserialize)]
+pub struct KeystonePrepareMigrationRequest {
+    /// Build the ZIP 318 note split instead of the next scheduled crossing.
+    #[serde(default)]
+    pub split: bool,
+    pub password: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct KeystonePrepareMigrationResponse {
+    pub migration: nozy::ironwood::KeystoneMigrationPczt,
+    pub ur_frames: Vec<String>,
+    pub ur_type: String,
+}
+
+#[command]
+pub async fn keystone_prepare_migration(
+    request: KeystonePrepareMigrationRequest,
+) -> Result<KeystonePrepareMigrationResponse, TauriError> {
+    ensure_keystone_mainnet()?;
+    let (config, _chain_tip, ironwood_active, _tip) = super::ironwood::chain_context().await?;
+    let wallet = load_session_wallet(request.password.as_deref()).await?;
+    let spendable_notes = scan_notes_for_sending(&wallet, &config.zebra_url)
+        .await
+        .map_err(TauriError::from)?;
+    let migration = if request.split {
+        nozy::ironwood::prepare_keystone_note_split_pczt(
+            &config.zebra_url,
+            ironwood_active,
+            &wallet,
+            &config.keystone,
+            &spendable_notes,
+        )
+        .await
+    } else {
+        nozy::ironwood::prepare_keystone_migration_pczt(
+            &config.zebra_url,
+            ironwood_active,
+            &wallet,
+            &config.keystone,
+            &spendable_notes,
+        )
+        .await
+    }
+    .map_err(TauriError::from)?;
+    let pczt_bytes = hex::decode(&migration.pczt_hex)
+        .map_err(|e| TauriError::from(format!("Invalid PCZT hex: {e}")))?;
+    let ur_frames = encode_pczt_ur_frames(&pczt_bytes, DEFAULT_UR_FRAGMENT_SIZE)
+        .map_err(TauriError::from)?;
+    Ok(KeystonePrepareMigrationResponse {
+        migration,
+        ur_frames,
+        ur_type: UR_TYPE_ZCASH_PCZT.to_string(),
+    })
+}
+
+#[derive(Debug, Deserialize)]
+pub struct KeystoneCompleteMigrationRequest {
+    pub pczt_hex: Option<String>,
+    pub ur_frames: Option<Vec<String>>,
+}
+
+#[command]
+pub async fn keystone_complete_migration(
+    request: KeystoneCompleteMigrationRequest,
+) -> Result<nozy::ironwood::KeystoneMigrationCompletion, TauriError> {
+    ensure_keystone_mainnet()?;
+    let pczt_bytes = signed_pczt_bytes(request.pczt_hex, request.ur_frames)?;
+    let config = load_config();
+    nozy::ironwood::complete_keystone_migration_pczt(&config.zebra_url, &pczt_bytes)
+        .await
+        .map_err(TauriError::from)
+}
+
+#[derive(Debug, Serialize)]
+pub struct KeystoneQueueResponse {
+    pub requests: Vec<SigningRequest>,
+    /// Requests dropped by this call because their expiry height passed.
+    pub expired: Vec<String>,
+}
+
+#[command]
+pub async fn keystone_list_queue() -> Result<KeystoneQueueResponse, TauriError> {
+    let config = load_config();
+    let expired = match ZebraClient::from_config(&config).get_block_count().await {
+        Ok(chain_tip) => {
+            nozy::prune_expired_signing_requests(chain_tip).map_err(TauriError::from)?
+        }
+        Err(_) => Vec::new(),
+    };
+    let queue = load_signing_queue().map_err(TauriError::from)?;
+    Ok(KeystoneQueueResponse {
+        requests: queue.requests,
+        expired: expired.into_iter().map(|r| r.id).collect(),
     })
 }
+
+#[command]
+pub async fn keystone_cancel_request(id: String) -> Result<SigningRequest, TauriError> {
+    nozy::cancel_signing_request(&id).map_err(TauriError::from)
+}
+
+#[command]
+pub async fn keystone_scan_frames(
+    id: String,
+    frames: Vec<String>,
+) -> Result<UrScanProgress, TauriError> {
+    nozy::record_scanned_frames(&id, &frames).map_err(TauriError::from)
+}
@@ -0,0 +1,27 @@
+use crate::error::TauriError;
+use nozy::{Annotation, AnnotationUpdate, LabelMatch, LabelStore, LabelTarget};
+use tauri::command;
+
+#[command]
+pub async fn label_update(
+    target: String,
+    id: String,
+    update: AnnotationUpdate,
+) -> Result<Annotation, TauriError> {
+    let target: LabelTarget = target.parse()?;
+    let mut store = LabelStore::new()?;
+    Ok(store.update(target, &id, update)?)
+}
+
+#[command]
+pub async fn label_remove(target: String, id: String) -> Result<bool, TauriError> {
+    let target: LabelTarget = target.parse()?;
+    let mut store = LabelStore::new()?;
+    Ok(store.remove(target, &id)?)
+}
+
+#[command]
+pub async fn label_search(query: Option<String>) -> Result<Vec<LabelMatch>, TauriError> {
+    let store = LabelStore::new()?;
+    Ok(store.search(query.as_deref().unwrap_or("")))
+}
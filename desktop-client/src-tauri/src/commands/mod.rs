@@ -9,13 +9,16 @@ pub mod notes;
 pub mod profile;
 pub mod proving;
 pub mod sapling;
+pub mod schedule;
 pub mod signing;
 pub mod cosign;
 pub mod keystone;
+pub mod labels;
 pub mod status;
 pub mod sync;
 pub mod transaction;
 pub mod wallet;
+pub mod zns;
 
 pub use address::*;
 pub use address_book::*;
@@ -28,10 +31,13 @@ pub use notes::*;
 pub use profile::*;
 pub use proving::*;
 pub use sapling::*;
+pub use schedule::*;
 pub use signing::*;
 pub use cosign::*;
 pub use keystone::*;
+pub use labels::*;
 pub use status::*;
 pub use sync::*;
 pub use transaction::*;
 pub use wallet::*;
+pub use zns::*;
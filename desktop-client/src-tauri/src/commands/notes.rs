@@ -41,3 +41,21 @@ pub async fn get_notes() -> Result<NotesResponse, TauriError> {
         count: notes.len(),
     })
 }
+
+/// Coin control view: ids (nullifier hex), frozen flag and labels.
+#[command]
+pub async fn notes_list(
+    include_spent: Option<bool>,
+) -> Result<Vec<nozy::NoteSummary>, TauriError> {
+    Ok(nozy::list_wallet_note_summaries(include_spent.unwrap_or(false))?)
+}
+
+#[command]
+pub async fn notes_freeze(ids: Vec<String>) -> Result<usize, TauriError> {
+    Ok(nozy::set_notes_frozen(&ids, true)?)
+}
+
+#[command]
+pub async fn notes_unfreeze(ids: Vec<String>) -> Result<usize, TauriError> {
+    Ok(nozy::set_notes_frozen(&ids, false)?)
+}
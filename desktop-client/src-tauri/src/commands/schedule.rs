@@ -0,0 +1,78 @@
+use crate::error::TauriError;
+use crate::session::load_session_wallet;
+use nozy::{
+    InsufficientFundsPolicy, NewScheduledPayment, PaymentFrequency, PaymentRun, PaymentSchedule,
+    ScheduledPayment,
+};
+use serde::Deserialize;
+use tauri::command;
+
+#[derive(Debug, Deserialize)]
+pub struct ScheduleAddRequest {
+    pub label: Option<String>,
+    pub recipient: String,
+    pub amount_zatoshis: u64,
+    pub memo: Option<String>,
+    /// `weekly` or `monthly` (default).
+    pub frequency: Option<String>,
+    /// First due date, `YYYY-MM-DD` or RFC 3339; defaults to now.
+    pub start: Option<String>,
+    pub runs: Option<u32>,
+    /// `skip` (default) or `retry`.
+    pub on_insufficient_funds: Option<String>,
+}
+
+#[command]
+pub async fn schedule_list() -> Result<PaymentSchedule, TauriError> {
+    Ok(nozy::load_payment_schedule()?)
+}
+
+#[command]
+pub async fn schedule_add(request: ScheduleAddRequest) -> Result<ScheduledPayment, TauriError> {
+    let new = NewScheduledPayment {
+        label: request.label,
+        recipient: request.recipient,
+        amount_zatoshis: request.amount_zatoshis,
+        memo: request.memo,
+        frequency: request
+            .frequency
+            .as_deref()
+            .map(str::parse::<PaymentFrequency>)
+            .transpose()?,
+        start_at: request
+            .start
+            .as_deref()
+            .map(nozy::parse_schedule_start)
+            .transpose()?,
+        runs: request.runs,
+        on_insufficient_funds: request
+            .on_insufficient_funds
+            .as_deref()
+            .map(str::parse::<InsufficientFundsPolicy>)
+            .transpose()?
+            .unwrap_or_default(),
+    };
+    Ok(nozy::add_scheduled_payment(new)?)
+}
+
+#[command]
+pub async fn schedule_remove(id: String) -> Result<(), TauriError> {
+    nozy::remove_scheduled_payment(&id)?;
+    Ok(())
+}
+
+#[command]
+pub async fn schedule_set_enabled(id: String, enabled: bool) -> Result<(), TauriError> {
+    Ok(nozy::set_scheduled_payment_enabled(&id, enabled)?)
+}
+
+/// Send every due payment now. Insufficient-funds runs come back as alerts, not errors.
+#[command]
+pub async fn schedule_run_due(
+    password: Option<String>,
+    zebra_url: Option<String>,
+) -> Result<Vec<PaymentRun>, TauriError> {
+    let wallet = load_session_wallet(password.as_deref()).await?;
+    let zebra_url = zebra_url.unwrap_or_else(|| nozy::load_config().zebra_url);
+    Ok(nozy::run_due_payments(&wallet, &zebra_url).await?)
+}
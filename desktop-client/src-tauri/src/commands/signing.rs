@@ -9,11 +9,24 @@ use tauri::command;
 pub struct SignMessageRequest {
     pub message: String,
     pub password: String,
+    /// With `address`, sign as that address (`nozy-sm-v2`) instead of the legacy digest.
+    #[serde(default)]
+    pub account: Option<u32>,
+    #[serde(default)]
+    pub address: Option<String>,
+    /// Required for `nozy-sm-v2`: the signature contains the account's full viewing key.
+    #[serde(default)]
+    pub reveal_viewing_key: bool,
 }
 
 #[derive(Debug, Serialize)]
 pub struct SignMessageResponse {
     pub signature: String,
+    pub scheme: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub warning: Option<String>,
 }
 
 #[command]
@@ -27,9 +40,41 @@ pub async fn sign_message(request: SignMessageRequest) -> Result<SignMessageResp
 
     let wallet = load_session_wallet(Some(&request.password)).await?;
     verify_wallet_password(&wallet, &request.password)?;
+    let seed_bytes = wallet.get_mnemonic_object().to_seed("");
+
+    if request.account.is_some() || request.address.is_some() {
+        if !request.reveal_viewing_key {
+            return Err(TauriError {
+                message: nozy::signed_message::VIEWING_KEY_WARNING.to_string(),
+                code: Some("VIEWING_KEY_OPT_IN_REQUIRED".to_string()),
+            });
+        }
+        let account = request
+            .account
+            .unwrap_or_else(|| nozy::load_config().active_orchard_account());
+        let address = match request.address {
+            Some(address) => address,
+            None => wallet
+                .generate_orchard_address(account, 0, crate::network_from_config())
+                .map_err(|e| TauriError::from(e.to_string()))?,
+        };
+        let signature = nozy::signed_message::sign_for_address(
+            &seed_bytes,
+            account,
+            &address,
+            &request.message,
+            request.reveal_viewing_key,
+        )
+        .map_err(|e| TauriError::from(e.to_string()))?;
+        return Ok(SignMessageResponse {
+            signature,
+            scheme: nozy::signed_message::SCHEME_ID_V2.to_string(),
+            address: Some(address),
+            warning: Some(nozy::signed_message::VIEWING_KEY_WARNING.to_string()),
+        });
+    }
 
     // Match browser-extension wasm-core `sign_message` (seed + message SHA-256).
-    let seed_bytes = wallet.get_mnemonic_object().to_seed("");
     let mut hasher = Sha256::new();
     hasher.update(&seed_bytes);
     hasher.update(request.message.as_bytes());
@@ -37,5 +82,38 @@ pub async fn sign_message(request: SignMessageRequest) -> Result<SignMessageResp
 
     Ok(SignMessageResponse {
         signature: hex::encode(signature),
+        scheme: "seed-sha256".to_string(),
+        address: None,
+        warning: None,
+    })
+}
+
+#[derive(Debug, Deserialize)]
+pub struct VerifyMessageRequest {
+    pub message: String,
+    pub signature: String,
+    /// Claimed signer; required for `nozy-sm-v2` and ZIP-304.
+    #[serde(default)]
+    pub address: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct VerifyMessageResponse {
+    pub valid: bool,
+    pub address_bound: bool,
+}
+
+#[command]
+pub async fn verify_message(
+    request: VerifyMessageRequest,
+) -> Result<VerifyMessageResponse, TauriError> {
+    let signature = request.signature.trim();
+    let valid =
+        nozy::signed_message::verify_any(signature, &request.message, request.address.as_deref())
+            .map_err(|e| TauriError::from(e.to_string()))?;
+    Ok(VerifyMessageResponse {
+        valid,
+        address_bound: signature.starts_with(nozy::signed_message::SCHEME_ID_V2)
+            || signature.starts_with(nozy::zip304::SIGNATURE_PREFIX),
     })
 }
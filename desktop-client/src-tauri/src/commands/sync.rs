@@ -60,6 +60,30 @@ pub async fn get_balance() -> Result<BalanceResponse, TauriError> {
     })
 }
 
+#[derive(Debug, Serialize)]
+pub struct FiatBalanceResponse {
+    pub currency: String,
+    pub available_zec: f64,
+    /// `None` when no price is available from the configured source.
+    pub fiat: Option<nozy::FiatValue>,
+}
+
+#[command]
+pub async fn get_fiat_balance(currency: Option<String>) -> Result<FiatBalanceResponse, TauriError> {
+    let config = load_config();
+    let currency = nozy::price_source::normalize_currency_code(
+        currency.as_deref().unwrap_or(&config.fiat.display_currency),
+    )?;
+    let snapshot = wallet_balance_snapshot().map_err(TauriError::from)?;
+    let fiat =
+        nozy::fiat_value_at_spot(&config, Some(&currency), snapshot.available_zatoshis).await?;
+    Ok(FiatBalanceResponse {
+        currency,
+        available_zec: zats_to_zec(snapshot.available_zatoshis),
+        fiat,
+    })
+}
+
 #[command]
 pub async fn sync_wallet(request: SyncRequest) -> Result<SyncResponse, TauriError> {
     let wallet = load_wallet(request.password.as_deref()).await?;
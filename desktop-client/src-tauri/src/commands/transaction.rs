@@ -117,6 +117,9 @@ pub struct SendTransactionRequest {
     pub password: Option<String>,
     #[serde(default)]
     pub priority: bool,
+    /// Coin control: only spend from these notes (nullifier hex). Empty = automatic.
+    #[serde(default)]
+    pub note_ids: Vec<String>,
 }
 
 #[command]
@@ -190,6 +193,7 @@ pub async fn send_transaction(
     let spendable_notes = scan_notes_for_sending(&wallet, &zebra_url)
         .await
         .map_err(|e| TauriError::from(e.to_string()))?;
+    let spendable_notes = nozy::restrict_to_note_ids(spendable_notes, &request.note_ids)?;
 
     let zebra_client = ZebraClient::new(zebra_url.clone());
 
@@ -259,6 +263,8 @@ pub async fn send_transaction(
             let spendable_notes_retry = scan_notes_for_sending(&wallet_retry, &zebra_url)
                 .await
                 .map_err(|e| TauriError::from(e.to_string()))?;
+            let spendable_notes_retry =
+                nozy::restrict_to_note_ids(spendable_notes_retry, &request.note_ids)?;
             emit_send_progress(
                 &app,
                 "Building proof",
@@ -361,6 +367,146 @@ pub async fn send_transaction(
     }
 }
 
+#[derive(Debug, Deserialize)]
+pub struct ProposeSendRequest {
+    pub recipient: String,
+    pub amount: Option<f64>,
+    pub amount_zatoshis: Option<u64>,
+    pub memo: Option<String>,
+    pub zebra_url: Option<String>,
+    pub password: Option<String>,
+    #[serde(default)]
+    pub note_ids: Vec<String>,
+}
+
+/// Phase one of a send: select notes, change, fee and expiry without proving.
+#[command]
+pub async fn propose_send(request: ProposeSendRequest) -> Result<nozy::SendProposal, TauriError> {
+    let config = load_config();
+    let recipient = nozy::input_validation::normalize_unified_address(&request.recipient);
+    let amount_zatoshis = nozy::input_validation::resolve_send_amount_zatoshis(
+        request.amount_zatoshis,
+        request.amount,
+    )
+    .map_err(|e| TauriError::from(e.to_string()))?;
+    let zebra_url = request
+        .zebra_url
+        .unwrap_or_else(|| config.zebra_url.clone());
+
+    let wallet = load_session_wallet(request.password.as_deref())
+        .await
+        .map_err(|e| TauriError {
+            message: e.message,
+            code: e.code,
+        })?;
+    let spendable_notes = scan_notes_for_sending(&wallet, &zebra_url)
+        .await
+        .map_err(|e| TauriError::from(e.to_string()))?;
+    let spendable_notes = nozy::restrict_to_note_ids(spendable_notes, &request.note_ids)?;
+
+    let zebra_client = ZebraClient::new(zebra_url);
+    let pilot = nozy::PilotSendOptions::for_send();
+    let memo_bytes = request
+        .memo
+        .as_ref()
+        .map(|m| m.trim().as_bytes().to_vec())
+        .filter(|b| !b.is_empty());
+    let fee_zatoshis =
+        estimate_transaction_fee_for_send(&zebra_client, memo_bytes.as_deref(), pilot.priority)
+            .await;
+
+    ZcashTransactionBuilder::new()
+        .propose_send_transaction(
+            &zebra_client,
+            &spendable_notes,
+            &recipient,
+            amount_zatoshis,
+            fee_zatoshis,
+            memo_bytes.as_deref(),
+            pilot,
+        )
+        .await
+        .map_err(TauriError::from)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExecuteSendProposalRequest {
+    pub proposal: nozy::SendProposal,
+    pub zebra_url: Option<String>,
+    pub password: Option<String>,
+}
+
+/// Phase two of a send: prove, sign and broadcast the proposal the user confirmed.
+#[command]
+pub async fn execute_send_proposal(
+    app: AppHandle,
+    request: ExecuteSendProposalRequest,
+) -> Result<SendTransactionResponse, TauriError> {
+    let config = load_config();
+    let zebra_url = request
+        .zebra_url
+        .unwrap_or_else(|| config.zebra_url.clone());
+
+    emit_send_progress(&app, "Unlocking wallet", 10, "Loading wallet keys…");
+    let wallet = load_session_wallet(request.password.as_deref())
+        .await
+        .map_err(|e| TauriError {
+            message: e.message,
+            code: e.code,
+        })?;
+
+    emit_send_progress(
+        &app,
+        "Selecting notes",
+        25,
+        "Checking proposed notes are still spendable…",
+    );
+    let spendable_notes = scan_notes_for_sending(&wallet, &zebra_url)
+        .await
+        .map_err(|e| TauriError::from(e.to_string()))?;
+
+    let zebra_client = ZebraClient::new(zebra_url.clone());
+    let mut tx_builder = ZcashTransactionBuilder::new();
+    tx_builder.set_zebra_url(&zebra_url);
+    tx_builder.enable_mainnet_broadcast();
+
+    emit_send_progress(
+        &app,
+        "Building proof",
+        58,
+        "Generating zero-knowledge proof — this can take several minutes…",
+    );
+    match tx_builder
+        .execute_proposal(&zebra_client, &spendable_notes, &request.proposal)
+        .await
+    {
+        Ok(transaction) => {
+            emit_send_progress(
+                &app,
+                "Saving transaction",
+                94,
+                "Recording the sent transaction locally…",
+            );
+            nozy::send_proposal::record_executed_proposal(&request.proposal, &transaction)
+                .map_err(TauriError::from)?;
+            emit_send_progress(&app, "Complete", 100, "Transaction broadcast successfully.");
+            Ok(SendTransactionResponse {
+                success: true,
+                txid: Some(transaction.txid.clone()),
+                message: format!(
+                    "Transaction broadcast successfully! TXID: {}",
+                    transaction.txid
+                ),
+            })
+        }
+        Err(e) => Ok(SendTransactionResponse {
+            success: false,
+            txid: None,
+            message: format!("Failed to send transaction: {}", e),
+        }),
+    }
+}
+
 #[command]
 pub async fn estimate_fee(
     zebra_url: Option<String>,
@@ -378,7 +524,10 @@ pub async fn estimate_fee(
 }
 
 #[command]
-pub async fn get_transaction_history() -> Result<Vec<serde_json::Value>, TauriError> {
+pub async fn get_transaction_history(
+    fiat_currency: Option<String>,
+    query: Option<String>,
+) -> Result<Vec<serde_json::Value>, TauriError> {
     use nozy::transaction_history::{
         collect_wallet_transaction_views, enrich_block_times_for_views,
         transaction_view_to_history_json,
@@ -391,6 +540,26 @@ pub async fn get_transaction_history() -> Result<Vec<serde_json::Value>, TauriEr
 
     let zebra_client = ZebraClient::new(config.zebra_url);
     enrich_block_times_for_views(&mut views, &zebra_client).await;
+    if let Some(currency) = fiat_currency.as_deref() {
+        // Fiat is display-only; history still loads when the price source fails.
+        if let Err(e) = nozy::apply_configured_fiat_values(&mut views, currency).await {
+            eprintln!("Warning: fiat valuation skipped: {e}");
+        }
+    }
+    match nozy::LabelStore::new() {
+        Ok(store) => {
+            if let Some(q) = query.as_deref() {
+                views.retain(|v| store.view_matches(v, q));
+            }
+            store.annotate_views(&mut views);
+        }
+        Err(e) => {
+            eprintln!("Warning: labels unavailable: {e}");
+            if query.is_some() {
+                views.clear();
+            }
+        }
+    }
 
     Ok(views
         .iter()
@@ -398,6 +567,45 @@ pub async fn get_transaction_history() -> Result<Vec<serde_json::Value>, TauriEr
         .collect())
 }
 
+#[command]
+pub async fn get_cost_basis_report(
+    year: i32,
+    method: Option<String>,
+    currency: Option<String>,
+) -> Result<nozy::CostBasisReport, TauriError> {
+    let method: nozy::CostBasisMethod = method.as_deref().unwrap_or("fifo").parse()?;
+    let config = load_config();
+    let zebra_client = ZebraClient::new(config.zebra_url);
+    Ok(
+        nozy::wallet_cost_basis_report(&zebra_client, year, method, currency.as_deref())
+            .await?,
+    )
+}
+
+#[command]
+pub async fn set_cost_basis_override(
+    note_id: String,
+    cost_basis: f64,
+    currency: Option<String>,
+    acquired_at: Option<String>,
+    note: Option<String>,
+) -> Result<(), TauriError> {
+    let acquired_at = acquired_at
+        .as_deref()
+        .map(nozy::parse_acquired_date)
+        .transpose()?;
+    let currency = currency.unwrap_or_else(|| load_config().fiat.display_currency);
+    let mut overrides = nozy::CostBasisOverrides::new()?;
+    overrides.set(nozy::CostBasisOverride {
+        note_id,
+        currency,
+        cost_basis,
+        acquired_at,
+        note,
+    })?;
+    Ok(())
+}
+
 #[command]
 pub async fn get_transaction(txid: String) -> Result<serde_json::Value, TauriError> {
     use nozy::transaction_history::{
@@ -0,0 +1,74 @@
+use crate::error::TauriError;
+use crate::session::load_session_wallet;
+use nozy::zns::ZnsIndexer;
+use nozy::zns_registration::{ZnsActionKind, ZnsNameAction, ZnsPreparedAction};
+use serde::{Deserialize, Serialize};
+use tauri::command;
+
+#[derive(Debug, Deserialize)]
+pub struct ZnsActionRequest {
+    pub name: String,
+    /// Target UA; defaults to the Business UA. Ignored for renewals.
+    pub address: Option<String>,
+    /// Return the signed memo and cost without sending.
+    #[serde(default)]
+    pub dry_run: bool,
+    pub password: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ZnsActionResponse {
+    pub prepared: ZnsPreparedAction,
+    pub action: Option<ZnsNameAction>,
+}
+
+async fn run_zns_action(
+    kind: ZnsActionKind,
+    request: ZnsActionRequest,
+) -> Result<ZnsActionResponse, TauriError> {
+    let config = nozy::load_config();
+    let indexer = ZnsIndexer::for_network(Some(&config.network))?;
+    let wallet = load_session_wallet(request.password.as_deref()).await?;
+    let prepared = nozy::zns_registration::prepare_zns_action(
+        &wallet,
+        &indexer,
+        kind,
+        &request.name,
+        request.address.as_deref(),
+        crate::network_from_config(),
+    )
+    .await?;
+    let action = if request.dry_run {
+        None
+    } else {
+        Some(
+            nozy::zns_registration::submit_zns_action(&wallet, &config.zebra_url, &prepared)
+                .await?,
+        )
+    };
+    Ok(ZnsActionResponse { prepared, action })
+}
+
+#[command]
+pub async fn zns_register(request: ZnsActionRequest) -> Result<ZnsActionResponse, TauriError> {
+    run_zns_action(ZnsActionKind::Claim, request).await
+}
+
+#[command]
+pub async fn zns_update(request: ZnsActionRequest) -> Result<ZnsActionResponse, TauriError> {
+    run_zns_action(ZnsActionKind::Update, request).await
+}
+
+#[command]
+pub async fn zns_renew(request: ZnsActionRequest) -> Result<ZnsActionResponse, TauriError> {
+    run_zns_action(ZnsActionKind::Renew, request).await
+}
+
+/// Submitted ZNS actions, re-checked against the chain and the indexer.
+#[command]
+pub async fn zns_actions() -> Result<Vec<ZnsNameAction>, TauriError> {
+    let config = nozy::load_config();
+    let indexer = ZnsIndexer::for_network(Some(&config.network))?;
+    let zebra_client = nozy::ZebraClient::from_config(&config);
+    Ok(nozy::zns_registration::refresh_zns_actions(&zebra_client, &indexer).await?)
+}
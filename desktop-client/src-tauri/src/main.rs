@@ -47,14 +47,25 @@ fn main() {
             create_or_restore_testnet_wallet,
             generate_address,
             get_balance,
+            get_fiat_balance,
             prepare_cosign_request,
             sign_cosign_request,
             complete_cosign_send,
+            frost_share_info,
+            frost_commit,
+            frost_build_request,
+            frost_sign,
+            frost_aggregate,
             get_keystone_status,
             set_keystone_enabled,
             export_keystone_ufvk,
             keystone_prepare_send,
             keystone_complete_send,
+            keystone_prepare_migration,
+            keystone_complete_migration,
+            keystone_list_queue,
+            keystone_cancel_request,
+            keystone_scan_frames,
             get_sync_status,
             get_orchard_pool_stats,
             get_ironwood_status,
@@ -67,8 +78,12 @@ fn main() {
             shield_sapling,
             sync_wallet,
             send_transaction,
+            propose_send,
+            execute_send_proposal,
             estimate_fee,
             get_transaction_history,
+            get_cost_basis_report,
+            set_cost_basis_override,
             get_transaction,
             speed_up_transaction,
             check_transaction_confirmations,
@@ -80,9 +95,16 @@ fn main() {
             get_zns_link,
             link_zns_name,
             unlink_zns_name,
+            zns_register,
+            zns_update,
+            zns_renew,
+            zns_actions,
             check_proving_status,
             download_proving_parameters,
             get_notes,
+            notes_list,
+            notes_freeze,
+            notes_unfreeze,
             lwd_get_info,
             lwd_chain_tip,
             lwd_sync_compact,
@@ -95,10 +117,19 @@ fn main() {
             address_book_remove,
             address_book_get,
             address_book_search,
+            label_update,
+            label_remove,
+            label_search,
+            schedule_list,
+            schedule_add,
+            schedule_remove,
+            schedule_set_enabled,
+            schedule_run_due,
             export_backup,
             restore_from_backup,
             list_backups,
             sign_message,
+            verify_message,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -679,6 +679,9 @@ export function SendForm({ onSuccess, onCancel }: SendFormProps) {
         return;
       }
       toast.success(`Transaction broadcast! TXID: ${data.txid}`, { id: toastId });
+      if (data.warning) {
+        toast.error(data.warning);
+      }
       setSentTxid(data.txid);
       setSuccess(true);
       resetKeystoneSendState();
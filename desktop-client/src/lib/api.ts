@@ -22,6 +22,8 @@ import {
   VerifyPasswordRequest as _VerifyPasswordRequest,
   SignMessageRequest,
   SignMessageResponse,
+  VerifyMessageRequest,
+  VerifyMessageResponse,
   AddressBookEntry,
   AddAddressBookRequest,
   BackupPathRequest,
@@ -360,8 +362,8 @@ export const walletApi = {
     urFrames?: string[];
     broadcast?: boolean;
     zebraUrl?: string;
-  }): Promise<{ data: { success: boolean; txid?: string; broadcast?: boolean } }> => {
-    const result = await invoke<{ success: boolean; txid?: string; broadcast?: boolean }>(
+  }): Promise<{ data: { success: boolean; txid?: string; broadcast?: boolean; warning?: string } }> => {
+    const result = await invoke<{ success: boolean; txid?: string; broadcast?: boolean; warning?: string }>(
       "keystone_complete_send",
       {
         request: {
@@ -520,6 +522,11 @@ export const walletApi = {
     return { data: result };
   },
 
+  verifyMessage: async (data: VerifyMessageRequest): Promise<{ data: VerifyMessageResponse }> => {
+    const result = await invoke<VerifyMessageResponse>("verify_message", { request: data });
+    return { data: result };
+  },
+
   listAddressBook: async (): Promise<{ data: AddressBookEntry[] }> => {
     const result = await invoke<AddressBookEntry[]>("address_book_list");
     return { data: result ?? [] };
@@ -341,11 +341,31 @@ export interface VerifyPasswordRequest {
 export interface SignMessageRequest {
   message: string;
   password: string;
+  /** With `address`, signs as that address (nozy-sm-v2) instead of the legacy digest. */
+  account?: number;
+  address?: string;
+  /** Required for nozy-sm-v2: the signature contains the account's full viewing key. */
+  reveal_viewing_key?: boolean;
 }
 
 export interface SignMessageResponse {
   signature: string;
   scheme?: string;
+  address?: string;
+  /** Set for nozy-sm-v2 signatures; show it to the user. */
+  warning?: string;
+}
+
+export interface VerifyMessageRequest {
+  message: string;
+  signature: string;
+  /** Claimed signer; required for nozy-sm-v2. */
+  address?: string;
+}
+
+export interface VerifyMessageResponse {
+  valid: boolean;
+  address_bound: boolean;
 }
 
 export interface AddressBookEntry {
@@ -432,6 +452,7 @@ export interface KeystoneStatusResponse {
   device_label: string | null;
   has_ufvk: boolean;
   pending_send: boolean;
+  pending_requests: number;
   network: string;
 }
 
@@ -134,6 +134,9 @@ export function KeystoneScreen({ navigation }: Props) {
           : { urFrames: lines, broadcast: true },
       );
       setMessage(res.txid ? `Broadcast OK: ${res.txid}` : "Send completed");
+      if (res.warning) {
+        setError(res.warning);
+      }
       setSignedInput("");
       await refreshStatus();
     } catch (e) {
@@ -393,7 +393,7 @@ export const api = {
     broadcast?: boolean;
     zebraUrl?: string;
   }) =>
-    request<{ success: boolean; txid?: string; broadcast?: boolean }>(
+    request<{ success: boolean; txid?: string; broadcast?: boolean; warning?: string }>(
       "/api/keystone/complete-send",
       {
         method: "POST",
@@ -103,6 +103,7 @@ export type KeystoneStatusResponse = {
   device_label: string | null;
   has_ufvk: boolean;
   pending_send: boolean;
+  pending_requests: number;
   network: string;
 };
 
@@ -14,6 +14,9 @@ pub struct AddressEntry {
     pub last_used: Option<chrono::DateTime<chrono::Utc>>,
     pub usage_count: u32,
     pub notes: Option<String>,
+    /// Hex `ak` this contact signs payment requests with.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub merchant_key: Option<String>,
 }
 
 impl AddressEntry {
@@ -25,6 +28,7 @@ impl AddressEntry {
             last_used: None,
             usage_count: 0,
             notes,
+            merchant_key: None,
         }
     }
 
@@ -263,6 +267,47 @@ impl AddressBook {
         Ok(updated)
     }
 
+    /// Set or clear the payment-request signing key for `name`.
+    pub fn set_merchant_key(&self, name: &str, merchant_key: Option<String>) -> NozyResult<bool> {
+        let merchant_key = match merchant_key {
+            Some(key) => {
+                let key = key.trim().to_ascii_lowercase();
+                if key.len() != 64 || !key.bytes().all(|b| b.is_ascii_hexdigit()) {
+                    return Err(NozyError::InvalidInput(
+                        "Merchant key must be 64 hex characters".to_string(),
+                    ));
+                }
+                Some(key)
+            }
+            None => None,
+        };
+        let updated = {
+            let mut addresses = self
+                .addresses
+                .lock()
+                .map_err(|e| NozyError::Storage(format!("Mutex poisoned: {}", e)))?;
+            if let Some(entry) = addresses.get_mut(name) {
+                entry.merchant_key = merchant_key;
+                true
+            } else {
+                false
+            }
+        };
+
+        if updated {
+            self.save_addresses()?;
+        }
+
+        Ok(updated)
+    }
+
+    pub fn find_by_merchant_key(&self, merchant_key: &str) -> Option<AddressEntry> {
+        let key = merchant_key.to_ascii_lowercase();
+        self.list_addresses()
+            .into_iter()
+            .find(|entry| entry.merchant_key.as_deref() == Some(key.as_str()))
+    }
+
     pub fn count(&self) -> usize {
         self.addresses
             .lock()
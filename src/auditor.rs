@@ -0,0 +1,699 @@
+//! Auditor packages: read-only history for one account over a block range.
+//!
+//! [`create_auditor_package`] writes a manifest of the receipts and sends the wallet has on
+//! record for a height range (usually a fiscal year resolved with [`height_for_date`]),
+//! signed with the account's SpendAuth key. No viewing key is packaged: each receipt
+//! carries the openings of the notes it received and each send the outgoing cipher keys
+//! (`ock`) of its payment outputs, the same per-output secrets as
+//! [`crate::payment_disclosure`]. [`run_audit`] fetches every listed transaction, opens
+//! exactly those outputs and reports every difference from the manifest.
+//!
+//! The package reveals nothing outside the listed outputs, so it also cannot show
+//! transactions the manifest leaves out; the signature makes the account holder answerable
+//! for the list.
+
+use crate::block_parser::ParsedTransaction;
+use crate::error::{NozyError, NozyResult};
+use crate::hd_wallet::HDWallet;
+use crate::notes::{load_wallet_notes, SerializableOrchardNote};
+use crate::ovk_recovery::{encode_orchard_receiver, normalize_memo, RecoveredOutputPool};
+use crate::payment_disclosure::{
+    decrypt_with_ock, derive_output_keys, fetch_transaction, parse_ock, pool_actions,
+    OutputDisclosureKey,
+};
+use crate::shielded_pool::ShieldedPool;
+use crate::signed_message::{
+    sign_with_key, signer_key_hex, spend_authorizing_key, verify, verifying_key_hex,
+};
+use crate::transaction_history::{
+    SentTransactionRecord, SentTransactionStorage, TransactionStatus,
+};
+use crate::zebra_integration::ZebraClient;
+use chrono::{DateTime, NaiveDate, Utc};
+use futures::future::join_all;
+use orchard::keys::{FullViewingKey, Scope, SpendAuthorizingKey};
+use orchard::note::{ExtractedNoteCommitment, NoteVersion, RandomSeed, Rho};
+use orchard::value::NoteValue;
+use orchard::{Address as OrchardAddress, Note};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use zcash_protocol::consensus::NetworkType;
+
+/// Version 2 replaced the packaged viewing key with per-output secrets.
+pub const AUDIT_PACKAGE_VERSION: u32 = 2;
+
+/// Opening of one received note; hex fields.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditNoteOpening {
+    pub pool: ShieldedPool,
+    /// Raw Orchard receiver `d || pk_d`.
+    pub recipient: String,
+    pub value_zatoshis: u64,
+    pub rho: String,
+    pub rseed: String,
+}
+
+/// Net value one transaction moved, as recorded in a manifest, with what opens it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub txid: String,
+    pub block_height: u32,
+    pub value_zatoshis: u64,
+    /// Receipts: the notes received.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub notes: Vec<AuditNoteOpening>,
+    /// Sends: outgoing cipher keys of the payment (non-change) outputs.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub output_keys: Vec<OutputDisclosureKey>,
+}
+
+/// What the wallet attests for the audited range.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditManifest {
+    pub version: u32,
+    /// `mainnet` or `testnet`.
+    pub network: String,
+    pub account: u32,
+    pub start_height: u32,
+    pub end_height: u32,
+    /// Dates the range was resolved from, when given as dates.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_date: Option<NaiveDate>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_date: Option<NaiveDate>,
+    pub created_at: DateTime<Utc>,
+    /// Hex `ak` of the SpendAuth key that signed the package.
+    pub signer_key: String,
+    pub received: Vec<AuditEntry>,
+    pub sent: Vec<AuditEntry>,
+}
+
+/// Exported auditor package: manifest plus `nozy-sm-v1` signature over its JSON.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditorPackage {
+    pub manifest: AuditManifest,
+    pub signature: String,
+}
+
+/// Range to package: heights, or the dates they were resolved from.
+#[derive(Debug, Clone, Copy)]
+pub struct AuditRange {
+    pub start_height: u32,
+    pub end_height: u32,
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+}
+
+/// One transaction found by the audit rescan.
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditedTransaction {
+    pub txid: String,
+    pub block_height: u32,
+    pub value_zatoshis: u64,
+    /// Recipient of the first payment output (sends only).
+    pub counterparty: Option<String>,
+    pub memo: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditReport {
+    pub network: String,
+    pub account: u32,
+    pub start_height: u32,
+    pub end_height: u32,
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+    pub received: Vec<AuditedTransaction>,
+    pub sent: Vec<AuditedTransaction>,
+    pub total_received_zatoshis: u64,
+    pub total_sent_zatoshis: u64,
+    /// Differences between the signed manifest and the chain; empty when verified.
+    pub discrepancies: Vec<String>,
+}
+
+impl AuditReport {
+    pub fn verified(&self) -> bool {
+        self.discrepancies.is_empty()
+    }
+}
+
+fn network_name(network: NetworkType) -> &'static str {
+    match network {
+        NetworkType::Main => "mainnet",
+        NetworkType::Test | NetworkType::Regtest => "testnet",
+    }
+}
+
+fn parse_network(name: &str) -> NetworkType {
+    if name == "testnet" {
+        NetworkType::Test
+    } else {
+        NetworkType::Main
+    }
+}
+
+async fn block_time(zebra: &ZebraClient, height: u32) -> NozyResult<i64> {
+    let hash = zebra.get_block_hash(height).await?;
+    let block = zebra.get_block_by_hash(&hash, 1).await?;
+    block
+        .get("time")
+        .and_then(|v| v.as_i64())
+        .ok_or_else(|| NozyError::InvalidOperation(format!("Block {height} has no timestamp")))
+}
+
+/// First block mined at or after `timestamp`; `tip + 1` when none is yet.
+async fn first_height_at_or_after(zebra: &ZebraClient, timestamp: i64) -> NozyResult<u32> {
+    let tip = zebra.get_block_count().await?;
+    let (mut lo, mut hi) = (0u32, tip + 1);
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        if block_time(zebra, mid).await? >= timestamp {
+            hi = mid;
+        } else {
+            lo = mid + 1;
+        }
+    }
+    Ok(lo)
+}
+
+/// Height range covering whole UTC days `from..=to`, found by bisecting block times.
+pub async fn height_for_date(
+    zebra: &ZebraClient,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> NozyResult<AuditRange> {
+    if to < from {
+        return Err(NozyError::InvalidInput(
+            "Audit end date is before its start date".into(),
+        ));
+    }
+    let midnight = |date: NaiveDate| date.and_hms_opt(0, 0, 0).map(|t| t.and_utc().timestamp());
+    let (Some(start_ts), Some(end_ts)) = (midnight(from), to.succ_opt().and_then(midnight)) else {
+        return Err(NozyError::InvalidInput("Audit date out of range".into()));
+    };
+    let start_height = first_height_at_or_after(zebra, start_ts).await?;
+    let end_height = first_height_at_or_after(zebra, end_ts)
+        .await?
+        .saturating_sub(1);
+    if end_height < start_height {
+        return Err(NozyError::InvalidInput(format!(
+            "No blocks were mined between {from} and {to}"
+        )));
+    }
+    Ok(AuditRange {
+        start_height,
+        end_height,
+        start_date: Some(from),
+        end_date: Some(to),
+    })
+}
+
+fn sorted_entries(by_txid: BTreeMap<String, AuditEntry>) -> Vec<AuditEntry> {
+    let mut entries: Vec<AuditEntry> = by_txid.into_values().collect();
+    entries.sort_by(|a, b| (a.block_height, &a.txid).cmp(&(b.block_height, &b.txid)));
+    entries
+}
+
+fn note_opening(note: &SerializableOrchardNote) -> Option<AuditNoteOpening> {
+    Some(AuditNoteOpening {
+        pool: note.pool,
+        recipient: hex::encode(<[u8; 43]>::try_from(note.address_bytes.as_slice()).ok()?),
+        value_zatoshis: note.value,
+        rho: hex::encode(<[u8; 32]>::try_from(note.rho_bytes.as_deref()?).ok()?),
+        rseed: hex::encode(<[u8; 32]>::try_from(note.rseed_bytes.as_deref()?).ok()?),
+    })
+}
+
+/// Manifest of recorded receipts (external-scope notes) and confirmed sends in range.
+///
+/// Receipts carry the openings of their notes and sends the outgoing cipher keys of their
+/// payment outputs (`record.disclosure_keys`); `fvk` only decides which notes are receipts
+/// and is not packaged.
+pub fn build_manifest(
+    fvk: &FullViewingKey,
+    account: u32,
+    network: NetworkType,
+    range: AuditRange,
+    notes: &[SerializableOrchardNote],
+    sent: &[SentTransactionRecord],
+) -> AuditManifest {
+    let in_range = |h: u32| (range.start_height..=range.end_height).contains(&h);
+    let entry = |txid: &str, block_height: u32| AuditEntry {
+        txid: txid.to_string(),
+        block_height,
+        value_zatoshis: 0,
+        notes: Vec::new(),
+        output_keys: Vec::new(),
+    };
+
+    let mut received: BTreeMap<String, AuditEntry> = BTreeMap::new();
+    for note in notes.iter().filter(|n| in_range(n.block_height)) {
+        let external = <[u8; 43]>::try_from(note.address_bytes.as_slice())
+            .ok()
+            .and_then(|raw| {
+                Option::<OrchardAddress>::from(OrchardAddress::from_raw_address_bytes(&raw))
+            })
+            .is_some_and(|addr| fvk.scope_for_address(&addr) == Some(Scope::External));
+        if external {
+            let receipt = received
+                .entry(note.txid.clone())
+                .or_insert_with(|| entry(&note.txid, note.block_height));
+            receipt.value_zatoshis = receipt.value_zatoshis.saturating_add(note.value);
+            receipt.notes.extend(note_opening(note));
+        }
+    }
+
+    let mut sent_by_txid: BTreeMap<String, AuditEntry> = BTreeMap::new();
+    for record in sent {
+        if let (TransactionStatus::Confirmed, Some(height)) = (&record.status, record.block_height)
+        {
+            if in_range(height) {
+                let mut payment = entry(&record.txid, height);
+                payment.value_zatoshis = record.amount_zatoshis;
+                payment.output_keys = record
+                    .disclosure_keys
+                    .iter()
+                    .filter(|k| !k.is_change)
+                    .cloned()
+                    .collect();
+                sent_by_txid.insert(record.txid.clone(), payment);
+            }
+        }
+    }
+
+    AuditManifest {
+        version: AUDIT_PACKAGE_VERSION,
+        network: network_name(network).to_string(),
+        account,
+        start_height: range.start_height,
+        end_height: range.end_height,
+        start_date: range.start_date,
+        end_date: range.end_date,
+        created_at: Utc::now(),
+        signer_key: String::new(),
+        received: sorted_entries(received),
+        sent: sorted_entries(sent_by_txid),
+    }
+}
+
+fn manifest_message(manifest: &AuditManifest) -> NozyResult<String> {
+    serde_json::to_string(manifest)
+        .map_err(|e| NozyError::InvalidOperation(format!("Failed to encode audit manifest: {e}")))
+}
+
+fn sign_manifest(
+    mut manifest: AuditManifest,
+    ask: &SpendAuthorizingKey,
+) -> NozyResult<AuditorPackage> {
+    manifest.signer_key = verifying_key_hex(ask);
+    let signature = sign_with_key(ask, &manifest_message(&manifest)?);
+    Ok(AuditorPackage {
+        manifest,
+        signature,
+    })
+}
+
+/// Build and sign a package for `account` from local notes and sent history.
+///
+/// Sends in range whose disclosure keys are not on record yet are fetched from Zebra once
+/// and their keys saved, as [`crate::payment_disclosure`] does.
+pub async fn create_auditor_package(
+    wallet: &HDWallet,
+    zebra: &ZebraClient,
+    account: u32,
+    network: NetworkType,
+    range: AuditRange,
+) -> NozyResult<AuditorPackage> {
+    let fvk = wallet.orchard_full_viewing_key(account)?;
+    let notes = load_wallet_notes()?;
+    let storage = SentTransactionStorage::new()?;
+    let mut sent = storage.get_all_transactions();
+    for record in sent.iter_mut().filter(|r| {
+        r.status == TransactionStatus::Confirmed
+            && r.disclosure_keys.is_empty()
+            && r.block_height
+                .is_some_and(|h| (range.start_height..=range.end_height).contains(&h))
+    }) {
+        let tx = fetch_transaction(zebra, &record.txid).await?;
+        record.disclosure_keys = derive_output_keys(&tx, std::slice::from_ref(&fvk));
+        if record.disclosure_keys.is_empty() {
+            eprintln!(
+                "Warning: no output of {} was encrypted to account {account}'s outgoing viewing key",
+                record.txid
+            );
+        } else {
+            storage.save_transaction(record.clone())?;
+        }
+    }
+    let manifest = build_manifest(&fvk, account, network, range, &notes, &sent);
+
+    let ask = spend_authorizing_key(&wallet.get_mnemonic_object().to_seed(""), account)?;
+    sign_manifest(manifest, &ask)
+}
+
+/// Check the package version and that `manifest.signer_key` signed the manifest.
+///
+/// Compare `signer_key` with the key the account holder gave you out of band to know
+/// whose package it is.
+pub fn verify_package(package: &AuditorPackage) -> NozyResult<()> {
+    let manifest = &package.manifest;
+    if manifest.version != AUDIT_PACKAGE_VERSION {
+        return Err(NozyError::InvalidInput(format!(
+            "Unsupported auditor package version {}",
+            manifest.version
+        )));
+    }
+    let signed_by_key = signer_key_hex(&package.signature)
+        .is_some_and(|ak| ak.eq_ignore_ascii_case(&manifest.signer_key));
+    if !signed_by_key || !verify(&package.signature, &manifest_message(manifest)?)? {
+        return Err(NozyError::InvalidInput(
+            "Auditor package signature does not match its manifest".into(),
+        ));
+    }
+    Ok(())
+}
+
+fn memo_text(memo: &[u8]) -> Option<String> {
+    Some(memo)
+        .filter(|m| m.first().is_some_and(|b| *b <= 0xF4))
+        .and_then(|m| String::from_utf8(m.to_vec()).ok())
+}
+
+fn hex_array<const N: usize>(hex_bytes: &str) -> Option<[u8; N]> {
+    hex::decode(hex_bytes.trim())
+        .ok()
+        .and_then(|b| <[u8; N]>::try_from(b).ok())
+}
+
+/// Value of `opening` when it opens an output of `tx`: an action of its pool whose
+/// nullifier is the note's `rho` and whose `cmx` commits to the note.
+fn opened_value(opening: &AuditNoteOpening, tx: &ParsedTransaction) -> Option<u64> {
+    let rho_bytes = hex_array::<32>(&opening.rho)?;
+    let rho = Rho::from_bytes(&rho_bytes).into_option()?;
+    let rseed = RandomSeed::from_bytes(hex_array::<32>(&opening.rseed)?, &rho).into_option()?;
+    let recipient = OrchardAddress::from_raw_address_bytes(&hex_array::<43>(&opening.recipient)?)
+        .into_option()?;
+    let version = match opening.pool {
+        ShieldedPool::Orchard => NoteVersion::V2,
+        ShieldedPool::Ironwood => NoteVersion::V3,
+    };
+    let note = Note::from_parts(
+        recipient,
+        NoteValue::from_raw(opening.value_zatoshis),
+        rho,
+        rseed,
+        version,
+    )
+    .into_option()?;
+    let cmx = ExtractedNoteCommitment::from(note.commitment()).to_bytes();
+    pool_actions(tx, RecoveredOutputPool::from(opening.pool))
+        .ok()?
+        .iter()
+        .any(|a| a.nullifier == rho_bytes && a.cmx == cmx)
+        .then_some(opening.value_zatoshis)
+}
+
+/// Value, recipient and memo of the output `key` opens in `tx`.
+fn opened_payment(
+    key: &OutputDisclosureKey,
+    tx: &ParsedTransaction,
+    network: NetworkType,
+) -> Option<(u64, String, Option<String>)> {
+    let action = pool_actions(tx, key.pool).ok()?.get(key.output_index)?;
+    let (value, address, memo) = decrypt_with_ock(action, key.pool, &parse_ock(&key.ock).ok()?)?;
+    let memo = normalize_memo(&memo).and_then(|m| memo_text(&m));
+    Some((
+        value,
+        encode_orchard_receiver(&address, network).ok()?,
+        memo,
+    ))
+}
+
+/// Check one manifest entry against its mined transaction.
+fn audit_entry(
+    kind: &str,
+    entry: &AuditEntry,
+    tx: &ParsedTransaction,
+    network: NetworkType,
+    discrepancies: &mut Vec<String>,
+) -> AuditedTransaction {
+    if tx.height != entry.block_height {
+        discrepancies.push(format!(
+            "{kind} {}: manifest says height {}, chain shows {}",
+            entry.txid, entry.block_height, tx.height
+        ));
+    }
+    let (mut value, mut counterparty, mut memo) = (0u64, None, None);
+    let mut unopened = 0;
+    for opening in &entry.notes {
+        match opened_value(opening, tx) {
+            Some(v) => value = value.saturating_add(v),
+            None => unopened += 1,
+        }
+    }
+    for key in &entry.output_keys {
+        match opened_payment(key, tx, network) {
+            Some((v, recipient, output_memo)) => {
+                value = value.saturating_add(v);
+                if counterparty.is_none() {
+                    counterparty = Some(recipient);
+                    memo = output_memo;
+                }
+            }
+            None => unopened += 1,
+        }
+    }
+    if unopened > 0 {
+        discrepancies.push(format!(
+            "{kind} {}: {unopened} packaged output(s) do not open an output of the transaction",
+            entry.txid
+        ));
+    }
+    if value != entry.value_zatoshis {
+        discrepancies.push(format!(
+            "{kind} {}: manifest says {} zatoshis, the packaged outputs show {value}",
+            entry.txid, entry.value_zatoshis
+        ));
+    }
+    AuditedTransaction {
+        txid: entry.txid.clone(),
+        block_height: tx.height,
+        value_zatoshis: value,
+        counterparty,
+        memo,
+    }
+}
+
+/// Fetch each listed transaction and check its packaged outputs.
+async fn audit_entries(
+    zebra: &ZebraClient,
+    kind: &str,
+    manifest: &AuditManifest,
+    entries: &[AuditEntry],
+    discrepancies: &mut Vec<String>,
+) -> Vec<AuditedTransaction> {
+    let network = parse_network(&manifest.network);
+    let fetched = join_all(entries.iter().map(|e| fetch_transaction(zebra, &e.txid))).await;
+    let mut audited = Vec::new();
+    for (entry, tx) in entries.iter().zip(fetched) {
+        if !(manifest.start_height..=manifest.end_height).contains(&entry.block_height) {
+            discrepancies.push(format!(
+                "{kind} {} at height {} is outside the audited range",
+                entry.txid, entry.block_height
+            ));
+        }
+        match tx {
+            Ok(tx) if tx.height > 0 => {
+                audited.push(audit_entry(kind, entry, &tx, network, discrepancies))
+            }
+            Ok(_) => discrepancies.push(format!("{kind} {} is not mined", entry.txid)),
+            Err(e) => discrepancies.push(format!(
+                "{kind} {} at height {} is in the manifest but not on chain: {e}",
+                entry.txid, entry.block_height
+            )),
+        }
+    }
+    audited
+}
+
+/// Read-only audit: verify the package signature, then fetch every listed transaction and
+/// open its packaged outputs.
+///
+/// Without a viewing key the audit cannot find transactions the manifest leaves out; it
+/// proves that what is listed happened on chain with the stated values.
+pub async fn run_audit(zebra: &ZebraClient, package: &AuditorPackage) -> NozyResult<AuditReport> {
+    verify_package(package)?;
+    let manifest = &package.manifest;
+    let mut discrepancies = Vec::new();
+    let received = audit_entries(
+        zebra,
+        "Receipt",
+        manifest,
+        &manifest.received,
+        &mut discrepancies,
+    )
+    .await;
+    let sent = audit_entries(
+        zebra,
+        "Payment",
+        manifest,
+        &manifest.sent,
+        &mut discrepancies,
+    )
+    .await;
+
+    Ok(AuditReport {
+        network: manifest.network.clone(),
+        account: manifest.account,
+        start_height: manifest.start_height,
+        end_height: manifest.end_height,
+        start_date: manifest.start_date,
+        end_date: manifest.end_date,
+        total_received_zatoshis: received.iter().map(|t| t.value_zatoshis).sum(),
+        total_sent_zatoshis: sent.iter().map(|t| t.value_zatoshis).sum(),
+        received,
+        sent,
+        discrepancies,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bip39::{Language, Mnemonic};
+
+    fn wallet() -> HDWallet {
+        let mnemonic = Mnemonic::from_entropy_in(Language::English, &[3u8; 16]).unwrap();
+        HDWallet::from_mnemonic(&mnemonic.to_string()).unwrap()
+    }
+
+    fn package(wallet: &HDWallet, sent: &[SentTransactionRecord]) -> AuditorPackage {
+        let fvk = wallet.orchard_full_viewing_key(0).unwrap();
+        let range = AuditRange {
+            start_height: 100,
+            end_height: 200,
+            start_date: None,
+            end_date: None,
+        };
+        let manifest = build_manifest(&fvk, 0, NetworkType::Main, range, &[], sent);
+        let ask = spend_authorizing_key(&wallet.get_mnemonic_object().to_seed(""), 0).unwrap();
+        sign_manifest(manifest, &ask).unwrap()
+    }
+
+    #[test]
+    fn manifest_keeps_confirmed_sends_in_range() {
+        let mut inside = SentTransactionRecord::new("aa".into(), "u1x".into(), 5, 1, None, vec![]);
+        inside.status = TransactionStatus::Confirmed;
+        inside.block_height = Some(150);
+        let mut outside = inside.clone();
+        outside.txid = "bb".into();
+        outside.block_height = Some(201);
+        let pending = SentTransactionRecord::new("cc".into(), "u1x".into(), 5, 1, None, vec![]);
+
+        let pkg = package(&wallet(), &[inside, outside, pending]);
+        assert_eq!(
+            pkg.manifest.sent,
+            vec![AuditEntry {
+                txid: "aa".into(),
+                block_height: 150,
+                value_zatoshis: 5,
+                notes: Vec::new(),
+                output_keys: Vec::new(),
+            }]
+        );
+        assert!(verify_package(&pkg).is_ok());
+        // Only per-output material is packaged, never the account's viewing key.
+        let fvk = hex::encode(wallet().orchard_full_viewing_key(0).unwrap().to_bytes());
+        assert!(!serde_json::to_string(&pkg).unwrap().contains(&fvk));
+    }
+
+    #[test]
+    fn edited_manifest_or_foreign_key_is_rejected() {
+        let w = wallet();
+        let mut edited = package(&w, &[]);
+        edited.manifest.end_height = 300;
+        assert!(verify_package(&edited).is_err());
+
+        let mut swapped = package(&w, &[]);
+        let other = Mnemonic::from_entropy_in(Language::English, &[4u8; 16]).unwrap();
+        let other_ask = spend_authorizing_key(&other.to_seed(""), 0).unwrap();
+        swapped.manifest.signer_key = verifying_key_hex(&other_ask);
+        assert!(verify_package(&swapped).is_err());
+    }
+
+    #[test]
+    fn note_opening_is_checked_against_the_action() {
+        use orchard::keys::SpendingKey;
+
+        let fvk = FullViewingKey::from(&SpendingKey::from_bytes([8u8; 32]).unwrap());
+        let recipient = fvk.address_at(2u32, Scope::External);
+        let (pczt, _) =
+            crate::tests::unproven_test_pczt(&fvk, 200_000, &[(recipient, 150_000)], &[]);
+        let action = pczt
+            .orchard()
+            .actions()
+            .iter()
+            .find(|a| *a.output().value() == Some(150_000))
+            .unwrap();
+        let tx = ParsedTransaction {
+            txid: "ab".repeat(32),
+            height: 150,
+            index: 0,
+            raw_data: Vec::new(),
+            orchard_actions: vec![crate::notes::OrchardActionData {
+                nullifier: *action.spend().nullifier(),
+                cmx: *action.output().cmx(),
+                ephemeral_key: [0u8; 32],
+                encrypted_note: [0u8; 580],
+                enc_ciphertext: [0u8; 80],
+                cv: [0u8; 32],
+                rk: [0u8; 32],
+            }],
+            ironwood_actions: Vec::new(),
+        };
+        let opening = AuditNoteOpening {
+            pool: ShieldedPool::Orchard,
+            recipient: hex::encode(recipient.to_raw_address_bytes()),
+            value_zatoshis: 150_000,
+            rho: hex::encode(action.spend().nullifier()),
+            rseed: hex::encode(action.output().rseed().unwrap()),
+        };
+        assert_eq!(opened_value(&opening, &tx), Some(150_000));
+
+        let entry = AuditEntry {
+            txid: tx.txid.clone(),
+            block_height: 150,
+            value_zatoshis: 150_000,
+            notes: vec![opening.clone()],
+            output_keys: Vec::new(),
+        };
+        let mut discrepancies = Vec::new();
+        let audited = audit_entry(
+            "Receipt",
+            &entry,
+            &tx,
+            NetworkType::Test,
+            &mut discrepancies,
+        );
+        assert!(discrepancies.is_empty());
+        assert_eq!(audited.value_zatoshis, 150_000);
+
+        let inflated = AuditEntry {
+            value_zatoshis: 250_000,
+            notes: vec![AuditNoteOpening {
+                value_zatoshis: 250_000,
+                ..opening
+            }],
+            ..entry
+        };
+        audit_entry(
+            "Receipt",
+            &inflated,
+            &tx,
+            NetworkType::Test,
+            &mut discrepancies,
+        );
+        assert_eq!(discrepancies.len(), 2);
+        assert!(discrepancies[0].contains("do not open"));
+    }
+}
@@ -167,13 +167,55 @@ pub async fn load_wallet() -> NozyResult<(HDWallet, WalletStorage)> {
     Ok((wallet, storage))
 }
 
+/// Unlock the notes vault without decrypting the seed: the empty password first, then a
+/// prompt. `probe` reads something the vault encrypts, to tell a wrong password apart.
+pub fn unlock_notes_vault_interactive(probe: impl Fn() -> NozyResult<()>) -> NozyResult<()> {
+    crate::notes_vault::unlock_notes_vault("")?;
+    if probe().is_ok() {
+        return Ok(());
+    }
+    let password = Password::new()
+        .with_prompt("Enter wallet password")
+        .interact()
+        .map_err(|e| NozyError::InvalidOperation(format!("Password input error: {}", e)))?;
+    crate::notes_vault::unlock_notes_vault(&password)?;
+    probe()
+}
+
+/// Keys the send-time scan finds notes with.
+#[derive(Clone, Copy)]
+enum SendScanKeys<'a> {
+    Wallet(&'a HDWallet),
+    ViewingKey(&'a orchard::keys::FullViewingKey),
+}
+
 pub async fn scan_notes_for_sending(
     wallet: &HDWallet,
     zebra_url: &str,
 ) -> NozyResult<Vec<crate::SpendableNote>> {
-    use crate::notes::load_spendable_notes_from_wallet;
+    scan_notes_for_send_keys(SendScanKeys::Wallet(wallet), zebra_url).await
+}
+
+/// [`scan_notes_for_sending`] for a watch-only or FROST profile, without a seed.
+///
+/// The notes carry `fvk` and no spending key, so they can only go into a PCZT. Such
+/// profiles have no `nozy sync`, so a fallback block scan is saved to `notes.json`.
+pub async fn scan_notes_for_viewing_key(
+    fvk: &orchard::keys::FullViewingKey,
+    zebra_url: &str,
+) -> NozyResult<Vec<crate::SpendableNote>> {
+    scan_notes_for_send_keys(SendScanKeys::ViewingKey(fvk), zebra_url).await
+}
+
+async fn scan_notes_for_send_keys(
+    keys: SendScanKeys<'_>,
+    zebra_url: &str,
+) -> NozyResult<Vec<crate::SpendableNote>> {
+    use crate::coin_control::apply_coin_control;
+    use crate::notes::{load_spendable_notes_for_viewing_key, load_spendable_notes_from_wallet};
     use crate::paths::get_wallet_data_dir;
     use crate::send_readiness::ensure_cached_witness_fresh_for_send;
+    use crate::signing_queue::exclude_locked_notes;
     use crate::wallet_sync::MAINNET_DEFAULT_SCAN_START;
 
     let mut config = load_config();
@@ -192,9 +234,13 @@ pub async fn scan_notes_for_sending(
 
     // Fast path: reuse persisted notes + witnesses from sync (witness catch-up at spend time).
     if !ironwood_witness_incomplete {
-        if let Ok(cached) = load_spendable_notes_from_wallet(&wallet) {
+        let cached = match keys {
+            SendScanKeys::Wallet(wallet) => load_spendable_notes_from_wallet(wallet),
+            SendScanKeys::ViewingKey(fvk) => load_spendable_notes_for_viewing_key(fvk),
+        };
+        if let Ok(cached) = cached {
             if !cached.is_empty() {
-                return Ok(cached);
+                return exclude_locked_notes(apply_coin_control(cached, &config)?, chain_tip);
             }
         }
     }
@@ -242,15 +288,27 @@ pub async fn scan_notes_for_sending(
     .min(tip_height);
 
     let notes_path = get_wallet_data_dir().join("notes.json");
-    let mut note_scanner = if notes_path.exists() {
-        NoteScanner::with_index_file(wallet, zebra_client.clone(), &notes_path)?
-    } else {
-        NoteScanner::new(wallet, zebra_client.clone())
+    let mut note_scanner = match keys {
+        SendScanKeys::Wallet(wallet) if notes_path.exists() => {
+            NoteScanner::with_index_file(wallet, zebra_client.clone(), &notes_path)?
+        }
+        SendScanKeys::Wallet(wallet) => NoteScanner::new(wallet, zebra_client.clone()),
+        SendScanKeys::ViewingKey(fvk) => {
+            let index = if notes_path.exists() {
+                Some(crate::note_index::NoteIndex::load_from_file(&notes_path)?)
+            } else {
+                None
+            };
+            NoteScanner::for_viewing_key(fvk.clone(), zebra_client.clone(), index)
+        }
     };
     let (_result, spendable) = note_scanner
         .scan_notes(Some(start_height), Some(tip_height))
         .await?;
-    Ok(spendable)
+    if let SendScanKeys::ViewingKey(_) = keys {
+        note_scanner.save_index(&notes_path)?;
+    }
+    exclude_locked_notes(apply_coin_control(spendable, &config)?, tip_height)
 }
 
 pub async fn build_and_broadcast_transaction(
@@ -314,13 +372,12 @@ pub async fn build_and_broadcast_transaction(
             }
             vec![nullifier_hex.clone()]
         } else {
-            use orchard::keys::FullViewingKey;
             let spent_note = crate::orchard_tx::select_single_spend_note(
                 spendable_notes,
                 amount_zatoshis,
                 fee_zatoshis,
             )?;
-            let fvk = FullViewingKey::from(&spent_note.spending_key);
+            let fvk = spent_note.fvk.clone();
             let nullifier_hex =
                 hex::encode(spent_note.orchard_note.note.nullifier(&fvk).to_bytes());
             if let Err(e) = crate::notes::mark_wallet_notes_spent_from_spendables(
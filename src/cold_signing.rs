@@ -0,0 +1,660 @@
+//! Offline cold signing of PCZTs by a second, air-gapped Nozy instance.
+//!
+//! The online instance holds only the signer's UFVK ([`crate::watch_only`]), never its seed.
+//! It builds the same proved, redacted PCZT it would hand to Keystone
+//! ([`crate::build_keystone_send_pczt`]) and passes it over as a file or animated
+//! `zcash-pczt` UR frames. The offline instance decodes it with [`read_pczt_transport`],
+//! shows [`preview_pczt`] and signs with its seed; the signed PCZT travels back the same
+//! way and [`broadcast_signed_pczt`] extracts and broadcasts it online.
+//!
+//! Every local signer checks [`inspect_pczt`] against the configured [`PcztPolicy`] first.
+
+use crate::address_book::{AddressBook, AddressEntry};
+use crate::error::{NozyError, NozyResult};
+use crate::fee_policy::NOZY_WALLET_PRIORITY_FEE;
+use crate::hd_wallet::HDWallet;
+use crate::keystone::{
+    decode_pczt_ur_frames, extract_signed_tx_from_pczt_bytes, orchard_spending_key_from_wallet,
+    KeystonePreparedSend,
+};
+use crate::notes::{load_wallet_notes, mark_wallet_notes_spent_by_nullifier_hex};
+use crate::ovk_recovery::encode_orchard_receiver;
+use crate::signed_message::orchard_receiver;
+use crate::signing_queue::{complete_signing_request, find_signing_request_for_pczt};
+use crate::transaction_history::{SentTransactionRecord, SentTransactionStorage};
+use crate::zebra_integration::ZebraClient;
+use orchard::keys::{FullViewingKey, Scope};
+use pczt::Pczt;
+use serde::{Deserialize, Serialize};
+use zcash_address::ZcashAddress;
+use zcash_protocol::consensus::{NetworkConstants, NetworkType};
+
+/// Value pool a PCZT spend or output belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PcztValuePool {
+    Transparent,
+    Sapling,
+    Orchard,
+    Ironwood,
+}
+
+/// One non-dummy output of a PCZT, in any pool.
+#[derive(Debug, Clone, Serialize)]
+pub struct PcztOutputView {
+    pub pool: PcztValuePool,
+    /// Index within its bundle; Orchard and Ironwood actions count together, Orchard first.
+    pub action_index: usize,
+    pub recipient: Option<String>,
+    pub value_zatoshis: Option<u64>,
+    /// Pays one of the signer's internal (change) addresses.
+    pub is_change: bool,
+    /// Pays any of the signer's addresses, so the wallet can decrypt it.
+    pub is_ours: bool,
+    /// Address book name of the recipient, set by [`inspect_pczt`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contact: Option<String>,
+}
+
+/// One real (non-dummy) spend or transparent input of a PCZT.
+#[derive(Debug, Clone, Serialize)]
+pub struct PcztSpendView {
+    pub pool: PcztValuePool,
+    pub action_index: usize,
+    pub value_zatoshis: Option<u64>,
+    /// Carries a spend authorization signature.
+    pub signed: bool,
+}
+
+/// What the signer is about to authorize.
+#[derive(Debug, Clone, Serialize)]
+pub struct PcztSigningPreview {
+    pub expiry_height: u32,
+    pub spend_count: usize,
+    pub spend_total_zatoshis: u64,
+    pub spends: Vec<PcztSpendView>,
+    pub outputs: Vec<PcztOutputView>,
+    /// Spends minus outputs, when every value is present.
+    pub fee_zatoshis: Option<u64>,
+}
+
+impl PcztSigningPreview {
+    pub fn payment_total_zatoshis(&self) -> u64 {
+        self.outputs
+            .iter()
+            .filter(|o| !o.is_change)
+            .filter_map(|o| o.value_zatoshis)
+            .sum()
+    }
+
+    pub fn fully_signed(&self) -> bool {
+        self.spends.iter().all(|s| s.signed)
+    }
+}
+
+/// Limits a PCZT must meet before this wallet signs it (`pczt_policy` in config).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PcztPolicy {
+    /// Largest total paid to others (change excluded).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_amount_zatoshis: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_fee_zatoshis: Option<u64>,
+    /// Only pay recipients saved in the address book.
+    #[serde(default)]
+    pub address_book_only: bool,
+}
+
+impl PcztPolicy {
+    /// Why `preview` breaks this policy; empty when signing is allowed.
+    pub fn violations(&self, preview: &PcztSigningPreview) -> Vec<String> {
+        let payments: Vec<_> = preview.outputs.iter().filter(|o| !o.is_change).collect();
+        let mut violations = Vec::new();
+        if let Some(max) = self.max_amount_zatoshis {
+            if payments.iter().any(|o| o.value_zatoshis.is_none()) {
+                violations.push(
+                    "an output amount is hidden, so the amount limit cannot be checked".into(),
+                );
+            } else if preview.payment_total_zatoshis() > max {
+                violations.push(format!(
+                    "pays {} zats, over the {max} zats limit",
+                    preview.payment_total_zatoshis()
+                ));
+            }
+        }
+        if let Some(max) = self.max_fee_zatoshis {
+            match preview.fee_zatoshis {
+                Some(fee) if fee > max => {
+                    violations.push(format!("fee of {fee} zats is over the {max} zats limit"))
+                }
+                Some(_) => {}
+                None => {
+                    violations.push("fee is unknown, so the fee limit cannot be checked".into())
+                }
+            }
+        }
+        if self.address_book_only {
+            for output in payments
+                .iter()
+                .filter(|o| o.contact.is_none() && !o.is_ours)
+            {
+                violations.push(format!(
+                    "action {} pays {}, which is not in the address book",
+                    output.action_index,
+                    output.recipient.as_deref().unwrap_or("a hidden recipient")
+                ));
+            }
+        }
+        violations
+    }
+}
+
+/// A decoded PCZT with its policy check.
+#[derive(Debug, Clone, Serialize)]
+pub struct PcztInspection {
+    #[serde(flatten)]
+    pub preview: PcztSigningPreview,
+    pub policy_violations: Vec<String>,
+}
+
+impl PcztInspection {
+    /// Refuse to sign when the policy is broken.
+    pub fn enforce_policy(&self) -> NozyResult<()> {
+        if self.policy_violations.is_empty() {
+            return Ok(());
+        }
+        Err(NozyError::InvalidOperation(format!(
+            "PCZT breaks the signing policy: {}",
+            self.policy_violations.join("; ")
+        )))
+    }
+}
+
+fn parse_pczt(pczt_bytes: &[u8]) -> NozyResult<Pczt> {
+    Pczt::parse(pczt_bytes)
+        .map_err(|e| NozyError::InvalidOperation(format!("PCZT parse failed: {e:?}")))
+}
+
+/// PCZT bytes from any transport the online instance may produce: a raw `.pczt` file,
+/// hex, a Keystone prepared-send JSON, or `ur:zcash-pczt/...` frames one per line.
+pub fn read_pczt_transport(data: &[u8]) -> NozyResult<Vec<u8>> {
+    if Pczt::parse(data).is_ok() {
+        return Ok(data.to_vec());
+    }
+    let text = std::str::from_utf8(data)
+        .map_err(|_| NozyError::InvalidInput("Not a PCZT, hex, JSON or UR frames".into()))?
+        .trim();
+    let pczt_bytes = if text.get(..3).is_some_and(|p| p.eq_ignore_ascii_case("ur:")) {
+        let frames: Vec<String> = text
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .map(str::to_string)
+            .collect();
+        decode_pczt_ur_frames(&frames)?
+    } else if text.starts_with('{') {
+        let prepared: KeystonePreparedSend = serde_json::from_str(text)
+            .map_err(|e| NozyError::InvalidInput(format!("Not a prepared PCZT send: {e}")))?;
+        hex::decode(prepared.pczt_hex.trim())
+            .map_err(|e| NozyError::InvalidInput(format!("Invalid PCZT hex: {e}")))?
+    } else {
+        hex::decode(text).map_err(|e| NozyError::InvalidInput(format!("Invalid PCZT hex: {e}")))?
+    };
+    parse_pczt(&pczt_bytes)?;
+    Ok(pczt_bytes)
+}
+
+/// Decode the spends and outputs of a PCZT as seen by the holder of `fvk`.
+pub fn preview_pczt(
+    pczt_bytes: &[u8],
+    fvk: &FullViewingKey,
+    network: NetworkType,
+) -> NozyResult<PcztSigningPreview> {
+    let pczt = parse_pczt(pczt_bytes)?;
+    if *pczt.global().coin_type() != network.coin_type() {
+        return Err(NozyError::InvalidInput(
+            "PCZT was built for a different network".into(),
+        ));
+    }
+
+    let mut spend_count = 0;
+    let mut spend_total: Option<u64> = Some(0);
+    let mut output_total: Option<u64> = Some(0);
+    let mut spends = Vec::new();
+    let mut outputs = Vec::new();
+
+    // Transparent and Sapling value must show up too: otherwise it would pass as fee and
+    // slip past the amount and address book limits.
+    for (index, input) in pczt.transparent().inputs().iter().enumerate() {
+        spend_count += 1;
+        spend_total = spend_total.map(|t| t.saturating_add(*input.value()));
+        spends.push(PcztSpendView {
+            pool: PcztValuePool::Transparent,
+            action_index: index,
+            value_zatoshis: Some(*input.value()),
+            signed: !input.partial_signatures().is_empty(),
+        });
+    }
+    for (index, output) in pczt.transparent().outputs().iter().enumerate() {
+        let value = *output.value();
+        output_total = output_total.map(|t| t.saturating_add(value));
+        outputs.push(PcztOutputView {
+            pool: PcztValuePool::Transparent,
+            action_index: index,
+            recipient: transparent_script_address(output.script_pubkey(), network),
+            value_zatoshis: Some(value),
+            is_change: false,
+            is_ours: false,
+            contact: None,
+        });
+    }
+    for (index, spend) in pczt.sapling().spends().iter().enumerate() {
+        let value = *spend.value();
+        spend_total = spend_total.zip(value).map(|(a, b)| a.saturating_add(b));
+        if value != Some(0) {
+            spend_count += 1;
+            spends.push(PcztSpendView {
+                pool: PcztValuePool::Sapling,
+                action_index: index,
+                value_zatoshis: value,
+                signed: spend.spend_auth_sig().is_some(),
+            });
+        }
+    }
+    for (index, output) in pczt.sapling().outputs().iter().enumerate() {
+        let value = *output.value();
+        output_total = output_total.zip(value).map(|(a, b)| a.saturating_add(b));
+        if value == Some(0) {
+            continue;
+        }
+        outputs.push(PcztOutputView {
+            pool: PcztValuePool::Sapling,
+            action_index: index,
+            recipient: (*output.recipient())
+                .map(|raw| ZcashAddress::from_sapling(network, raw).encode()),
+            value_zatoshis: value,
+            is_change: false,
+            is_ours: false,
+            contact: None,
+        });
+    }
+
+    let orchard_count = pczt.orchard().actions().len();
+    let actions = pczt
+        .orchard()
+        .actions()
+        .iter()
+        .chain(pczt.ironwood().actions());
+    for (action_index, action) in actions.enumerate() {
+        let pool = if action_index < orchard_count {
+            PcztValuePool::Orchard
+        } else {
+            PcztValuePool::Ironwood
+        };
+        let spend_value = *action.spend().value();
+        if spend_value != Some(0) {
+            spend_count += 1;
+            spends.push(PcztSpendView {
+                pool,
+                action_index,
+                value_zatoshis: spend_value,
+                signed: action.spend().spend_auth_sig().is_some(),
+            });
+        }
+        spend_total = spend_total
+            .zip(spend_value)
+            .map(|(a, b)| a.saturating_add(b));
+
+        let value = *action.output().value();
+        output_total = output_total.zip(value).map(|(a, b)| a.saturating_add(b));
+        let address = (*action.output().recipient()).and_then(|raw| {
+            Option::<orchard::Address>::from(orchard::Address::from_raw_address_bytes(&raw))
+        });
+        let scope = address.as_ref().and_then(|a| fvk.scope_for_address(a));
+        if value == Some(0) && scope.is_none() {
+            // Padding output added by the builder.
+            continue;
+        }
+        outputs.push(PcztOutputView {
+            pool,
+            action_index,
+            recipient: address
+                .map(|a| encode_orchard_receiver(&a, network))
+                .transpose()?,
+            value_zatoshis: value,
+            is_change: scope == Some(Scope::Internal),
+            is_ours: scope.is_some(),
+            contact: None,
+        });
+    }
+
+    Ok(PcztSigningPreview {
+        expiry_height: *pczt.global().expiry_height(),
+        spend_count,
+        spend_total_zatoshis: spend_total.unwrap_or(0),
+        spends,
+        outputs,
+        fee_zatoshis: spend_total
+            .zip(output_total)
+            .and_then(|(s, o)| s.checked_sub(o)),
+    })
+}
+
+/// `t1…` / `t3…` address paid by a P2PKH or P2SH script, if it is one of those.
+fn transparent_script_address(script: &[u8], network: NetworkType) -> Option<String> {
+    match script {
+        [0x76, 0xa9, 0x14, hash @ .., 0x88, 0xac] if hash.len() == 20 => {
+            Some(ZcashAddress::from_transparent_p2pkh(network, hash.try_into().ok()?).encode())
+        }
+        [0xa9, 0x14, hash @ .., 0x87] if hash.len() == 20 => {
+            Some(ZcashAddress::from_transparent_p2sh(network, hash.try_into().ok()?).encode())
+        }
+        _ => None,
+    }
+}
+
+/// Name each output's recipient from `contacts`: shielded outputs match on the Orchard
+/// receiver, transparent and Sapling ones on the exact address.
+fn label_contacts(preview: &mut PcztSigningPreview, contacts: &[AddressEntry]) {
+    let known: Vec<([u8; 43], &str)> = contacts
+        .iter()
+        .filter_map(|c| Some((orchard_receiver(&c.address).ok()?, c.name.as_str())))
+        .collect();
+    for output in &mut preview.outputs {
+        let Some(recipient) = output.recipient.as_deref() else {
+            continue;
+        };
+        output.contact = match output.pool {
+            PcztValuePool::Orchard | PcztValuePool::Ironwood => {
+                let Ok(receiver) = orchard_receiver(recipient) else {
+                    continue;
+                };
+                known
+                    .iter()
+                    .find(|(raw, _)| *raw == receiver)
+                    .map(|(_, name)| name.to_string())
+            }
+            PcztValuePool::Transparent | PcztValuePool::Sapling => contacts
+                .iter()
+                .find(|c| c.address.trim() == recipient)
+                .map(|c| c.name.clone()),
+        };
+    }
+}
+
+/// [`preview_pczt`] with address book names and the result of `policy`.
+pub fn inspect_pczt(
+    pczt_bytes: &[u8],
+    fvk: &FullViewingKey,
+    network: NetworkType,
+    policy: &PcztPolicy,
+) -> NozyResult<PcztInspection> {
+    let mut preview = preview_pczt(pczt_bytes, fvk, network)?;
+    label_contacts(&mut preview, &AddressBook::new()?.list_addresses());
+    let policy_violations = policy.violations(&preview);
+    Ok(PcztInspection {
+        preview,
+        policy_violations,
+    })
+}
+
+/// [`inspect_pczt`] with the viewing key of the wallet's signing account.
+pub fn inspect_pczt_for_wallet(
+    pczt_bytes: &[u8],
+    wallet: &HDWallet,
+    network: NetworkType,
+    policy: &PcztPolicy,
+) -> NozyResult<PcztInspection> {
+    let fvk = FullViewingKey::from(&orchard_spending_key_from_wallet(wallet)?);
+    inspect_pczt(pczt_bytes, &fvk, network, policy)
+}
+
+/// Nullifiers of every Orchard and Ironwood spend in a PCZT.
+pub fn pczt_spend_nullifiers(pczt_bytes: &[u8]) -> NozyResult<Vec<[u8; 32]>> {
+    let pczt = parse_pczt(pczt_bytes)?;
+    Ok(pczt
+        .orchard()
+        .actions()
+        .iter()
+        .chain(pczt.ironwood().actions())
+        .map(|a| *a.spend().nullifier())
+        .collect())
+}
+
+/// Extract and broadcast a signed PCZT, then record it against its signing request.
+///
+/// Once Zebra accepts the transaction the txid is returned; a bookkeeping failure is only
+/// reported as a warning so the caller never rebroadcasts a sent transaction.
+pub async fn broadcast_signed_pczt(zebra: &ZebraClient, pczt_bytes: &[u8]) -> NozyResult<String> {
+    let extracted = extract_signed_tx_from_pczt_bytes(pczt_bytes)?;
+    let txid = zebra
+        .broadcast_transaction(&hex::encode(&extracted.raw_transaction))
+        .await?;
+    if let Err(e) = record_signed_pczt_broadcast(pczt_bytes, &txid) {
+        eprintln!("Warning: could not record signed PCZT broadcast {txid}: {e}");
+    }
+    Ok(txid)
+}
+
+/// Mark the notes a broadcast PCZT spends and record it against its signing request.
+///
+/// Notes are matched by the nullifiers the PCZT reveals, so multi-note and Ironwood sends
+/// are recorded without a rescan. The request leaves the [`crate::signing_queue`] last:
+/// until the notes are marked spent, its lock keeps them out of new sends.
+pub fn record_signed_pczt_broadcast(pczt_bytes: &[u8], txid: &str) -> NozyResult<()> {
+    let nullifiers: Vec<String> = pczt_spend_nullifiers(pczt_bytes)?
+        .iter()
+        .map(hex::encode)
+        .collect();
+    let expiry_height = *parse_pczt(pczt_bytes)?.global().expiry_height();
+    mark_wallet_notes_spent_by_nullifier_hex(&nullifiers, Some(txid))?;
+
+    let Some(request) = find_signing_request_for_pczt(pczt_bytes)? else {
+        return Ok(());
+    };
+    let spent: Vec<String> = load_wallet_notes()?
+        .iter()
+        .map(|n| hex::encode(&n.nullifier_bytes))
+        .filter(|nf| nullifiers.contains(nf))
+        .collect();
+    let prepared = request.send.clone();
+    let mut record = SentTransactionRecord::new_pilot(
+        txid.to_string(),
+        prepared.recipient,
+        prepared.amount_zatoshis,
+        prepared.fee_zatoshis,
+        None,
+        spent,
+        NOZY_WALLET_PRIORITY_FEE,
+        expiry_height,
+    );
+    record.mark_broadcast();
+    SentTransactionStorage::new()?.save_transaction(record)?;
+    complete_signing_request(&request.id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keystone::encode_pczt_ur_frames;
+
+    #[test]
+    fn transport_rejects_non_pczt_payloads() {
+        assert!(read_pczt_transport(b"not a pczt").is_err());
+        assert!(read_pczt_transport(b"deadbeef").is_err());
+        let frames = encode_pczt_ur_frames(b"not a pczt either", 0).unwrap();
+        assert!(read_pczt_transport(frames.join("\n").as_bytes()).is_err());
+    }
+
+    fn payment(value: Option<u64>, contact: Option<&str>) -> PcztOutputView {
+        PcztOutputView {
+            pool: PcztValuePool::Orchard,
+            action_index: 0,
+            recipient: Some("u1recipient".into()),
+            value_zatoshis: value,
+            is_change: false,
+            is_ours: false,
+            contact: contact.map(str::to_string),
+        }
+    }
+
+    fn preview(outputs: Vec<PcztOutputView>, fee: Option<u64>) -> PcztSigningPreview {
+        PcztSigningPreview {
+            expiry_height: 3_000_000,
+            spend_count: 1,
+            spend_total_zatoshis: 0,
+            spends: Vec::new(),
+            outputs,
+            fee_zatoshis: fee,
+        }
+    }
+
+    #[test]
+    fn policy_limits_amount_fee_and_recipients() {
+        let policy = PcztPolicy {
+            max_amount_zatoshis: Some(100_000),
+            max_fee_zatoshis: Some(20_000),
+            address_book_only: true,
+        };
+        let ok = preview(vec![payment(Some(100_000), Some("alice"))], Some(15_000));
+        assert!(policy.violations(&ok).is_empty());
+
+        let over = preview(vec![payment(Some(100_001), None)], Some(20_001));
+        assert_eq!(policy.violations(&over).len(), 3);
+
+        let hidden = preview(vec![payment(None, Some("alice"))], None);
+        assert_eq!(policy.violations(&hidden).len(), 2);
+        assert!(PcztPolicy::default().violations(&hidden).is_empty());
+    }
+
+    #[test]
+    fn transparent_outputs_are_payments_not_fee() {
+        use orchard::keys::SpendingKey;
+        use zcash_transparent::address::TransparentAddress;
+
+        let fvk = FullViewingKey::from(&SpendingKey::from_bytes([3u8; 32]).unwrap());
+        let change = fvk.address_at(0u32, Scope::Internal);
+        let (pczt, _) = crate::tests::unproven_test_pczt(
+            &fvk,
+            200_000,
+            &[(change, 40_000)],
+            &[(TransparentAddress::PublicKeyHash([9u8; 20]), 150_000)],
+        );
+        let preview = preview_pczt(&pczt.serialize().unwrap(), &fvk, NetworkType::Test).unwrap();
+
+        assert_eq!(preview.fee_zatoshis, Some(10_000));
+        assert_eq!(preview.payment_total_zatoshis(), 150_000);
+        let payment = preview
+            .outputs
+            .iter()
+            .find(|o| o.pool == PcztValuePool::Transparent)
+            .unwrap();
+        assert_eq!(
+            payment.recipient.as_deref(),
+            Some(
+                ZcashAddress::from_transparent_p2pkh(NetworkType::Test, [9u8; 20])
+                    .encode()
+                    .as_str()
+            )
+        );
+
+        let policy = PcztPolicy {
+            max_amount_zatoshis: Some(100_000),
+            max_fee_zatoshis: None,
+            address_book_only: true,
+        };
+        assert_eq!(policy.violations(&preview).len(), 2);
+    }
+
+    #[test]
+    fn broadcast_marks_notes_spent_and_completes_the_request() {
+        use crate::keystone::{
+            prepared_send_from_build, sign_pczt_orchard_spends, KeystonePcztBuild,
+        };
+        use crate::notes::{save_wallet_notes, SerializableOrchardNote};
+        use crate::notes_vault::{clear_notes_vault, unlock_notes_vault};
+        use crate::paths::with_wallet_data_dir;
+        use crate::signing_queue::enqueue_signing_request;
+        use orchard::keys::SpendingKey;
+
+        let _g = crate::notes_vault::lock_notes_vault_for_test();
+        let dir =
+            std::env::temp_dir().join(format!("nozy-cold-broadcast-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let sk = SpendingKey::from_bytes([5u8; 32]).unwrap();
+        let fvk = FullViewingKey::from(&sk);
+        let recipient = FullViewingKey::from(&SpendingKey::from_bytes([6u8; 32]).unwrap())
+            .address_at(0u32, Scope::External);
+        let (pczt, note) =
+            crate::tests::unproven_test_pczt(&fvk, 200_000, &[(recipient, 150_000)], &[]);
+        let pczt_bytes = pczt.serialize().unwrap();
+        let nullifier = note.nullifier(&fvk).to_bytes();
+
+        let preview = preview_pczt(&pczt_bytes, &fvk, NetworkType::Test).unwrap();
+        assert_eq!(preview.spend_total_zatoshis, 200_000);
+        assert_eq!(preview.payment_total_zatoshis(), 150_000);
+        assert_eq!(preview.fee_zatoshis, Some(50_000));
+
+        with_wallet_data_dir(&dir, || {
+            clear_notes_vault();
+            unlock_notes_vault("cold").unwrap();
+            save_wallet_notes(&[SerializableOrchardNote {
+                note_bytes: vec![1],
+                value: 200_000,
+                address_bytes: vec![0; 43],
+                nullifier_bytes: nullifier.to_vec(),
+                block_height: 3_000_000,
+                txid: "ab".repeat(32),
+                spent: false,
+                memo: vec![],
+                orchard_incremental_witness_hex: None,
+                orchard_witness_tip_height: None,
+                ironwood_incremental_witness_hex: None,
+                ironwood_witness_tip_height: None,
+                rho_bytes: None,
+                rseed_bytes: None,
+                spent_in_txid: None,
+                pool: crate::shielded_pool::ShieldedPool::Orchard,
+                frozen: false,
+            }])
+            .unwrap();
+            let build = KeystonePcztBuild {
+                pczt_bytes: pczt_bytes.clone(),
+                summary: "test send".to_string(),
+                action_count: 2,
+                fee_zatoshis: 50_000,
+            };
+            enqueue_signing_request(prepared_send_from_build("u1recipient", 150_000, &build))
+                .unwrap();
+        });
+
+        // An unsigned PCZT fails extraction before anything reaches Zebra.
+        let zebra = ZebraClient::new("http://127.0.0.1:9".to_string());
+        let unsigned = with_wallet_data_dir(&dir, || {
+            futures::executor::block_on(broadcast_signed_pczt(&zebra, &pczt_bytes))
+        });
+        assert!(unsigned.is_err());
+
+        let signed = sign_pczt_orchard_spends(&pczt_bytes, &sk).unwrap();
+        let txid = "cd".repeat(32);
+        with_wallet_data_dir(&dir, || {
+            assert!(find_signing_request_for_pczt(&signed).unwrap().is_some());
+            record_signed_pczt_broadcast(&signed, &txid).unwrap();
+
+            let notes = load_wallet_notes().unwrap();
+            assert!(notes[0].spent);
+            assert_eq!(notes[0].spent_in_txid.as_deref(), Some(txid.as_str()));
+            let record = SentTransactionStorage::new()
+                .unwrap()
+                .get_transaction(&txid)
+                .unwrap();
+            assert_eq!(record.spent_note_ids, vec![hex::encode(nullifier)]);
+            assert_eq!(record.amount_zatoshis, 150_000);
+            assert!(find_signing_request_for_pczt(&signed).unwrap().is_none());
+            clear_notes_vault();
+        });
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -116,9 +116,34 @@ pub struct WalletConfig {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub business_display_name: Option<String>,
 
-    /// Linked Zcash name (bare, no `.zcash` suffix). Claimed externally; linked in Nozy.
+    /// Linked Zcash name (bare, no `.zcash` suffix). Registered with `nozy zns register` or
+    /// claimed externally, then linked.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub linked_zns_name: Option<String>,
+
+    /// Pinned ZNS registry address and cost ceiling for in-wallet registration.
+    #[serde(default)]
+    pub zns_registration: crate::zns_registration::ZnsRegistrationConfig,
+
+    /// Fiat valuation: display currency and price source (CSV table or HTTP via proxy).
+    #[serde(default)]
+    pub fiat: crate::price_source::FiatConfig,
+
+    /// Coin control: notes tagged with any of these (e.g. `cold`) are never selected for sends.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub never_spend_tags: Vec<String>,
+
+    /// Signed invoice-status webhooks (global endpoint and HMAC secret).
+    #[serde(default)]
+    pub merchant_webhooks: crate::merchant_webhooks::WebhookConfig,
+
+    /// Merchant invoice defaults (payment tolerance).
+    #[serde(default)]
+    pub merchant_invoices: crate::merchant_invoices::InvoiceConfig,
+
+    /// Limits checked before signing a PCZT (cold signing, FROST, desktop co-sign).
+    #[serde(default)]
+    pub pczt_policy: crate::cold_signing::PcztPolicy,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -282,6 +307,12 @@ impl Default for WalletConfig {
             active_role: WalletRole::Personal,
             business_display_name: None,
             linked_zns_name: None,
+            zns_registration: crate::zns_registration::ZnsRegistrationConfig::default(),
+            fiat: crate::price_source::FiatConfig::default(),
+            never_spend_tags: Vec::new(),
+            merchant_webhooks: crate::merchant_webhooks::WebhookConfig::default(),
+            merchant_invoices: crate::merchant_invoices::InvoiceConfig::default(),
+            pczt_policy: crate::cold_signing::PcztPolicy::default(),
         }
     }
 }
@@ -0,0 +1,627 @@
+//! Local cost-basis engine and realized-gains report.
+//!
+//! Lots are external deposits (one per received note, keyed by nullifier hex) priced
+//! with the historical fiat value attached to the history row; disposals are confirmed
+//! sends. The fee is disposed of together with the payment but only the payment earns
+//! proceeds, so fees show up as a loss equal to their basis. Nothing leaves the machine:
+//! inputs are `SentTransactionStorage`, `notes.json` and the configured price source.
+
+use crate::error::{NozyError, NozyResult};
+use crate::paths::get_wallet_data_dir;
+use crate::price_source::{apply_fiat_values, configured_price_source, normalize_currency_code};
+use crate::transaction_history::{
+    collect_wallet_transaction_views, enrich_block_times_for_views, TransactionType,
+    TransactionView,
+};
+use crate::zebra_integration::ZebraClient;
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+pub const COST_BASIS_OVERRIDES_FILE: &str = "cost_basis_overrides.json";
+
+/// Holding period after which a gain counts as long-term.
+pub const LONG_TERM_HOLDING_DAYS: i64 = 365;
+
+const ZATOSHIS_PER_ZEC: f64 = 100_000_000.0;
+
+pub const COST_BASIS_CSV_HEADER: &str =
+    "disposed_at,txid,note_id,acquired_at,amount_zec,proceeds,cost_basis,gain,term";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum CostBasisMethod {
+    #[default]
+    Fifo,
+    Lifo,
+    /// Consume the notes the transaction actually spent, then fall back to FIFO.
+    SpecificId,
+}
+
+impl CostBasisMethod {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CostBasisMethod::Fifo => "fifo",
+            CostBasisMethod::Lifo => "lifo",
+            CostBasisMethod::SpecificId => "specific_id",
+        }
+    }
+}
+
+impl FromStr for CostBasisMethod {
+    type Err = NozyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().replace('-', "_").as_str() {
+            "fifo" => Ok(CostBasisMethod::Fifo),
+            "lifo" => Ok(CostBasisMethod::Lifo),
+            "specific_id" | "specific" | "spec_id" => Ok(CostBasisMethod::SpecificId),
+            other => Err(NozyError::InvalidInput(format!(
+                "Unknown cost-basis method '{other}' (expected fifo, lifo or specific-id)"
+            ))),
+        }
+    }
+}
+
+/// Manual cost basis for one incoming note (e.g. coins bought off-exchange).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CostBasisOverride {
+    pub note_id: String,
+    pub currency: String,
+    /// Total fiat cost of the whole note.
+    pub cost_basis: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub acquired_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+/// Per-profile override store (`cost_basis_overrides.json` in the wallet data dir).
+pub struct CostBasisOverrides {
+    storage_path: PathBuf,
+    overrides: HashMap<String, CostBasisOverride>,
+}
+
+impl CostBasisOverrides {
+    pub fn new() -> NozyResult<Self> {
+        Self::with_path(get_wallet_data_dir().join(COST_BASIS_OVERRIDES_FILE))
+    }
+
+    pub fn with_path(storage_path: PathBuf) -> NozyResult<Self> {
+        let overrides = if storage_path.exists() {
+            let content = fs::read_to_string(&storage_path).map_err(|e| {
+                NozyError::Storage(format!("Failed to read cost-basis overrides: {}", e))
+            })?;
+            serde_json::from_str(&content).map_err(|e| {
+                NozyError::Storage(format!("Failed to parse cost-basis overrides: {}", e))
+            })?
+        } else {
+            HashMap::new()
+        };
+        Ok(Self {
+            storage_path,
+            overrides,
+        })
+    }
+
+    pub fn get(&self, note_id: &str) -> Option<&CostBasisOverride> {
+        self.overrides.get(note_id)
+    }
+
+    pub fn list(&self) -> Vec<CostBasisOverride> {
+        let mut all: Vec<_> = self.overrides.values().cloned().collect();
+        all.sort_by(|a, b| a.note_id.cmp(&b.note_id));
+        all
+    }
+
+    pub fn set(&mut self, entry: CostBasisOverride) -> NozyResult<()> {
+        if !entry.cost_basis.is_finite() || entry.cost_basis < 0.0 {
+            return Err(NozyError::InvalidInput(
+                "Cost basis must be a non-negative amount".to_string(),
+            ));
+        }
+        let mut entry = entry;
+        entry.note_id = entry.note_id.trim().to_ascii_lowercase();
+        entry.currency = normalize_currency_code(&entry.currency)?;
+        self.overrides.insert(entry.note_id.clone(), entry);
+        self.save()
+    }
+
+    pub fn remove(&mut self, note_id: &str) -> NozyResult<bool> {
+        let removed = self
+            .overrides
+            .remove(&note_id.trim().to_ascii_lowercase())
+            .is_some();
+        if removed {
+            self.save()?;
+        }
+        Ok(removed)
+    }
+
+    fn save(&self) -> NozyResult<()> {
+        if let Some(parent) = self.storage_path.parent() {
+            fs::create_dir_all(parent).map_err(|e| {
+                NozyError::Storage(format!("Failed to create storage directory: {}", e))
+            })?;
+        }
+        let content = serde_json::to_string_pretty(&self.overrides).map_err(|e| {
+            NozyError::Storage(format!("Failed to serialize cost-basis overrides: {}", e))
+        })?;
+        fs::write(&self.storage_path, content)
+            .map_err(|e| NozyError::Storage(format!("Failed to write cost-basis overrides: {}", e)))
+    }
+}
+
+/// Parse a `YYYY-MM-DD` acquisition day (midnight UTC) for an override.
+pub fn parse_acquired_date(day: &str) -> NozyResult<DateTime<Utc>> {
+    NaiveDate::parse_from_str(day.trim(), "%Y-%m-%d")
+        .ok()
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .map(|d| d.and_utc())
+        .ok_or_else(|| {
+            NozyError::InvalidInput(format!("Invalid date '{day}' (expected YYYY-MM-DD)"))
+        })
+}
+
+/// One incoming note available for disposal.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AcquisitionLot {
+    pub note_id: String,
+    pub txid: String,
+    pub acquired_at: DateTime<Utc>,
+    pub zatoshis: u64,
+    /// Total fiat cost of the whole lot; `None` when no price was available.
+    pub cost_basis: Option<f64>,
+    pub overridden: bool,
+}
+
+/// A confirmed send: payment plus fee leave the wallet.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Disposal {
+    pub txid: String,
+    pub disposed_at: DateTime<Utc>,
+    pub amount_zatoshis: u64,
+    pub fee_zatoshis: u64,
+    /// Fiat value of `amount_zatoshis` at disposal.
+    pub proceeds: Option<f64>,
+    pub spent_note_ids: Vec<String>,
+}
+
+/// Part of a disposal matched against part of one lot.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RealizedGain {
+    pub txid: String,
+    pub disposed_at: DateTime<Utc>,
+    pub note_id: String,
+    pub acquired_at: DateTime<Utc>,
+    pub zatoshis: u64,
+    pub proceeds: Option<f64>,
+    pub cost_basis: Option<f64>,
+    pub gain: Option<f64>,
+    pub long_term: bool,
+}
+
+/// Part of a disposal with no earlier lot to match.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UnmatchedDisposal {
+    pub txid: String,
+    pub disposed_at: DateTime<Utc>,
+    pub zatoshis: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CostBasisReport {
+    pub year: i32,
+    pub currency: String,
+    pub method: CostBasisMethod,
+    pub entries: Vec<RealizedGain>,
+    pub total_proceeds: f64,
+    pub total_cost_basis: f64,
+    pub short_term_gain: f64,
+    pub long_term_gain: f64,
+    pub total_gain: f64,
+    /// Entries left out of the totals because a price was missing.
+    pub unpriced_entries: usize,
+    /// Zatoshis disposed of in `year` with no earlier lot to match (history incomplete).
+    pub unmatched_zatoshis: u64,
+}
+
+fn round_cents(v: f64) -> f64 {
+    (v * 100.0).round() / 100.0
+}
+
+fn share(total: Option<f64>, part: u64, whole: u64) -> Option<f64> {
+    if whole == 0 {
+        return total.map(|_| 0.0);
+    }
+    total.map(|t| t * part as f64 / whole as f64)
+}
+
+fn view_time(view: &TransactionView) -> Option<DateTime<Utc>> {
+    view.block_time
+        .or(Some(view.created_at))
+        .filter(|d| *d > DateTime::UNIX_EPOCH)
+}
+
+/// Lots from external deposits. `note_values` maps nullifier hex → zatoshis; a row
+/// whose notes are unknown becomes a single lot keyed by txid.
+pub fn lots_from_views(
+    views: &[TransactionView],
+    note_values: &HashMap<String, u64>,
+    overrides: &HashMap<String, CostBasisOverride>,
+    currency: &str,
+) -> Vec<AcquisitionLot> {
+    let mut lots = Vec::new();
+    for view in views {
+        if view.transaction_type != TransactionType::Received || view.net_amount_zatoshis <= 0 {
+            continue;
+        }
+        let Some(received_at) = view_time(view) else {
+            continue;
+        };
+        let price = view
+            .fiat_value
+            .as_ref()
+            .filter(|v| v.currency == currency)
+            .map(|v| v.price_per_zec);
+
+        let mut parts: Vec<(String, u64)> = view
+            .notes_involved
+            .iter()
+            .filter_map(|id| note_values.get(id).map(|value| (id.clone(), *value)))
+            .collect();
+        if parts.is_empty() {
+            parts.push((view.txid.clone(), view.net_amount_zatoshis as u64));
+        }
+
+        for (note_id, zatoshis) in parts {
+            let manual = overrides.get(&note_id).filter(|o| o.currency == currency);
+            lots.push(AcquisitionLot {
+                acquired_at: manual.and_then(|o| o.acquired_at).unwrap_or(received_at),
+                cost_basis: match manual {
+                    Some(o) => Some(o.cost_basis),
+                    None => price.map(|p| zatoshis as f64 / ZATOSHIS_PER_ZEC * p),
+                },
+                overridden: manual.is_some(),
+                note_id,
+                txid: view.txid.clone(),
+                zatoshis,
+            });
+        }
+    }
+    lots
+}
+
+/// Confirmed sends as disposals.
+pub fn disposals_from_views(views: &[TransactionView], currency: &str) -> Vec<Disposal> {
+    views
+        .iter()
+        .filter(|v| v.transaction_type == TransactionType::Sent && v.status.is_confirmed())
+        .filter_map(|v| {
+            let disposed_at = view_time(v)?;
+            Some(Disposal {
+                txid: v.txid.clone(),
+                disposed_at,
+                amount_zatoshis: v.net_amount_zatoshis.unsigned_abs(),
+                fee_zatoshis: v.fee_zatoshis.unwrap_or(0),
+                proceeds: v
+                    .fiat_value
+                    .as_ref()
+                    .filter(|f| f.currency == currency)
+                    .map(|f| f.amount.abs()),
+                spent_note_ids: v.notes_involved.clone(),
+            })
+        })
+        .collect()
+}
+
+/// Match disposals against lots in time order. Returns realized gains for every
+/// disposal plus the disposals that could not be matched in full to earlier lots.
+pub fn compute_realized_gains(
+    lots: &[AcquisitionLot],
+    disposals: &[Disposal],
+    method: CostBasisMethod,
+) -> (Vec<RealizedGain>, Vec<UnmatchedDisposal>) {
+    let mut remaining: Vec<u64> = lots.iter().map(|l| l.zatoshis).collect();
+    let mut fifo_order: Vec<usize> = (0..lots.len()).collect();
+    fifo_order.sort_by(|&a, &b| {
+        (lots[a].acquired_at, &lots[a].note_id).cmp(&(lots[b].acquired_at, &lots[b].note_id))
+    });
+
+    let mut disposals: Vec<&Disposal> = disposals.iter().collect();
+    disposals.sort_by(|a, b| (a.disposed_at, &a.txid).cmp(&(b.disposed_at, &b.txid)));
+
+    let mut gains = Vec::new();
+    let mut unmatched = Vec::new();
+
+    for disposal in disposals {
+        let total = disposal.amount_zatoshis + disposal.fee_zatoshis;
+        let mut left = total;
+
+        let available = |i: &usize| lots[*i].acquired_at <= disposal.disposed_at;
+        let mut order: Vec<usize> = match method {
+            CostBasisMethod::Fifo => fifo_order.iter().copied().filter(available).collect(),
+            CostBasisMethod::Lifo => fifo_order.iter().rev().copied().filter(available).collect(),
+            CostBasisMethod::SpecificId => {
+                let mut picked: Vec<usize> = disposal
+                    .spent_note_ids
+                    .iter()
+                    .filter_map(|id| lots.iter().position(|l| &l.note_id == id))
+                    .filter(available)
+                    .collect();
+                let rest: Vec<usize> = fifo_order
+                    .iter()
+                    .copied()
+                    .filter(available)
+                    .filter(|i| !picked.contains(i))
+                    .collect();
+                picked.extend(rest);
+                picked
+            }
+        };
+        order.retain(|i| remaining[*i] > 0);
+
+        for i in order {
+            if left == 0 {
+                break;
+            }
+            let lot = &lots[i];
+            let take = left.min(remaining[i]);
+            remaining[i] -= take;
+            left -= take;
+
+            // Only the payment part of `take` earns proceeds; the fee part is a cost.
+            let consumed_before = total - left - take;
+            let paid = disposal
+                .amount_zatoshis
+                .saturating_sub(consumed_before)
+                .min(take);
+            let proceeds = share(disposal.proceeds, paid, disposal.amount_zatoshis);
+            let cost_basis = share(lot.cost_basis, take, lot.zatoshis);
+            gains.push(RealizedGain {
+                txid: disposal.txid.clone(),
+                disposed_at: disposal.disposed_at,
+                note_id: lot.note_id.clone(),
+                acquired_at: lot.acquired_at,
+                zatoshis: take,
+                proceeds: proceeds.map(round_cents),
+                cost_basis: cost_basis.map(round_cents),
+                gain: proceeds.zip(cost_basis).map(|(p, c)| round_cents(p - c)),
+                long_term: (disposal.disposed_at - lot.acquired_at).num_days()
+                    > LONG_TERM_HOLDING_DAYS,
+            });
+        }
+        if left > 0 {
+            unmatched.push(UnmatchedDisposal {
+                txid: disposal.txid.clone(),
+                disposed_at: disposal.disposed_at,
+                zatoshis: left,
+            });
+        }
+    }
+
+    (gains, unmatched)
+}
+
+/// Summarise the gains realized in calendar year `year` (UTC).
+pub fn build_cost_basis_report(
+    gains: &[RealizedGain],
+    unmatched: &[UnmatchedDisposal],
+    year: i32,
+    currency: &str,
+    method: CostBasisMethod,
+) -> CostBasisReport {
+    let entries: Vec<RealizedGain> = gains
+        .iter()
+        .filter(|g| g.disposed_at.year() == year)
+        .cloned()
+        .collect();
+    let mut report = CostBasisReport {
+        year,
+        currency: currency.to_string(),
+        method,
+        entries: Vec::new(),
+        total_proceeds: 0.0,
+        total_cost_basis: 0.0,
+        short_term_gain: 0.0,
+        long_term_gain: 0.0,
+        total_gain: 0.0,
+        unpriced_entries: 0,
+        unmatched_zatoshis: unmatched
+            .iter()
+            .filter(|u| u.disposed_at.year() == year)
+            .map(|u| u.zatoshis)
+            .sum(),
+    };
+    for entry in &entries {
+        match (entry.proceeds, entry.cost_basis, entry.gain) {
+            (Some(p), Some(c), Some(g)) => {
+                report.total_proceeds += p;
+                report.total_cost_basis += c;
+                if entry.long_term {
+                    report.long_term_gain += g;
+                } else {
+                    report.short_term_gain += g;
+                }
+            }
+            _ => report.unpriced_entries += 1,
+        }
+    }
+    report.total_proceeds = round_cents(report.total_proceeds);
+    report.total_cost_basis = round_cents(report.total_cost_basis);
+    report.short_term_gain = round_cents(report.short_term_gain);
+    report.long_term_gain = round_cents(report.long_term_gain);
+    report.total_gain = round_cents(report.short_term_gain + report.long_term_gain);
+    report.entries = entries;
+    report
+}
+
+fn csv_money(v: Option<f64>) -> String {
+    v.map(|v| format!("{v:.2}")).unwrap_or_default()
+}
+
+pub fn render_cost_basis_csv(report: &CostBasisReport) -> String {
+    let mut out = String::from(COST_BASIS_CSV_HEADER);
+    out.push('\n');
+    for e in &report.entries {
+        out.push_str(&format!(
+            "{},{},{},{},{:.8},{},{},{},{}\n",
+            e.disposed_at.format("%Y-%m-%d"),
+            e.txid,
+            e.note_id,
+            e.acquired_at.format("%Y-%m-%d"),
+            e.zatoshis as f64 / ZATOSHIS_PER_ZEC,
+            csv_money(e.proceeds),
+            csv_money(e.cost_basis),
+            csv_money(e.gain),
+            if e.long_term { "long" } else { "short" },
+        ));
+    }
+    out
+}
+
+/// Load history for the active profile, price it and build the report for `year`.
+pub async fn wallet_cost_basis_report(
+    zebra_client: &ZebraClient,
+    year: i32,
+    method: CostBasisMethod,
+    currency: Option<&str>,
+) -> NozyResult<CostBasisReport> {
+    let config = crate::config::load_config();
+    let currency = normalize_currency_code(currency.unwrap_or(&config.fiat.display_currency))?;
+    if NaiveDate::from_ymd_opt(year, 1, 1).is_none() {
+        return Err(NozyError::InvalidInput(format!("Invalid year {year}")));
+    }
+
+    let current_height = zebra_client.get_block_count().await.unwrap_or(0);
+    let mut views = collect_wallet_transaction_views(current_height)?;
+    enrich_block_times_for_views(&mut views, zebra_client).await;
+    if let Some(source) = configured_price_source(&config)? {
+        apply_fiat_values(&mut views, source.as_ref(), &currency, false).await?;
+    }
+
+    let note_values: HashMap<String, u64> = crate::notes::load_wallet_notes()
+        .unwrap_or_default()
+        .iter()
+        .map(|n| (hex::encode(&n.nullifier_bytes), n.value))
+        .collect();
+    let overrides = CostBasisOverrides::new()?;
+    let overrides: HashMap<String, CostBasisOverride> = overrides
+        .list()
+        .into_iter()
+        .map(|o| (o.note_id.clone(), o))
+        .collect();
+
+    let lots = lots_from_views(&views, &note_values, &overrides, &currency);
+    let disposals = disposals_from_views(&views, &currency);
+    let (gains, unmatched) = compute_realized_gains(&lots, &disposals, method);
+    Ok(build_cost_basis_report(
+        &gains, &unmatched, year, &currency, method,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn day(y: i32, m: u32, d: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, m, d, 0, 0, 0).unwrap()
+    }
+
+    fn lot(id: &str, at: DateTime<Utc>, zec: u64, cost: f64) -> AcquisitionLot {
+        AcquisitionLot {
+            note_id: id.to_string(),
+            txid: format!("tx-{id}"),
+            acquired_at: at,
+            zatoshis: zec * 100_000_000,
+            cost_basis: Some(cost),
+            overridden: false,
+        }
+    }
+
+    fn sale(at: DateTime<Utc>, zec: u64, proceeds: f64, spent: &[&str]) -> Disposal {
+        Disposal {
+            txid: "sale".to_string(),
+            disposed_at: at,
+            amount_zatoshis: zec * 100_000_000,
+            fee_zatoshis: 0,
+            proceeds: Some(proceeds),
+            spent_note_ids: spent.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    fn lots() -> Vec<AcquisitionLot> {
+        vec![
+            lot("a", day(2024, 1, 1), 1, 20.0),
+            lot("b", day(2026, 2, 1), 1, 50.0),
+        ]
+    }
+
+    #[test]
+    fn fifo_and_lifo_pick_opposite_lots() {
+        let disposals = [sale(day(2026, 3, 1), 1, 60.0, &["b"])];
+        let (fifo, _) = compute_realized_gains(&lots(), &disposals, CostBasisMethod::Fifo);
+        assert_eq!(fifo[0].note_id, "a");
+        assert_eq!(fifo[0].gain, Some(40.0));
+        assert!(fifo[0].long_term);
+
+        let (lifo, _) = compute_realized_gains(&lots(), &disposals, CostBasisMethod::Lifo);
+        assert_eq!(lifo[0].note_id, "b");
+        assert_eq!(lifo[0].gain, Some(10.0));
+        assert!(!lifo[0].long_term);
+    }
+
+    #[test]
+    fn specific_id_uses_spent_notes_then_falls_back() {
+        let disposals = [sale(day(2026, 3, 1), 2, 100.0, &["b"])];
+        let (gains, unmatched) =
+            compute_realized_gains(&lots(), &disposals, CostBasisMethod::SpecificId);
+        assert!(unmatched.is_empty());
+        let ids: Vec<&str> = gains.iter().map(|g| g.note_id.as_str()).collect();
+        assert_eq!(ids, ["b", "a"]);
+        assert_eq!(gains[0].proceeds, Some(50.0));
+    }
+
+    #[test]
+    fn fee_is_a_cost_without_proceeds_and_report_sums_year() {
+        let mut disposal = sale(day(2026, 3, 1), 1, 60.0, &[]);
+        disposal.amount_zatoshis = 50_000_000;
+        disposal.fee_zatoshis = 50_000_000;
+        disposal.proceeds = Some(30.0);
+        let (gains, _) = compute_realized_gains(
+            &[lot("a", day(2026, 1, 1), 1, 40.0)],
+            &[disposal],
+            CostBasisMethod::Fifo,
+        );
+        assert_eq!(gains[0].proceeds, Some(30.0));
+        assert_eq!(gains[0].cost_basis, Some(40.0));
+
+        let report = build_cost_basis_report(&gains, &[], 2026, "USD", CostBasisMethod::Fifo);
+        assert_eq!(report.total_gain, -10.0);
+        assert!(
+            build_cost_basis_report(&gains, &[], 2025, "USD", CostBasisMethod::Fifo)
+                .entries
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn lots_before_disposal_only_and_unmatched_reported() {
+        let disposals = [sale(day(2025, 6, 1), 2, 10.0, &[])];
+        let (gains, unmatched) = compute_realized_gains(&lots(), &disposals, CostBasisMethod::Fifo);
+        assert_eq!(gains.len(), 1);
+        assert_eq!(unmatched.len(), 1);
+        assert_eq!(unmatched[0].zatoshis, 100_000_000);
+
+        let in_year =
+            build_cost_basis_report(&gains, &unmatched, 2025, "USD", CostBasisMethod::Fifo);
+        assert_eq!(in_year.unmatched_zatoshis, 100_000_000);
+        let other_year =
+            build_cost_basis_report(&gains, &unmatched, 2026, "USD", CostBasisMethod::Fifo);
+        assert_eq!(other_year.unmatched_zatoshis, 0);
+    }
+}
@@ -0,0 +1,443 @@
+//! Background sync daemon and wallet event bus.
+//!
+//! [`WalletDaemon`] is what `nozy daemon` and the api-server run in the background: each
+//! tick it syncs notes and witnesses to tip, refreshes pending transactions, expires stale
+//! pilot sends through [`crate::tx_lifecycle`], delivers merchant webhooks, runs due
+//! scheduled payments, and publishes a [`WalletEvent`] for everything that changed. Any
+//! task in the process can listen with [`subscribe_wallet_events`]; events are
+//! best-effort and dropped when nobody listens.
+
+use crate::config::load_config;
+use crate::error::NozyResult;
+use crate::hd_wallet::HDWallet;
+use crate::merchant_invoices::{list_invoices, InvoiceStatus};
+use crate::merchant_webhooks::deliver_due_webhooks;
+use crate::notes::load_wallet_notes;
+use crate::scheduled_payments::{run_due_payments, PaymentRun};
+use crate::shielded_pool::ShieldedPool;
+use crate::transaction_history::{SentTransactionStorage, TransactionStatus};
+use crate::tx_lifecycle::expire_stale_pending_transactions;
+use crate::wallet_sync::{sync_wallet_notes, WalletSyncOptions};
+use crate::zebra_integration::ZebraClient;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::sync::OnceLock;
+use std::time::Duration;
+use tokio::sync::broadcast;
+
+/// Default pause between daemon ticks (~one Zcash block is 75 s).
+pub const DEFAULT_DAEMON_INTERVAL_SECS: u64 = 30;
+
+/// Events buffered per subscriber before slow listeners start missing them.
+const EVENT_CHANNEL_CAPACITY: usize = 256;
+
+static WALLET_EVENTS: OnceLock<broadcast::Sender<WalletEvent>> = OnceLock::new();
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WalletEvent {
+    NewBlock {
+        height: u32,
+    },
+    SyncStarted {
+        chain_tip: u32,
+    },
+    /// Published by the note scanner whenever the scanned share of its range grows by a
+    /// percent, whoever started the scan.
+    ScanProgress {
+        height: u32,
+        start_height: u32,
+        end_height: u32,
+        percent: u8,
+    },
+    SyncCompleted {
+        chain_tip: u32,
+        blocks_scanned: u32,
+        new_notes: usize,
+        balance_zatoshis: u64,
+    },
+    SyncFailed {
+        error: String,
+    },
+    NoteReceived {
+        note_id: String,
+        txid: String,
+        value_zatoshis: u64,
+        block_height: u32,
+        pool: ShieldedPool,
+    },
+    TxConfirmed {
+        txid: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        block_height: Option<u32>,
+    },
+    TxExpired {
+        txid: String,
+    },
+    InvoiceStatusChanged {
+        invoice_id: String,
+        status: InvoiceStatus,
+    },
+    InvoicePaid {
+        invoice_id: String,
+        amount_zatoshis: u64,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        txid: Option<String>,
+    },
+    ScheduledPayment {
+        run: PaymentRun,
+    },
+}
+
+impl WalletEvent {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::NewBlock { .. } => "new_block",
+            Self::SyncStarted { .. } => "sync_started",
+            Self::ScanProgress { .. } => "scan_progress",
+            Self::SyncCompleted { .. } => "sync_completed",
+            Self::SyncFailed { .. } => "sync_failed",
+            Self::NoteReceived { .. } => "note_received",
+            Self::TxConfirmed { .. } => "tx_confirmed",
+            Self::TxExpired { .. } => "tx_expired",
+            Self::InvoiceStatusChanged { .. } => "invoice_status_changed",
+            Self::InvoicePaid { .. } => "invoice_paid",
+            Self::ScheduledPayment { .. } => "scheduled_payment",
+        }
+    }
+}
+
+fn wallet_event_sender() -> &'static broadcast::Sender<WalletEvent> {
+    WALLET_EVENTS.get_or_init(|| broadcast::channel(EVENT_CHANNEL_CAPACITY).0)
+}
+
+/// Listen for wallet events published anywhere in this process.
+pub fn subscribe_wallet_events() -> broadcast::Receiver<WalletEvent> {
+    wallet_event_sender().subscribe()
+}
+
+pub fn publish_wallet_event(event: WalletEvent) {
+    // Err only means there are no subscribers right now.
+    let _ = wallet_event_sender().send(event);
+}
+
+#[derive(Debug, Clone)]
+pub struct DaemonOptions {
+    pub interval: Duration,
+    pub zebra_url: Option<String>,
+    /// Send due [`crate::scheduled_payments`] each tick. Off by default: the daemon only
+    /// spends when explicitly asked to.
+    pub run_scheduled_payments: bool,
+}
+
+impl Default for DaemonOptions {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(DEFAULT_DAEMON_INTERVAL_SECS),
+            zebra_url: None,
+            run_scheduled_payments: false,
+        }
+    }
+}
+
+/// What one [`WalletDaemon::tick`] did.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DaemonTick {
+    pub chain_tip: u32,
+    pub synced: bool,
+    pub events: usize,
+}
+
+pub struct WalletDaemon {
+    wallet: HDWallet,
+    options: DaemonOptions,
+    last_tip: Option<u32>,
+}
+
+impl WalletDaemon {
+    pub fn new(wallet: HDWallet, options: DaemonOptions) -> Self {
+        Self {
+            wallet,
+            options,
+            last_tip: None,
+        }
+    }
+
+    pub fn options(&self) -> &DaemonOptions {
+        &self.options
+    }
+
+    pub fn last_tip(&self) -> Option<u32> {
+        self.last_tip
+    }
+
+    fn zebra_url(&self) -> String {
+        self.options
+            .zebra_url
+            .clone()
+            .unwrap_or_else(|| load_config().zebra_url)
+    }
+
+    /// One pass: sync on a new block, refresh pending / expired txs, invoices, payments.
+    pub async fn tick(&mut self) -> NozyResult<DaemonTick> {
+        let zebra_url = self.zebra_url();
+        let mut config = load_config();
+        config.zebra_url = zebra_url.clone();
+        let zebra_client = ZebraClient::from_config(&config);
+        let mut events = Vec::new();
+
+        let chain_tip = match zebra_client.get_block_count().await {
+            Ok(tip) => tip,
+            Err(e) => {
+                publish_wallet_event(WalletEvent::SyncFailed {
+                    error: e.to_string(),
+                });
+                return Err(e);
+            }
+        };
+        let new_block = self.last_tip.is_none_or(|last| chain_tip > last);
+        let invoices_before = invoice_statuses();
+
+        let mut synced = false;
+        if new_block {
+            events.push(WalletEvent::NewBlock { height: chain_tip });
+            let sync_events = self.sync_to_tip(&zebra_url, chain_tip).await;
+            synced = !sync_events
+                .iter()
+                .any(|e| matches!(e, WalletEvent::SyncFailed { .. }));
+            events.extend(sync_events);
+        }
+        events.extend(refresh_sent_transactions(&zebra_client).await?);
+        events.extend(invoice_events(&invoices_before, &invoice_statuses()));
+        if let Err(e) = deliver_due_webhooks().await {
+            eprintln!("Warning: merchant webhook delivery failed: {e}");
+        }
+
+        if self.options.run_scheduled_payments {
+            match run_due_payments(&self.wallet, &zebra_url).await {
+                Ok(runs) => events.extend(
+                    runs.into_iter()
+                        .map(|run| WalletEvent::ScheduledPayment { run }),
+                ),
+                Err(e) => eprintln!("Warning: scheduled payments not run: {e}"),
+            }
+        }
+
+        // A failed sync is retried next tick even if no new block arrives.
+        if synced || !new_block {
+            self.last_tip = Some(chain_tip);
+        }
+        let tick = DaemonTick {
+            chain_tip,
+            synced,
+            events: events.len(),
+        };
+        for event in events {
+            publish_wallet_event(event);
+        }
+        Ok(tick)
+    }
+
+    async fn sync_to_tip(&self, zebra_url: &str, chain_tip: u32) -> Vec<WalletEvent> {
+        publish_wallet_event(WalletEvent::SyncStarted { chain_tip });
+        let known: HashSet<Vec<u8>> = load_wallet_notes()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|n| n.nullifier_bytes)
+            .collect();
+        let options = WalletSyncOptions {
+            zebra_url: Some(zebra_url.to_string()),
+            ..WalletSyncOptions::to_tip()
+        };
+        let result = match sync_wallet_notes(&self.wallet, options).await {
+            Ok(result) => result,
+            Err(e) => {
+                return vec![WalletEvent::SyncFailed {
+                    error: e.to_string(),
+                }]
+            }
+        };
+
+        let mut events: Vec<WalletEvent> = load_wallet_notes()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|n| !n.spent && !known.contains(&n.nullifier_bytes))
+            .map(|n| WalletEvent::NoteReceived {
+                note_id: hex::encode(&n.nullifier_bytes),
+                txid: n.txid,
+                value_zatoshis: n.value,
+                block_height: n.block_height,
+                pool: n.pool,
+            })
+            .collect();
+        events.push(WalletEvent::SyncCompleted {
+            chain_tip: result.chain_tip,
+            blocks_scanned: result.blocks_scanned,
+            new_notes: result.new_notes_in_scan,
+            balance_zatoshis: result.balance_zatoshis,
+        });
+        events
+    }
+
+    /// Tick every `options.interval` until `shutdown` resolves. Tick errors are reported
+    /// as [`WalletEvent::SyncFailed`] and retried on the next tick.
+    pub async fn run_until<F: Future<Output = ()>>(mut self, shutdown: F) {
+        tokio::pin!(shutdown);
+        loop {
+            if let Err(e) = self.tick().await {
+                eprintln!("Warning: daemon tick failed: {e}");
+            }
+            tokio::select! {
+                _ = &mut shutdown => break,
+                _ = tokio::time::sleep(self.options.interval) => {}
+            }
+        }
+    }
+}
+
+/// Check pending sends for confirmation, expire stale ones, and report what changed.
+async fn refresh_sent_transactions(zebra_client: &ZebraClient) -> NozyResult<Vec<WalletEvent>> {
+    let pending: HashSet<String> = SentTransactionStorage::new()?
+        .get_all_transactions()
+        .into_iter()
+        .filter(|tx| tx.status == TransactionStatus::Pending)
+        .map(|tx| tx.txid)
+        .collect();
+    if pending.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let storage = SentTransactionStorage::new()?;
+    storage.check_all_pending_transactions(zebra_client).await?;
+    drop(storage);
+    expire_stale_pending_transactions(zebra_client).await?;
+
+    let events = SentTransactionStorage::new()?
+        .get_all_transactions()
+        .into_iter()
+        .filter(|tx| pending.contains(&tx.txid))
+        .filter_map(|tx| match tx.status {
+            TransactionStatus::Confirmed => Some(WalletEvent::TxConfirmed {
+                txid: tx.txid,
+                block_height: tx.block_height,
+            }),
+            TransactionStatus::Expired => Some(WalletEvent::TxExpired { txid: tx.txid }),
+            TransactionStatus::Pending | TransactionStatus::Failed => None,
+        })
+        .collect();
+    Ok(events)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct InvoiceSnapshot {
+    status: InvoiceStatus,
+    amount_zatoshis: u64,
+    txid: Option<String>,
+}
+
+fn invoice_statuses() -> HashMap<String, InvoiceSnapshot> {
+    list_invoices(usize::MAX)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|inv| {
+            (
+                inv.invoice_id,
+                InvoiceSnapshot {
+                    status: inv.status,
+                    amount_zatoshis: inv.paid_zatoshis,
+                    txid: inv.confirmed_txid.or(inv.detected_txid),
+                },
+            )
+        })
+        .collect()
+}
+
+fn invoice_events(
+    before: &HashMap<String, InvoiceSnapshot>,
+    after: &HashMap<String, InvoiceSnapshot>,
+) -> Vec<WalletEvent> {
+    let mut changed: Vec<(&String, &InvoiceSnapshot)> = after
+        .iter()
+        .filter(|(id, now)| before.get(*id).is_none_or(|was| was.status != now.status))
+        .collect();
+    changed.sort_by(|a, b| a.0.cmp(b.0));
+
+    let mut events = Vec::new();
+    for (id, now) in changed {
+        events.push(WalletEvent::InvoiceStatusChanged {
+            invoice_id: id.clone(),
+            status: now.status.clone(),
+        });
+        // Overpaid is only reached once every payment is confirmed.
+        if matches!(
+            now.status,
+            InvoiceStatus::Confirmed | InvoiceStatus::Overpaid
+        ) {
+            events.push(WalletEvent::InvoicePaid {
+                invoice_id: id.clone(),
+                amount_zatoshis: now.amount_zatoshis,
+                txid: now.txid.clone(),
+            });
+        }
+    }
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snap(status: InvoiceStatus) -> InvoiceSnapshot {
+        InvoiceSnapshot {
+            status,
+            amount_zatoshis: 5_000_000,
+            txid: Some("ab".to_string()),
+        }
+    }
+
+    #[test]
+    fn confirmed_invoice_emits_paid_event() {
+        let before = HashMap::from([("inv1".to_string(), snap(InvoiceStatus::Detected))]);
+        let after = HashMap::from([("inv1".to_string(), snap(InvoiceStatus::Confirmed))]);
+        let events = invoice_events(&before, &after);
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[1].kind(), "invoice_paid");
+        assert!(invoice_events(&after, &after).is_empty());
+
+        let overpaid = HashMap::from([("inv1".to_string(), snap(InvoiceStatus::Overpaid))]);
+        let events = invoice_events(&before, &overpaid);
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[1].kind(), "invoice_paid");
+    }
+
+    #[test]
+    fn events_serialize_with_type_tag() {
+        let json = serde_json::to_value(WalletEvent::TxExpired {
+            txid: "ff".to_string(),
+        })
+        .unwrap();
+        assert_eq!(json["type"], "tx_expired");
+        assert_eq!(json["txid"], "ff");
+
+        let progress = WalletEvent::ScanProgress {
+            height: 150,
+            start_height: 100,
+            end_height: 200,
+            percent: 50,
+        };
+        let json = serde_json::to_value(&progress).unwrap();
+        assert_eq!(json["type"], progress.kind());
+        assert_eq!(json["percent"], 50);
+    }
+
+    #[tokio::test]
+    async fn subscribers_receive_published_events() {
+        let mut rx = subscribe_wallet_events();
+        publish_wallet_event(WalletEvent::NewBlock { height: 7 });
+        assert_eq!(
+            rx.recv().await.unwrap(),
+            WalletEvent::NewBlock { height: 7 }
+        );
+    }
+}
@@ -76,7 +76,11 @@ pub struct OrchardSendFeeShape {
 impl OrchardSendFeeShape {
     /// Shape for `build_single_spend`: one spend, one recipient output, optional change output.
     pub fn single_spend_send(has_change: bool, memo: Option<&[u8]>) -> Self {
-        let spends = 1u32;
+        Self::multi_spend_send(1, has_change, memo)
+    }
+
+    /// Shape for a send spending `spends` notes to one recipient and optional change.
+    pub fn multi_spend_send(spends: u32, has_change: bool, memo: Option<&[u8]>) -> Self {
         let outputs = if has_change { 2 } else { 1 };
         Self {
             // An Orchard bundle has max(spends, outputs) actions (the shorter side
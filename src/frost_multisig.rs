@@ -0,0 +1,872 @@
+//! FROST (RedPallas) t-of-n threshold spend authorization for Orchard.
+//!
+//! The FROST group verifying key is the Orchard `ak`. `nk` and `rivk` come from the
+//! dealer, or in a DKG from a hash of seeds every participant sends the others with its
+//! private round 2 packages, so all share holders derive the same full viewing key
+//! ([`FrostShare::group_fvk`]) and nobody who only saw the round 1 broadcasts can. A
+//! profile of kind [`WalletProfileKind::FrostShare`] keeps one share in
+//! `frost_share.json`, encrypted with the notes vault key (NZF1).
+//!
+//! Signing a PCZT takes two rounds. Each signer commits to nonces for every unsigned
+//! Orchard spend ([`commit_to_pczt`]); a coordinator bundles `t` commitments with the
+//! PCZT ([`build_signing_request`]); each signer returns signature shares randomized by
+//! the spend's `alpha` ([`sign_request`]); [`aggregate_signatures`] writes the spend
+//! authorization signatures into the PCZT for extraction. Messages are JSON and travel as
+//! files or `nozy-frost` UR frames.
+//!
+//! DKG round 2 packages are secret to their recipient: move them over a private channel.
+//! Anyone who reads them learns the group viewing key, and with it every group payment.
+
+use crate::error::{NozyError, NozyResult};
+use crate::keystone::{unwrap_pczt_cbor, wrap_pczt_cbor};
+use crate::notes_vault::{decrypt_frost_file_content, encrypt_frost_json};
+use crate::ovk_recovery::encode_orchard_receiver;
+use crate::paths::get_wallet_data_dir;
+use crate::wallet_profiles::WalletProfileKind;
+use group::ff::{Field, FromUniformBytes, PrimeField};
+use orchard::keys::{FullViewingKey, Scope};
+use pasta_curves::pallas;
+use pczt::roles::signer::Signer;
+use pczt::Pczt;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use reddsa::frost::redpallas as frost;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use zcash_address::unified::{self, Encoding};
+use zcash_protocol::consensus::NetworkType;
+
+pub const FROST_SHARE_VERSION: u32 = 1;
+pub const UR_TYPE_NOZY_FROST: &str = "nozy-frost";
+const SHARE_FILE: &str = "frost_share.json";
+const NONCES_FILE: &str = "frost_nonces.json";
+const DKG_STATE_FILE: &str = "frost_dkg.json";
+
+/// One participant's share of a threshold Orchard key.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct FrostShare {
+    pub version: u32,
+    /// `mainnet` or `testnet`.
+    pub network: String,
+    pub identifier: u16,
+    pub min_signers: u16,
+    pub max_signers: u16,
+    pub key_package: frost::keys::KeyPackage,
+    pub public_key_package: frost::keys::PublicKeyPackage,
+    /// Hex Orchard nullifier key shared by the group.
+    pub nk: String,
+    /// Hex Orchard `rivk` shared by the group.
+    pub rivk: String,
+}
+
+impl FrostShare {
+    pub fn network_type(&self) -> NetworkType {
+        if self.network == "testnet" {
+            NetworkType::Test
+        } else {
+            NetworkType::Main
+        }
+    }
+
+    /// Orchard full viewing key of the group: `ak || nk || rivk`.
+    pub fn group_fvk(&self) -> NozyResult<FullViewingKey> {
+        group_fvk(&self.public_key_package, &self.nk, &self.rivk)
+    }
+
+    /// Default external address of the group.
+    pub fn group_address(&self) -> NozyResult<String> {
+        let address = self.group_fvk()?.address_at(0u32, Scope::External);
+        encode_orchard_receiver(&address, self.network_type())
+    }
+
+    /// Orchard-only UFVK, e.g. for a watch or PCZT-building instance.
+    pub fn group_ufvk(&self) -> NozyResult<String> {
+        let ufvk = unified::Ufvk::try_from_items(vec![unified::Fvk::Orchard(
+            self.group_fvk()?.to_bytes(),
+        )])
+        .map_err(|e| NozyError::KeyDerivation(format!("Failed to encode UFVK: {e:?}")))?;
+        Ok(ufvk.encode(&self.network_type()))
+    }
+}
+
+fn frost_err(context: &str) -> impl Fn(frost::Error) -> NozyError + '_ {
+    move |e| NozyError::InvalidOperation(format!("FROST {context}: {e}"))
+}
+
+fn identifier(id: u16) -> NozyResult<frost::Identifier> {
+    frost::Identifier::try_from(id)
+        .map_err(|_| NozyError::InvalidInput(format!("Invalid FROST participant id {id}")))
+}
+
+fn check_threshold(min_signers: u16, max_signers: u16) -> NozyResult<()> {
+    if min_signers < 2 || min_signers > max_signers {
+        return Err(NozyError::InvalidInput(format!(
+            "Threshold must satisfy 2 <= t <= n (got {min_signers}-of-{max_signers})"
+        )));
+    }
+    Ok(())
+}
+
+fn group_fvk(
+    public_key_package: &frost::keys::PublicKeyPackage,
+    nk: &str,
+    rivk: &str,
+) -> NozyResult<FullViewingKey> {
+    let ak = public_key_package
+        .verifying_key()
+        .serialize()
+        .map_err(frost_err("group key"))?;
+    let mut bytes = [0u8; 96];
+    bytes[..32].copy_from_slice(&ak);
+    hex::decode_to_slice(nk, &mut bytes[32..64])
+        .map_err(|e| NozyError::InvalidInput(format!("Invalid group nk: {e}")))?;
+    hex::decode_to_slice(rivk, &mut bytes[64..])
+        .map_err(|e| NozyError::InvalidInput(format!("Invalid group rivk: {e}")))?;
+    // Orchard requires `ak` with ỹ = 0; the RedPallas ciphersuite normalizes group keys
+    // to that form, so a failure here means a corrupt package.
+    FullViewingKey::from_bytes(&bytes).ok_or_else(|| {
+        NozyError::KeyDerivation("FROST group key is not a valid Orchard viewing key".into())
+    })
+}
+
+/// Trusted-dealer key generation: one share per participant, ids `1..=max_signers`.
+pub fn dealer_keygen(
+    min_signers: u16,
+    max_signers: u16,
+    network: NetworkType,
+) -> NozyResult<Vec<FrostShare>> {
+    check_threshold(min_signers, max_signers)?;
+    let mut rng = OsRng;
+    let (secret_shares, public_key_package) = frost::keys::generate_with_dealer(
+        max_signers,
+        min_signers,
+        frost::keys::IdentifierList::Default,
+        &mut rng,
+    )
+    .map_err(frost_err("dealer"))?;
+    let nk = hex::encode(pallas::Base::random(&mut rng).to_repr());
+    let rivk = hex::encode(pallas::Scalar::random(&mut rng).to_repr());
+
+    let mut shares = Vec::with_capacity(secret_shares.len());
+    for id in 1..=max_signers {
+        let secret = secret_shares
+            .get(&identifier(id)?)
+            .cloned()
+            .ok_or_else(|| NozyError::InvalidOperation(format!("Dealer skipped id {id}")))?;
+        shares.push(FrostShare {
+            version: FROST_SHARE_VERSION,
+            network: network_name(network).to_string(),
+            identifier: id,
+            min_signers,
+            max_signers,
+            key_package: frost::keys::KeyPackage::try_from(secret).map_err(frost_err("share"))?,
+            public_key_package: public_key_package.clone(),
+            nk: nk.clone(),
+            rivk: rivk.clone(),
+        });
+    }
+    shares[0].group_fvk()?;
+    Ok(shares)
+}
+
+fn network_name(network: NetworkType) -> &'static str {
+    match network {
+        NetworkType::Main => "mainnet",
+        NetworkType::Test | NetworkType::Regtest => "testnet",
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Distributed key generation
+// ---------------------------------------------------------------------------
+
+/// Broadcast to every other participant after round 1.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct DkgRound1Message {
+    pub identifier: u16,
+    pub package: frost::keys::dkg::round1::Package,
+}
+
+/// Sent privately from `from` to `to` after round 2.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct DkgRound2Message {
+    pub from: u16,
+    pub to: u16,
+    pub package: frost::keys::dkg::round2::Package,
+    /// Hex contribution of `from` to the group `nk` / `rivk`; private like the package.
+    pub view_seed: String,
+}
+
+/// Local DKG progress, kept encrypted in the participant's profile.
+#[derive(Serialize, Deserialize)]
+pub struct DkgState {
+    pub network: String,
+    pub identifier: u16,
+    pub min_signers: u16,
+    pub max_signers: u16,
+    view_seed: String,
+    round1_secret: Option<frost::keys::dkg::round1::SecretPackage>,
+    round2_secret: Option<frost::keys::dkg::round2::SecretPackage>,
+}
+
+pub fn dkg_round1(
+    id: u16,
+    min_signers: u16,
+    max_signers: u16,
+    network: NetworkType,
+) -> NozyResult<(DkgState, DkgRound1Message)> {
+    check_threshold(min_signers, max_signers)?;
+    if id == 0 || id > max_signers {
+        return Err(NozyError::InvalidInput(format!(
+            "Participant id must be between 1 and {max_signers}"
+        )));
+    }
+    let (secret, package) =
+        frost::keys::dkg::part1(identifier(id)?, max_signers, min_signers, OsRng)
+            .map_err(frost_err("DKG round 1"))?;
+    let mut seed = [0u8; 32];
+    OsRng.fill_bytes(&mut seed);
+    Ok((
+        DkgState {
+            network: network_name(network).to_string(),
+            identifier: id,
+            min_signers,
+            max_signers,
+            view_seed: hex::encode(seed),
+            round1_secret: Some(secret),
+            round2_secret: None,
+        },
+        DkgRound1Message {
+            identifier: id,
+            package,
+        },
+    ))
+}
+
+fn others_round1(
+    state: &DkgState,
+    round1: &[DkgRound1Message],
+) -> NozyResult<BTreeMap<frost::Identifier, frost::keys::dkg::round1::Package>> {
+    let mut packages = BTreeMap::new();
+    for message in round1.iter().filter(|m| m.identifier != state.identifier) {
+        packages.insert(identifier(message.identifier)?, message.package.clone());
+    }
+    if packages.len() + 1 != state.max_signers as usize {
+        return Err(NozyError::InvalidInput(format!(
+            "Need round 1 messages from all {} other participants, got {}",
+            state.max_signers - 1,
+            packages.len()
+        )));
+    }
+    Ok(packages)
+}
+
+/// Consume everyone's round 1 messages; returns one private message per other participant.
+pub fn dkg_round2(
+    state: &mut DkgState,
+    round1: &[DkgRound1Message],
+) -> NozyResult<Vec<DkgRound2Message>> {
+    let secret = state
+        .round1_secret
+        .take()
+        .ok_or_else(|| NozyError::InvalidOperation("DKG round 2 already done".into()))?;
+    let (round2_secret, packages) = frost::keys::dkg::part2(secret, &others_round1(state, round1)?)
+        .map_err(frost_err("DKG round 2"))?;
+    state.round2_secret = Some(round2_secret);
+
+    let mut messages = Vec::with_capacity(packages.len());
+    for to in (1..=state.max_signers).filter(|id| *id != state.identifier) {
+        let package = packages
+            .get(&identifier(to)?)
+            .cloned()
+            .ok_or_else(|| NozyError::InvalidOperation(format!("No package for {to}")))?;
+        messages.push(DkgRound2Message {
+            from: state.identifier,
+            to,
+            package,
+            view_seed: state.view_seed.clone(),
+        });
+    }
+    Ok(messages)
+}
+
+/// Group `nk` and `rivk` from every participant's seed, ordered by id.
+fn derive_group_view_keys(seeds: Vec<(u16, &str)>) -> NozyResult<(String, String)> {
+    let mut seeds: Vec<(u16, Vec<u8>)> = seeds
+        .into_iter()
+        .map(|(id, seed)| {
+            hex::decode(seed)
+                .map(|s| (id, s))
+                .map_err(|e| NozyError::InvalidInput(format!("Invalid view seed: {e}")))
+        })
+        .collect::<NozyResult<_>>()?;
+    seeds.sort();
+    let hash = |personal: &[u8; 16]| {
+        let mut state = blake2b_simd::Params::new()
+            .hash_length(64)
+            .personal(personal)
+            .to_state();
+        for (id, seed) in &seeds {
+            state.update(&id.to_le_bytes()).update(seed);
+        }
+        let mut out = [0u8; 64];
+        out.copy_from_slice(state.finalize().as_bytes());
+        out
+    };
+    let nk = pallas::Base::from_uniform_bytes(&hash(b"NozyFROST_nk____"));
+    let rivk = pallas::Scalar::from_uniform_bytes(&hash(b"NozyFROST_rivk__"));
+    Ok((hex::encode(nk.to_repr()), hex::encode(rivk.to_repr())))
+}
+
+/// Finish the DKG with all round 1 messages and the round 2 messages addressed to us.
+pub fn dkg_finish(
+    state: &DkgState,
+    round1: &[DkgRound1Message],
+    round2: &[DkgRound2Message],
+) -> NozyResult<FrostShare> {
+    let secret = state
+        .round2_secret
+        .as_ref()
+        .ok_or_else(|| NozyError::InvalidOperation("Run DKG round 2 first".into()))?;
+    let mut received = BTreeMap::new();
+    let mut seeds: Vec<(u16, &str)> = Vec::new();
+    for message in round2
+        .iter()
+        .filter(|m| m.to == state.identifier && m.from != state.identifier)
+    {
+        if received
+            .insert(identifier(message.from)?, message.package.clone())
+            .is_none()
+        {
+            seeds.push((message.from, message.view_seed.as_str()));
+        }
+    }
+    let (key_package, public_key_package) =
+        frost::keys::dkg::part3(secret, &others_round1(state, round1)?, &received)
+            .map_err(frost_err("DKG finish"))?;
+
+    // Our own seed comes from local state, not from whatever file was passed back in.
+    seeds.push((state.identifier, &state.view_seed));
+    let (nk, rivk) = derive_group_view_keys(seeds)?;
+
+    let share = FrostShare {
+        version: FROST_SHARE_VERSION,
+        network: state.network.clone(),
+        identifier: state.identifier,
+        min_signers: state.min_signers,
+        max_signers: state.max_signers,
+        key_package,
+        public_key_package,
+        nk,
+        rivk,
+    };
+    share.group_fvk()?;
+    Ok(share)
+}
+
+// ---------------------------------------------------------------------------
+// Signing rounds
+// ---------------------------------------------------------------------------
+
+/// Round 1 output of one signer: nonce commitments per unsigned Orchard action.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct FrostCommitments {
+    pub identifier: u16,
+    /// Hex ZIP 244 shielded sighash the commitments are bound to.
+    pub sighash: String,
+    pub actions: Vec<(usize, frost::round1::SigningCommitments)>,
+}
+
+/// Coordinator message: the PCZT plus the commitments of the chosen signers.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct FrostSigningRequest {
+    pub pczt_hex: String,
+    pub sighash: String,
+    pub commitments: Vec<FrostCommitments>,
+}
+
+/// Round 2 output of one signer.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct FrostSignatureShares {
+    pub identifier: u16,
+    pub sighash: String,
+    pub shares: Vec<(usize, frost::round2::SignatureShare)>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct StoredNonces {
+    /// Sighash hex -> nonces per action. Removed once used.
+    sessions: BTreeMap<String, Vec<(usize, frost::round1::SigningNonces)>>,
+}
+
+fn parse_pczt(pczt_bytes: &[u8]) -> NozyResult<Pczt> {
+    Pczt::parse(pczt_bytes)
+        .map_err(|e| NozyError::InvalidOperation(format!("PCZT parse failed: {e:?}")))
+}
+
+fn shielded_sighash(pczt: Pczt) -> NozyResult<[u8; 32]> {
+    let signer = Signer::new(pczt)
+        .map_err(|e| NozyError::InvalidOperation(format!("PCZT signer init failed: {e:?}")))?;
+    Ok(signer.shielded_sighash())
+}
+
+/// Orchard actions whose spend the group must still authorize, with their `alpha`.
+fn unsigned_spends(pczt: &Pczt) -> Vec<(usize, [u8; 32], [u8; 32])> {
+    pczt.orchard()
+        .actions()
+        .iter()
+        .enumerate()
+        .filter(|(_, a)| a.spend().spend_auth_sig().is_none())
+        .filter_map(|(i, a)| (*a.spend().alpha()).map(|alpha| (i, alpha, *a.spend().rk())))
+        .collect()
+}
+
+fn randomized_params(
+    share: &FrostShare,
+    alpha: &[u8; 32],
+    rk: &[u8; 32],
+) -> NozyResult<Option<frost::RandomizedParams>> {
+    let alpha = Option::<pallas::Scalar>::from(pallas::Scalar::from_repr(*alpha))
+        .ok_or_else(|| NozyError::InvalidInput("PCZT spend has an invalid alpha".into()))?;
+    let params = frost::RandomizedParams::from_randomizer(
+        share.public_key_package.verifying_key(),
+        frost::Randomizer::from_scalar(alpha),
+    );
+    let randomized = params
+        .randomized_verifying_key()
+        .serialize()
+        .map_err(frost_err("randomized key"))?;
+    // Spends of other keys (e.g. a second account in the same PCZT) are left alone.
+    Ok((randomized.as_slice() == rk.as_slice()).then_some(params))
+}
+
+/// Round 1: commit to fresh nonces for every group spend in the PCZT.
+///
+/// Nonces are stored in the profile and used once by [`sign_request`].
+pub fn commit_to_pczt(share: &FrostShare, pczt_bytes: &[u8]) -> NozyResult<FrostCommitments> {
+    let pczt = parse_pczt(pczt_bytes)?;
+    let spends = unsigned_spends(&pczt);
+    let sighash = hex::encode(shielded_sighash(pczt)?);
+
+    let mut rng = OsRng;
+    let mut nonces = Vec::new();
+    let mut actions = Vec::new();
+    for (index, alpha, rk) in spends {
+        if randomized_params(share, &alpha, &rk)?.is_none() {
+            continue;
+        }
+        let (nonce, commitment) =
+            frost::round1::commit(share.key_package.signing_share(), &mut rng);
+        nonces.push((index, nonce));
+        actions.push((index, commitment));
+    }
+    if actions.is_empty() {
+        return Err(NozyError::InvalidInput(
+            "PCZT has no unsigned spends of this FROST group".into(),
+        ));
+    }
+
+    let mut stored: StoredNonces = load_vault_json(NONCES_FILE)?.unwrap_or_default();
+    stored.sessions.insert(sighash.clone(), nonces);
+    save_vault_json(NONCES_FILE, &stored)?;
+
+    Ok(FrostCommitments {
+        identifier: share.identifier,
+        sighash,
+        actions,
+    })
+}
+
+/// Coordinator: bundle the PCZT with commitments from at least `t` signers.
+pub fn build_signing_request(
+    share: &FrostShare,
+    pczt_bytes: &[u8],
+    commitments: Vec<FrostCommitments>,
+) -> NozyResult<FrostSigningRequest> {
+    let sighash = hex::encode(shielded_sighash(parse_pczt(pczt_bytes)?)?);
+    let mut signers: Vec<u16> = commitments.iter().map(|c| c.identifier).collect();
+    signers.sort_unstable();
+    signers.dedup();
+    if signers.len() != commitments.len() {
+        return Err(NozyError::InvalidInput(
+            "Duplicate commitments from one signer".into(),
+        ));
+    }
+    if signers.len() < share.min_signers as usize {
+        return Err(NozyError::InvalidInput(format!(
+            "Need commitments from {} signers, got {}",
+            share.min_signers,
+            signers.len()
+        )));
+    }
+    if let Some(stale) = commitments.iter().find(|c| c.sighash != sighash) {
+        return Err(NozyError::InvalidInput(format!(
+            "Commitments from signer {} are for a different PCZT",
+            stale.identifier
+        )));
+    }
+    Ok(FrostSigningRequest {
+        pczt_hex: hex::encode(pczt_bytes),
+        sighash,
+        commitments,
+    })
+}
+
+fn signing_packages(
+    request: &FrostSigningRequest,
+) -> NozyResult<BTreeMap<usize, frost::SigningPackage>> {
+    let sighash = hex::decode(&request.sighash)
+        .map_err(|e| NozyError::InvalidInput(format!("Invalid sighash: {e}")))?;
+    let mut per_action: BTreeMap<usize, BTreeMap<frost::Identifier, _>> = BTreeMap::new();
+    for signer in &request.commitments {
+        for (index, commitment) in &signer.actions {
+            per_action
+                .entry(*index)
+                .or_default()
+                .insert(identifier(signer.identifier)?, *commitment);
+        }
+    }
+    Ok(per_action
+        .into_iter()
+        .map(|(index, commitments)| (index, frost::SigningPackage::new(commitments, &sighash)))
+        .collect())
+}
+
+/// PCZT bytes of a request, checked against the sighash everyone committed to.
+pub fn request_pczt(request: &FrostSigningRequest) -> NozyResult<Vec<u8>> {
+    let pczt_bytes = hex::decode(&request.pczt_hex)
+        .map_err(|e| NozyError::InvalidInput(format!("Invalid PCZT hex: {e}")))?;
+    if hex::encode(shielded_sighash(parse_pczt(&pczt_bytes)?)?) != request.sighash {
+        return Err(NozyError::InvalidInput(
+            "Signing request sighash does not match its PCZT".into(),
+        ));
+    }
+    Ok(pczt_bytes)
+}
+
+/// Round 2: sign every group spend with the nonces stored by [`commit_to_pczt`].
+pub fn sign_request(
+    share: &FrostShare,
+    request: &FrostSigningRequest,
+) -> NozyResult<FrostSignatureShares> {
+    let pczt = parse_pczt(&request_pczt(request)?)?;
+    let mut stored: StoredNonces = load_vault_json(NONCES_FILE)?.unwrap_or_default();
+    // Taking the nonces out first guarantees they are never used twice.
+    let nonces = stored.sessions.remove(&request.sighash).ok_or_else(|| {
+        NozyError::InvalidInput("No stored nonces for this PCZT: commit to it first".into())
+    })?;
+    save_vault_json(NONCES_FILE, &stored)?;
+
+    let packages = signing_packages(request)?;
+    let mut shares = Vec::with_capacity(nonces.len());
+    for (index, alpha, rk) in unsigned_spends(&pczt) {
+        let Some(params) = randomized_params(share, &alpha, &rk)? else {
+            continue;
+        };
+        let (Some(package), Some((_, nonce))) = (
+            packages.get(&index),
+            nonces.iter().find(|(i, _)| *i == index),
+        ) else {
+            return Err(NozyError::InvalidInput(format!(
+                "Signing request has no commitments for action {index}"
+            )));
+        };
+        let signature_share =
+            frost::round2::sign(package, nonce, &share.key_package, *params.randomizer())
+                .map_err(frost_err("round 2"))?;
+        shares.push((index, signature_share));
+    }
+    Ok(FrostSignatureShares {
+        identifier: share.identifier,
+        sighash: request.sighash.clone(),
+        shares,
+    })
+}
+
+/// Aggregate `t` signers' shares and apply the spend authorization signatures.
+pub fn aggregate_signatures(
+    share: &FrostShare,
+    request: &FrostSigningRequest,
+    signature_shares: &[FrostSignatureShares],
+) -> NozyResult<Vec<u8>> {
+    let pczt = parse_pczt(&request_pczt(request)?)?;
+    let spends = unsigned_spends(&pczt);
+    let packages = signing_packages(request)?;
+    let mut signer = Signer::new(pczt)
+        .map_err(|e| NozyError::InvalidOperation(format!("PCZT signer init failed: {e:?}")))?;
+
+    let mut applied = 0;
+    for (index, alpha, rk) in spends {
+        let Some(params) = randomized_params(share, &alpha, &rk)? else {
+            continue;
+        };
+        let package = packages
+            .get(&index)
+            .ok_or_else(|| NozyError::InvalidInput(format!("No commitments for action {index}")))?;
+        let mut shares = BTreeMap::new();
+        for from in signature_shares {
+            if from.sighash != request.sighash {
+                return Err(NozyError::InvalidInput(format!(
+                    "Signature shares from signer {} are for a different PCZT",
+                    from.identifier
+                )));
+            }
+            if let Some((_, s)) = from.shares.iter().find(|(i, _)| *i == index) {
+                shares.insert(identifier(from.identifier)?, *s);
+            }
+        }
+        let signature = frost::aggregate(package, &shares, &share.public_key_package, &params)
+            .map_err(frost_err("aggregate"))?;
+        let bytes: [u8; 64] = signature
+            .serialize()
+            .map_err(frost_err("signature"))?
+            .try_into()
+            .map_err(|_| NozyError::InvalidOperation("FROST signature is not 64 bytes".into()))?;
+        signer
+            .apply_orchard_signature(
+                index,
+                orchard::primitives::redpallas::Signature::from(bytes),
+            )
+            .map_err(|e| {
+                NozyError::InvalidOperation(format!("Applying signature to action {index}: {e:?}"))
+            })?;
+        applied += 1;
+    }
+    if applied == 0 {
+        return Err(NozyError::InvalidInput(
+            "PCZT has no unsigned spends of this FROST group".into(),
+        ));
+    }
+    signer
+        .finish()
+        .serialize()
+        .map_err(|e| NozyError::InvalidOperation(format!("PCZT serialize failed: {e:?}")))
+}
+
+// ---------------------------------------------------------------------------
+// Storage and transport
+// ---------------------------------------------------------------------------
+
+fn vault_path(file: &str) -> PathBuf {
+    get_wallet_data_dir().join(file)
+}
+
+fn load_vault_json<T: DeserializeOwned>(file: &str) -> NozyResult<Option<T>> {
+    let path = vault_path(file);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| NozyError::Storage(format!("Failed to read {file}: {e}")))?;
+    let json = decrypt_frost_file_content(&content, file)?;
+    serde_json::from_str(&json)
+        .map(Some)
+        .map_err(|e| NozyError::Storage(format!("Failed to parse {file}: {e}")))
+}
+
+fn save_vault_json<T: Serialize>(file: &str, value: &T) -> NozyResult<()> {
+    let json = serde_json::to_string(value)
+        .map_err(|e| NozyError::Storage(format!("Failed to encode {file}: {e}")))?;
+    let path = vault_path(file);
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    std::fs::write(&path, encrypt_frost_json(&json)?)
+        .map_err(|e| NozyError::Storage(format!("Failed to write {file}: {e}")))
+}
+
+/// Store `share` in the active profile and mark the profile as a FROST share holder.
+pub fn save_share(share: &FrostShare) -> NozyResult<()> {
+    save_vault_json(SHARE_FILE, share)?;
+    if let Some(id) = crate::wallet_profiles::active_profile_id() {
+        crate::wallet_profiles::set_profile_kind(&id, WalletProfileKind::FrostShare)?;
+    }
+    Ok(())
+}
+
+pub fn load_share() -> NozyResult<FrostShare> {
+    load_vault_json(SHARE_FILE)?.ok_or_else(|| {
+        NozyError::InvalidOperation(
+            "The active profile holds no FROST share (see `nozy frost import`)".into(),
+        )
+    })
+}
+
+pub fn save_dkg_state(state: &DkgState) -> NozyResult<()> {
+    save_vault_json(DKG_STATE_FILE, state)
+}
+
+pub fn load_dkg_state() -> NozyResult<DkgState> {
+    load_vault_json(DKG_STATE_FILE)?.ok_or_else(|| {
+        NozyError::InvalidOperation("No DKG in progress in the active profile".into())
+    })
+}
+
+pub fn clear_dkg_state() -> NozyResult<()> {
+    let path = vault_path(DKG_STATE_FILE);
+    if path.exists() {
+        std::fs::remove_file(&path)
+            .map_err(|e| NozyError::Storage(format!("Failed to remove DKG state: {e}")))?;
+    }
+    Ok(())
+}
+
+/// Encode a FROST message as `nozy-frost` UR frames.
+pub fn encode_frost_ur_frames(message_json: &str) -> NozyResult<Vec<String>> {
+    let cbor = wrap_pczt_cbor(message_json.as_bytes());
+    let mut encoder = ur::ur::Encoder::new(
+        &cbor,
+        crate::keystone::DEFAULT_UR_FRAGMENT_SIZE,
+        UR_TYPE_NOZY_FROST,
+    )
+    .map_err(|e| NozyError::InvalidOperation(format!("UR encoder: {e:?}")))?;
+    (0..encoder.fragment_count())
+        .map(|_| {
+            encoder
+                .next_part()
+                .map_err(|e| NozyError::InvalidOperation(format!("UR part: {e:?}")))
+        })
+        .collect()
+}
+
+/// Parse a FROST message given as JSON or as UR frames one per line.
+pub fn read_frost_message<T: DeserializeOwned>(text: &str) -> NozyResult<T> {
+    let text = text.trim();
+    let json = if text.get(..3).is_some_and(|p| p.eq_ignore_ascii_case("ur:")) {
+        let mut decoder = ur::ur::Decoder::default();
+        for frame in text.lines().map(str::trim).filter(|l| !l.is_empty()) {
+            decoder
+                .receive(frame)
+                .map_err(|e| NozyError::InvalidInput(format!("UR decode: {e:?}")))?;
+        }
+        let cbor = decoder
+            .message()
+            .map_err(|e| NozyError::InvalidInput(format!("UR message incomplete: {e:?}")))?
+            .ok_or_else(|| NozyError::InvalidInput("UR frames are incomplete".into()))?;
+        String::from_utf8(unwrap_pczt_cbor(&cbor)?)
+            .map_err(|e| NozyError::InvalidInput(format!("UR payload is not UTF-8: {e}")))?
+    } else {
+        text.to_string()
+    };
+    serde_json::from_str(&json)
+        .map_err(|e| NozyError::InvalidInput(format!("Not the expected FROST message: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dealer_shares_agree_on_group_key() {
+        let shares = dealer_keygen(2, 3, NetworkType::Test).unwrap();
+        assert_eq!(shares.len(), 3);
+        let address = shares[0].group_address().unwrap();
+        assert!(shares.iter().all(|s| s.group_address().unwrap() == address));
+        assert!(shares[0].group_ufvk().unwrap().starts_with("uviewtest"));
+        assert!(dealer_keygen(1, 3, NetworkType::Test).is_err());
+    }
+
+    #[test]
+    fn dkg_participants_derive_the_same_viewing_key() {
+        let mut states = Vec::new();
+        let mut round1 = Vec::new();
+        for id in 1..=3 {
+            let (state, message) = dkg_round1(id, 2, 3, NetworkType::Main).unwrap();
+            states.push(state);
+            round1.push(message);
+        }
+        let mut round2 = Vec::new();
+        for state in &mut states {
+            round2.extend(dkg_round2(state, &round1).unwrap());
+        }
+        let shares: Vec<FrostShare> = states
+            .iter()
+            .map(|s| dkg_finish(s, &round1, &round2).unwrap())
+            .collect();
+        let fvk = shares[0].group_fvk().unwrap().to_bytes();
+        assert!(shares
+            .iter()
+            .all(|s| s.group_fvk().unwrap().to_bytes() == fvk));
+
+        // The broadcast round carries no viewing key material.
+        assert!(round1
+            .iter()
+            .all(|m| !serde_json::to_string(m).unwrap().contains("view_seed")));
+        let partial: Vec<DkgRound2Message> =
+            round2.iter().filter(|m| m.from != 2).cloned().collect();
+        assert!(dkg_finish(&states[0], &round1, &partial).is_err());
+    }
+
+    #[test]
+    fn aggregate_signature_verifies_against_action_rk() {
+        use crate::notes_vault::{clear_notes_vault, unlock_notes_vault};
+        use crate::paths::with_wallet_data_dir;
+        use orchard::primitives::redpallas::{Signature, SpendAuth, VerificationKey};
+
+        let _g = crate::notes_vault::lock_notes_vault_for_test();
+        let shares = dealer_keygen(2, 3, NetworkType::Test).unwrap();
+        let fvk = shares[0].group_fvk().unwrap();
+        let recipient = fvk.address_at(1u32, Scope::External);
+        let (pczt, _) =
+            crate::tests::unproven_test_pczt(&fvk, 200_000, &[(recipient, 150_000)], &[]);
+        let pczt_bytes = pczt.serialize().unwrap();
+
+        let signers = [&shares[0], &shares[2]];
+        let dirs: Vec<PathBuf> = signers
+            .iter()
+            .map(|s| {
+                let dir = std::env::temp_dir().join(format!(
+                    "nozy-frost-sign-test-{}-{}",
+                    std::process::id(),
+                    s.identifier
+                ));
+                let _ = std::fs::remove_dir_all(&dir);
+                std::fs::create_dir_all(&dir).unwrap();
+                dir
+            })
+            .collect();
+        clear_notes_vault();
+        unlock_notes_vault("frost").unwrap();
+
+        let commitments: Vec<FrostCommitments> = signers
+            .iter()
+            .zip(&dirs)
+            .map(|(s, dir)| with_wallet_data_dir(dir, || commit_to_pczt(s, &pczt_bytes)).unwrap())
+            .collect();
+        assert!(commitments.iter().all(|c| c.actions.len() == 1));
+        let request = build_signing_request(&shares[0], &pczt_bytes, commitments).unwrap();
+        let signature_shares: Vec<FrostSignatureShares> = signers
+            .iter()
+            .zip(&dirs)
+            .map(|(s, dir)| with_wallet_data_dir(dir, || sign_request(s, &request)).unwrap())
+            .collect();
+        // Nonces are single use.
+        assert!(with_wallet_data_dir(&dirs[0], || sign_request(signers[0], &request)).is_err());
+        assert!(aggregate_signatures(&shares[0], &request, &signature_shares[..1]).is_err());
+        let signed = aggregate_signatures(&shares[0], &request, &signature_shares).unwrap();
+
+        let sighash = shielded_sighash(parse_pczt(&pczt_bytes).unwrap()).unwrap();
+        let signed = parse_pczt(&signed).unwrap();
+        assert!(unsigned_spends(&signed).is_empty());
+        for action in signed.orchard().actions() {
+            let sig = action.spend().spend_auth_sig().unwrap();
+            let rk = VerificationKey::<SpendAuth>::try_from(*action.spend().rk()).unwrap();
+            rk.verify(&sighash, &Signature::from(sig)).unwrap();
+        }
+
+        clear_notes_vault();
+        for dir in dirs {
+            let _ = std::fs::remove_dir_all(dir);
+        }
+    }
+
+    #[test]
+    fn frost_message_roundtrips_through_ur() {
+        let message = r#"{"identifier":1,"sighash":"00","actions":[]}"#;
+        let frames = encode_frost_ur_frames(message).unwrap();
+        let parsed: FrostCommitments = read_frost_message(&frames.join("\n")).unwrap();
+        assert_eq!(parsed.identifier, 1);
+    }
+}
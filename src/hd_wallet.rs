@@ -137,6 +137,21 @@ impl HDWallet {
         Ok(derived_key)
     }
 
+    /// Orchard full viewing key for a ZIP-32 `account` (Business invoices use account 1).
+    pub fn orchard_full_viewing_key(&self, account: u32) -> NozyResult<FullViewingKey> {
+        let seed_bytes = self.mnemonic.to_seed("").to_vec();
+        let secure_seed = SecureSeed::new(seed_bytes);
+
+        let account_id = AccountId::try_from(account)
+            .map_err(|e| NozyError::KeyDerivation(format!("Invalid account ID: {:?}", e)))?;
+
+        let orchard_sk = SpendingKey::from_zip32_seed(secure_seed.as_bytes(), 133, account_id)
+            .map_err(|e| {
+                NozyError::KeyDerivation(format!("Failed to derive Orchard spending key: {:?}", e))
+            })?;
+        Ok(FullViewingKey::from(&orchard_sk))
+    }
+
     pub fn generate_orchard_address(
         &self,
         account: u32,
@@ -0,0 +1,613 @@
+//! Accounting exports of wallet history (CSV, JSON Lines, OFX).
+//!
+//! Rows are built from [`collect_wallet_transaction_views`] so exports match what the
+//! UI shows. Counterparty labels come from the profile's address book; an optional
+//! `account` selects another wallet profile's data directory for the export.
+
+use crate::address_book::AddressBook;
+use crate::error::{NozyError, NozyResult};
+use crate::price_source::{apply_fiat_values, configured_price_source};
+use crate::transaction_history::{
+    collect_wallet_transaction_views, enrich_block_times_for_views, TransactionStatus,
+    TransactionType, TransactionView,
+};
+use crate::zebra_integration::ZebraClient;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// Minimum plausible Zcash mainnet block time (Oct 2016); older timestamps are placeholders.
+const MIN_PLAUSIBLE_TIMESTAMP_SECS: i64 = 1_477_000_000;
+
+const ZATOSHIS_PER_ZEC: u64 = 100_000_000;
+
+/// Max length of OFX `<NAME>` (OFX 2.2 §11.4.4.1).
+const OFX_NAME_MAX_LEN: usize = 32;
+
+pub const HISTORY_EXPORT_CSV_HEADER: &str = "date,txid,direction,amount_zec,amount_zatoshis,fee_zec,fee_zatoshis,memo,counterparty,counterparty_label,confirmations,block_height,status,account,fiat_currency,fiat_amount,fiat_price_per_zec";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HistoryExportFormat {
+    Csv,
+    Jsonl,
+    Ofx,
+}
+
+impl HistoryExportFormat {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            HistoryExportFormat::Csv => "csv",
+            HistoryExportFormat::Jsonl => "jsonl",
+            HistoryExportFormat::Ofx => "ofx",
+        }
+    }
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            HistoryExportFormat::Csv => "text/csv; charset=utf-8",
+            HistoryExportFormat::Jsonl => "application/x-ndjson",
+            HistoryExportFormat::Ofx => "application/x-ofx",
+        }
+    }
+
+    /// Suggested download name, e.g. `nozy-history.csv`.
+    pub fn file_name(self) -> String {
+        format!("nozy-history.{}", self.as_str())
+    }
+}
+
+impl FromStr for HistoryExportFormat {
+    type Err = NozyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "csv" => Ok(HistoryExportFormat::Csv),
+            "jsonl" | "json-lines" | "ndjson" => Ok(HistoryExportFormat::Jsonl),
+            "ofx" => Ok(HistoryExportFormat::Ofx),
+            other => Err(NozyError::InvalidInput(format!(
+                "Unknown export format '{other}' (expected csv, jsonl or ofx)"
+            ))),
+        }
+    }
+}
+
+/// Row selection for an export. Dates are inclusive calendar days in UTC.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HistoryExportFilter {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    /// Wallet profile id; `None` exports the active profile.
+    pub account: Option<String>,
+}
+
+impl HistoryExportFilter {
+    /// Parse `YYYY-MM-DD` bounds as given on the CLI / API query string.
+    pub fn parse(
+        from: Option<&str>,
+        to: Option<&str>,
+        account: Option<String>,
+    ) -> NozyResult<Self> {
+        let filter = Self {
+            from: from.map(parse_export_date).transpose()?,
+            to: to.map(parse_export_date).transpose()?,
+            account: account.filter(|a| !a.trim().is_empty()),
+        };
+        if let (Some(from), Some(to)) = (filter.from, filter.to) {
+            if from > to {
+                return Err(NozyError::InvalidInput(format!(
+                    "Export range start {from} is after end {to}"
+                )));
+            }
+        }
+        Ok(filter)
+    }
+
+    fn has_date_bounds(&self) -> bool {
+        self.from.is_some() || self.to.is_some()
+    }
+
+    /// Undated rows (e.g. pending sends without a block time) only pass when no date
+    /// bound is set, since they cannot be placed inside a range.
+    fn matches_date(&self, date: Option<DateTime<Utc>>) -> bool {
+        if !self.has_date_bounds() {
+            return true;
+        }
+        let Some(day) = date.map(|d| d.date_naive()) else {
+            return false;
+        };
+        self.from.is_none_or(|from| day >= from) && self.to.is_none_or(|to| day <= to)
+    }
+}
+
+fn parse_export_date(s: &str) -> NozyResult<NaiveDate> {
+    NaiveDate::parse_from_str(s.trim(), "%Y-%m-%d")
+        .map_err(|e| NozyError::InvalidInput(format!("Invalid date '{s}' (YYYY-MM-DD): {e}")))
+}
+
+/// One bookkeeping line.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HistoryExportRow {
+    pub date: Option<DateTime<Utc>>,
+    pub txid: String,
+    /// `incoming`, `outgoing` or `self` (change / internal movement).
+    pub direction: String,
+    /// Signed net amount: negative for outgoing.
+    pub amount_zatoshis: i64,
+    pub fee_zatoshis: u64,
+    pub memo: Option<String>,
+    pub counterparty: Option<String>,
+    pub counterparty_label: Option<String>,
+    pub confirmations: u32,
+    pub block_height: Option<u32>,
+    pub status: String,
+    pub account: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fiat_currency: Option<String>,
+    /// Historical fiat value of `amount_zatoshis` at block time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fiat_amount: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fiat_price_per_zec: Option<f64>,
+}
+
+impl HistoryExportRow {
+    pub fn from_view(
+        view: &TransactionView,
+        labels: &HashMap<String, String>,
+        account: &str,
+    ) -> Self {
+        let direction = match view.transaction_type {
+            TransactionType::Sent => "outgoing",
+            TransactionType::Received => "incoming",
+            TransactionType::Change => "self",
+            TransactionType::Mixed if view.net_amount_zatoshis < 0 => "outgoing",
+            TransactionType::Mixed => "incoming",
+        };
+        let counterparty = view.recipient_address.clone();
+        let counterparty_label = counterparty
+            .as_ref()
+            .and_then(|addr| labels.get(addr).cloned());
+        Self {
+            date: view_date(view),
+            txid: view.txid.clone(),
+            direction: direction.to_string(),
+            amount_zatoshis: view.net_amount_zatoshis,
+            fee_zatoshis: view.fee_zatoshis.unwrap_or(0),
+            memo: view.memo.clone().filter(|m| !m.trim().is_empty()),
+            counterparty,
+            counterparty_label,
+            confirmations: view.confirmations,
+            block_height: view.block_height,
+            status: status_label(&view.status).to_string(),
+            account: account.to_string(),
+            fiat_currency: view.fiat_value.as_ref().map(|f| f.currency.clone()),
+            fiat_amount: view.fiat_value.as_ref().map(|f| f.amount),
+            fiat_price_per_zec: view.fiat_value.as_ref().map(|f| f.price_per_zec),
+        }
+    }
+}
+
+fn status_label(status: &TransactionStatus) -> &'static str {
+    match status {
+        TransactionStatus::Pending => "pending",
+        TransactionStatus::Confirmed => "confirmed",
+        TransactionStatus::Failed => "failed",
+        TransactionStatus::Expired => "expired",
+    }
+}
+
+fn view_date(view: &TransactionView) -> Option<DateTime<Utc>> {
+    view.block_time
+        .or(Some(view.created_at))
+        .filter(|d| d.timestamp() >= MIN_PLAUSIBLE_TIMESTAMP_SECS)
+}
+
+/// History views plus address-book labels for one wallet profile.
+pub struct HistoryExportSource {
+    pub account: String,
+    pub views: Vec<TransactionView>,
+    /// Address → address-book name.
+    pub labels: HashMap<String, String>,
+}
+
+/// Load views and labels for `account` (or the active profile).
+pub fn load_history_export_source(
+    current_height: u32,
+    account: Option<&str>,
+) -> NozyResult<HistoryExportSource> {
+    let load = || -> NozyResult<(Vec<TransactionView>, HashMap<String, String>)> {
+        let views = collect_wallet_transaction_views(current_height)?;
+        let labels = AddressBook::new()
+            .map(|book| {
+                book.list_addresses()
+                    .into_iter()
+                    .map(|entry| (entry.address, entry.name))
+                    .collect()
+            })
+            .unwrap_or_default();
+        Ok((views, labels))
+    };
+    let (account, (views, labels)) = match account {
+        Some(id) => {
+            let dir = crate::wallet_profiles::profile_data_dir(id)?;
+            (
+                id.to_string(),
+                crate::paths::with_wallet_data_dir(dir, load)?,
+            )
+        }
+        None => (
+            crate::wallet_profiles::active_profile_id().unwrap_or_else(|| "default".to_string()),
+            load()?,
+        ),
+    };
+    Ok(HistoryExportSource {
+        account,
+        views,
+        labels,
+    })
+}
+
+/// Filtered rows, oldest first (bookkeeping order).
+pub fn build_history_export_rows(
+    source: &HistoryExportSource,
+    filter: &HistoryExportFilter,
+) -> Vec<HistoryExportRow> {
+    let mut rows: Vec<HistoryExportRow> = source
+        .views
+        .iter()
+        .map(|v| HistoryExportRow::from_view(v, &source.labels, &source.account))
+        .filter(|row| filter.matches_date(row.date))
+        .collect();
+    rows.sort_by(|a, b| {
+        (a.block_height.unwrap_or(u32::MAX), a.date, &a.txid).cmp(&(
+            b.block_height.unwrap_or(u32::MAX),
+            b.date,
+            &b.txid,
+        ))
+    });
+    rows
+}
+
+pub fn render_history_export(
+    rows: &[HistoryExportRow],
+    format: HistoryExportFormat,
+    filter: &HistoryExportFilter,
+) -> NozyResult<String> {
+    match format {
+        HistoryExportFormat::Csv => Ok(render_csv(rows)),
+        HistoryExportFormat::Jsonl => render_json_lines(rows),
+        HistoryExportFormat::Ofx => Ok(render_ofx(rows, filter, Utc::now())),
+    }
+}
+
+/// Collect, enrich block times from Zebra, filter and render in one call (CLI / API).
+///
+/// With `fiat_currency`, rows also carry historical fiat values from the configured
+/// price source (see [`crate::price_source`]).
+pub async fn export_wallet_history(
+    zebra_client: &ZebraClient,
+    filter: &HistoryExportFilter,
+    format: HistoryExportFormat,
+    fiat_currency: Option<&str>,
+) -> NozyResult<String> {
+    let current_height = zebra_client.get_block_count().await.unwrap_or(0);
+    let mut source = load_history_export_source(current_height, filter.account.as_deref())?;
+    enrich_block_times_for_views(&mut source.views, zebra_client).await;
+    if let Some(currency) = fiat_currency {
+        let config = crate::config::load_config();
+        let prices = configured_price_source(&config)?.ok_or_else(|| {
+            NozyError::InvalidInput(
+                "Fiat export requested but no price source is configured (fiat.source)".to_string(),
+            )
+        })?;
+        apply_fiat_values(&mut source.views, prices.as_ref(), currency, false).await?;
+    }
+    let rows = build_history_export_rows(&source, filter);
+    render_history_export(&rows, format, filter)
+}
+
+/// Signed ZEC amount with exactly eight decimals (no float rounding).
+pub fn format_zec_amount(zatoshis: i64) -> String {
+    let sign = if zatoshis < 0 { "-" } else { "" };
+    let abs = zatoshis.unsigned_abs();
+    format!(
+        "{sign}{}.{:08}",
+        abs / ZATOSHIS_PER_ZEC,
+        abs % ZATOSHIS_PER_ZEC
+    )
+}
+
+fn csv_field(value: &str) -> String {
+    // Spreadsheet apps evaluate cells starting with these as formulas.
+    let value = if value.starts_with(['=', '+', '-', '@']) {
+        format!("'{value}")
+    } else {
+        value.to_string()
+    };
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+pub fn render_csv(rows: &[HistoryExportRow]) -> String {
+    let mut out = String::from(HISTORY_EXPORT_CSV_HEADER);
+    out.push('\n');
+    for row in rows {
+        let fields = [
+            row.date.map(|d| d.to_rfc3339()).unwrap_or_default(),
+            row.txid.clone(),
+            row.direction.clone(),
+            format_zec_amount(row.amount_zatoshis),
+            row.amount_zatoshis.to_string(),
+            format_zec_amount(row.fee_zatoshis as i64),
+            row.fee_zatoshis.to_string(),
+            csv_field(row.memo.as_deref().unwrap_or("")),
+            csv_field(row.counterparty.as_deref().unwrap_or("")),
+            csv_field(row.counterparty_label.as_deref().unwrap_or("")),
+            row.confirmations.to_string(),
+            row.block_height.map(|h| h.to_string()).unwrap_or_default(),
+            row.status.clone(),
+            csv_field(&row.account),
+            row.fiat_currency.clone().unwrap_or_default(),
+            row.fiat_amount
+                .map(|a| format!("{a:.2}"))
+                .unwrap_or_default(),
+            row.fiat_price_per_zec
+                .map(|p| p.to_string())
+                .unwrap_or_default(),
+        ];
+        out.push_str(&fields.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+pub fn render_json_lines(rows: &[HistoryExportRow]) -> NozyResult<String> {
+    let mut out = String::new();
+    for row in rows {
+        let mut value = serde_json::to_value(row).map_err(|e| {
+            NozyError::InvalidOperation(format!("Failed to serialize export row: {e}"))
+        })?;
+        if let Some(obj) = value.as_object_mut() {
+            obj.insert(
+                "amount_zec".to_string(),
+                format_zec_amount(row.amount_zatoshis).into(),
+            );
+            obj.insert(
+                "fee_zec".to_string(),
+                format_zec_amount(row.fee_zatoshis as i64).into(),
+            );
+        }
+        out.push_str(&value.to_string());
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn ofx_datetime(dt: DateTime<Utc>) -> String {
+    dt.format("%Y%m%d%H%M%S").to_string()
+}
+
+fn ofx_transaction(
+    out: &mut String,
+    trntype: &str,
+    posted: DateTime<Utc>,
+    amount_zatoshis: i64,
+    fitid: &str,
+    name: Option<&str>,
+    memo: Option<&str>,
+) {
+    out.push_str("<STMTTRN>\n");
+    out.push_str(&format!("<TRNTYPE>{trntype}</TRNTYPE>\n"));
+    out.push_str(&format!("<DTPOSTED>{}</DTPOSTED>\n", ofx_datetime(posted)));
+    out.push_str(&format!(
+        "<TRNAMT>{}</TRNAMT>\n",
+        format_zec_amount(amount_zatoshis)
+    ));
+    out.push_str(&format!("<FITID>{}</FITID>\n", xml_escape(fitid)));
+    if let Some(name) = name.filter(|n| !n.is_empty()) {
+        let name: String = name.chars().take(OFX_NAME_MAX_LEN).collect();
+        out.push_str(&format!("<NAME>{}</NAME>\n", xml_escape(&name)));
+    }
+    if let Some(memo) = memo.filter(|m| !m.is_empty()) {
+        out.push_str(&format!("<MEMO>{}</MEMO>\n", xml_escape(memo)));
+    }
+    out.push_str("</STMTTRN>\n");
+}
+
+/// OFX 2.2 bank statement. ZEC is not an ISO 4217 code, so `CURDEF` is `ZEC` and
+/// amounts are in ZEC; fees become separate `FEE` lines with FITID `<txid>:fee`.
+pub fn render_ofx(
+    rows: &[HistoryExportRow],
+    filter: &HistoryExportFilter,
+    generated_at: DateTime<Utc>,
+) -> String {
+    let account = rows
+        .first()
+        .map(|r| r.account.clone())
+        .or_else(|| filter.account.clone())
+        .unwrap_or_else(|| "default".to_string());
+    let dated: Vec<DateTime<Utc>> = rows.iter().filter_map(|r| r.date).collect();
+    let start = filter
+        .from
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .map(|d| d.and_utc())
+        .or_else(|| dated.iter().min().copied())
+        .unwrap_or(generated_at);
+    let end = filter
+        .to
+        .and_then(|d| d.and_hms_opt(23, 59, 59))
+        .map(|d| d.and_utc())
+        .or_else(|| dated.iter().max().copied())
+        .unwrap_or(generated_at);
+
+    let mut out = String::new();
+    out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"no\"?>\n");
+    out.push_str("<?OFX OFXHEADER=\"200\" VERSION=\"220\" SECURITY=\"NONE\" OLDFILEUID=\"NONE\" NEWFILEUID=\"NONE\"?>\n");
+    out.push_str("<OFX>\n<SIGNONMSGSRSV1>\n<SONRS>\n");
+    out.push_str("<STATUS>\n<CODE>0</CODE>\n<SEVERITY>INFO</SEVERITY>\n</STATUS>\n");
+    out.push_str(&format!(
+        "<DTSERVER>{}</DTSERVER>\n",
+        ofx_datetime(generated_at)
+    ));
+    out.push_str("<LANGUAGE>ENG</LANGUAGE>\n</SONRS>\n</SIGNONMSGSRSV1>\n");
+    out.push_str("<BANKMSGSRSV1>\n<STMTTRNRS>\n<TRNUID>0</TRNUID>\n");
+    out.push_str("<STATUS>\n<CODE>0</CODE>\n<SEVERITY>INFO</SEVERITY>\n</STATUS>\n");
+    out.push_str("<STMTRS>\n<CURDEF>ZEC</CURDEF>\n");
+    out.push_str(&format!(
+        "<BANKACCTFROM>\n<BANKID>NOZY</BANKID>\n<ACCTID>{}</ACCTID>\n<ACCTTYPE>CHECKING</ACCTTYPE>\n</BANKACCTFROM>\n",
+        xml_escape(&account)
+    ));
+    out.push_str(&format!(
+        "<BANKTRANLIST>\n<DTSTART>{}</DTSTART>\n<DTEND>{}</DTEND>\n",
+        ofx_datetime(start),
+        ofx_datetime(end)
+    ));
+
+    let mut balance: i64 = 0;
+    for row in rows {
+        if row.direction == "self" || row.status == "failed" || row.status == "expired" {
+            continue;
+        }
+        let posted = row.date.unwrap_or(generated_at);
+        let name = row
+            .counterparty_label
+            .as_deref()
+            .or(row.counterparty.as_deref());
+        let trntype = if row.amount_zatoshis < 0 {
+            "DEBIT"
+        } else {
+            "CREDIT"
+        };
+        ofx_transaction(
+            &mut out,
+            trntype,
+            posted,
+            row.amount_zatoshis,
+            &row.txid,
+            name,
+            row.memo.as_deref(),
+        );
+        balance += row.amount_zatoshis;
+        if row.fee_zatoshis > 0 {
+            let fee = -(row.fee_zatoshis as i64);
+            ofx_transaction(
+                &mut out,
+                "FEE",
+                posted,
+                fee,
+                &format!("{}:fee", row.txid),
+                Some("Zcash network fee"),
+                None,
+            );
+            balance += fee;
+        }
+    }
+
+    out.push_str("</BANKTRANLIST>\n");
+    out.push_str(&format!(
+        "<LEDGERBAL>\n<BALAMT>{}</BALAMT>\n<DTASOF>{}</DTASOF>\n</LEDGERBAL>\n",
+        format_zec_amount(balance),
+        ofx_datetime(end)
+    ));
+    out.push_str("</STMTRS>\n</STMTTRNRS>\n</BANKMSGSRSV1>\n</OFX>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn row(txid: &str, amount: i64, fee: u64, day: u32) -> HistoryExportRow {
+        HistoryExportRow {
+            date: Some(Utc.with_ymd_and_hms(2026, 3, day, 12, 0, 0).unwrap()),
+            txid: txid.to_string(),
+            direction: if amount < 0 { "outgoing" } else { "incoming" }.to_string(),
+            amount_zatoshis: amount,
+            fee_zatoshis: fee,
+            memo: Some("invoice #7, \"march\"".to_string()),
+            counterparty: Some("u1abc".to_string()),
+            counterparty_label: Some("Landlord".to_string()),
+            confirmations: 12,
+            block_height: Some(3_200_000 + day),
+            status: "confirmed".to_string(),
+            account: "p1".to_string(),
+            fiat_currency: Some("USD".to_string()),
+            fiat_amount: Some(amount as f64 / 1e8 * 40.0),
+            fiat_price_per_zec: Some(40.0),
+        }
+    }
+
+    #[test]
+    fn format_zec_amount_is_exact() {
+        assert_eq!(format_zec_amount(0), "0.00000000");
+        assert_eq!(format_zec_amount(150_000_001), "1.50000001");
+        assert_eq!(format_zec_amount(-10_000), "-0.00010000");
+    }
+
+    #[test]
+    fn filter_parses_and_bounds_dates_inclusively() {
+        let filter = HistoryExportFilter::parse(Some("2026-03-02"), Some("2026-03-03"), None)
+            .expect("valid range");
+        assert!(!filter.matches_date(row("a", 1, 0, 1).date));
+        assert!(filter.matches_date(row("b", 1, 0, 2).date));
+        assert!(filter.matches_date(row("c", 1, 0, 3).date));
+        assert!(!filter.matches_date(None));
+        assert!(HistoryExportFilter::parse(Some("2026-03-04"), Some("2026-03-03"), None).is_err());
+        assert!(HistoryExportFilter::parse(Some("03/04/2026"), None, None).is_err());
+    }
+
+    #[test]
+    fn csv_quotes_memo_and_neutralises_formulas() {
+        let mut r = row("aa", -70_000, 10_000, 5);
+        r.counterparty_label = Some("=HYPERLINK(\"x\")".to_string());
+        let csv = render_csv(&[r]);
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some(HISTORY_EXPORT_CSV_HEADER));
+        let line = lines.next().expect("data row");
+        assert!(line.contains("\"invoice #7, \"\"march\"\"\""));
+        assert!(line.contains("\"'=HYPERLINK(\"\"x\"\")\""));
+        assert!(line.contains(",-0.00070000,-70000,0.00010000,10000,"));
+        assert!(line.ends_with(",USD,-0.03,40"));
+    }
+
+    #[test]
+    fn json_lines_emit_one_object_per_row() {
+        let out = render_json_lines(&[row("aa", 5, 0, 1), row("bb", -5, 1, 2)]).unwrap();
+        let lines: Vec<_> = out.lines().collect();
+        assert_eq!(lines.len(), 2);
+        let v: serde_json::Value = serde_json::from_str(lines[1]).unwrap();
+        assert_eq!(v["txid"], "bb");
+        assert_eq!(v["amount_zec"], "-0.00000005");
+        assert_eq!(v["counterparty_label"], "Landlord");
+    }
+
+    #[test]
+    fn ofx_splits_fees_and_balances() {
+        let rows = [row("in1", 100_000, 0, 1), row("out1", -40_000, 10_000, 2)];
+        let ofx = render_ofx(
+            &rows,
+            &HistoryExportFilter::default(),
+            Utc.with_ymd_and_hms(2026, 4, 1, 0, 0, 0).unwrap(),
+        );
+        assert!(ofx.contains("<FITID>out1:fee</FITID>"));
+        assert!(ofx.contains("<TRNTYPE>FEE</TRNTYPE>"));
+        assert!(ofx.contains("<BALAMT>0.00050000</BALAMT>"));
+        assert!(ofx.contains("<MEMO>invoice #7, &quot;march&quot;</MEMO>"));
+        assert!(ofx.contains("<ACCTID>p1</ACCTID>"));
+    }
+}
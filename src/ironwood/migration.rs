@@ -17,11 +17,12 @@ use crate::orchard_tx::OrchardWitnessProvider;
 use crate::paths::get_wallet_data_dir;
 use crate::shielded_pool::ShieldedPool;
 use chrono::Utc;
-use orchard::keys::{FullViewingKey, Scope, SpendAuthorizingKey};
+use orchard::keys::{FullViewingKey, Scope};
 use pczt::roles::{
     creator::Creator, io_finalizer::IoFinalizer, prover::Prover, signer::Signer,
     tx_extractor::TransactionExtractor,
 };
+use pczt::Pczt;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
@@ -1370,14 +1371,18 @@ fn parse_network_type(info: &std::collections::HashMap<String, serde_json::Value
     }
 }
 
-async fn build_migration_transaction_for_transfer(
+/// Build the proved, IO-finalized (unsigned) turnstile PCZT for one scheduled transfer.
+///
+/// `fvk` overrides the spend key's FVK (Keystone signs with keys Nozy never holds).
+async fn build_migration_pczt_for_transfer<'a>(
     zebra: &crate::zebra_integration::ZebraClient,
     network: NetworkType,
     chain_tip: u32,
     transfer: &MigrationScheduledTransfer,
-    spendable_notes: &[SpendableNote],
+    spendable_notes: &'a [SpendableNote],
     fee_zatoshis: u64,
-) -> NozyResult<PreparedMigrationTransaction> {
+    fvk: Option<&FullViewingKey>,
+) -> NozyResult<(Pczt, MigrationSpendSelection<'a>)> {
     let selection = select_migration_spend(spendable_notes, transfer.value_zat, fee_zatoshis)
         .map_err(|_| {
             NozyError::InvalidOperation(
@@ -1388,7 +1393,6 @@ async fn build_migration_transaction_for_transfer(
         })?;
     let spend_note = selection.spend_note;
     let ironwood_output_zat = selection.ironwood_output_zat;
-    let funding_mode = selection.funding_mode;
     let fee_zat = selection.fee_zat;
     let total_input = spend_note.orchard_note.value;
     let change = total_input
@@ -1400,7 +1404,6 @@ async fn build_migration_transaction_for_transfer(
              Split notes to exact denominations before prebuilding."
         )));
     }
-    let source_nullifier_hex = hex::encode(spend_note.orchard_note.nullifier.to_bytes());
 
     let witness_provider = crate::orchard_tx::ZebraJsonRpcOrchardWitnessProvider;
     let (orchard_anchor, merkle_path) = witness_provider
@@ -1408,7 +1411,7 @@ async fn build_migration_transaction_for_transfer(
         .await?;
 
     let target_height = BlockHeight::from_u32(chain_tip.saturating_add(1));
-    let fvk = FullViewingKey::from(&spend_note.spending_key);
+    let fvk = fvk.cloned().unwrap_or_else(|| spend_note.fvk.clone());
     let self_ironwood_address = fvk.to_ivk(Scope::Internal).address_at(0u64);
     let transfer_value = Zatoshis::from_u64(ironwood_output_zat).map_err(|_| {
         NozyError::InvalidOperation("Invalid Ironwood migration transfer amount".to_string())
@@ -1460,20 +1463,42 @@ async fn build_migration_transaction_for_transfer(
         NetworkType::Test | NetworkType::Regtest => build_pczt_for_network!(TestNetwork)?,
     };
 
-    let proving_key =
-        orchard::circuit::ProvingKey::build(orchard::circuit::OrchardCircuitVersion::PostNu6_3);
+    let proving_key = crate::keystone::post_nu6_3_proving_key();
     let pczt = Prover::new(pczt)
-        .create_orchard_proof(&proving_key)
+        .create_orchard_proof(proving_key)
         .map_err(|e| NozyError::InvalidOperation(format!("create_orchard_proof: {e:?}")))?
-        .create_ironwood_proof(&proving_key)
+        .create_ironwood_proof(proving_key)
         .map_err(|e| NozyError::InvalidOperation(format!("create_ironwood_proof: {e:?}")))?
         .finish();
 
     let pczt = IoFinalizer::new(pczt)
         .finalize_io()
         .map_err(|e| NozyError::InvalidOperation(format!("migration io_finalize: {e:?}")))?;
+    Ok((pczt, selection))
+}
 
-    let ask = SpendAuthorizingKey::from(&spend_note.spending_key);
+async fn build_migration_transaction_for_transfer(
+    zebra: &crate::zebra_integration::ZebraClient,
+    network: NetworkType,
+    chain_tip: u32,
+    transfer: &MigrationScheduledTransfer,
+    spendable_notes: &[SpendableNote],
+    fee_zatoshis: u64,
+) -> NozyResult<PreparedMigrationTransaction> {
+    let (pczt, selection) = build_migration_pczt_for_transfer(
+        zebra,
+        network,
+        chain_tip,
+        transfer,
+        spendable_notes,
+        fee_zatoshis,
+        None,
+    )
+    .await?;
+    let spend_note = selection.spend_note;
+    let source_nullifier_hex = hex::encode(spend_note.orchard_note.nullifier.to_bytes());
+
+    let ask = spend_note.spend_authorizing_key()?;
     let orchard_action_count = pczt.orchard().actions().len();
     let mut signer = Signer::new(pczt)
         .map_err(|e| NozyError::InvalidOperation(format!("migration signer init: {e:?}")))?;
@@ -1512,18 +1537,35 @@ async fn build_migration_transaction_for_transfer(
         expires_at_height: transfer
             .not_before_height
             .saturating_add(ZIP318_TRANSFER_EXPIRY_BLOCKS),
-        funding_mode,
-        ironwood_output_zat,
-        fee_zat,
+        funding_mode: selection.funding_mode,
+        ironwood_output_zat: selection.ironwood_output_zat,
+        fee_zat: selection.fee_zat,
     })
 }
 
-/// Execute migration — builds turnstile txs (one note per tx until multi-spend lands).
-pub async fn execute_orchard_migration(
+/// Chain state and the scheduled transfer chosen for the next turnstile prebuild.
+struct MigrationPrebuildTarget {
+    zebra: crate::zebra_integration::ZebraClient,
+    network: NetworkType,
+    chain_tip: u32,
+    plan: MigrationPlanSummary,
+    schedule: MigrationSchedule,
+    rebuilt_transfer_windows: usize,
+    transfer: MigrationScheduledTransfer,
+    fee_zat: u64,
+}
+
+/// Either a transfer ready to prebuild, or the result to report when nothing can be built yet.
+enum MigrationPrebuildStep {
+    Ready(Box<MigrationPrebuildTarget>),
+    Deferred(MigrationExecutionResult),
+}
+
+async fn next_migration_prebuild_step(
     zebra_url: &str,
     ironwood_active: bool,
     spendable_notes: &[SpendableNote],
-) -> NozyResult<MigrationExecutionResult> {
+) -> NozyResult<MigrationPrebuildStep> {
     let zebra = crate::zebra_integration::ZebraClient::new(zebra_url.to_string());
     let chain_tip = zebra.get_best_block_height().await?;
     let info = zebra.get_blockchain_info().await?;
@@ -1531,7 +1573,7 @@ pub async fn execute_orchard_migration(
     let plan = plan_orchard_migration_at(ironwood_active, chain_tip)?;
 
     if plan.orchard_notes_to_migrate == 0 {
-        return Ok(MigrationExecutionResult {
+        return Ok(MigrationPrebuildStep::Deferred(MigrationExecutionResult {
             orchard_notes_to_migrate: 0,
             total_zatoshis: 0,
             total_transfer_count: 0,
@@ -1540,7 +1582,7 @@ pub async fn execute_orchard_migration(
             readiness_state: MigrationReadinessState::NoOrchardNotes,
             blockers: Vec::new(),
             rebuilt_transfer_windows: 0,
-        });
+        }));
     }
 
     if !ironwood_active {
@@ -1550,7 +1592,7 @@ pub async fn execute_orchard_migration(
         ));
     }
 
-    let (mut schedule, schedule_path, rebuilt_transfer_windows) =
+    let (schedule, schedule_path, rebuilt_transfer_windows) =
         load_or_rebuild_orchard_migration_schedule(ironwood_active, chain_tip, &plan)?;
     let migration_fee = crate::fee_policy::estimate_orchard_send_fee_zatoshis(
         None,
@@ -1571,7 +1613,7 @@ pub async fn execute_orchard_migration(
         | MigrationReadinessState::WaitingForWindow
         | MigrationReadinessState::PresignedWaitingForBroadcast
         | MigrationReadinessState::ReadyToBroadcast => {
-            return Ok(MigrationExecutionResult {
+            return Ok(MigrationPrebuildStep::Deferred(MigrationExecutionResult {
                 orchard_notes_to_migrate: plan.orchard_notes_to_migrate,
                 total_zatoshis: plan.total_zatoshis,
                 total_transfer_count: plan.zip318.total_transfer_count,
@@ -1580,7 +1622,7 @@ pub async fn execute_orchard_migration(
                 readiness_state: readiness.state,
                 blockers: readiness.blockers,
                 rebuilt_transfer_windows,
-            });
+            }));
         }
         MigrationReadinessState::ReadyToPrebuild => {}
         MigrationReadinessState::PlanningOnly
@@ -1610,7 +1652,7 @@ pub async fn execute_orchard_migration(
         )
     })?;
     if !can_cover_transfer_with_current_notes(&transfer, spendable_notes, migration_fee) {
-        return Ok(MigrationExecutionResult {
+        return Ok(MigrationPrebuildStep::Deferred(MigrationExecutionResult {
             orchard_notes_to_migrate: plan.orchard_notes_to_migrate,
             total_zatoshis: plan.total_zatoshis,
             total_transfer_count: plan.zip318.total_transfer_count,
@@ -1624,18 +1666,28 @@ pub async fn execute_orchard_migration(
                 transfer.sequence, transfer.value_zat
             )],
             rebuilt_transfer_windows,
-        });
+        }));
     }
 
-    let prepared = build_migration_transaction_for_transfer(
-        &zebra,
-        network,
-        chain_tip,
-        &transfer,
-        spendable_notes,
-        migration_fee,
-    )
-    .await?;
+    Ok(MigrationPrebuildStep::Ready(Box::new(
+        MigrationPrebuildTarget {
+            zebra,
+            network,
+            chain_tip,
+            plan,
+            schedule,
+            rebuilt_transfer_windows,
+            transfer,
+            fee_zat: migration_fee,
+        },
+    )))
+}
+
+/// Mark `sequence` presigned in the schedule with the signed transaction it will broadcast.
+fn record_presigned_transfer(
+    schedule: &mut MigrationSchedule,
+    prepared: &PreparedMigrationTransaction,
+) {
     if let Some(transfer) = schedule
         .transfers
         .iter_mut()
@@ -1648,6 +1700,40 @@ pub async fn execute_orchard_migration(
         transfer.prepared_at_height = Some(prepared.prepared_at_height);
         transfer.expires_at_height = Some(prepared.expires_at_height);
     }
+}
+
+/// Execute migration — builds turnstile txs (one note per tx until multi-spend lands).
+pub async fn execute_orchard_migration(
+    zebra_url: &str,
+    ironwood_active: bool,
+    spendable_notes: &[SpendableNote],
+) -> NozyResult<MigrationExecutionResult> {
+    let target =
+        match next_migration_prebuild_step(zebra_url, ironwood_active, spendable_notes).await? {
+            MigrationPrebuildStep::Ready(target) => *target,
+            MigrationPrebuildStep::Deferred(result) => return Ok(result),
+        };
+    let MigrationPrebuildTarget {
+        zebra,
+        network,
+        chain_tip,
+        plan,
+        mut schedule,
+        rebuilt_transfer_windows,
+        transfer,
+        fee_zat,
+    } = target;
+
+    let prepared = build_migration_transaction_for_transfer(
+        &zebra,
+        network,
+        chain_tip,
+        &transfer,
+        spendable_notes,
+        fee_zat,
+    )
+    .await?;
+    record_presigned_transfer(&mut schedule, &prepared);
     let schedule_path = save_orchard_migration_schedule(&schedule)?;
 
     // ZIP 318 turnstile flow:
@@ -2082,14 +2168,16 @@ fn pick_orchard_note_for_split<'a>(notes: &'a [SpendableNote]) -> Option<&'a Spe
         .max_by_key(|note| note.orchard_note.value)
 }
 
-async fn build_orchard_split_transaction(
+/// Build the proved, IO-finalized (unsigned) send-to-self split PCZT for `spend_note`.
+async fn build_orchard_split_pczt(
     zebra: &crate::zebra_integration::ZebraClient,
     network: NetworkType,
     chain_tip: u32,
     spend_note: &SpendableNote,
+    fvk: &FullViewingKey,
     output_values_zat: &[u64],
     fee_zatoshis: u64,
-) -> NozyResult<(String, String)> {
+) -> NozyResult<Pczt> {
     if output_values_zat.is_empty() {
         return Err(NozyError::InvalidOperation(
             "Split transaction requires at least one Orchard output.".to_string(),
@@ -2112,7 +2200,6 @@ async fn build_orchard_split_transaction(
         .await?;
 
     let target_height = BlockHeight::from_u32(chain_tip.saturating_add(1));
-    let fvk = FullViewingKey::from(&spend_note.spending_key);
     // Preserve the source note's Orchard receiver (External vs Internal) — V6 rejects cross-scope outputs.
     let self_orchard_address = spend_note.orchard_note.address.clone();
     let fee = Zatoshis::from_u64(fee_zatoshis)
@@ -2168,18 +2255,37 @@ async fn build_orchard_split_transaction(
         NetworkType::Test | NetworkType::Regtest => build_split_pczt!(TestNetwork),
     };
 
-    let proving_key =
-        orchard::circuit::ProvingKey::build(orchard::circuit::OrchardCircuitVersion::PostNu6_3);
     let pczt = Prover::new(pczt)
-        .create_orchard_proof(&proving_key)
+        .create_orchard_proof(crate::keystone::post_nu6_3_proving_key())
         .map_err(|e| NozyError::InvalidOperation(format!("create_orchard_proof: {e:?}")))?
         .finish();
 
-    let pczt = IoFinalizer::new(pczt)
+    IoFinalizer::new(pczt)
         .finalize_io()
-        .map_err(|e| NozyError::InvalidOperation(format!("split io_finalize: {e:?}")))?;
+        .map_err(|e| NozyError::InvalidOperation(format!("split io_finalize: {e:?}")))
+}
 
-    let ask = SpendAuthorizingKey::from(&spend_note.spending_key);
+async fn build_orchard_split_transaction(
+    zebra: &crate::zebra_integration::ZebraClient,
+    network: NetworkType,
+    chain_tip: u32,
+    spend_note: &SpendableNote,
+    output_values_zat: &[u64],
+    fee_zatoshis: u64,
+) -> NozyResult<(String, String)> {
+    let fvk = spend_note.fvk.clone();
+    let pczt = build_orchard_split_pczt(
+        zebra,
+        network,
+        chain_tip,
+        spend_note,
+        &fvk,
+        output_values_zat,
+        fee_zatoshis,
+    )
+    .await?;
+
+    let ask = spend_note.spend_authorizing_key()?;
     let orchard_action_count = pczt.orchard().actions().len();
     let mut signer = Signer::new(pczt)
         .map_err(|e| NozyError::InvalidOperation(format!("split signer init: {e:?}")))?;
@@ -2283,6 +2389,310 @@ pub async fn execute_orchard_note_split(
     })
 }
 
+pub const KEYSTONE_MIGRATION_PENDING_FILE: &str = "keystone_migration_pending.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeystoneMigrationKind {
+    /// ZIP 318 Phase 1 Orchard send-to-self split.
+    NoteSplit,
+    /// Orchard → Ironwood turnstile crossing for one scheduled transfer.
+    Crossing,
+}
+
+/// Unsigned migration PCZT awaiting a Keystone signature.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeystoneMigrationPczt {
+    pub kind: KeystoneMigrationKind,
+    /// Schedule sequence for crossings; `None` for note splits.
+    pub sequence: Option<u32>,
+    pub source_nullifier_hex: String,
+    pub source_value_zat: u64,
+    pub output_values_zat: Vec<u64>,
+    pub fee_zat: u64,
+    #[serde(default)]
+    pub funding_mode: Option<Zip318FundingMode>,
+    pub prepared_at_height: u32,
+    pub expires_at_height: u32,
+    pub summary: String,
+    pub pczt_hex: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeystoneMigrationCompletion {
+    pub kind: KeystoneMigrationKind,
+    pub sequence: Option<u32>,
+    pub txid: String,
+    /// True once a note split was submitted; crossings wait for `nozy ironwood broadcast`.
+    pub broadcast: bool,
+    pub schedule_path: Option<PathBuf>,
+}
+
+pub fn keystone_migration_pending_path() -> PathBuf {
+    get_wallet_data_dir().join(KEYSTONE_MIGRATION_PENDING_FILE)
+}
+
+pub fn load_keystone_migration_pending() -> NozyResult<Option<KeystoneMigrationPczt>> {
+    let path = keystone_migration_pending_path();
+    if !path.exists() {
+        return Ok(None);
+    }
+    let json = fs::read_to_string(&path)
+        .map_err(|e| NozyError::Storage(format!("Read pending Keystone migration: {e}")))?;
+    serde_json::from_str(&json)
+        .map(Some)
+        .map_err(|e| NozyError::Storage(format!("Parse pending Keystone migration: {e}")))
+}
+
+fn save_keystone_migration_pending(pending: &KeystoneMigrationPczt) -> NozyResult<()> {
+    let json = serde_json::to_string_pretty(pending)
+        .map_err(|e| NozyError::Storage(format!("Serialize pending Keystone migration: {e}")))?;
+    fs::write(keystone_migration_pending_path(), json)
+        .map_err(|e| NozyError::Storage(format!("Write pending Keystone migration: {e}")))
+}
+
+pub fn clear_keystone_migration_pending() -> NozyResult<()> {
+    let path = keystone_migration_pending_path();
+    if path.exists() {
+        fs::remove_file(&path)
+            .map_err(|e| NozyError::Storage(format!("Remove pending Keystone migration: {e}")))?;
+    }
+    Ok(())
+}
+
+fn finish_keystone_migration_pczt(
+    pczt: Pczt,
+    pending: KeystoneMigrationPczt,
+) -> NozyResult<KeystoneMigrationPczt> {
+    let pczt_bytes = crate::keystone::redact_pczt_for_signer(pczt)
+        .serialize()
+        .map_err(|e| NozyError::InvalidOperation(format!("PCZT serialize failed: {e:?}")))?;
+    let pczt_hex = hex::encode(pczt_bytes);
+    let pending = KeystoneMigrationPczt {
+        pczt_hex,
+        ..pending
+    };
+    save_keystone_migration_pending(&pending)?;
+    Ok(pending)
+}
+
+/// ZIP 318 Phase 1 for Keystone: build the split PCZT for the device to sign.
+pub async fn prepare_keystone_note_split_pczt(
+    zebra_url: &str,
+    ironwood_active: bool,
+    wallet: &crate::hd_wallet::HDWallet,
+    keystone: &crate::keystone::KeystoneWalletConfig,
+    spendable_notes: &[SpendableNote],
+) -> NozyResult<KeystoneMigrationPczt> {
+    if !ironwood_active {
+        return Err(NozyError::InvalidOperation(
+            "Ironwood (NU6.3) is not active yet. Note splitting opens after activation."
+                .to_string(),
+        ));
+    }
+    let spend_note = pick_orchard_note_for_split(spendable_notes).ok_or_else(|| {
+        NozyError::InvalidOperation(
+            "No Orchard note requires canonical splitting. Run `nozy ironwood preflight` to confirm."
+                .to_string(),
+        )
+    })?;
+    let (output_values_zat, fee_zat) =
+        plan_orchard_note_split_outputs(spend_note.orchard_note.value)?;
+
+    let zebra = crate::zebra_integration::ZebraClient::new(zebra_url.to_string());
+    let chain_tip = zebra.get_best_block_height().await?;
+    let info = zebra.get_blockchain_info().await?;
+    let network = parse_network_type(&info);
+    let fvk = crate::keystone::orchard_fvk_for_send(wallet, keystone, spend_note)?;
+
+    let pczt = build_orchard_split_pczt(
+        &zebra,
+        network,
+        chain_tip,
+        spend_note,
+        &fvk,
+        &output_values_zat,
+        fee_zat,
+    )
+    .await?;
+    let expires_at_height = *pczt.global().expiry_height();
+    let source_value_zat = spend_note.orchard_note.value;
+    finish_keystone_migration_pczt(
+        pczt,
+        KeystoneMigrationPczt {
+            kind: KeystoneMigrationKind::NoteSplit,
+            sequence: None,
+            source_nullifier_hex: hex::encode(spend_note.orchard_note.nullifier.to_bytes()),
+            source_value_zat,
+            summary: format!(
+                "Split {:.8} ZEC Orchard note into {} canonical note(s)\nFee: {:.8} ZEC",
+                source_value_zat as f64 / 100_000_000.0,
+                output_values_zat.len(),
+                fee_zat as f64 / 100_000_000.0
+            ),
+            output_values_zat,
+            fee_zat,
+            funding_mode: None,
+            prepared_at_height: chain_tip,
+            expires_at_height,
+            pczt_hex: String::new(),
+        },
+    )
+}
+
+/// Build the next scheduled turnstile crossing as a PCZT for Keystone to sign.
+///
+/// Errors with the readiness blockers when no transfer can be prebuilt yet.
+pub async fn prepare_keystone_migration_pczt(
+    zebra_url: &str,
+    ironwood_active: bool,
+    wallet: &crate::hd_wallet::HDWallet,
+    keystone: &crate::keystone::KeystoneWalletConfig,
+    spendable_notes: &[SpendableNote],
+) -> NozyResult<KeystoneMigrationPczt> {
+    let target =
+        match next_migration_prebuild_step(zebra_url, ironwood_active, spendable_notes).await? {
+            MigrationPrebuildStep::Ready(target) => *target,
+            MigrationPrebuildStep::Deferred(result) => {
+                return Err(NozyError::InvalidOperation(format!(
+                    "Ironwood migration is {}: {}",
+                    result.readiness_state.label(),
+                    result.blockers.join("; ")
+                )));
+            }
+        };
+    let transfer = &target.transfer;
+    let source = select_migration_spend(spendable_notes, transfer.value_zat, target.fee_zat)?;
+    let fvk = crate::keystone::orchard_fvk_for_send(wallet, keystone, source.spend_note)?;
+
+    let (pczt, selection) = build_migration_pczt_for_transfer(
+        &target.zebra,
+        target.network,
+        target.chain_tip,
+        transfer,
+        spendable_notes,
+        target.fee_zat,
+        Some(&fvk),
+    )
+    .await?;
+    finish_keystone_migration_pczt(
+        pczt,
+        KeystoneMigrationPczt {
+            kind: KeystoneMigrationKind::Crossing,
+            sequence: Some(transfer.sequence),
+            source_nullifier_hex: hex::encode(
+                selection.spend_note.orchard_note.nullifier.to_bytes(),
+            ),
+            source_value_zat: selection.spend_note.orchard_note.value,
+            output_values_zat: vec![selection.ironwood_output_zat],
+            fee_zat: selection.fee_zat,
+            funding_mode: Some(selection.funding_mode),
+            prepared_at_height: target.chain_tip,
+            expires_at_height: transfer
+                .not_before_height
+                .saturating_add(ZIP318_TRANSFER_EXPIRY_BLOCKS),
+            summary: format!(
+                "Migrate {:.8} ZEC Orchard → Ironwood (ZIP 318 transfer #{})\nFee: {:.8} ZEC",
+                selection.ironwood_output_zat as f64 / 100_000_000.0,
+                transfer.sequence,
+                selection.fee_zat as f64 / 100_000_000.0
+            ),
+            pczt_hex: String::new(),
+        },
+    )
+}
+
+/// Accept the Keystone-signed migration PCZT.
+///
+/// Note splits are broadcast immediately; crossings are stored as presigned schedule
+/// entries so `nozy ironwood broadcast` keeps its bucket-window and network-privacy gates.
+pub async fn complete_keystone_migration_pczt(
+    zebra_url: &str,
+    signed_pczt_bytes: &[u8],
+) -> NozyResult<KeystoneMigrationCompletion> {
+    let pending = load_keystone_migration_pending()?.ok_or_else(|| {
+        NozyError::InvalidOperation(
+            "No Keystone migration PCZT is awaiting a signature.".to_string(),
+        )
+    })?;
+    let spends = crate::cold_signing::pczt_spend_nullifiers(signed_pczt_bytes)?;
+    if !spends
+        .iter()
+        .any(|nf| hex::encode(nf) == pending.source_nullifier_hex)
+    {
+        return Err(NozyError::InvalidOperation(
+            "Signed PCZT does not spend the note of the pending Keystone migration.".to_string(),
+        ));
+    }
+    let extracted = crate::keystone::extract_signed_tx_from_pczt_bytes(signed_pczt_bytes)?;
+
+    let completion = match pending.kind {
+        KeystoneMigrationKind::NoteSplit => {
+            let zebra = crate::zebra_integration::ZebraClient::new(zebra_url.to_string());
+            zebra
+                .broadcast_transaction(&hex::encode(&extracted.raw_transaction))
+                .await?;
+            crate::notes::mark_wallet_notes_spent_by_nullifier_hex(
+                std::slice::from_ref(&pending.source_nullifier_hex),
+                Some(&extracted.txid),
+            )?;
+            KeystoneMigrationCompletion {
+                kind: pending.kind,
+                sequence: None,
+                txid: extracted.txid,
+                broadcast: true,
+                schedule_path: None,
+            }
+        }
+        KeystoneMigrationKind::Crossing => {
+            let sequence = pending.sequence.ok_or_else(|| {
+                NozyError::InvalidOperation(
+                    "Pending Keystone crossing has no schedule sequence.".to_string(),
+                )
+            })?;
+            let mut schedule = load_orchard_migration_schedule()?.ok_or_else(|| {
+                NozyError::InvalidOperation("No Ironwood migration schedule found.".to_string())
+            })?;
+            let value_zat = schedule
+                .transfers
+                .iter()
+                .find(|t| t.sequence == sequence && t.status == MigrationTransferStatus::Pending)
+                .map(|t| t.value_zat)
+                .ok_or_else(|| {
+                    NozyError::InvalidOperation(format!(
+                        "ZIP 318 transfer #{sequence} is no longer pending; prepare a fresh migration PCZT."
+                    ))
+                })?;
+            record_presigned_transfer(
+                &mut schedule,
+                &PreparedMigrationTransaction {
+                    sequence,
+                    value_zat,
+                    txid: extracted.txid.clone(),
+                    raw_tx_hex: hex::encode(&extracted.raw_transaction),
+                    source_nullifier_hex: pending.source_nullifier_hex.clone(),
+                    prepared_at_height: pending.prepared_at_height,
+                    expires_at_height: pending.expires_at_height,
+                    funding_mode: pending
+                        .funding_mode
+                        .unwrap_or(Zip318FundingMode::ExactCover),
+                    ironwood_output_zat: pending.output_values_zat.iter().sum(),
+                    fee_zat: pending.fee_zat,
+                },
+            );
+            KeystoneMigrationCompletion {
+                kind: pending.kind,
+                sequence: Some(sequence),
+                txid: extracted.txid,
+                broadcast: false,
+                schedule_path: Some(save_orchard_migration_schedule(&schedule)?),
+            }
+        }
+    };
+    clear_keystone_migration_pending()?;
+    Ok(completion)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -2326,6 +2736,90 @@ mod tests {
         }
     }
 
+    #[test]
+    fn keystone_crossing_completion_requires_the_pending_note_and_stores_it_presigned() {
+        use crate::notes_vault::{clear_notes_vault, unlock_notes_vault};
+        use crate::paths::with_wallet_data_dir;
+        use orchard::keys::SpendingKey;
+
+        let _g = crate::notes_vault::lock_notes_vault_for_test();
+        let dir = std::env::temp_dir().join(format!(
+            "nozy-keystone-migration-test-{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let sk = SpendingKey::from_bytes([12u8; 32]).unwrap();
+        let fvk = FullViewingKey::from(&sk);
+        let self_address = fvk.address_at(0u32, Scope::Internal);
+        let (pczt, note) =
+            crate::tests::unproven_ironwood_test_pczt(&fvk, 200_000, &[(self_address, 150_000)]);
+        let pczt_bytes = Prover::new(pczt)
+            .create_ironwood_proof(crate::keystone::post_nu6_3_proving_key())
+            .unwrap()
+            .finish()
+            .serialize()
+            .unwrap();
+        let signed = crate::keystone::sign_pczt_orchard_spends(&pczt_bytes, &sk).unwrap();
+        let txid = crate::keystone::extract_signed_tx_from_pczt_bytes(&signed)
+            .unwrap()
+            .txid;
+        let nullifier_hex = hex::encode(note.nullifier(&fvk).to_bytes());
+
+        with_wallet_data_dir(&dir, || {
+            clear_notes_vault();
+            unlock_notes_vault("keystone").unwrap();
+            let plan = sample_plan(1_000);
+            let schedule = build_schedule_from_plan(&plan, 1_000);
+            let sequence = schedule.transfers[0].sequence;
+            save_orchard_migration_schedule(&schedule).unwrap();
+            let pending = |source_nullifier_hex: String| KeystoneMigrationPczt {
+                kind: KeystoneMigrationKind::Crossing,
+                sequence: Some(sequence),
+                source_nullifier_hex,
+                source_value_zat: 200_000,
+                output_values_zat: vec![150_000],
+                fee_zat: 50_000,
+                funding_mode: None,
+                prepared_at_height: 1_024,
+                expires_at_height: 1_280,
+                summary: "test crossing".to_string(),
+                pczt_hex: hex::encode(&pczt_bytes),
+            };
+            let complete = || {
+                futures::executor::block_on(complete_keystone_migration_pczt(
+                    "http://127.0.0.1:9",
+                    &signed,
+                ))
+            };
+
+            // A PCZT for another note leaves the pending crossing in place.
+            save_keystone_migration_pending(&pending("ff".repeat(32))).unwrap();
+            assert!(complete().is_err());
+            assert!(load_keystone_migration_pending().unwrap().is_some());
+
+            save_keystone_migration_pending(&pending(nullifier_hex.clone())).unwrap();
+            let completion = complete().unwrap();
+            assert!(!completion.broadcast);
+            assert_eq!(completion.sequence, Some(sequence));
+            assert_eq!(completion.txid, txid);
+            assert!(load_keystone_migration_pending().unwrap().is_none());
+
+            let schedule = load_orchard_migration_schedule().unwrap().unwrap();
+            let transfer = &schedule.transfers[0];
+            assert_eq!(transfer.status, MigrationTransferStatus::Presigned);
+            assert_eq!(transfer.prepared_txid.as_deref(), Some(txid.as_str()));
+            assert_eq!(
+                transfer.source_nullifier_hex.as_deref(),
+                Some(nullifier_hex.as_str())
+            );
+            assert!(transfer.presigned_tx_hex.is_some());
+            clear_notes_vault();
+        });
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
     #[test]
     fn previous_anchor_boundary_uses_zip318_interval() {
         assert_eq!(previous_zip318_anchor_boundary(0), 0);
@@ -2653,6 +3147,7 @@ mod tests {
                 rseed_bytes: None,
                 spent_in_txid: None,
                 pool: crate::shielded_pool::ShieldedPool::Orchard,
+                frozen: false,
             }
         }
 
@@ -15,21 +15,25 @@ pub use baseline_hygiene::{
     DEFAULT_CHECKPOINT_SPACING_BLOCKS, DEFAULT_MAX_OVERLAP_BLOCKS, DEFAULT_MIN_SECS_AFTER_TIP_SYNC,
 };
 pub use migration::{
-    assess_orchard_migration_readiness, build_schedule_from_plan, execute_orchard_migration,
+    assess_orchard_migration_readiness, build_schedule_from_plan, clear_keystone_migration_pending,
+    complete_keystone_migration_pczt, execute_orchard_migration,
     execute_orchard_migration_broadcast, execute_orchard_note_split,
     flatten_canonical_denomination_zatoshis, ironwood_migration_schedule_path,
+    keystone_migration_pending_path, load_keystone_migration_pending,
     load_orchard_migration_schedule, next_zip318_anchor_boundary, note_requires_canonical_split,
     plan_orchard_migration, plan_orchard_migration_at, plan_orchard_note_split_outputs,
+    prepare_keystone_migration_pczt, prepare_keystone_note_split_pczt,
     presigned_transfer_broadcastable, previous_zip318_anchor_boundary, propose_zip318_crossing,
     propose_zip318_crossing_from_orchard_notes, reconcile_migration_broadcast_confirmations,
     refresh_orchard_migration_schedule_at, save_orchard_migration_plan_at,
     save_orchard_migration_schedule, select_canonical_zip318_funding, select_zooko_round_amount,
-    validate_orchard_migration_schedule, MigrationBroadcastResult, MigrationDenomination,
+    validate_orchard_migration_schedule, KeystoneMigrationCompletion, KeystoneMigrationKind,
+    KeystoneMigrationPczt, MigrationBroadcastResult, MigrationDenomination,
     MigrationExecutionResult, MigrationPlanSummary, MigrationReadinessReport,
     MigrationReadinessState, MigrationSchedule, MigrationScheduleValidation,
     MigrationScheduledTransfer, MigrationTransfer, MigrationTransferStatus, OrchardNoteSplitResult,
     PreparedMigrationTransaction, Zip318CrossingProposal, Zip318FundingMode, Zip318ScheduleSummary,
-    IRONWOOD_MIGRATION_SCHEDULE_FILE, MIGRATION_SCHEDULE_VERSION,
+    IRONWOOD_MIGRATION_SCHEDULE_FILE, KEYSTONE_MIGRATION_PENDING_FILE, MIGRATION_SCHEDULE_VERSION,
     ZIP318_ANCHOR_BUCKET_INTERVAL_BLOCKS, ZIP318_DEFAULT_K_MAX, ZIP318_TRANSFER_EXPIRY_BLOCKS,
     ZOOKO_RESIDUAL_ABANDON_ZAT,
 };
@@ -136,8 +136,9 @@ pub async fn legacy_hardware_send_blocker(
     }
     if orchard_notes_unspent_zat > 0 {
         return Ok(Some(
-            "Orchard notes remain after Ironwood activation. Run Ironwood Plan → Migrate → Broadcast \
-             (or `nozy ironwood migrate`) before sending from a hardware wallet."
+            "Orchard notes remain after Ironwood activation. Migrate them with Keystone first \
+             (`nozy ironwood split --pczt`, `nozy ironwood migrate --pczt`, then \
+             `nozy ironwood complete`) before sending from a hardware wallet."
                 .to_string(),
         ));
     }
@@ -16,7 +16,6 @@ use crate::shielded_pool::ShieldedPool;
 use crate::zebra_integration::ZebraClient;
 use async_trait::async_trait;
 use futures::future::join_all;
-use orchard::keys::{FullViewingKey, SpendAuthorizingKey};
 use orchard::tree::{Anchor, MerklePath};
 use orchard::Address as OrchardAddress;
 use pczt::roles::{
@@ -226,7 +225,7 @@ pub async fn build_single_ironwood_spend(
         NozyError::InvalidOperation(format!("Invalid memo for Ironwood send: {e:?}"))
     })?;
 
-    let fvk = FullViewingKey::from(&spend_note.spending_key);
+    let fvk = spend_note.fvk.clone();
     let change_address = spend_note.orchard_note.address.clone();
 
     for attempt in 1..=PILOT_EXPIRY_MAX_REBUILD_ATTEMPTS {
@@ -316,7 +315,7 @@ pub async fn build_single_ironwood_spend(
             NozyError::InvalidOperation(format!("ironwood send io_finalize: {e:?}"))
         })?;
 
-        let ask = SpendAuthorizingKey::from(&spend_note.spending_key);
+        let ask = spend_note.spend_authorizing_key()?;
         let ironwood_action_count = pczt.ironwood().actions().len();
         let mut signer = Signer::new(pczt)
             .map_err(|e| NozyError::InvalidOperation(format!("ironwood signer init: {e:?}")))?;
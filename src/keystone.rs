@@ -5,11 +5,13 @@
 //! for the device. Keystone signs and returns a signed PCZT; Nozy extracts and broadcasts.
 
 use crate::error::{NozyError, NozyResult};
-use crate::fee_policy::PilotSendOptions;
+use crate::fee_policy::{self, PilotSendOptions};
 use crate::hd_wallet::HDWallet;
+use crate::ironwood::IronwoodAwareMainNetwork;
+use crate::ironwood_tx::{IronwoodWitnessProvider, ZebraJsonRpcIronwoodWitnessProvider};
 use crate::notes::SpendableNote;
-use crate::orchard_tx::{select_single_spend_note, OrchardWitnessProvider};
-use crate::paths::get_wallet_data_dir;
+use crate::orchard_tx::OrchardWitnessProvider;
+use crate::shielded_pool::ShieldedPool;
 use crate::zebra_integration::ZebraClient;
 use orchard::keys::{FullViewingKey, Scope, SpendAuthorizingKey, SpendingKey};
 use pczt::roles::{
@@ -19,8 +21,6 @@ use pczt::roles::{
 use pczt::Pczt;
 use rand::rngs::OsRng;
 use serde::{Deserialize, Serialize};
-use std::fs;
-use std::path::PathBuf;
 use std::sync::OnceLock;
 use zcash_address::unified::{Container, Encoding};
 use zcash_keys::keys::UnifiedFullViewingKey;
@@ -33,7 +33,11 @@ use zcash_protocol::value::Zatoshis;
 pub const UR_TYPE_ZCASH_PCZT: &str = "zcash-pczt";
 pub const DEFAULT_UR_FRAGMENT_SIZE: usize = 200;
 
+/// Transaction version introduced by NU6.3 (Ironwood); proved with the post-NU6.3 circuit.
+const V6_TX_VERSION: u32 = 6;
+
 static ORCHARD_PROVING_KEY: OnceLock<orchard::circuit::ProvingKey> = OnceLock::new();
+static POST_NU6_3_PROVING_KEY: OnceLock<orchard::circuit::ProvingKey> = OnceLock::new();
 
 fn orchard_proving_key() -> &'static orchard::circuit::ProvingKey {
     ORCHARD_PROVING_KEY.get_or_init(|| {
@@ -41,6 +45,12 @@ fn orchard_proving_key() -> &'static orchard::circuit::ProvingKey {
     })
 }
 
+pub(crate) fn post_nu6_3_proving_key() -> &'static orchard::circuit::ProvingKey {
+    POST_NU6_3_PROVING_KEY.get_or_init(|| {
+        orchard::circuit::ProvingKey::build(orchard::circuit::OrchardCircuitVersion::PostNu6_3)
+    })
+}
+
 /// Keystone pairing / custody settings persisted in `config.json`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct KeystoneWalletConfig {
@@ -86,6 +96,8 @@ pub struct KeystonePcztBuild {
     pub pczt_bytes: Vec<u8>,
     pub summary: String,
     pub action_count: u32,
+    /// May exceed the requested fee when more than one note is spent.
+    pub fee_zatoshis: u64,
 }
 
 #[derive(Debug, Clone)]
@@ -94,8 +106,8 @@ pub struct KeystoneExtractedTx {
     pub txid: String,
 }
 
-struct FixedFeeRule {
-    fee: Zatoshis,
+pub(crate) struct FixedFeeRule {
+    pub(crate) fee: Zatoshis,
 }
 
 impl FeeRule for FixedFeeRule {
@@ -133,6 +145,17 @@ pub fn redact_pczt_for_signer(pczt: Pczt) -> Pczt {
                 a.clear_output_proprietary();
             });
         })
+        .redact_ironwood_with(|mut o| {
+            o.redact_actions(|mut a| {
+                a.clear_spend_witness();
+                a.clear_spend_zip32_derivation();
+                a.clear_spend_dummy_sk();
+                a.clear_spend_proprietary();
+                a.clear_output_zip32_derivation();
+                a.clear_output_user_address();
+                a.clear_output_proprietary();
+            });
+        })
         .redact_transparent_with(|mut t| {
             t.redact_outputs(|mut o| {
                 o.clear_user_address();
@@ -142,10 +165,18 @@ pub fn redact_pczt_for_signer(pczt: Pczt) -> Pczt {
         .finish()
 }
 
-fn orchard_fvk_for_send(
+pub(crate) fn orchard_fvk_for_send(
     _wallet: &HDWallet,
     keystone: &KeystoneWalletConfig,
     spendable: &SpendableNote,
+) -> NozyResult<FullViewingKey> {
+    send_fvk(keystone, spendable)
+}
+
+/// The paired Keystone's Orchard FVK, else the key the note was found with.
+fn send_fvk(
+    keystone: &KeystoneWalletConfig,
+    spendable: &SpendableNote,
 ) -> NozyResult<FullViewingKey> {
     if let Some(ufvk_str) = keystone.ufvk.as_deref().filter(|s| !s.is_empty()) {
         let is_mainnet = ufvk_str.starts_with('u') && !ufvk_str.starts_with("utest");
@@ -159,7 +190,7 @@ fn orchard_fvk_for_send(
             NozyError::InvalidOperation("Keystone UFVK has no Orchard component".to_string())
         });
     }
-    Ok(FullViewingKey::from(&spendable.spending_key))
+    Ok(spendable.fvk.clone())
 }
 
 /// Export the wallet's Orchard UFVK (ZIP-316) for Keystone pairing.
@@ -210,11 +241,89 @@ pub fn validate_ufvk(ufvk: &str, network: NetworkType) -> NozyResult<()> {
     Ok(())
 }
 
+/// Most notes one hardware PCZT spends, so the animated QR stays short enough to scan.
+pub const MAX_KEYSTONE_SPEND_NOTES: usize = 10;
+
+/// Notes picked for a hardware send and the fee for that many spends.
+#[derive(Debug)]
+pub struct KeystoneSpendSelection<'a> {
+    pub notes: Vec<&'a SpendableNote>,
+    pub fee_zatoshis: u64,
+}
+
+/// Pick note indices covering `amount + fee_for(spends)`: the smallest single note when one
+/// suffices, otherwise the largest notes first, up to [`MAX_KEYSTONE_SPEND_NOTES`].
+fn pick_spend_values(
+    values: &[u64],
+    amount_zatoshis: u64,
+    fee_for: impl Fn(u32) -> u64,
+) -> Option<(Vec<usize>, u64)> {
+    let single_fee = fee_for(1);
+    if let Some((index, _)) = values
+        .iter()
+        .enumerate()
+        .filter(|(_, v)| **v >= amount_zatoshis.saturating_add(single_fee))
+        .min_by_key(|(_, v)| **v)
+    {
+        return Some((vec![index], single_fee));
+    }
+    let mut order: Vec<usize> = (0..values.len()).collect();
+    order.sort_by_key(|i| std::cmp::Reverse(values[*i]));
+    let mut total = 0u64;
+    for (count, index) in order.iter().take(MAX_KEYSTONE_SPEND_NOTES).enumerate() {
+        total = total.saturating_add(values[*index]);
+        let fee = fee_for(count as u32 + 1);
+        if total >= amount_zatoshis.saturating_add(fee) {
+            return Some((order[..=count].to_vec(), fee));
+        }
+    }
+    None
+}
+
+/// Select unspent `pool` notes for a hardware send; `min_fee_zatoshis` is the caller's
+/// single-spend estimate and is raised by ZIP-317 as spends are added.
+pub fn select_keystone_spend_notes<'a>(
+    spendable_notes: &'a [SpendableNote],
+    pool: ShieldedPool,
+    amount_zatoshis: u64,
+    min_fee_zatoshis: u64,
+    memo: Option<&[u8]>,
+) -> NozyResult<KeystoneSpendSelection<'a>> {
+    let candidates: Vec<&SpendableNote> = spendable_notes
+        .iter()
+        .filter(|n| !n.orchard_note.spent && n.pool == pool)
+        .collect();
+    let values: Vec<u64> = candidates.iter().map(|n| n.orchard_note.value).collect();
+    let fee_for = |spends: u32| {
+        fee_policy::fee_zatoshis(
+            &fee_policy::OrchardSendFeeShape::multi_spend_send(spends, true, memo),
+            fee_policy::NOZY_WALLET_PRIORITY_FEE,
+        )
+        .max(min_fee_zatoshis)
+    };
+    let (indices, fee_zatoshis) =
+        pick_spend_values(&values, amount_zatoshis, fee_for).ok_or_else(|| {
+            NozyError::InvalidOperation(format!(
+                "{} {pool:?} notes ({} zats in total) do not cover {amount_zatoshis} zats plus fee \
+                 within {MAX_KEYSTONE_SPEND_NOTES} spends",
+                values.len(),
+                values.iter().sum::<u64>()
+            ))
+        })?;
+    Ok(KeystoneSpendSelection {
+        notes: indices.into_iter().map(|i| candidates[i]).collect(),
+        fee_zatoshis,
+    })
+}
+
 /// Build a proved, IO-finalized PCZT ready for Keystone signing.
+///
+/// Spends as many notes as needed. Before NU6.3 they are Orchard notes; after activation
+/// they are Ironwood notes (Orchard notes must go through the ZIP 318 migration first).
+/// Needs no seed: notes from a watch-only or FROST profile carry the viewing key.
 pub async fn build_keystone_send_pczt(
     zebra: &ZebraClient,
     witness_provider: &dyn OrchardWitnessProvider,
-    wallet: &HDWallet,
     keystone: &KeystoneWalletConfig,
     spendable_notes: &[SpendableNote],
     recipient_address: &str,
@@ -225,25 +334,36 @@ pub async fn build_keystone_send_pczt(
     network: NetworkType,
 ) -> NozyResult<KeystonePcztBuild> {
     let tip_height = zebra.get_best_block_height().await?;
-    let orchard_zat: u64 = spendable_notes
-        .iter()
-        .filter(|n| !n.orchard_note.spent && n.pool == crate::shielded_pool::ShieldedPool::Orchard)
-        .map(|n| n.orchard_note.value)
-        .sum();
-    let ironwood_zat: u64 = spendable_notes
-        .iter()
-        .filter(|n| !n.orchard_note.spent && n.pool == crate::shielded_pool::ShieldedPool::Ironwood)
-        .map(|n| n.orchard_note.value)
-        .sum();
-    if let Some(reason) =
-        crate::ironwood::legacy_hardware_send_blocker(zebra, tip_height, orchard_zat, ironwood_zat)
-            .await?
+    let pool_total = |pool: ShieldedPool| -> u64 {
+        spendable_notes
+            .iter()
+            .filter(|n| !n.orchard_note.spent && n.pool == pool)
+            .map(|n| n.orchard_note.value)
+            .sum()
+    };
+    if let Some(reason) = crate::ironwood::legacy_hardware_send_blocker(
+        zebra,
+        tip_height,
+        pool_total(ShieldedPool::Orchard),
+        pool_total(ShieldedPool::Ironwood),
+    )
+    .await?
     {
         return Err(NozyError::InvalidOperation(reason));
     }
+    let info = zebra.get_blockchain_info().await?;
+    let pool = if crate::ironwood::status::get_blockchain_info_reports_ironwood_active(
+        &info, tip_height,
+    ) {
+        ShieldedPool::Ironwood
+    } else {
+        ShieldedPool::Orchard
+    };
 
-    let spendable = select_single_spend_note(spendable_notes, amount_zatoshis, fee_zatoshis)?;
-    let fvk = orchard_fvk_for_send(wallet, keystone, spendable)?;
+    let selection =
+        select_keystone_spend_notes(spendable_notes, pool, amount_zatoshis, fee_zatoshis, memo)?;
+    let fee_zatoshis = selection.fee_zatoshis;
+    let fvk = send_fvk(keystone, selection.notes[0])?;
 
     let (_, decoded) = zcash_address::unified::Address::decode(recipient_address)
         .map_err(|e| NozyError::InvalidOperation(format!("Invalid recipient address: {e}")))?;
@@ -265,11 +385,32 @@ pub async fn build_keystone_send_pczt(
         .ok_or_else(|| NozyError::AddressParsing("Invalid Orchard receiver bytes".to_string()))?;
 
     let target_height = BlockHeight::from_u32(tip_height.saturating_add(1));
-    let (anchor, merkle_path) = witness_provider
-        .prepare_spend_anchor_and_path(zebra, spendable, tip_height)
-        .await?;
+    // Every spend is witnessed at the tip, so all paths share one anchor.
+    let mut anchor = None;
+    let mut spends = Vec::with_capacity(selection.notes.len());
+    for note in &selection.notes {
+        let (note_anchor, merkle_path) = match pool {
+            ShieldedPool::Ironwood => {
+                ZebraJsonRpcIronwoodWitnessProvider
+                    .prepare_spend_anchor_and_path(zebra, note, tip_height)
+                    .await?
+            }
+            _ => {
+                witness_provider
+                    .prepare_spend_anchor_and_path(zebra, note, tip_height)
+                    .await?
+            }
+        };
+        if anchor.is_some_and(|first| first != note_anchor) {
+            return Err(NozyError::InvalidOperation(
+                "Spend witnesses returned different anchors; rescan and try again".to_string(),
+            ));
+        }
+        anchor = Some(note_anchor);
+        spends.push((note.orchard_note.note.clone(), merkle_path));
+    }
 
-    let total_input = spendable.orchard_note.value;
+    let total_input: u64 = selection.notes.iter().map(|n| n.orchard_note.value).sum();
     let change = total_input
         .saturating_sub(amount_zatoshis)
         .saturating_sub(fee_zatoshis);
@@ -288,79 +429,64 @@ pub async fn build_keystone_send_pczt(
     let fee_rule = FixedFeeRule { fee: fee_zat };
 
     let change_addr = fvk.to_ivk(Scope::Internal).address_at(0u64);
-    let build_config = BuildConfig::Standard {
-        sapling_anchor: None,
-        orchard_anchor: Some(anchor),
-        ironwood_anchor: None,
+    let build_config = match pool {
+        ShieldedPool::Ironwood => BuildConfig::Standard {
+            sapling_anchor: None,
+            orchard_anchor: None,
+            ironwood_anchor: anchor,
+        },
+        _ => BuildConfig::Standard {
+            sapling_anchor: None,
+            orchard_anchor: anchor,
+            ironwood_anchor: None,
+        },
     };
+    let mut outputs = vec![(recipient, amount_zat, recipient_memo)];
+    if change > 0 {
+        let change_zat = Zatoshis::from_u64(change)
+            .map_err(|_| NozyError::InvalidOperation("Invalid change amount".to_string()))?;
+        outputs.push((change_addr, change_zat, MemoBytes::empty()));
+    }
 
-    let pczt = match network {
-        NetworkType::Main => {
-            let mut builder = Builder::new(MainNetwork, target_height, build_config);
-            builder
-                .add_orchard_spend::<NozyError>(
-                    fvk.clone(),
-                    spendable.orchard_note.note.clone(),
-                    merkle_path,
-                )
-                .map_err(|e| NozyError::InvalidOperation(format!("add_orchard_spend: {e:?}")))?;
-            builder
-                .add_orchard_output::<NozyError>(
-                    None,
-                    recipient,
-                    amount_zat,
-                    recipient_memo.clone(),
-                )
-                .map_err(|e| NozyError::InvalidOperation(format!("add_orchard_output: {e:?}")))?;
-            if change > 0 {
-                let change_zat = Zatoshis::from_u64(change).map_err(|_| {
-                    NozyError::InvalidOperation("Invalid change amount".to_string())
-                })?;
-                builder
-                    .add_orchard_output::<NozyError>(
-                        None,
-                        change_addr,
-                        change_zat,
-                        MemoBytes::empty(),
-                    )
-                    .map_err(|e| {
-                        NozyError::InvalidOperation(format!("add_orchard_output (change): {e:?}"))
-                    })?;
+    macro_rules! build_pczt_for_network {
+        ($params:expr) => {{
+            let mut builder = Builder::new($params, target_height, build_config);
+            for (note, merkle_path) in spends {
+                match pool {
+                    ShieldedPool::Ironwood => builder
+                        .add_ironwood_spend::<core::convert::Infallible>(
+                            fvk.clone(),
+                            note,
+                            merkle_path,
+                        )
+                        .map_err(|e| {
+                            NozyError::InvalidOperation(format!("add_ironwood_spend: {e:?}"))
+                        })?,
+                    _ => builder
+                        .add_orchard_spend::<NozyError>(fvk.clone(), note, merkle_path)
+                        .map_err(|e| {
+                            NozyError::InvalidOperation(format!("add_orchard_spend: {e:?}"))
+                        })?,
+                }
             }
-            let parts = builder
-                .build_for_pczt(OsRng, &fee_rule)
-                .map_err(|e| NozyError::InvalidOperation(format!("build_for_pczt: {e:?}")))?
-                .pczt_parts;
-            Creator::build_from_parts(parts).ok_or_else(|| {
-                NozyError::InvalidOperation("PCZT creator: incompatible tx version".to_string())
-            })?
-        }
-        NetworkType::Test | NetworkType::Regtest => {
-            let mut builder = Builder::new(TestNetwork, target_height, build_config);
-            builder
-                .add_orchard_spend::<NozyError>(
-                    fvk.clone(),
-                    spendable.orchard_note.note.clone(),
-                    merkle_path,
-                )
-                .map_err(|e| NozyError::InvalidOperation(format!("add_orchard_spend: {e:?}")))?;
-            builder
-                .add_orchard_output::<NozyError>(None, recipient, amount_zat, recipient_memo)
-                .map_err(|e| NozyError::InvalidOperation(format!("add_orchard_output: {e:?}")))?;
-            if change > 0 {
-                let change_zat = Zatoshis::from_u64(change).map_err(|_| {
-                    NozyError::InvalidOperation("Invalid change amount".to_string())
-                })?;
-                builder
-                    .add_orchard_output::<NozyError>(
-                        None,
-                        change_addr,
-                        change_zat,
-                        MemoBytes::empty(),
-                    )
-                    .map_err(|e| {
-                        NozyError::InvalidOperation(format!("add_orchard_output (change): {e:?}"))
-                    })?;
+            for (address, value, output_memo) in outputs {
+                match pool {
+                    ShieldedPool::Ironwood => builder
+                        .add_ironwood_output::<core::convert::Infallible>(
+                            None,
+                            address,
+                            value,
+                            output_memo,
+                        )
+                        .map_err(|e| {
+                            NozyError::InvalidOperation(format!("add_ironwood_output: {e:?}"))
+                        })?,
+                    _ => builder
+                        .add_orchard_output::<NozyError>(None, address, value, output_memo)
+                        .map_err(|e| {
+                            NozyError::InvalidOperation(format!("add_orchard_output: {e:?}"))
+                        })?,
+                }
             }
             let parts = builder
                 .build_for_pczt(OsRng, &fee_rule)
@@ -369,20 +495,31 @@ pub async fn build_keystone_send_pczt(
             Creator::build_from_parts(parts).ok_or_else(|| {
                 NozyError::InvalidOperation("PCZT creator: incompatible tx version".to_string())
             })?
-        }
+        }};
+    }
+
+    let pczt = match network {
+        NetworkType::Main => build_pczt_for_network!(IronwoodAwareMainNetwork),
+        NetworkType::Test | NetworkType::Regtest => build_pczt_for_network!(TestNetwork),
     };
 
-    let pczt = Prover::new(pczt)
-        .create_orchard_proof(orchard_proving_key())
-        .map_err(|e| NozyError::InvalidOperation(format!("create_orchard_proof: {e:?}")))?
-        .finish();
+    let pczt = match pool {
+        ShieldedPool::Ironwood => Prover::new(pczt)
+            .create_ironwood_proof(post_nu6_3_proving_key())
+            .map_err(|e| NozyError::InvalidOperation(format!("create_ironwood_proof: {e:?}")))?
+            .finish(),
+        _ => Prover::new(pczt)
+            .create_orchard_proof(orchard_proving_key())
+            .map_err(|e| NozyError::InvalidOperation(format!("create_orchard_proof: {e:?}")))?
+            .finish(),
+    };
 
     let pczt = IoFinalizer::new(pczt)
         .finalize_io()
         .map_err(|e| NozyError::InvalidOperation(format!("io_finalize: {e:?}")))?;
 
     let pczt = redact_pczt_for_signer(pczt);
-    let action_count = pczt.orchard().actions().len() as u32;
+    let action_count = (pczt.orchard().actions().len() + pczt.ironwood().actions().len()) as u32;
     let pczt_bytes = pczt
         .serialize()
         .map_err(|e| NozyError::InvalidOperation(format!("PCZT serialize failed: {e:?}")))?;
@@ -393,10 +530,16 @@ pub async fn build_keystone_send_pczt(
         recipient_address.to_string()
     };
     let summary = format!(
-        "Send {:.8} ZEC to {}\nFee: {:.8} ZEC\nSpending 1 Orchard note",
+        "Send {:.8} ZEC to {}\nFee: {:.8} ZEC\nSpending {} {} note(s)",
         amount_zatoshis as f64 / 100_000_000.0,
         recipient_short,
         fee_zatoshis as f64 / 100_000_000.0,
+        selection.notes.len(),
+        if pool == ShieldedPool::Ironwood {
+            "Ironwood"
+        } else {
+            "Orchard"
+        },
     );
 
     let _ = pilot; // expiry encoded in target_height via chain tip
@@ -405,20 +548,30 @@ pub async fn build_keystone_send_pczt(
         pczt_bytes,
         summary,
         action_count,
+        fee_zatoshis,
     })
 }
 
-/// Extract a broadcast-ready v5 transaction from a Keystone-signed PCZT.
+/// Extract a broadcast-ready v5 or V6 (NU6.3) transaction from a Keystone-signed PCZT.
 pub fn extract_signed_tx_from_pczt_bytes(pczt_bytes: &[u8]) -> NozyResult<KeystoneExtractedTx> {
     let pczt = Pczt::parse(pczt_bytes)
         .map_err(|e| NozyError::InvalidOperation(format!("PCZT parse failed: {e:?}")))?;
 
     static ORCHARD_VK: OnceLock<orchard::circuit::VerifyingKey> = OnceLock::new();
-    let vk = ORCHARD_VK.get_or_init(|| {
-        orchard::circuit::VerifyingKey::build(
-            orchard::circuit::OrchardCircuitVersion::FixedPostNu6_2,
-        )
-    });
+    static POST_NU6_3_VK: OnceLock<orchard::circuit::VerifyingKey> = OnceLock::new();
+    let vk = if *pczt.global().tx_version() >= V6_TX_VERSION {
+        POST_NU6_3_VK.get_or_init(|| {
+            orchard::circuit::VerifyingKey::build(
+                orchard::circuit::OrchardCircuitVersion::PostNu6_3,
+            )
+        })
+    } else {
+        ORCHARD_VK.get_or_init(|| {
+            orchard::circuit::VerifyingKey::build(
+                orchard::circuit::OrchardCircuitVersion::FixedPostNu6_2,
+            )
+        })
+    };
 
     let tx = TransactionExtractor::new(pczt)
         .with_orchard(vk)
@@ -448,7 +601,7 @@ pub fn orchard_spending_key_from_wallet(wallet: &HDWallet) -> NozyResult<Spendin
     })
 }
 
-/// Add Orchard spend authorization signatures to a proved, IO-finalized PCZT.
+/// Add Orchard and Ironwood spend authorization signatures to a proved, IO-finalized PCZT.
 pub fn sign_pczt_orchard_spends(
     pczt_bytes: &[u8],
     spending_key: &SpendingKey,
@@ -457,9 +610,10 @@ pub fn sign_pczt_orchard_spends(
         .map_err(|e| NozyError::InvalidOperation(format!("PCZT parse failed: {e:?}")))?;
 
     let action_count = pczt.orchard().actions().len();
-    if action_count == 0 {
+    let ironwood_action_count = pczt.ironwood().actions().len();
+    if action_count + ironwood_action_count == 0 {
         return Err(NozyError::InvalidOperation(
-            "PCZT has no Orchard actions to sign".to_string(),
+            "PCZT has no Orchard or Ironwood actions to sign".to_string(),
         ));
     }
 
@@ -473,6 +627,11 @@ pub fn sign_pczt_orchard_spends(
             signed_any = true;
         }
     }
+    for index in 0..ironwood_action_count {
+        if signer.sign_ironwood(index, &ask).is_ok() {
+            signed_any = true;
+        }
+    }
 
     if !signed_any {
         return Err(NozyError::InvalidOperation(
@@ -490,13 +649,12 @@ pub fn sign_pczt_orchard_spends(
 pub fn prepared_send_from_build(
     recipient: &str,
     amount_zatoshis: u64,
-    fee_zatoshis: u64,
     build: &KeystonePcztBuild,
 ) -> KeystonePreparedSend {
     KeystonePreparedSend {
         recipient: recipient.to_string(),
         amount_zatoshis,
-        fee_zatoshis,
+        fee_zatoshis: build.fee_zatoshis,
         summary: build.summary.clone(),
         action_count: build.action_count,
         pczt_hex: hex::encode(&build.pczt_bytes),
@@ -625,39 +783,6 @@ pub fn decode_pczt_ur_frames(parts: &[String]) -> NozyResult<Vec<u8>> {
     unwrap_pczt_cbor(&cbor)
 }
 
-pub fn pending_send_path() -> PathBuf {
-    get_wallet_data_dir().join("keystone_pending_send.json")
-}
-
-pub fn save_pending_send(prepared: &KeystonePreparedSend) -> NozyResult<()> {
-    let path = pending_send_path();
-    let json = serde_json::to_string_pretty(prepared)
-        .map_err(|e| NozyError::Storage(format!("Serialize pending send: {e}")))?;
-    fs::write(&path, json).map_err(|e| NozyError::Storage(format!("Write pending send: {e}")))?;
-    Ok(())
-}
-
-pub fn load_pending_send() -> NozyResult<Option<KeystonePreparedSend>> {
-    let path = pending_send_path();
-    if !path.exists() {
-        return Ok(None);
-    }
-    let content = fs::read_to_string(&path)
-        .map_err(|e| NozyError::Storage(format!("Read pending send: {e}")))?;
-    let prepared = serde_json::from_str(&content)
-        .map_err(|e| NozyError::Storage(format!("Parse pending send: {e}")))?;
-    Ok(Some(prepared))
-}
-
-pub fn clear_pending_send() -> NozyResult<()> {
-    let path = pending_send_path();
-    if path.exists() {
-        fs::remove_file(&path)
-            .map_err(|e| NozyError::Storage(format!("Remove pending send: {e}")))?;
-    }
-    Ok(())
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -670,6 +795,29 @@ mod tests {
         assert_eq!(decoded, payload);
     }
 
+    #[test]
+    fn spend_selection_prefers_one_note_then_largest_first() {
+        let fee_for = |spends: u32| 10_000 * u64::from(spends.max(2));
+        assert_eq!(
+            pick_spend_values(&[50_000, 200_000, 120_000], 100_000, fee_for),
+            Some((vec![2], 20_000))
+        );
+        // 150k + 100k covers 200k plus the two-spend fee; three notes are not needed.
+        assert_eq!(
+            pick_spend_values(&[100_000, 40_000, 150_000], 200_000, fee_for),
+            Some((vec![2, 0], 20_000))
+        );
+        // A third spend raises the fee to 30k; 290k covers 260k + 30k exactly.
+        assert_eq!(
+            pick_spend_values(&[100_000, 100_000, 90_000], 260_000, fee_for),
+            Some((vec![0, 1, 2], 30_000))
+        );
+        assert_eq!(
+            pick_spend_values(&[100_000, 100_000, 89_999], 260_000, fee_for),
+            None
+        );
+    }
+
     #[test]
     fn ur_single_frame_roundtrip() {
         let pczt = b"sample-pczt";
@@ -678,4 +826,37 @@ mod tests {
         let decoded = decode_pczt_ur_frames(&frames).unwrap();
         assert_eq!(decoded, pczt);
     }
+
+    #[test]
+    fn ironwood_pczt_signs_and_extracts_a_v6_transaction() {
+        let sk = SpendingKey::from_bytes([9u8; 32]).unwrap();
+        let fvk = FullViewingKey::from(&sk);
+        let recipient = FullViewingKey::from(&SpendingKey::from_bytes([10u8; 32]).unwrap())
+            .address_at(0u32, Scope::External);
+        let (pczt, _) =
+            crate::tests::unproven_ironwood_test_pczt(&fvk, 200_000, &[(recipient, 150_000)]);
+        assert!(pczt.orchard().actions().is_empty());
+        let pczt_bytes = Prover::new(pczt)
+            .create_ironwood_proof(post_nu6_3_proving_key())
+            .unwrap()
+            .finish()
+            .serialize()
+            .unwrap();
+
+        assert!(extract_signed_tx_from_pczt_bytes(&pczt_bytes).is_err());
+        let wrong_key = SpendingKey::from_bytes([11u8; 32]).unwrap();
+        assert!(sign_pczt_orchard_spends(&pczt_bytes, &wrong_key).is_err());
+
+        let signed = sign_pczt_orchard_spends(&pczt_bytes, &sk).unwrap();
+        let signed_pczt = Pczt::parse(&signed).unwrap();
+        assert!(signed_pczt
+            .ironwood()
+            .actions()
+            .iter()
+            .all(|a| a.spend().spend_auth_sig().is_some()));
+        let extracted = extract_signed_tx_from_pczt_bytes(&signed).unwrap();
+        // fOverwintered | version 6, little-endian.
+        assert_eq!(extracted.raw_transaction[..4], [6, 0, 0, 0x80]);
+        assert_eq!(extracted.txid.len(), 64);
+    }
 }
@@ -0,0 +1,403 @@
+//! User labels, tags and notes on transactions and individual notes.
+//!
+//! Stored in `labels.json`, encrypted with the notes vault session key (NZL1). Notes
+//! are keyed by nullifier hex, transactions by txid. Tags listed in
+//! `WalletConfig::never_spend_tags` keep notes out of send selection (coin control).
+
+use crate::error::{NozyError, NozyResult};
+use crate::notes::SpendableNote;
+use crate::notes_vault::{decrypt_labels_file_content, encrypt_labels_json};
+use crate::paths::get_wallet_data_dir;
+use crate::transaction_history::TransactionView;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+pub const LABELS_FILE: &str = "labels.json";
+const LABELS_VERSION: u32 = 1;
+const MAX_LABEL_LEN: usize = 120;
+const MAX_TAG_LEN: usize = 40;
+const MAX_NOTE_LEN: usize = 2_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LabelTarget {
+    Transaction,
+    Note,
+}
+
+impl FromStr for LabelTarget {
+    type Err = NozyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "tx" | "transaction" => Ok(LabelTarget::Transaction),
+            "note" => Ok(LabelTarget::Note),
+            other => Err(NozyError::InvalidInput(format!(
+                "Unknown label target '{other}' (expected tx or note)"
+            ))),
+        }
+    }
+}
+
+/// Label, tags and free-form note attached to one transaction or note.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Annotation {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub tags: BTreeSet<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl Annotation {
+    pub fn is_empty(&self) -> bool {
+        self.label.is_none() && self.tags.is_empty() && self.note.is_none()
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.contains(&normalize_tag(tag))
+    }
+
+    /// Case-insensitive substring match on label, tags and note; `#tag` matches a tag exactly.
+    pub fn matches(&self, query: &str) -> bool {
+        let q = query.trim().to_lowercase();
+        if q.is_empty() {
+            return true;
+        }
+        if let Some(tag) = q.strip_prefix('#') {
+            return self.has_tag(tag);
+        }
+        self.label
+            .as_deref()
+            .is_some_and(|l| l.to_lowercase().contains(&q))
+            || self.tags.iter().any(|t| t.contains(&q))
+            || self
+                .note
+                .as_deref()
+                .is_some_and(|n| n.to_lowercase().contains(&q))
+    }
+}
+
+/// Partial update; `None` fields are left untouched.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AnnotationUpdate {
+    /// `Some("")` clears the label.
+    #[serde(default)]
+    pub label: Option<String>,
+    #[serde(default)]
+    pub add_tags: Vec<String>,
+    #[serde(default)]
+    pub remove_tags: Vec<String>,
+    /// `Some("")` clears the note.
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+/// Lowercase, trimmed; spaces become `-`.
+pub fn normalize_tag(tag: &str) -> String {
+    tag.trim()
+        .trim_start_matches('#')
+        .to_lowercase()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+fn normalize_id(id: &str) -> String {
+    id.trim().to_ascii_lowercase()
+}
+
+fn optional_text(value: String, max: usize, what: &str) -> NozyResult<Option<String>> {
+    let value = value.trim().to_string();
+    if value.chars().count() > max {
+        return Err(NozyError::InvalidInput(format!(
+            "{what} is longer than {max} characters"
+        )));
+    }
+    Ok((!value.is_empty()).then_some(value))
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct LabelsFile {
+    version: u32,
+    #[serde(default)]
+    transactions: BTreeMap<String, Annotation>,
+    #[serde(default)]
+    notes: BTreeMap<String, Annotation>,
+}
+
+/// A search hit.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LabelMatch {
+    pub target: LabelTarget,
+    pub id: String,
+    pub annotation: Annotation,
+}
+
+/// Per-profile label store backed by `labels.json` (NZL1).
+pub struct LabelStore {
+    storage_path: PathBuf,
+    data: LabelsFile,
+}
+
+impl LabelStore {
+    pub fn new() -> NozyResult<Self> {
+        Self::with_path(get_wallet_data_dir().join(LABELS_FILE))
+    }
+
+    pub fn with_path(storage_path: PathBuf) -> NozyResult<Self> {
+        let data = if storage_path.exists() {
+            let content = fs::read_to_string(&storage_path)
+                .map_err(|e| NozyError::Storage(format!("Failed to read labels: {e}")))?;
+            let plaintext = decrypt_labels_file_content(&content)?;
+            serde_json::from_str(&plaintext)
+                .map_err(|e| NozyError::Storage(format!("Failed to parse labels: {e}")))?
+        } else {
+            LabelsFile {
+                version: LABELS_VERSION,
+                ..LabelsFile::default()
+            }
+        };
+        Ok(Self { storage_path, data })
+    }
+
+    fn map(&self, target: LabelTarget) -> &BTreeMap<String, Annotation> {
+        match target {
+            LabelTarget::Transaction => &self.data.transactions,
+            LabelTarget::Note => &self.data.notes,
+        }
+    }
+
+    fn map_mut(&mut self, target: LabelTarget) -> &mut BTreeMap<String, Annotation> {
+        match target {
+            LabelTarget::Transaction => &mut self.data.transactions,
+            LabelTarget::Note => &mut self.data.notes,
+        }
+    }
+
+    pub fn get(&self, target: LabelTarget, id: &str) -> Option<&Annotation> {
+        self.map(target).get(&normalize_id(id))
+    }
+
+    /// Apply `update` and persist. Returns the resulting annotation (empty ones are dropped).
+    pub fn update(
+        &mut self,
+        target: LabelTarget,
+        id: &str,
+        update: AnnotationUpdate,
+    ) -> NozyResult<Annotation> {
+        let id = normalize_id(id);
+        if id.is_empty() {
+            return Err(NozyError::InvalidInput("Label target id is empty".into()));
+        }
+        let mut entry = self.map(target).get(&id).cloned().unwrap_or_default();
+        if let Some(label) = update.label {
+            entry.label = optional_text(label, MAX_LABEL_LEN, "Label")?;
+        }
+        if let Some(note) = update.note {
+            entry.note = optional_text(note, MAX_NOTE_LEN, "Note")?;
+        }
+        for tag in update.add_tags {
+            let tag = normalize_tag(&tag);
+            if tag.is_empty() || tag.chars().count() > MAX_TAG_LEN {
+                return Err(NozyError::InvalidInput(format!(
+                    "Tags must be 1-{MAX_TAG_LEN} characters"
+                )));
+            }
+            entry.tags.insert(tag);
+        }
+        for tag in update.remove_tags {
+            entry.tags.remove(&normalize_tag(&tag));
+        }
+        entry.updated_at = Some(Utc::now());
+
+        if entry.is_empty() {
+            self.map_mut(target).remove(&id);
+        } else {
+            self.map_mut(target).insert(id, entry.clone());
+        }
+        self.save()?;
+        Ok(entry)
+    }
+
+    pub fn remove(&mut self, target: LabelTarget, id: &str) -> NozyResult<bool> {
+        let removed = self.map_mut(target).remove(&normalize_id(id)).is_some();
+        if removed {
+            self.save()?;
+        }
+        Ok(removed)
+    }
+
+    pub fn search(&self, query: &str) -> Vec<LabelMatch> {
+        [LabelTarget::Transaction, LabelTarget::Note]
+            .into_iter()
+            .flat_map(|target| {
+                self.map(target)
+                    .iter()
+                    .filter(|(_, a)| a.matches(query))
+                    .map(move |(id, a)| LabelMatch {
+                        target,
+                        id: id.clone(),
+                        annotation: a.clone(),
+                    })
+            })
+            .collect()
+    }
+
+    /// Nullifier hexes of notes carrying any of `tags`.
+    pub fn note_ids_with_any_tag(&self, tags: &[String]) -> HashSet<String> {
+        let tags: Vec<String> = tags.iter().map(|t| normalize_tag(t)).collect();
+        self.data
+            .notes
+            .iter()
+            .filter(|(_, a)| tags.iter().any(|t| a.tags.contains(t)))
+            .map(|(id, _)| id.clone())
+            .collect()
+    }
+
+    /// Attach transaction annotations to history rows.
+    pub fn annotate_views(&self, views: &mut [TransactionView]) {
+        for view in views.iter_mut() {
+            view.annotation = self.get(LabelTarget::Transaction, &view.txid).cloned();
+        }
+    }
+
+    /// True when the row's own annotation or any of its notes' annotations match.
+    pub fn view_matches(&self, view: &TransactionView, query: &str) -> bool {
+        self.get(LabelTarget::Transaction, &view.txid)
+            .is_some_and(|a| a.matches(query))
+            || view
+                .notes_involved
+                .iter()
+                .filter_map(|id| self.get(LabelTarget::Note, id))
+                .any(|a| a.matches(query))
+    }
+
+    fn save(&self) -> NozyResult<()> {
+        if let Some(parent) = self.storage_path.parent() {
+            fs::create_dir_all(parent).map_err(|e| {
+                NozyError::Storage(format!("Failed to create labels directory: {e}"))
+            })?;
+        }
+        let serialized = serde_json::to_string_pretty(&self.data)
+            .map_err(|e| NozyError::Storage(format!("Failed to serialize labels: {e}")))?;
+        let encrypted = encrypt_labels_json(&serialized)?;
+        let temp_path = self.storage_path.with_extension("tmp");
+        fs::write(&temp_path, encrypted)
+            .map_err(|e| NozyError::Storage(format!("Failed to write labels: {e}")))?;
+        fs::rename(&temp_path, &self.storage_path)
+            .map_err(|e| NozyError::Storage(format!("Failed to save labels: {e}")))
+    }
+}
+
+/// Drop notes tagged with any of `never_spend_tags` from send candidates.
+pub fn exclude_tagged_spendable_notes(
+    spendable: Vec<SpendableNote>,
+    never_spend_tags: &[String],
+) -> NozyResult<Vec<SpendableNote>> {
+    if never_spend_tags.is_empty() {
+        return Ok(spendable);
+    }
+    let excluded = LabelStore::new()?.note_ids_with_any_tag(never_spend_tags);
+    if excluded.is_empty() {
+        return Ok(spendable);
+    }
+    Ok(spendable
+        .into_iter()
+        .filter(|n| !excluded.contains(&hex::encode(n.orchard_note.nullifier.to_bytes())))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notes_vault::{clear_notes_vault, unlock_notes_vault};
+    use crate::paths::with_wallet_data_dir;
+
+    #[test]
+    fn labels_roundtrip_encrypted_and_searchable() {
+        let _g = crate::notes_vault::lock_notes_vault_for_test();
+        let dir = std::env::temp_dir().join(format!("nozy-labels-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        with_wallet_data_dir(&dir, || {
+            clear_notes_vault();
+            unlock_notes_vault("secret").unwrap();
+            let mut store = LabelStore::new().unwrap();
+            store
+                .update(
+                    LabelTarget::Note,
+                    "AB01",
+                    AnnotationUpdate {
+                        label: Some("Savings".into()),
+                        add_tags: vec!["Cold Storage".into()],
+                        ..AnnotationUpdate::default()
+                    },
+                )
+                .unwrap();
+            store
+                .update(
+                    LabelTarget::Transaction,
+                    "tx1",
+                    AnnotationUpdate {
+                        note: Some("Rent for March".into()),
+                        ..AnnotationUpdate::default()
+                    },
+                )
+                .unwrap();
+
+            let on_disk = std::fs::read_to_string(dir.join(LABELS_FILE)).unwrap();
+            assert!(!on_disk.contains("Savings"));
+
+            let store = LabelStore::new().unwrap();
+            assert!(store
+                .get(LabelTarget::Note, "ab01")
+                .unwrap()
+                .has_tag("cold-storage"));
+            assert_eq!(store.search("#cold-storage").len(), 1);
+            assert_eq!(store.search("rent")[0].target, LabelTarget::Transaction);
+            assert!(store
+                .note_ids_with_any_tag(&["COLD STORAGE".into()])
+                .contains("ab01"));
+            clear_notes_vault();
+        });
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn clearing_everything_drops_entry() {
+        let _g = crate::notes_vault::lock_notes_vault_for_test();
+        let dir =
+            std::env::temp_dir().join(format!("nozy-labels-clear-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        with_wallet_data_dir(&dir, || {
+            clear_notes_vault();
+            unlock_notes_vault("secret").unwrap();
+            let mut store = LabelStore::new().unwrap();
+            let tagged = AnnotationUpdate {
+                add_tags: vec!["cold".into()],
+                ..AnnotationUpdate::default()
+            };
+            store.update(LabelTarget::Note, "n1", tagged).unwrap();
+            let cleared = AnnotationUpdate {
+                remove_tags: vec!["cold".into()],
+                ..AnnotationUpdate::default()
+            };
+            assert!(store
+                .update(LabelTarget::Note, "n1", cleared)
+                .unwrap()
+                .is_empty());
+            assert!(store.get(LabelTarget::Note, "n1").is_none());
+            clear_notes_vault();
+        });
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -22,6 +22,8 @@ pub mod version_info;
 #[cfg(feature = "native")]
 pub mod address_book;
 #[cfg(feature = "native")]
+pub mod auditor;
+#[cfg(feature = "native")]
 pub mod benchmarks;
 #[cfg(feature = "native")]
 pub mod block_parser;
@@ -32,10 +34,22 @@ pub mod cache;
 #[cfg(feature = "native")]
 pub mod cli_helpers;
 #[cfg(feature = "native")]
+pub mod coin_control;
+#[cfg(feature = "native")]
+pub mod cold_signing;
+#[cfg(feature = "native")]
 pub mod config;
 #[cfg(feature = "native")]
+pub mod cost_basis;
+#[cfg(feature = "native")]
+pub mod daemon;
+#[cfg(feature = "native")]
+pub mod frost_multisig;
+#[cfg(feature = "native")]
 pub mod grpc_client;
 #[cfg(feature = "native")]
+pub mod history_export;
+#[cfg(feature = "native")]
 pub mod ironwood;
 #[cfg(feature = "native")]
 pub mod ironwood_tree_codec;
@@ -46,6 +60,8 @@ pub mod ironwood_witness;
 #[cfg(feature = "native")]
 pub mod keystone;
 #[cfg(feature = "native")]
+pub mod labels;
+#[cfg(feature = "native")]
 pub mod lite_ops;
 #[cfg(feature = "native")]
 pub mod lite_tui;
@@ -54,6 +70,8 @@ pub mod local_analytics;
 #[cfg(feature = "native")]
 pub mod merchant_invoices;
 #[cfg(feature = "native")]
+pub mod merchant_webhooks;
+#[cfg(feature = "native")]
 pub mod monero;
 #[cfg(feature = "native")]
 pub mod monero_zk_verifier;
@@ -80,10 +98,22 @@ pub mod orchard_tx;
 #[cfg(feature = "native")]
 pub mod orchard_witness;
 #[cfg(feature = "native")]
+pub mod ovk_recovery;
+#[cfg(feature = "native")]
 pub mod paths;
 #[cfg(feature = "native")]
+pub mod payment_disclosure;
+#[cfg(feature = "native")]
+pub mod payment_request;
+#[cfg(feature = "native")]
 pub mod pilot_metrics;
 #[cfg(feature = "native")]
+pub mod pos;
+#[cfg(feature = "native")]
+pub mod pos_tui;
+#[cfg(feature = "native")]
+pub mod price_source;
+#[cfg(feature = "native")]
 pub mod privacy_network;
 #[cfg(feature = "native")]
 pub mod privacy_ui;
@@ -105,13 +135,19 @@ pub mod sapling_tx;
 pub mod sapling_witness;
 #[cfg(feature = "native")]
 pub mod scan_log;
+#[cfg(feature = "native")]
+pub mod scheduled_payments;
 #[cfg(feature = "secret-network")]
 pub mod secret;
 #[cfg(feature = "native")]
+pub mod send_proposal;
+#[cfg(feature = "native")]
 pub mod send_readiness;
 #[cfg(feature = "native")]
 pub mod shielded_pool;
 #[cfg(feature = "native")]
+pub mod signing_queue;
+#[cfg(feature = "native")]
 pub mod storage;
 #[cfg(feature = "native")]
 pub mod swap;
@@ -132,15 +168,21 @@ pub mod wallet_profiles;
 #[cfg(feature = "native")]
 pub mod wallet_sync;
 #[cfg(feature = "native")]
+pub mod watch_only;
+#[cfg(feature = "native")]
 pub mod zeaking_adapter;
 #[cfg(feature = "native")]
 pub mod zebra_integration;
 #[cfg(feature = "native")]
 pub mod zebra_tree_rpc;
 #[cfg(feature = "native")]
+pub mod zip304;
+#[cfg(feature = "native")]
 pub mod zip321;
 #[cfg(feature = "native")]
 pub mod zns;
+#[cfg(feature = "native")]
+pub mod zns_registration;
 
 // ============================================================
 // WASM-safe re-exports
@@ -172,7 +214,13 @@ pub use bridge::{
 pub use cli_helpers::{
     cached_unspent_balance_zatoshis, estimate_transaction_fee, estimate_transaction_fee_for_send,
     format_insufficient_funds_message, is_insufficient_funds_error, is_zebra_unavailable_error,
-    scan_notes_for_sending, wallet_balance_snapshot, zebra_connect_api_code, WalletBalanceSnapshot,
+    scan_notes_for_sending, scan_notes_for_viewing_key, wallet_balance_snapshot,
+    zebra_connect_api_code, WalletBalanceSnapshot,
+};
+#[cfg(feature = "native")]
+pub use coin_control::{
+    apply_coin_control, list_wallet_note_summaries, restrict_to_note_ids, set_notes_frozen,
+    NoteSummary,
 };
 #[cfg(feature = "native")]
 pub use config::{
@@ -182,6 +230,23 @@ pub use config::{
 #[cfg(feature = "native")]
 pub use config::{BackendKind, Protocol};
 #[cfg(feature = "native")]
+pub use cost_basis::{
+    build_cost_basis_report, compute_realized_gains, parse_acquired_date, render_cost_basis_csv,
+    wallet_cost_basis_report, AcquisitionLot, CostBasisMethod, CostBasisOverride,
+    CostBasisOverrides, CostBasisReport, Disposal, RealizedGain, UnmatchedDisposal,
+};
+#[cfg(feature = "native")]
+pub use daemon::{
+    publish_wallet_event, subscribe_wallet_events, DaemonOptions, DaemonTick, WalletDaemon,
+    WalletEvent, DEFAULT_DAEMON_INTERVAL_SECS,
+};
+#[cfg(feature = "native")]
+pub use history_export::{
+    build_history_export_rows, export_wallet_history, format_zec_amount,
+    load_history_export_source, render_history_export, HistoryExportFilter, HistoryExportFormat,
+    HistoryExportRow, HistoryExportSource,
+};
+#[cfg(feature = "native")]
 pub use ironwood::{
     amount_timing_status, assess_migration_cover_traffic, assess_migration_network_privacy,
     assess_orchard_migration_readiness, baseline_hygiene_status_notes, build_schedule_from_plan,
@@ -217,12 +282,16 @@ pub use ironwood_tx::{
 };
 #[cfg(feature = "native")]
 pub use keystone::{
-    build_keystone_send_pczt, clear_pending_send, decode_pczt_ur_frames, encode_pczt_ur_frames,
-    export_ufvk_from_wallet, extract_signed_tx_from_pczt_bytes, load_pending_send,
-    orchard_spending_key_from_wallet, prepared_send_from_build, redact_pczt_for_signer,
-    save_pending_send, sign_pczt_orchard_spends, unwrap_pczt_cbor, validate_ufvk, wrap_pczt_cbor,
-    KeystoneExtractedTx, KeystonePcztBuild, KeystonePreparedSend, KeystoneWalletConfig,
-    DEFAULT_UR_FRAGMENT_SIZE, UR_TYPE_ZCASH_PCZT,
+    build_keystone_send_pczt, decode_pczt_ur_frames, encode_pczt_ur_frames,
+    export_ufvk_from_wallet, extract_signed_tx_from_pczt_bytes, orchard_spending_key_from_wallet,
+    prepared_send_from_build, redact_pczt_for_signer, sign_pczt_orchard_spends, unwrap_pczt_cbor,
+    validate_ufvk, wrap_pczt_cbor, KeystoneExtractedTx, KeystonePcztBuild, KeystonePreparedSend,
+    KeystoneWalletConfig, DEFAULT_UR_FRAGMENT_SIZE, UR_TYPE_ZCASH_PCZT,
+};
+#[cfg(feature = "native")]
+pub use labels::{
+    exclude_tagged_spendable_notes, normalize_tag, Annotation, AnnotationUpdate, LabelMatch,
+    LabelStore, LabelTarget,
 };
 #[cfg(feature = "native")]
 pub use lite_ops::{
@@ -249,7 +318,7 @@ pub use notes::{
     missing_scanned_nullifiers_after_merge, note_cache_integrity,
     reconcile_wallet_spends_from_local_state, release_wallet_notes_by_nullifier_hex,
     save_wallet_notes, wallet_unspent_balance_zatoshis, NoteCacheIntegrity, NoteScanResult,
-    NoteScanner, OrchardNote, SerializableOrchardNote, SpendableNote,
+    NoteScanner, OrchardNote, SerializableOrchardNote, SpendableNote, WatchedPayment,
 };
 #[cfg(feature = "native")]
 pub use orchard_tx::{
@@ -257,12 +326,29 @@ pub use orchard_tx::{
     OrchardTransactionBuilder, OrchardWitnessProvider, ZebraJsonRpcOrchardWitnessProvider,
 };
 #[cfg(feature = "native")]
+pub use ovk_recovery::{
+    persist_recovered_sent_transactions, OutgoingRecoveryKeys, RecoveredOutput,
+    RecoveredOutputPool, RecoveredSentTransaction,
+};
+#[cfg(feature = "native")]
 pub use paths::{
     get_wallet_base_dir, get_wallet_config_dir, get_wallet_config_path, get_wallet_data_dir,
     get_wallet_data_path, resolve_allowlisted_user_path, resolve_wallet_scoped_db_path,
     with_wallet_data_dir,
 };
 #[cfg(feature = "native")]
+pub use payment_request::{
+    verify_payment_uri, PaymentRequestCheck, PaymentRequestSigner, RequestAuthenticity,
+};
+#[cfg(feature = "native")]
+pub use pos_tui::run_pos_tui;
+#[cfg(feature = "native")]
+pub use price_source::{
+    apply_configured_fiat_values, apply_fiat_values, configured_price_source, fiat_value_at_spot,
+    price_http_client, CoinGeckoPriceSource, CsvPriceTable, FiatConfig, FiatValue, PricePoint,
+    PriceSource, PriceSourceKind,
+};
+#[cfg(feature = "native")]
 pub use rpc_test::RpcTester;
 #[cfg(feature = "native")]
 pub use sapling_keys::{
@@ -281,6 +367,13 @@ pub use sapling_tx::{
     build_sapling_shield_to_self, refresh_sapling_witnesses_from_compact_store,
     sapling_note_ready_to_shield, sapling_shield_fee_zatoshis, SaplingShieldBuilt,
 };
+#[cfg(feature = "native")]
+pub use scheduled_payments::{
+    add_scheduled_payment, load_payment_schedule, parse_schedule_start, remove_scheduled_payment,
+    run_due_payments, save_payment_schedule, set_scheduled_payment_enabled,
+    InsufficientFundsPolicy, NewScheduledPayment, PaymentFrequency, PaymentRun, PaymentRunOutcome,
+    PaymentSchedule, ScheduledPayment,
+};
 #[cfg(feature = "secret-network")]
 pub use secret::{
     SecretRpcClient, SecretTransactionRecord, SecretTransactionStatus, SecretTransactionStorage,
@@ -292,6 +385,11 @@ pub use secret_keys::{
     SECRET_COIN_TYPE,
 };
 #[cfg(feature = "native")]
+pub use send_proposal::{
+    PoolCrossing, ProposedInput, ProposedOutput, ProposedOutputKind, SendProposal,
+    SEND_PROPOSAL_MAX_AGE_BLOCKS, SEND_PROPOSAL_VERSION,
+};
+#[cfg(feature = "native")]
 pub use send_readiness::{
     ensure_cached_witness_fresh_for_send, ensure_witness_fresh_for_send,
     is_witness_stale_for_send_error, max_serialized_witness_lag_blocks, max_witness_lag_blocks,
@@ -301,6 +399,13 @@ pub use send_readiness::{
 #[cfg(feature = "native")]
 pub use shielded_pool::ShieldedPool;
 #[cfg(feature = "native")]
+pub use signing_queue::{
+    cancel_signing_request, complete_signing_request, enqueue_signing_request,
+    find_signing_request_for_pczt, load_signing_queue, prune_expired_signing_requests,
+    record_scanned_frames, scanned_signed_pczt, SigningQueue, SigningRequest, UrScanProgress,
+    UrScanState,
+};
+#[cfg(feature = "native")]
 pub use storage::{WalletData, WalletStorage};
 #[cfg(feature = "native")]
 pub use swap::{SwapDirection, SwapEngine, SwapRequest, SwapResponse, SwapService, SwapStatus};
@@ -318,12 +423,13 @@ pub use transaction_history::{
 pub use tx_lifecycle::{expire_stale_pending_transactions, speed_up_transaction};
 #[cfg(feature = "native")]
 pub use wallet_profiles::{
-    active_profile_id, active_wallet_exists, apply_profile_connection_to_config,
-    configure_profile_network, create_new_profile, default_network_for_profile_name,
-    default_zebra_url_for_network, list_wallet_profiles, migrate_orphaned_sent_transactions,
-    profile_connection_settings, profile_has_wallet, save_profile_connection_settings,
-    set_active_wallet_profile, snapshot_active_profile_from_config,
-    touch_active_profile_scan_height, ProfileConnectionSettings, WalletProfile,
+    active_profile_id, active_profile_kind, active_wallet_exists,
+    apply_profile_connection_to_config, configure_profile_network, create_new_profile,
+    default_network_for_profile_name, default_zebra_url_for_network, list_wallet_profiles,
+    migrate_orphaned_sent_transactions, profile_connection_settings, profile_data_dir,
+    profile_has_wallet, save_profile_connection_settings, set_active_wallet_profile,
+    set_profile_kind, snapshot_active_profile_from_config, touch_active_profile_scan_height,
+    ProfileConnectionSettings, WalletProfile, WalletProfileKind,
 };
 #[cfg(feature = "native")]
 pub use wallet_sync::{
@@ -31,6 +31,294 @@ fn use_plain_terminal_output() -> bool {
         .unwrap_or(false)
 }
 
+fn read_pczt_input(input: &str) -> NozyResult<Vec<u8>> {
+    use std::io::Read;
+
+    let mut data = Vec::new();
+    if input == "-" {
+        std::io::stdin()
+            .read_to_end(&mut data)
+            .map_err(|e| NozyError::Storage(format!("Failed to read stdin: {e}")))?;
+    } else {
+        data = std::fs::read(input)
+            .map_err(|e| NozyError::Storage(format!("Failed to read {input}: {e}")))?;
+    }
+    nozy::cold_signing::read_pczt_transport(&data)
+}
+
+/// Write a PCZT to `out`, cycle it as UR QR codes, or print the UR frames.
+async fn emit_pczt(pczt_bytes: &[u8], out: Option<&std::path::Path>, qr: bool) -> NozyResult<()> {
+    if let Some(path) = out {
+        std::fs::write(path, pczt_bytes)
+            .map_err(|e| NozyError::Storage(format!("Failed to write {}: {e}", path.display())))?;
+        println!("📄 PCZT written to {}", path.display());
+    }
+    let frames = nozy::encode_pczt_ur_frames(pczt_bytes, nozy::DEFAULT_UR_FRAGMENT_SIZE)?;
+    if qr {
+        show_ur_qr(&frames).await?;
+    } else if out.is_none() {
+        for frame in &frames {
+            println!("{frame}");
+        }
+    }
+    Ok(())
+}
+
+/// Show a Keystone migration PCZT and hand it to the device.
+async fn emit_keystone_migration(
+    pending: &nozy::ironwood::KeystoneMigrationPczt,
+    out: Option<&std::path::Path>,
+    qr: bool,
+) -> NozyResult<()> {
+    println!("🔏 {}", pending.summary);
+    println!("   Source: {}", pending.source_nullifier_hex);
+    println!("   Expires at height: {}", pending.expires_at_height);
+    let pczt_bytes = hex::decode(&pending.pczt_hex)
+        .map_err(|e| NozyError::InvalidOperation(format!("Invalid PCZT hex: {e}")))?;
+    emit_pczt(&pczt_bytes, out, qr).await?;
+    println!("   Next: sign on Keystone, then run `nozy ironwood complete <signed-pczt>`");
+    Ok(())
+}
+
+fn print_pczt_inspection(inspection: &nozy::cold_signing::PcztInspection) {
+    let preview = &inspection.preview;
+    let zec = |z: u64| z as f64 / 100_000_000.0;
+    let signed = preview.spends.iter().filter(|s| s.signed).count();
+    println!(
+        "🔏 PCZT spending {} note(s), {:.8} ZEC in total; expires at height {}",
+        preview.spend_count,
+        zec(preview.spend_total_zatoshis),
+        preview.expiry_height
+    );
+    println!("   Signed {signed}/{} spend(s)", preview.spends.len());
+    for output in &preview.outputs {
+        let value = output
+            .value_zatoshis
+            .map(|v| format!("{:.8} ZEC", zec(v)))
+            .unwrap_or_else(|| "unknown amount".to_string());
+        let recipient = output.recipient.as_deref().unwrap_or("unknown recipient");
+        let pool = match output.pool {
+            nozy::cold_signing::PcztValuePool::Transparent => " [transparent]",
+            nozy::cold_signing::PcztValuePool::Sapling => " [Sapling]",
+            _ => "",
+        };
+        let label = if output.is_change {
+            "Change"
+        } else if output.is_ours {
+            "Self"
+        } else {
+            "Pay"
+        };
+        match &output.contact {
+            Some(name) => println!("   {label:<6} {value} → {name} ({recipient}){pool}"),
+            None => println!("   {label:<6} {value} → {recipient}{pool}"),
+        }
+    }
+    match preview.fee_zatoshis {
+        Some(fee) => println!("   Fee    {:.8} ZEC", zec(fee)),
+        None => println!("   Fee    unknown (PCZT omits some values)"),
+    }
+    for violation in &inspection.policy_violations {
+        println!("⛔ Policy: {violation}");
+    }
+}
+
+/// Show UR frames as one QR code, or cycle them until Ctrl+C.
+async fn show_ur_qr(frames: &[String]) -> NozyResult<()> {
+    let rendered = frames
+        .iter()
+        .map(|f| nozy::pos::render_qr_terminal(f))
+        .collect::<NozyResult<Vec<_>>>()?;
+    if let [single] = rendered.as_slice() {
+        println!("{single}");
+        return Ok(());
+    }
+    let stop = tokio::signal::ctrl_c();
+    tokio::pin!(stop);
+    for (i, qr) in rendered.iter().enumerate().cycle() {
+        print!("\x1B[2J\x1B[H{qr}");
+        println!(
+            "Frame {}/{} — Ctrl+C when the other side has scanned it",
+            i + 1,
+            rendered.len()
+        );
+        tokio::select! {
+            _ = &mut stop => break,
+            _ = tokio::time::sleep(std::time::Duration::from_millis(300)) => {}
+        }
+    }
+    Ok(())
+}
+
+fn read_text_input(input: &str) -> NozyResult<String> {
+    use std::io::Read;
+
+    if input == "-" {
+        let mut text = String::new();
+        std::io::stdin()
+            .read_to_string(&mut text)
+            .map_err(|e| NozyError::Storage(format!("Failed to read stdin: {e}")))?;
+        return Ok(text);
+    }
+    std::fs::read_to_string(input)
+        .map_err(|e| NozyError::Storage(format!("Failed to read {input}: {e}")))
+}
+
+fn read_frost_input<T: serde::de::DeserializeOwned>(input: &str) -> NozyResult<T> {
+    nozy::frost_multisig::read_frost_message(&read_text_input(input)?)
+}
+
+/// Write a FROST message to `out`, show it as UR QR codes, or print the JSON.
+async fn emit_frost_message<T: serde::Serialize>(
+    message: &T,
+    out: Option<&std::path::Path>,
+    qr: bool,
+) -> NozyResult<()> {
+    let json = serde_json::to_string_pretty(message)
+        .map_err(|e| NozyError::InvalidOperation(format!("json encode: {e}")))?;
+    if let Some(path) = out {
+        std::fs::write(path, &json)
+            .map_err(|e| NozyError::Storage(format!("Failed to write {}: {e}", path.display())))?;
+        println!("📄 Written to {}", path.display());
+    }
+    if qr {
+        show_ur_qr(&nozy::frost_multisig::encode_frost_ur_frames(&json)?).await?;
+    } else if out.is_none() {
+        println!("{json}");
+    }
+    Ok(())
+}
+
+/// Unlock the notes vault that encrypts a FROST profile's share and nonces.
+fn unlock_frost_vault(confirm: bool) -> NozyResult<()> {
+    let mut prompt = Password::new()
+        .with_prompt("FROST share password (empty for none)")
+        .allow_empty_password(true);
+    if confirm {
+        prompt = prompt.with_confirmation("Confirm password", "Passwords don't match");
+    }
+    let password = prompt
+        .interact()
+        .map_err(|e| NozyError::InvalidOperation(format!("Password input error: {e}")))?;
+    nozy::notes_vault::unlock_notes_vault(&password)
+}
+
+fn print_pos_sale(invoice: &nozy::merchant_invoices::MerchantInvoice, uri: &str) -> NozyResult<()> {
+    println!("{}", nozy::pos::render_qr_terminal(uri)?);
+    println!("🧾 Invoice {}", invoice.invoice_id);
+    if let Some(product) = &invoice.product_name {
+        println!("   {product}");
+    }
+    if let (Some(amount), Some(currency)) = (invoice.amount_fiat, &invoice.fiat_currency) {
+        println!("   {amount:.2} {currency}");
+    }
+    println!(
+        "   {:.8} ZEC",
+        invoice.outstanding_zatoshis as f64 / 100_000_000.0
+    );
+    if let Some(rate) = &invoice.locked_rate {
+        println!(
+            "   Rate locked: 1 ZEC = {:.2} {} ({}, {})",
+            rate.price_per_zec,
+            rate.currency,
+            rate.source,
+            rate.at.format("%Y-%m-%d %H:%M UTC")
+        );
+    }
+    println!("   Expires {}", invoice.expires_at);
+    println!("   {uri}");
+    Ok(())
+}
+
+/// Plain-output POS watch: print each status change until the sale finishes.
+async fn follow_pos_sale(
+    wallet: &HDWallet,
+    config: &nozy::WalletConfig,
+    invoice: nozy::merchant_invoices::MerchantInvoice,
+    watch: &PosWatchArgs,
+    zebra_url: Option<String>,
+) -> NozyResult<nozy::merchant_invoices::MerchantInvoice> {
+    use nozy::pos::{pos_sale_finished, pos_status_line, PosWatcher};
+
+    let mut watcher = PosWatcher::new(wallet, config, &invoice.invoice_id, zebra_url)?;
+    let mut current = invoice;
+    println!("⏳ {} (Ctrl+C to stop)", pos_status_line(&current));
+    while !pos_sale_finished(&current, watch.zero_conf) {
+        tokio::time::sleep(std::time::Duration::from_secs(watch.poll)).await;
+        match watcher.poll().await {
+            Ok(next) => {
+                if next.status != current.status || next.paid_zatoshis != current.paid_zatoshis {
+                    println!("🔔 {}", pos_status_line(&next));
+                }
+                current = next;
+            }
+            Err(e) => eprintln!("⚠️  Payment check failed: {e}"),
+        }
+    }
+    Ok(current)
+}
+
+fn describe_wallet_event(event: &nozy::WalletEvent) -> String {
+    use nozy::{PaymentRunOutcome, WalletEvent};
+
+    let zec = |zat: u64| zat as f64 / 100_000_000.0;
+    match event {
+        WalletEvent::NewBlock { height } => format!("⛓️  New block {height}"),
+        WalletEvent::SyncStarted { chain_tip } => format!("🔄 Syncing to {chain_tip}"),
+        WalletEvent::ScanProgress {
+            height,
+            end_height,
+            percent,
+            ..
+        } => format!("🔎 Scanned to {height} of {end_height} ({percent}%)"),
+        WalletEvent::SyncCompleted {
+            chain_tip,
+            blocks_scanned,
+            new_notes,
+            balance_zatoshis,
+        } => format!(
+            "✅ Synced to {chain_tip} ({blocks_scanned} blocks, {new_notes} new notes), balance {:.8} ZEC",
+            zec(*balance_zatoshis)
+        ),
+        WalletEvent::SyncFailed { error } => format!("❌ Sync failed: {error}"),
+        WalletEvent::NoteReceived {
+            txid,
+            value_zatoshis,
+            block_height,
+            ..
+        } => format!(
+            "📥 Received {:.8} ZEC in {txid} (height {block_height})",
+            zec(*value_zatoshis)
+        ),
+        WalletEvent::TxConfirmed { txid, .. } => format!("✅ Confirmed {txid}"),
+        WalletEvent::TxExpired { txid } => format!("⌛ Expired {txid} (notes released)"),
+        WalletEvent::InvoiceStatusChanged { invoice_id, status } => {
+            format!("🧾 Invoice {invoice_id} is now {status:?}")
+        }
+        WalletEvent::InvoicePaid {
+            invoice_id,
+            amount_zatoshis,
+            ..
+        } => format!(
+            "💰 Invoice {invoice_id} paid ({:.8} ZEC)",
+            zec(*amount_zatoshis)
+        ),
+        WalletEvent::ScheduledPayment { run } => match &run.outcome {
+            PaymentRunOutcome::Sent { txid } => format!(
+                "🗓️  Scheduled payment {} sent: {txid}",
+                run.payment_id
+            ),
+            PaymentRunOutcome::InsufficientFunds { .. } => format!(
+                "⚠️  Scheduled payment {} skipped: insufficient funds",
+                run.payment_id
+            ),
+            PaymentRunOutcome::Failed { error } => {
+                format!("❌ Scheduled payment {} failed: {error}", run.payment_id)
+            }
+        },
+    }
+}
+
 fn network_type_from_config(network: &str) -> NetworkType {
     if network == "testnet" {
         NetworkType::Test
@@ -101,6 +389,60 @@ async fn save_wallet_to_active_profile(wallet: &mut HDWallet) -> NozyResult<()>
     Ok(())
 }
 
+fn print_send_proposal(proposal: &nozy::SendProposal) {
+    let zec = |zat: u64| zat as f64 / 100_000_000.0;
+    println!("\n📋 Send Proposal ({})", proposal.network);
+    println!("{}", "=".repeat(60));
+    println!("  Recipient: {}", proposal.recipient_address);
+    println!("  Amount:    {:.8} ZEC", zec(proposal.amount_zatoshis));
+    println!("  Fee:       {:.8} ZEC", zec(proposal.fee_zatoshis));
+    println!("  Change:    {:.8} ZEC", zec(proposal.change_zatoshis));
+    println!(
+        "  Expiry:    ~{} (tip {} + {} blocks)",
+        proposal.expiry_height, proposal.proposed_at_height, proposal.expiry_delta_blocks
+    );
+    println!("  Inputs:");
+    for input in &proposal.inputs {
+        println!(
+            "    • {:.8} ZEC  {}  height {}  nf {}…",
+            zec(input.value_zatoshis),
+            input.pool,
+            input.block_height,
+            &input.nullifier_hex[..input.nullifier_hex.len().min(16)]
+        );
+    }
+    println!("  Outputs:");
+    for output in &proposal.outputs {
+        let label = match output.kind {
+            nozy::ProposedOutputKind::Recipient => "recipient",
+            nozy::ProposedOutputKind::Change => "change",
+        };
+        println!(
+            "    • {:.8} ZEC  {}  {}{}",
+            zec(output.value_zatoshis),
+            output.pool,
+            label,
+            if output.memo_len > 0 {
+                format!("  (memo {} bytes)", output.memo_len)
+            } else {
+                String::new()
+            }
+        );
+    }
+    if proposal.pool_crossings.is_empty() {
+        println!("  Pool crossings: none");
+    } else {
+        for crossing in &proposal.pool_crossings {
+            println!(
+                "  Pool crossing: {:.8} ZEC {} → {}",
+                zec(crossing.value_zatoshis),
+                crossing.from_pool,
+                crossing.to_pool
+            );
+        }
+    }
+}
+
 #[derive(Parser)]
 #[command(name = "nozy")]
 #[command(version = nozy::version_info::VERSION_DISPLAY)]
@@ -167,16 +509,40 @@ pub enum Commands {
         zebra_url: Option<String>,
     },
 
+    #[command(about = "Keep the wallet synced at tip in the foreground and print wallet events")]
+    Daemon {
+        #[arg(
+            long,
+            default_value_t = nozy::DEFAULT_DAEMON_INTERVAL_SECS,
+            value_parser = clap::value_parser!(u64).range(1..),
+            help = "Seconds between chain tip checks"
+        )]
+        interval: u64,
+        #[arg(
+            long,
+            help = "Override Zebra RPC URL (overrides config and global --zebra-url)"
+        )]
+        zebra_url: Option<String>,
+        #[arg(long, help = "Also send due scheduled payments each tick")]
+        scheduled_payments: bool,
+        #[arg(long, help = "Print events as JSON lines (also accepts global --json)")]
+        json: bool,
+    },
+
     #[command(about = "Send ZEC to a shielded Orchard address")]
     Send {
         #[arg(
             long,
             short = 'r',
-            help = "Recipient's shielded Orchard address (must start with 'u1')"
+            help = "Recipient's shielded Orchard address (must start with 'u1'), address-book name, or zcash: payment URI"
         )]
         recipient: String,
-        #[arg(long, short = 'a', help = "Amount to send in ZEC (e.g., 0.1)")]
-        amount: f64,
+        #[arg(
+            long,
+            short = 'a',
+            help = "Amount to send in ZEC (e.g., 0.1); taken from the URI when omitted"
+        )]
+        amount: Option<f64>,
         #[arg(
             long,
             help = "Override Zebra RPC URL (overrides config and global --zebra-url)"
@@ -185,6 +551,42 @@ pub enum Commands {
         // No short flag: global `--mainnet` already uses `-m`.
         #[arg(long, help = "Optional memo message (max 512 characters)")]
         memo: Option<String>,
+        #[arg(
+            long,
+            help = "Show the send proposal (notes, change, fee, expiry) without proving or broadcasting"
+        )]
+        preview: bool,
+        #[arg(
+            long,
+            value_name = "FILE",
+            help = "With --preview, write the proposal JSON to FILE for `nozy execute-proposal`"
+        )]
+        proposal_out: Option<String>,
+        #[arg(
+            long = "note",
+            value_name = "NOTE_ID",
+            help = "Only spend from these notes (nullifier hex; repeatable, see `nozy notes list`)"
+        )]
+        notes: Vec<String>,
+        #[arg(
+            long,
+            help = "Pay a payment URI signed by a merchant key that is not in the address book"
+        )]
+        allow_unknown_signer: bool,
+    },
+
+    #[command(about = "Prove, sign and broadcast a send proposal saved with `send --preview`")]
+    ExecuteProposal {
+        #[arg(
+            value_name = "FILE",
+            help = "Proposal JSON written by `send --proposal-out`"
+        )]
+        file: String,
+        #[arg(
+            long,
+            help = "Override Zebra RPC URL (overrides config and global --zebra-url)"
+        )]
+        zebra_url: Option<String>,
     },
 
     #[command(about = "Display wallet information including addresses and network")]
@@ -194,6 +596,19 @@ pub enum Commands {
     Balance {
         #[arg(long, help = "Emit JSON (also accepts global --json)")]
         json: bool,
+        #[arg(
+            long,
+            help = "Also show the spot fiat value in this currency (e.g. USD)"
+        )]
+        fiat: Option<String>,
+    },
+
+    #[command(about = "Show the ZEC price from the configured fiat price source")]
+    Price {
+        #[arg(long, help = "Fiat currency (defaults to fiat.display_currency)")]
+        currency: Option<String>,
+        #[arg(long, help = "Historical day (YYYY-MM-DD); spot price when omitted")]
+        at: Option<String>,
     },
 
     #[command(
@@ -201,6 +616,74 @@ pub enum Commands {
     )]
     NotesDoctor,
 
+    #[command(about = "Coin control: list, freeze and unfreeze notes")]
+    Notes {
+        #[command(subcommand)]
+        command: NotesCommand,
+    },
+
+    #[command(about = "Recurring payments stored in the encrypted schedule vault")]
+    Schedule {
+        #[command(subcommand)]
+        command: ScheduleCommand,
+    },
+
+    #[command(about = "Signed merchant webhooks for invoice status changes")]
+    Webhooks {
+        #[command(subcommand)]
+        command: WebhooksCommand,
+    },
+
+    #[command(about = "Point of sale: sell for a fiat amount and show a payment QR")]
+    Pos {
+        #[command(subcommand)]
+        command: PosCommand,
+    },
+
+    #[command(
+        about = "Cold signing: build a PCZT online, sign it on an offline Nozy, broadcast it"
+    )]
+    Pczt {
+        #[command(subcommand)]
+        command: PcztCommand,
+    },
+
+    #[command(about = "FROST t-of-n threshold signing for Orchard spends")]
+    Frost {
+        #[command(subcommand)]
+        command: FrostCommand,
+    },
+
+    #[command(about = "Export a date-scoped auditor package, or audit one read-only")]
+    Audit {
+        #[command(subcommand)]
+        command: AuditCommand,
+    },
+
+    #[command(about = "Prove that a sent transaction paid an address, or check such a proof")]
+    Disclosure {
+        #[command(subcommand)]
+        command: DisclosureCommand,
+    },
+
+    #[command(about = "Sign a message as the owner of an address, or verify one")]
+    Message {
+        #[command(subcommand)]
+        command: MessageCommand,
+    },
+
+    #[command(about = "Sign and verify merchant payment requests (zcash: URIs)")]
+    PaymentRequest {
+        #[command(subcommand)]
+        command: PaymentRequestCommand,
+    },
+
+    #[command(about = "Register, renew and update Zcash names (ZNS) from this wallet")]
+    Zns {
+        #[command(subcommand)]
+        command: ZnsCommand,
+    },
+
     #[command(about = "Manage local wallet profiles")]
     Profile {
         #[command(subcommand)]
@@ -263,7 +746,16 @@ pub enum Commands {
     Analytics,
 
     #[command(about = "Display transaction history")]
-    History,
+    History {
+        #[command(subcommand)]
+        command: Option<HistoryCommand>,
+    },
+
+    #[command(about = "Realized gains / cost basis (computed locally)")]
+    CostBasis {
+        #[command(subcommand)]
+        command: CostBasisCommand,
+    },
 
     #[command(about = "Check confirmation status of a transaction")]
     CheckConfirmations {
@@ -327,6 +819,12 @@ pub enum Commands {
         command: SaplingCommand,
     },
 
+    #[command(about = "Label, tag and annotate transactions and notes (encrypted)")]
+    Label {
+        #[command(subcommand)]
+        command: LabelCommand,
+    },
+
     #[command(about = "Manage saved addresses in your address book")]
     AddressBook {
         #[command(subcommand)]
@@ -386,7 +884,14 @@ pub enum IronwoodCommand {
     #[command(about = "Check Ironwood migration readiness without building transactions")]
     Preflight,
     #[command(about = "Migrate Orchard notes to Ironwood (requires NU6.3 active)")]
-    Migrate,
+    Migrate {
+        #[arg(long, help = "Build an unsigned PCZT for Keystone to sign")]
+        pczt: bool,
+        #[arg(long, short = 'o', help = "Write the PCZT to this file")]
+        out: Option<std::path::PathBuf>,
+        #[arg(long, help = "Show the PCZT as animated UR QR codes")]
+        qr: bool,
+    },
     #[command(
         about = "Broadcast a presigned ZIP 318 turnstile transaction in its anchor bucket window"
     )]
@@ -426,6 +931,17 @@ pub enum IronwoodCommand {
             help = "Plan the split and build the transaction without broadcasting"
         )]
         dry_run: bool,
+        #[arg(long, help = "Build an unsigned PCZT for Keystone to sign")]
+        pczt: bool,
+        #[arg(long, short = 'o', help = "Write the PCZT to this file")]
+        out: Option<std::path::PathBuf>,
+        #[arg(long, help = "Show the PCZT as animated UR QR codes")]
+        qr: bool,
+    },
+    #[command(about = "Accept a Keystone-signed split or migration PCZT")]
+    Complete {
+        #[arg(help = "Signed PCZT file, hex or UR frames one per line; - for stdin")]
+        input: String,
     },
 }
 
@@ -468,96 +984,671 @@ pub enum TestnetWalletCommand {
 }
 
 #[derive(Subcommand)]
-pub enum AddressBookCommand {
-    List,
-    Add {
-        #[arg(long)]
-        name: String,
-        #[arg(long)]
-        address: String,
-        #[arg(long)]
-        notes: Option<String>,
+pub enum HistoryCommand {
+    #[command(about = "Export history for accounting (CSV, JSON Lines or OFX)")]
+    Export {
+        #[arg(long, default_value = "csv", help = "csv, jsonl or ofx")]
+        format: String,
+        #[arg(long, help = "First day to include (YYYY-MM-DD, UTC)")]
+        from: Option<String>,
+        #[arg(long, help = "Last day to include (YYYY-MM-DD, UTC)")]
+        to: Option<String>,
+        #[arg(long, help = "Wallet profile id (default: active profile)")]
+        account: Option<String>,
+        #[arg(long, short = 'o', help = "Write to file instead of stdout")]
+        output: Option<String>,
+        #[arg(long, help = "Add historical fiat values in this currency (e.g. USD)")]
+        fiat: Option<String>,
     },
-    Remove {
-        #[arg(long)]
-        name: String,
+    #[command(about = "Find history rows by label, tag (#tag) or note")]
+    Search { query: String },
+}
+
+#[derive(Subcommand)]
+pub enum NotesCommand {
+    #[command(about = "List notes with id, value, frozen flag and labels")]
+    List {
+        #[arg(long, help = "Include spent notes")]
+        all: bool,
+        #[arg(long, help = "Emit JSON (also accepts global --json)")]
+        json: bool,
     },
-    Get {
-        #[arg(long)]
-        name: String,
+    #[command(about = "Never select these notes for spends")]
+    Freeze {
+        #[arg(required = true, value_name = "NOTE_ID")]
+        ids: Vec<String>,
     },
-    Search {
-        #[arg(long)]
-        query: String,
+    #[command(about = "Make frozen notes spendable again")]
+    Unfreeze {
+        #[arg(required = true, value_name = "NOTE_ID")]
+        ids: Vec<String>,
     },
 }
 
 #[derive(Subcommand)]
-pub enum LwdCommand {
-    #[command(about = "Remove compact blocks above current lightwalletd tip (fixes stale cache)")]
-    Prune,
-    #[command(about = "Download compact blocks from next missing height through lightwalletd tip")]
-    SyncToTip {
+pub enum ScheduleCommand {
+    #[command(about = "Add a weekly or monthly payment")]
+    Add {
+        #[arg(long, short = 'r')]
+        recipient: String,
+        #[arg(long, short = 'a', help = "Amount in ZEC per payment")]
+        amount: f64,
+        #[arg(long, default_value = "monthly", help = "weekly or monthly")]
+        every: String,
         #[arg(
             long,
-            help = "Optional lower bound for first height to fetch (e.g. wallet birthday)"
+            help = "First due date (YYYY-MM-DD or RFC 3339); defaults to now"
         )]
-        start_floor: Option<u64>,
-    },
-    #[command(
-        about = "Scan cached compact blocks for Sapling notes (quiet legacy compatibility; no UA change)"
-    )]
-    ScanSapling {
+        start: Option<String>,
+        #[arg(long, help = "Stop after this many payments")]
+        runs: Option<u32>,
+        #[arg(long)]
+        memo: Option<String>,
+        #[arg(long)]
+        label: Option<String>,
         #[arg(
             long,
-            help = "Optional lower bound for first height to scan (e.g. wallet birthday)"
+            default_value = "skip",
+            help = "On insufficient funds or failure: skip (next period) or retry"
         )]
-        start_floor: Option<u64>,
+        on_insufficient: String,
+    },
+    #[command(about = "List scheduled payments and recent runs")]
+    List {
+        #[arg(long, help = "Emit JSON (also accepts global --json)")]
+        json: bool,
+    },
+    Remove {
+        id: String,
+    },
+    #[command(about = "Stop running a payment without deleting it")]
+    Pause {
+        id: String,
+    },
+    Resume {
+        id: String,
+    },
+    #[command(about = "Send every payment that is due now")]
+    Run {
         #[arg(
             long,
-            help = "Rescan from the start of the compact cache (ignore progress)"
+            help = "Override Zebra RPC URL (overrides config and global --zebra-url)"
         )]
-        full: bool,
+        zebra_url: Option<String>,
     },
 }
 
 #[derive(Subcommand)]
-pub enum SaplingCommand {
-    #[command(
-        about = "Print this wallet's Sapling payment address (zs1… / ztestsapling…) for legacy inbound tests"
-    )]
-    Address,
-    #[command(about = "Show Sapling note / rseed readiness (Phase 4 spend prep)")]
-    Status,
-    #[command(
-        about = "Shield Sapling notes into Orchard/Ironwood (Phase 4 — LWD witnesses + Groth16)"
-    )]
-    Shield {
+pub enum WebhooksCommand {
+    #[command(about = "Show or change the global endpoint and HMAC secret")]
+    Config {
         #[arg(
             long,
-            help = "Report what would be shielded without building a transaction"
+            help = "Global endpoint for invoices without their own webhook_url"
         )]
-        dry_run: bool,
-        #[arg(long, help = "Build and prove but do not broadcast")]
-        no_broadcast: bool,
+        url: Option<String>,
+        #[arg(long, conflicts_with = "url")]
+        clear_url: bool,
+        #[arg(long, help = "Shared HMAC-SHA256 secret")]
+        secret: Option<String>,
+        #[arg(long, conflicts_with = "secret")]
+        generate_secret: bool,
+        #[arg(long, help = "Give up after this many attempts (default 8)")]
+        max_attempts: Option<u32>,
+        #[arg(long, help = "Deliver without Tor / I2P (true / false)")]
+        allow_direct_http: Option<bool>,
+    },
+    #[command(about = "Show the delivery log, newest first")]
+    List {
+        #[arg(long)]
+        invoice: Option<String>,
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+        #[arg(long, help = "Emit JSON (also accepts global --json)")]
+        json: bool,
+    },
+    #[command(about = "Send every due delivery now")]
+    Deliver,
+    #[command(about = "Re-queue a delivery and send it")]
+    Retry { id: String },
+    #[command(about = "Run a local receiver that prints and verifies deliveries")]
+    Receive {
+        #[arg(long, default_value = "127.0.0.1:8787")]
+        bind: String,
+        #[arg(
+            long,
+            help = "Secret to verify against (defaults to the configured one)"
+        )]
+        secret: Option<String>,
     },
 }
 
 #[derive(Subcommand)]
-pub enum ZeakingCommand {
-    Sync,
-    Index {
+pub enum PosCommand {
+    #[command(about = "Create a sale invoice at a locked rate and watch for payment")]
+    Sell {
+        #[arg(help = "Price in fiat, e.g. 4.50")]
+        amount: f64,
+        #[arg(long, help = "Fiat currency (default: fiat.display_currency)")]
+        currency: Option<String>,
         #[arg(long)]
-        start: u32,
+        product: Option<String>,
         #[arg(long)]
-        end: u32,
+        memo: Option<String>,
+        #[arg(long, help = "Minutes until the invoice expires (default 60)")]
+        ttl: Option<i64>,
+        #[arg(long, help = "Lock this ZEC price instead of asking the price source")]
+        rate: Option<f64>,
+        #[arg(long, help = "Also write the QR code to this PNG file")]
+        png: Option<std::path::PathBuf>,
+        #[command(flatten)]
+        watch: PosWatchArgs,
     },
-    Stats,
-    Block {
-        #[arg(long)]
-        height: u32,
+    #[command(about = "Show an existing invoice's QR code and watch it")]
+    Watch {
+        invoice_id: String,
+        #[arg(long, help = "Also write the QR code to this PNG file")]
+        png: Option<std::path::PathBuf>,
+        #[command(flatten)]
+        watch: PosWatchArgs,
     },
-    Transaction {
+}
+
+#[derive(clap::Args)]
+pub struct PosWatchArgs {
+    #[arg(long, help = "Full-screen display instead of plain output")]
+    tui: bool,
+    #[arg(long, help = "Finish as soon as a payment is seen in the mempool")]
+    zero_conf: bool,
+    #[arg(
+        long,
+        default_value_t = 5,
+        value_parser = clap::value_parser!(u64).range(1..),
+        help = "Seconds between payment checks"
+    )]
+    poll: u64,
+    #[arg(long, help = "Print the QR code and exit without watching")]
+    no_watch: bool,
+    #[arg(
+        long,
+        help = "Override Zebra RPC URL (overrides config and global --zebra-url)"
+    )]
+    zebra_url: Option<String>,
+}
+
+#[derive(Subcommand)]
+pub enum PcztCommand {
+    #[command(
+        about = "Create a watch-only profile from a UFVK for building PCZTs (online instance)"
+    )]
+    Watch {
+        #[arg(help = "Unified full viewing key of the signing wallet")]
+        ufvk: String,
+        #[arg(long, default_value = "Watch-only")]
+        name: String,
+    },
+    #[command(about = "Build a proved, unsigned PCZT for a send (online instance)")]
+    Create {
+        #[arg(long, help = "Recipient unified address")]
+        to: String,
+        #[arg(long, help = "Amount in ZEC")]
+        amount: f64,
+        #[arg(long)]
+        memo: Option<String>,
+        #[arg(long, short = 'o', help = "Write the PCZT to this file")]
+        out: Option<std::path::PathBuf>,
+        #[arg(long, help = "Show the PCZT as animated UR QR codes")]
+        qr: bool,
+    },
+    #[command(about = "Review and sign a PCZT with this wallet's seed (offline signer)")]
+    Sign {
+        #[arg(help = "PCZT file, hex, prepared-send JSON or UR frames one per line; - for stdin")]
+        input: String,
+        #[arg(long, short = 'o', help = "Write the signed PCZT to this file")]
+        out: Option<std::path::PathBuf>,
+        #[arg(long, help = "Show the signed PCZT as animated UR QR codes")]
+        qr: bool,
+        #[arg(long, help = "Sign without asking for confirmation")]
+        yes: bool,
+    },
+    #[command(about = "Extract and broadcast a signed PCZT (online instance)")]
+    Broadcast {
+        #[arg(help = "Signed PCZT file, hex or UR frames one per line; - for stdin")]
+        input: Option<String>,
+        #[arg(
+            long,
+            conflicts_with = "input",
+            help = "Use the frames saved by `nozy pczt scan` for this request"
+        )]
+        request: Option<String>,
+    },
+    #[command(about = "List PCZTs waiting for a hardware signature; expired ones are dropped")]
+    Queue,
+    #[command(about = "Cancel a queued signing request and release its notes")]
+    Cancel { id: String },
+    #[command(about = "Add scanned signed UR frames to a request; resumes an interrupted scan")]
+    Scan {
+        id: String,
+        #[arg(help = "UR frames one per line, from a file or - for stdin")]
+        input: String,
+    },
+    #[command(about = "Decode a signed or unsigned PCZT and check it against the signing policy")]
+    Inspect {
+        #[arg(help = "PCZT file, hex, prepared-send JSON or UR frames one per line; - for stdin")]
+        input: String,
+    },
+    #[command(about = "Show or set the limits checked before signing a PCZT")]
+    Policy {
+        #[arg(long, help = "Largest total paid to others, in ZEC")]
+        max_amount: Option<f64>,
+        #[arg(long, help = "Largest fee, in ZEC")]
+        max_fee: Option<f64>,
+        #[arg(
+            long,
+            help = "Only sign payments to address book recipients (true/false)"
+        )]
+        address_book_only: Option<bool>,
+        #[arg(long, help = "Remove all limits")]
+        clear: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum FrostCommand {
+    #[command(about = "Trusted dealer: generate all n shares of a new t-of-n key")]
+    Dealer {
+        #[arg(long, help = "Signatures required (t)")]
+        threshold: u16,
+        #[arg(long, help = "Number of shares (n)")]
+        signers: u16,
+        #[arg(long, help = "Directory for the share files (one per participant)")]
+        out_dir: std::path::PathBuf,
+    },
+    #[command(about = "Import one share into a new FROST profile")]
+    Import {
+        share: String,
+        #[arg(long, default_value = "FROST share")]
+        name: String,
+    },
+    #[command(about = "Distributed key generation without a dealer")]
+    Dkg {
+        #[command(subcommand)]
+        command: FrostDkgCommand,
+    },
+    #[command(about = "Show the group address, UFVK and this profile's share")]
+    Info,
+    #[command(about = "Signing round 1: commit to nonces for a PCZT's group spends")]
+    Commit {
+        #[arg(help = "PCZT file, hex or UR frames; - for stdin")]
+        pczt: String,
+        #[arg(long, short = 'o')]
+        out: Option<std::path::PathBuf>,
+        #[arg(long, help = "Show as animated UR QR codes")]
+        qr: bool,
+    },
+    #[command(about = "Coordinator: bundle a PCZT with t signers' commitments")]
+    Request {
+        pczt: String,
+        #[arg(required = true)]
+        commitments: Vec<String>,
+        #[arg(long, short = 'o')]
+        out: Option<std::path::PathBuf>,
+        #[arg(long)]
+        qr: bool,
+    },
+    #[command(about = "Signing round 2: review a signing request and produce signature shares")]
+    Sign {
+        request: String,
+        #[arg(long, short = 'o')]
+        out: Option<std::path::PathBuf>,
+        #[arg(long)]
+        qr: bool,
+        #[arg(long, help = "Sign without asking for confirmation")]
+        yes: bool,
+    },
+    #[command(about = "Coordinator: aggregate signature shares into a signed PCZT")]
+    Aggregate {
+        request: String,
+        #[arg(required = true)]
+        shares: Vec<String>,
+        #[arg(long, short = 'o', help = "Write the signed PCZT to this file")]
+        out: Option<std::path::PathBuf>,
+        #[arg(long)]
+        qr: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum FrostDkgCommand {
+    #[command(about = "Start a DKG in a new profile and write the round 1 broadcast")]
+    Round1 {
+        #[arg(long, help = "This participant's id, 1..=n")]
+        id: u16,
+        #[arg(long)]
+        threshold: u16,
+        #[arg(long)]
+        signers: u16,
+        #[arg(long, default_value = "FROST share")]
+        name: String,
+        #[arg(long, short = 'o')]
+        out: Option<std::path::PathBuf>,
+    },
+    #[command(about = "Read every round 1 broadcast; write one private package per participant")]
+    Round2 {
+        #[arg(required = true)]
+        round1: Vec<String>,
+        #[arg(long, help = "Directory for the per-recipient round 2 files")]
+        out_dir: std::path::PathBuf,
+    },
+    #[command(about = "Finish the DKG and store the share in this profile")]
+    Finish {
+        #[arg(long, required = true, num_args = 1.., help = "All round 1 broadcasts")]
+        round1: Vec<String>,
+        #[arg(long, required = true, num_args = 1.., help = "Round 2 packages addressed to us")]
+        round2: Vec<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum AuditCommand {
+    #[command(about = "Export a signed auditor package for one account and period")]
+    Export {
+        #[arg(long, help = "First day to include (YYYY-MM-DD, UTC)")]
+        from: Option<String>,
+        #[arg(long, help = "Last day to include (YYYY-MM-DD, UTC)")]
+        to: Option<String>,
+        #[arg(long, conflicts_with = "from", help = "First block height to include")]
+        start_height: Option<u32>,
+        #[arg(long, conflicts_with = "to", help = "Last block height to include")]
+        end_height: Option<u32>,
+        #[arg(long, help = "Orchard account (default: active role's account)")]
+        account: Option<u32>,
+        #[arg(long, short = 'o', help = "Write the package to this file")]
+        file: std::path::PathBuf,
+    },
+    #[command(about = "Check every transaction a package lists on chain and report the history")]
+    Run {
+        #[arg(help = "Auditor package file")]
+        package: std::path::PathBuf,
+        #[arg(long, short = 'o', help = "Also write the report as JSON to this file")]
+        out: Option<std::path::PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum DisclosureCommand {
+    #[command(about = "Export a payment disclosure for one output of a sent transaction")]
+    Export {
+        txid: String,
+        #[arg(long, help = "Output index (default: the first non-change output)")]
+        output: Option<usize>,
+        #[arg(long, help = "Write the disclosure to this file instead of stdout")]
+        file: Option<std::path::PathBuf>,
+    },
+    #[command(about = "Verify a payment disclosure against the transaction on chain")]
+    Verify {
+        #[arg(help = "Disclosure JSON, or a path to a file containing it")]
+        disclosure: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum MessageCommand {
+    #[command(
+        about = "Sign with the key behind an address (nozy-sm-v2, or ZIP-304 with --sapling)"
+    )]
+    Sign {
+        message: String,
+        #[arg(long, help = "ZIP-32 account (default: active profile's account)")]
+        account: Option<u32>,
+        #[arg(
+            long,
+            help = "Address to sign for (default: the account's first address)"
+        )]
+        address: Option<String>,
+        #[arg(
+            long,
+            help = "Sign a Sapling address with ZIP-304 (zs1… or a UA's Sapling receiver)"
+        )]
+        sapling: bool,
+        #[arg(
+            long,
+            conflicts_with = "sapling",
+            help = "Allow a nozy-sm-v2 signature, which contains the account's full viewing key"
+        )]
+        reveal_viewing_key: bool,
+    },
+    #[command(about = "Verify a nozy-sm-v1, nozy-sm-v2 or ZIP-304 signature")]
+    Verify {
+        message: String,
+        #[arg(long)]
+        signature: String,
+        #[arg(long, help = "Claimed signer address (required for v2)")]
+        address: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum PaymentRequestCommand {
+    #[command(about = "Print the Business key customers save to recognise your requests")]
+    Key,
+    #[command(about = "Sign a zcash: URI with the Business key")]
+    Sign {
+        uri: String,
+        #[arg(long, help = "Minutes until the request expires (default: no expiry)")]
+        expires_in: Option<i64>,
+    },
+    #[command(about = "Check a zcash: URI's signature against address-book merchant keys")]
+    Verify { uri: String },
+}
+
+#[derive(Subcommand)]
+pub enum ZnsCommand {
+    #[command(about = "Claim a name; it points at your Business address unless --address is set")]
+    Register {
+        name: String,
+        #[arg(long)]
+        address: Option<String>,
+        #[arg(long, help = "Show the memo and cost without sending")]
+        dry_run: bool,
+        #[arg(long, help = "Pay without asking for confirmation")]
+        yes: bool,
+    },
+    #[command(about = "Point a name this wallet owns at another address")]
+    Update {
+        name: String,
+        #[arg(long, help = "New address (default: your Business address)")]
+        address: Option<String>,
+        #[arg(long)]
+        dry_run: bool,
+        #[arg(long, help = "Pay without asking for confirmation")]
+        yes: bool,
+    },
+    #[command(about = "Re-send a name's current address to keep it active")]
+    Renew {
+        name: String,
+        #[arg(long)]
+        dry_run: bool,
+        #[arg(long, help = "Pay without asking for confirmation")]
+        yes: bool,
+    },
+    #[command(about = "Check submitted ZNS actions against the chain and the indexer")]
+    Status,
+    #[command(about = "Show or set the pinned registry address and cost ceiling")]
+    Config {
+        #[arg(long, help = "Registry address, verified out of band")]
+        registry_address: Option<String>,
+        #[arg(long, help = "Highest cost one action may have (ZEC)")]
+        max_cost: Option<f64>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum LabelCommand {
+    #[command(about = "Set label / tags / note (target: tx or note)")]
+    Set {
+        target: String,
+        #[arg(help = "Txid, or note id (nullifier hex)")]
+        id: String,
+        #[arg(long, help = "Label (empty string clears)")]
+        label: Option<String>,
+        #[arg(long = "tag", help = "Add a tag (repeatable)")]
+        tags: Vec<String>,
+        #[arg(long = "untag", help = "Remove a tag (repeatable)")]
+        untags: Vec<String>,
+        #[arg(long, help = "Free-form note (empty string clears)")]
+        note: Option<String>,
+    },
+    Show {
+        target: String,
+        id: String,
+    },
+    Remove {
+        target: String,
+        id: String,
+    },
+    #[command(about = "Search labels, tags (#tag) and notes")]
+    Search {
+        query: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum CostBasisCommand {
+    #[command(about = "Realized gains for a calendar year")]
+    Report {
+        #[arg(long, help = "Tax year (UTC calendar year)")]
+        year: i32,
+        #[arg(long, default_value = "fifo", help = "fifo, lifo or specific-id")]
+        method: String,
+        #[arg(long, help = "Fiat currency (defaults to fiat.display_currency)")]
+        currency: Option<String>,
+        #[arg(long, default_value = "text", help = "text, csv or json")]
+        format: String,
+        #[arg(long, short = 'o', help = "Write to file instead of stdout")]
+        output: Option<String>,
+    },
+    #[command(about = "Set a manual cost basis for an incoming note")]
+    Override {
+        #[arg(help = "Note id (nullifier hex, as shown in history)")]
+        note_id: String,
+        #[arg(long, help = "Total fiat cost of the note")]
+        cost: f64,
+        #[arg(long, help = "Fiat currency (defaults to fiat.display_currency)")]
+        currency: Option<String>,
+        #[arg(long, help = "Acquisition day (YYYY-MM-DD) if different from receipt")]
+        acquired: Option<String>,
+        #[arg(long)]
+        note: Option<String>,
+    },
+    #[command(about = "Remove a manual cost basis")]
+    ClearOverride { note_id: String },
+    #[command(about = "List manual cost-basis overrides")]
+    Overrides,
+}
+
+#[derive(Subcommand)]
+pub enum AddressBookCommand {
+    List,
+    Add {
+        #[arg(long)]
+        name: String,
+        #[arg(long)]
+        address: String,
+        #[arg(long)]
+        notes: Option<String>,
+        #[arg(long, help = "Key the contact signs payment requests with (64 hex)")]
+        merchant_key: Option<String>,
+    },
+    Remove {
+        #[arg(long)]
+        name: String,
+    },
+    #[command(about = "Set or clear the payment-request signing key of a contact")]
+    MerchantKey {
+        #[arg(long)]
+        name: String,
+        #[arg(long, required_unless_present = "clear")]
+        key: Option<String>,
+        #[arg(long, conflicts_with = "key")]
+        clear: bool,
+    },
+    Get {
+        #[arg(long)]
+        name: String,
+    },
+    Search {
+        #[arg(long)]
+        query: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum LwdCommand {
+    #[command(about = "Remove compact blocks above current lightwalletd tip (fixes stale cache)")]
+    Prune,
+    #[command(about = "Download compact blocks from next missing height through lightwalletd tip")]
+    SyncToTip {
+        #[arg(
+            long,
+            help = "Optional lower bound for first height to fetch (e.g. wallet birthday)"
+        )]
+        start_floor: Option<u64>,
+    },
+    #[command(
+        about = "Scan cached compact blocks for Sapling notes (quiet legacy compatibility; no UA change)"
+    )]
+    ScanSapling {
+        #[arg(
+            long,
+            help = "Optional lower bound for first height to scan (e.g. wallet birthday)"
+        )]
+        start_floor: Option<u64>,
+        #[arg(
+            long,
+            help = "Rescan from the start of the compact cache (ignore progress)"
+        )]
+        full: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum SaplingCommand {
+    #[command(
+        about = "Print this wallet's Sapling payment address (zs1… / ztestsapling…) for legacy inbound tests"
+    )]
+    Address,
+    #[command(about = "Show Sapling note / rseed readiness (Phase 4 spend prep)")]
+    Status,
+    #[command(
+        about = "Shield Sapling notes into Orchard/Ironwood (Phase 4 — LWD witnesses + Groth16)"
+    )]
+    Shield {
+        #[arg(
+            long,
+            help = "Report what would be shielded without building a transaction"
+        )]
+        dry_run: bool,
+        #[arg(long, help = "Build and prove but do not broadcast")]
+        no_broadcast: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ZeakingCommand {
+    Sync,
+    Index {
+        #[arg(long)]
+        start: u32,
+        #[arg(long)]
+        end: u32,
+    },
+    Stats,
+    Block {
+        #[arg(long)]
+        height: u32,
+    },
+    Transaction {
         #[arg(long)]
         txid: String,
     },
@@ -849,6 +1940,56 @@ async fn execute_command(_command: Commands, mut config: nozy::WalletConfig) ->
             }
         }
 
+        Commands::Daemon {
+            interval,
+            zebra_url,
+            scheduled_payments,
+            json,
+        } => {
+            if let Some(url) = zebra_url {
+                config.zebra_url = url;
+            }
+            let (wallet, _storage) = load_wallet().await?;
+            let daemon = nozy::WalletDaemon::new(
+                wallet,
+                nozy::DaemonOptions {
+                    interval: std::time::Duration::from_secs(interval),
+                    zebra_url: Some(config.zebra_url.clone()),
+                    run_scheduled_payments: scheduled_payments,
+                },
+            );
+            let json = json || cli.json;
+            let mut events = nozy::subscribe_wallet_events();
+            tokio::spawn(async move {
+                use tokio::sync::broadcast::error::RecvError;
+                loop {
+                    match events.recv().await {
+                        Ok(event) if json => match serde_json::to_string(&event) {
+                            Ok(line) => println!("{line}"),
+                            Err(e) => eprintln!("Warning: could not encode event: {e}"),
+                        },
+                        Ok(event) => println!("{}", describe_wallet_event(&event)),
+                        Err(RecvError::Lagged(n)) => eprintln!("⚠️  Missed {n} event(s)"),
+                        Err(RecvError::Closed) => break,
+                    }
+                }
+            });
+            if !json {
+                println!(
+                    "🛰️  Daemon running against {} (every {}s). Ctrl+C to stop.",
+                    config.zebra_url, interval
+                );
+            }
+            daemon
+                .run_until(async {
+                    let _ = tokio::signal::ctrl_c().await;
+                })
+                .await;
+            if !json {
+                println!("👋 Daemon stopped");
+            }
+        }
+
         Commands::Sync {
             start_height,
             end_height,
@@ -908,6 +2049,15 @@ async fn execute_command(_command: Commands, mut config: nozy::WalletConfig) ->
                             println!("   Found {} new notes", result.new_notes_in_scan);
                         }
                     }
+                    if result.recovered_sent_transactions > 0 {
+                        println!(
+                            "   Recovered {} sent transaction(s) into history",
+                            result.recovered_sent_transactions
+                        );
+                    }
+                    if result.invoices_updated > 0 {
+                        println!("   Updated {} merchant invoice(s)", result.invoices_updated);
+                    }
                     println!("   Last scanned height: {}", result.last_scan_height);
                     if let Ok(notes) = nozy::load_wallet_notes() {
                         let report = nozy::note_cache_integrity(&notes);
@@ -958,26 +2108,86 @@ async fn execute_command(_command: Commands, mut config: nozy::WalletConfig) ->
             amount,
             zebra_url,
             memo,
+            preview,
+            proposal_out,
+            notes,
+            allow_unknown_signer,
         } => {
             if let Some(url) = zebra_url {
                 config.zebra_url = url;
             }
 
-            let (wallet, _storage) = load_wallet().await?;
-
             let address_book = AddressBook::new()?;
-            let mut actual_recipient =
-                if let Some(address) = address_book.get_address_by_name(&recipient) {
-                    println!("📇 Found '{}' in address book: {}", recipient, address);
-                    let _ = address_book.update_address_usage(&recipient);
-                    address
-                } else {
-                    recipient.clone()
-                };
 
-            // Resolve Zcash Names (`alice` / `alice.zcash`) before address validation.
-            if nozy::zns::is_likely_zns_name(&actual_recipient) {
-                let net = if config.network == "testnet" {
+            // Payment URIs: check the merchant signature before anything else.
+            let (recipient, amount, memo) = if recipient
+                .trim_start()
+                .to_ascii_lowercase()
+                .starts_with("zcash:")
+            {
+                use nozy::RequestAuthenticity;
+
+                let (request, check) =
+                    nozy::verify_payment_uri(&recipient, &address_book.list_addresses())?;
+                let icon = match &check.authenticity {
+                    RequestAuthenticity::Signed {
+                        merchant: Some(_), ..
+                    } if !check.expired => "✅",
+                    RequestAuthenticity::Unsigned => "ℹ️ ",
+                    RequestAuthenticity::Invalid => "❌",
+                    _ => "⚠️ ",
+                };
+                println!("{icon} {}", check.summary());
+                if check.signed_by_unknown_key() && !allow_unknown_signer {
+                    return Err(NozyError::InvalidInput(
+                        "Payment request is signed by a key not in your address book; save the \
+                         merchant as a contact or pass --allow-unknown-signer"
+                            .to_string(),
+                    ));
+                }
+                if !check.is_acceptable(allow_unknown_signer) {
+                    return Err(NozyError::InvalidInput(
+                        "Refusing to pay a payment request with a bad signature or past its expiry"
+                            .to_string(),
+                    ));
+                }
+                let amount = match (amount, request.amount_zec) {
+                    (Some(given), Some(requested)) if (given - requested).abs() >= 1e-9 => {
+                        return Err(NozyError::InvalidInput(format!(
+                            "--amount {given} differs from the requested {requested} ZEC"
+                        )));
+                    }
+                    (given, requested) => given.or(requested),
+                };
+                let memo = match (memo, request.memo) {
+                    (Some(given), Some(requested)) if given != requested => {
+                        return Err(NozyError::InvalidInput(
+                            "--memo differs from the memo in the payment request".to_string(),
+                        ));
+                    }
+                    (given, requested) => given.or(requested),
+                };
+                (request.address, amount, memo)
+            } else {
+                (recipient, amount, memo)
+            };
+            let amount = amount.ok_or_else(|| {
+                NozyError::InvalidInput("--amount is required unless the URI has one".to_string())
+            })?;
+
+            let (wallet, _storage) = load_wallet().await?;
+            let mut actual_recipient =
+                if let Some(address) = address_book.get_address_by_name(&recipient) {
+                    println!("📇 Found '{}' in address book: {}", recipient, address);
+                    let _ = address_book.update_address_usage(&recipient);
+                    address
+                } else {
+                    recipient.clone()
+                };
+
+            // Resolve Zcash Names (`alice` / `alice.zcash`) before address validation.
+            if nozy::zns::is_likely_zns_name(&actual_recipient) {
+                let net = if config.network == "testnet" {
                     "testnet"
                 } else {
                     "mainnet"
@@ -1123,6 +2333,7 @@ async fn execute_command(_command: Commands, mut config: nozy::WalletConfig) ->
                 pb.finish_with_message("✅ Scan complete");
                 res
             };
+            let spendable_notes = nozy::restrict_to_note_ids(spendable_notes, &notes)?;
 
             if spendable_notes.is_empty() {
                 return Err(NozyError::InvalidOperation(
@@ -1164,6 +2375,35 @@ async fn execute_command(_command: Commands, mut config: nozy::WalletConfig) ->
                 return Err(error);
             }
 
+            if preview {
+                let proposal = nozy::ZcashTransactionBuilder::new()
+                    .propose_send_transaction(
+                        &zebra_client,
+                        &spendable_notes,
+                        &actual_recipient,
+                        amount_zatoshis,
+                        fee_zatoshis,
+                        memo_preview,
+                        nozy::PilotSendOptions::for_send(),
+                    )
+                    .await?;
+                if let Some(path) = proposal_out.as_ref() {
+                    std::fs::write(path, proposal.to_json_pretty()?).map_err(|e| {
+                        NozyError::Storage(format!("Failed to write proposal {path}: {e}"))
+                    })?;
+                }
+                if cli.json {
+                    println!("{}", proposal.to_json_pretty()?);
+                } else {
+                    print_send_proposal(&proposal);
+                    if let Some(path) = proposal_out.as_ref() {
+                        println!("\n📝 Proposal saved to {path}");
+                        println!("   Run `nozy execute-proposal {path}` to prove and broadcast.");
+                    }
+                }
+                return Ok(());
+            }
+
             println!("\n{}", "=".repeat(60));
 
             if is_mainnet {
@@ -1302,85 +2542,1684 @@ async fn execute_command(_command: Commands, mut config: nozy::WalletConfig) ->
                         "  Remaining:   {:.8} ZEC",
                         balance_after as f64 / 100_000_000.0
                     );
-                    println!();
-                    println!("🛡️  This transaction is private and untraceable.");
-                    println!("🔒 Privacy is enforced by NozyWallet.");
-                    println!("{}", "=".repeat(60));
-                    println!("\n💡 Run 'nozy history' to view transaction details");
+                    println!();
+                    println!("🛡️  This transaction is private and untraceable.");
+                    println!("🔒 Privacy is enforced by NozyWallet.");
+                    println!("{}", "=".repeat(60));
+                    println!("\n💡 Run 'nozy history' to view transaction details");
+                }
+                Err(e) => {
+                    println!("❌ Failed to send: {}", e);
+                    handle_insufficient_funds_error(&e);
+                }
+            }
+        }
+
+        Commands::ExecuteProposal { file, zebra_url } => {
+            if let Some(url) = zebra_url {
+                config.zebra_url = url;
+            }
+
+            let content = std::fs::read_to_string(&file)
+                .map_err(|e| NozyError::Storage(format!("Failed to read proposal {file}: {e}")))?;
+            let proposal = nozy::SendProposal::from_json(&content)?;
+            let expected_network = if config.network == "testnet" {
+                "testnet"
+            } else {
+                "mainnet"
+            };
+            if proposal.network != expected_network {
+                return Err(NozyError::InvalidInput(format!(
+                    "Proposal was built for {} but wallet is configured for {}",
+                    proposal.network, expected_network
+                )));
+            }
+
+            print_send_proposal(&proposal);
+
+            let confirm_word = if proposal.network == "mainnet" {
+                "SEND"
+            } else {
+                "yes"
+            };
+            println!("\nType '{confirm_word}' to prove and broadcast, or anything else to cancel:");
+            use std::io::{self, Write};
+            print!("> ");
+            let _ = io::stdout().flush();
+            let mut input = String::new();
+            io::stdin()
+                .read_line(&mut input)
+                .map_err(|e| NozyError::InvalidOperation(format!("Failed to read input: {e}")))?;
+            if input.trim() != confirm_word {
+                println!("❌ Transaction cancelled.");
+                return Ok(());
+            }
+
+            let (wallet, _storage) = load_wallet().await?;
+            let zebra_client = ZebraClient::from_config(&config);
+            println!("\n🔍 Scanning for spendable notes...");
+            let spendable_notes = scan_notes_for_sending(&wallet, &config.zebra_url).await?;
+
+            println!("🔨 Proving and signing proposal (Halo 2)...");
+            let mut tx_builder = nozy::ZcashTransactionBuilder::new();
+            tx_builder.set_zebra_url(&config.zebra_url);
+            tx_builder.enable_mainnet_broadcast();
+            nozy::warm_orchard_proving_key();
+
+            let transaction = tx_builder
+                .execute_proposal(&zebra_client, &spendable_notes, &proposal)
+                .await?;
+            nozy::send_proposal::record_executed_proposal(&proposal, &transaction)?;
+
+            println!("\n✅ Transaction sent successfully!");
+            println!("🆔 Transaction ID: {}", transaction.txid);
+            println!("   Expiry height: {}", transaction.expiry_height);
+        }
+
+        Commands::Balance { json, fiat } => {
+            let as_json = json || cli.json;
+            if as_json {
+                match nozy::balance_to_json() {
+                    Ok(b) => {
+                        println!(
+                            "{}",
+                            serde_json::to_string_pretty(&b).map_err(|e| {
+                                NozyError::InvalidOperation(format!("json encode: {e}"))
+                            })?
+                        );
+                    }
+                    Err(e) => {
+                        eprintln!("{}", e);
+                        std::process::exit(4);
+                    }
+                }
+            } else {
+                use nozy::paths::get_wallet_data_dir;
+                use nozy::wallet_balance_snapshot;
+
+                let notes_path = get_wallet_data_dir().join("notes.json");
+                let snapshot = match wallet_balance_snapshot() {
+                    Ok(snapshot) => snapshot,
+                    Err(e) => {
+                        eprintln!("❌ Failed to read wallet balance: {}", e);
+                        return Ok(());
+                    }
+                };
+
+                println!("💰 Balance Information");
+                println!("{}", "=".repeat(50));
+                println!(
+                    "   Confirmed: {:.8} ZEC ({} unspent notes)",
+                    snapshot.confirmed_zatoshis as f64 / 100_000_000.0,
+                    snapshot.unspent_note_count
+                );
+
+                if snapshot.pending_zatoshis > 0 {
+                    println!(
+                        "   Pending:   -{:.8} ZEC",
+                        snapshot.pending_zatoshis as f64 / 100_000_000.0
+                    );
+                    println!(
+                        "   Available: {:.8} ZEC",
+                        snapshot.available_zatoshis as f64 / 100_000_000.0
+                    );
+                    println!(
+                        "\n   💡 Pending transactions reduce available balance until confirmed"
+                    );
+                } else {
+                    println!(
+                        "   Available: {:.8} ZEC",
+                        snapshot.available_zatoshis as f64 / 100_000_000.0
+                    );
+                }
+
+                if snapshot.sapling_unspent_zatoshis > 0 {
+                    println!(
+                        "   Sapling:   {:.8} ZEC (legacy; not spendable yet)",
+                        snapshot.sapling_unspent_zatoshis as f64 / 100_000_000.0
+                    );
+                }
+
+                if let Some(currency) = fiat.as_deref() {
+                    let config = load_config();
+                    match nozy::fiat_value_at_spot(
+                        &config,
+                        Some(currency),
+                        snapshot.available_zatoshis,
+                    )
+                    .await
+                    {
+                        Ok(Some(value)) => println!(
+                            "   ≈ {:.2} {} (at {:.2} {}/ZEC, {})",
+                            value.amount,
+                            value.currency,
+                            value.price_per_zec,
+                            value.currency,
+                            value.source
+                        ),
+                        Ok(None) => println!("   ≈ no {} price available", currency),
+                        Err(e) => eprintln!("   ⚠️  Fiat valuation unavailable: {}", e),
+                    }
+                }
+
+                if !notes_path.exists() {
+                    println!("\n   ⚠️  Run 'sync' to update your balance.");
+                }
+            }
+        }
+
+        Commands::Price { currency, at } => {
+            use nozy::price_source::normalize_currency_code;
+
+            let config = load_config();
+            let currency = normalize_currency_code(
+                currency.as_deref().unwrap_or(&config.fiat.display_currency),
+            )?;
+            let Some(source) = nozy::configured_price_source(&config)? else {
+                println!("No price source configured (set fiat.source in config).");
+                return Ok(());
+            };
+            let price = match at.as_deref() {
+                Some(day) => {
+                    let at = chrono::NaiveDate::parse_from_str(day, "%Y-%m-%d")
+                        .ok()
+                        .and_then(|d| d.and_hms_opt(0, 0, 0))
+                        .map(|d| d.and_utc())
+                        .ok_or_else(|| {
+                            NozyError::InvalidInput(format!(
+                                "Invalid date '{day}' (expected YYYY-MM-DD)"
+                            ))
+                        })?;
+                    source.historical_price(&currency, at).await?
+                }
+                None => source.spot_price(&currency).await?,
+            };
+            match price {
+                Some(p) => println!(
+                    "1 ZEC = {:.2} {} ({} at {})",
+                    p.price_per_zec,
+                    p.currency,
+                    p.source,
+                    p.at.format("%Y-%m-%d %H:%M UTC")
+                ),
+                None => println!("No {} price available from {}", currency, source.name()),
+            }
+        }
+
+        Commands::Notes { command } => match command {
+            NotesCommand::List { all, json } => {
+                let _ = load_wallet().await?;
+                let rows = nozy::list_wallet_note_summaries(all)?;
+                if json || cli.json {
+                    println!(
+                        "{}",
+                        serde_json::to_string_pretty(&rows).map_err(|e| {
+                            NozyError::InvalidOperation(format!("json encode: {e}"))
+                        })?
+                    );
+                } else if rows.is_empty() {
+                    println!("No notes. Run 'sync' first.");
+                } else {
+                    println!("🪙 Notes ({})", rows.len());
+                    println!("{}", "=".repeat(80));
+                    for row in &rows {
+                        let mut flags = Vec::new();
+                        if row.spent {
+                            flags.push("spent".to_string());
+                        }
+                        if row.frozen {
+                            flags.push("frozen".to_string());
+                        }
+                        if !row.witness_ready && !row.spent {
+                            flags.push("needs sync".to_string());
+                        }
+                        if let Some(a) = &row.annotation {
+                            if let Some(label) = &a.label {
+                                flags.push(format!("\"{label}\""));
+                            }
+                            flags.extend(a.tags.iter().map(|t| format!("#{t}")));
+                        }
+                        println!(
+                            "{}  {:.8} ZEC  h={}  {:?}  {}",
+                            row.id,
+                            row.value_zatoshis as f64 / 100_000_000.0,
+                            row.block_height,
+                            row.pool,
+                            flags.join(" ")
+                        );
+                    }
+                }
+            }
+            NotesCommand::Freeze { ids } => {
+                let _ = load_wallet().await?;
+                let changed = nozy::set_notes_frozen(&ids, true)?;
+                println!("🧊 Froze {} note(s)", changed);
+            }
+            NotesCommand::Unfreeze { ids } => {
+                let _ = load_wallet().await?;
+                let changed = nozy::set_notes_frozen(&ids, false)?;
+                println!("✅ Unfroze {} note(s)", changed);
+            }
+        },
+
+        Commands::Webhooks { command } => {
+            use nozy::merchant_webhooks::{
+                deliver_due_webhooks, generate_webhook_secret, list_webhook_deliveries,
+                retry_webhook_delivery, run_webhook_test_receiver, DeliveryStatus, WebhookDelivery,
+            };
+
+            let print_attempts = |attempted: &[WebhookDelivery]| {
+                if attempted.is_empty() {
+                    println!("No webhook deliveries are due.");
+                }
+                for d in attempted {
+                    match d.status {
+                        DeliveryStatus::Delivered => {
+                            println!("✅ {} {} → {}", d.id, d.event, d.url)
+                        }
+                        _ => eprintln!(
+                            "⚠️  {} {} → {}: {} (attempt {})",
+                            d.id,
+                            d.event,
+                            d.url,
+                            d.last_error.as_deref().unwrap_or("failed"),
+                            d.attempts
+                        ),
+                    }
+                }
+            };
+
+            match command {
+                WebhooksCommand::Config {
+                    url,
+                    clear_url,
+                    secret,
+                    generate_secret,
+                    max_attempts,
+                    allow_direct_http,
+                } => {
+                    // Fresh copy so CLI network overrides are not persisted.
+                    let mut stored = load_config();
+                    let hooks = &mut stored.merchant_webhooks;
+                    let changed = url.is_some()
+                        || clear_url
+                        || secret.is_some()
+                        || generate_secret
+                        || max_attempts.is_some()
+                        || allow_direct_http.is_some();
+                    if let Some(url) = url {
+                        if !(url.starts_with("https://") || url.starts_with("http://")) {
+                            return Err(NozyError::InvalidInput(
+                                "Webhook URL must be http(s)".into(),
+                            ));
+                        }
+                        hooks.url = Some(url);
+                    }
+                    if clear_url {
+                        hooks.url = None;
+                    }
+                    if generate_secret {
+                        let secret = generate_webhook_secret();
+                        println!("🔑 New webhook secret (share with your shop backend):");
+                        println!("   {secret}");
+                        hooks.secret = Some(secret);
+                    } else if let Some(secret) = secret {
+                        hooks.secret = Some(secret);
+                    }
+                    if max_attempts.is_some() {
+                        hooks.max_attempts = max_attempts;
+                    }
+                    if let Some(allow) = allow_direct_http {
+                        hooks.allow_direct_http = allow;
+                    }
+                    println!(
+                        "Webhook URL: {}",
+                        hooks.url.as_deref().unwrap_or("(none, per-invoice only)")
+                    );
+                    println!(
+                        "Secret: {}",
+                        if hooks.secret.is_some() {
+                            "set"
+                        } else {
+                            "not set (deliveries disabled)"
+                        }
+                    );
+                    println!("Max attempts: {}", hooks.max_attempts());
+                    println!(
+                        "Clearnet delivery: {}",
+                        if hooks.allow_direct_http {
+                            "allowed"
+                        } else {
+                            "off (Tor / I2P only)"
+                        }
+                    );
+                    if changed {
+                        save_config(&stored)?;
+                    }
+                }
+                WebhooksCommand::List {
+                    invoice,
+                    limit,
+                    json,
+                } => {
+                    let deliveries = list_webhook_deliveries(invoice.as_deref(), limit)?;
+                    if json || cli.json {
+                        println!(
+                            "{}",
+                            serde_json::to_string_pretty(&deliveries).map_err(|e| {
+                                NozyError::InvalidOperation(format!("json encode: {e}"))
+                            })?
+                        );
+                    } else if deliveries.is_empty() {
+                        println!("No webhook deliveries yet.");
+                    } else {
+                        for d in &deliveries {
+                            println!(
+                                "{}  {:<18} {:<9?} attempts={}  {}  {}",
+                                d.id,
+                                d.event,
+                                d.status,
+                                d.attempts,
+                                d.invoice_id,
+                                d.last_error.as_deref().unwrap_or("")
+                            );
+                        }
+                    }
+                }
+                WebhooksCommand::Deliver => {
+                    let attempted = deliver_due_webhooks().await?;
+                    print_attempts(&attempted);
+                }
+                WebhooksCommand::Retry { id } => {
+                    retry_webhook_delivery(&id)?;
+                    let attempted = deliver_due_webhooks().await?;
+                    print_attempts(&attempted);
+                }
+                WebhooksCommand::Receive { bind, secret } => {
+                    let secret = secret.or(config.merchant_webhooks.secret.clone());
+                    println!("📡 Listening for webhooks on http://{bind} (Ctrl+C to stop)");
+                    if secret.is_none() {
+                        println!("   No secret configured: signatures are not checked.");
+                    }
+                    run_webhook_test_receiver(&bind, secret, |hook| {
+                        let sig = match hook.signature_valid {
+                            Some(true) => "signature OK",
+                            Some(false) => "BAD SIGNATURE",
+                            None => "unchecked",
+                        };
+                        println!(
+                            "\n📨 {} ({}) [{}]",
+                            hook.event.as_deref().unwrap_or("?"),
+                            hook.delivery_id.as_deref().unwrap_or("?"),
+                            sig
+                        );
+                        println!("{}", hook.body);
+                    })
+                    .await?;
+                }
+            }
+        }
+
+        Commands::Pos { command } => {
+            use nozy::merchant_invoices::get_invoice;
+            use nozy::pos::{create_pos_sale, write_qr_png, PosSaleRequest};
+
+            let (wallet, _storage) = load_wallet().await?;
+            let (invoice, png, watch) = match command {
+                PosCommand::Sell {
+                    amount,
+                    currency,
+                    product,
+                    memo,
+                    ttl,
+                    rate,
+                    png,
+                    watch,
+                } => {
+                    let request = PosSaleRequest {
+                        amount_fiat: amount,
+                        currency,
+                        product_name: product,
+                        memo,
+                        ttl_minutes: ttl,
+                        price_per_zec: rate,
+                    };
+                    let invoice = create_pos_sale(&wallet, &config, request).await?;
+                    (invoice, png, watch)
+                }
+                PosCommand::Watch {
+                    invoice_id,
+                    png,
+                    watch,
+                } => {
+                    let invoice = get_invoice(&invoice_id)?.ok_or_else(|| {
+                        NozyError::InvalidInput(format!("Invoice {invoice_id} not found"))
+                    })?;
+                    (invoice, png, watch)
+                }
+            };
+
+            let uri = invoice.outstanding_payment_uri()?;
+            if let Some(path) = &png {
+                write_qr_png(&uri, path, 8)?;
+            }
+            if cli.json {
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&invoice)
+                        .map_err(|e| NozyError::InvalidOperation(format!("json encode: {e}")))?
+                );
+                return Ok(());
+            }
+            if watch.no_watch || !watch.tui {
+                print_pos_sale(&invoice, &uri)?;
+                if let Some(path) = &png {
+                    println!("🖼️  QR saved to {}", path.display());
+                }
+            }
+            if watch.no_watch {
+                return Ok(());
+            }
+
+            let zebra_url = watch.zebra_url.clone().or(Some(config.zebra_url.clone()));
+            let last = if watch.tui {
+                nozy::run_pos_tui(
+                    &wallet,
+                    &config,
+                    invoice,
+                    watch.poll,
+                    watch.zero_conf,
+                    zebra_url,
+                )
+                .await?
+            } else {
+                follow_pos_sale(&wallet, &config, invoice, &watch, zebra_url).await?
+            };
+            println!("{}: {}", last.invoice_id, nozy::pos::pos_status_line(&last));
+        }
+
+        Commands::Pczt { command } => match command {
+            PcztCommand::Watch { ufvk, name } => {
+                let (network, network_name) = if ufvk.trim().starts_with("uviewtest") {
+                    (NetworkType::Test, "testnet")
+                } else {
+                    (NetworkType::Main, "mainnet")
+                };
+                nozy::watch_only::StoredViewingKey::new(&ufvk, network)?;
+                let profile = nozy::create_new_profile(Some(&name))?;
+                nozy::configure_profile_network(
+                    &profile.id,
+                    network_name,
+                    nozy::default_zebra_url_for_network(network_name),
+                    true,
+                )?;
+                let password = Password::new()
+                    .with_prompt("Password for this profile (empty for none)")
+                    .allow_empty_password(true)
+                    .with_confirmation("Confirm password", "Passwords don't match")
+                    .interact()
+                    .map_err(|e| {
+                        NozyError::InvalidOperation(format!("Password input error: {e}"))
+                    })?;
+                nozy::notes_vault::unlock_notes_vault(&password)?;
+                nozy::watch_only::import_viewing_key(&ufvk, network)?;
+                println!(
+                    "✅ Watch-only profile {} ({}) created on {network_name}",
+                    profile.name, profile.id
+                );
+                println!("🧾 Build sends with `nozy pczt create`; the seed stays on the signer.");
+            }
+            PcztCommand::Create {
+                to,
+                amount,
+                memo,
+                out,
+                qr,
+            } => {
+                let network = if config.network == "testnet" {
+                    NetworkType::Test
+                } else {
+                    NetworkType::Main
+                };
+                let amount_zatoshis =
+                    nozy::input_validation::resolve_send_amount_zatoshis(None, Some(amount))?;
+                if nozy::active_profile_kind() == nozy::WalletProfileKind::Seed {
+                    return Err(NozyError::InvalidOperation(
+                        "The online instance builds PCZTs from a viewing key: create a \
+                         watch-only profile with `nozy pczt watch <ufvk>`"
+                            .to_string(),
+                    ));
+                }
+                nozy::cli_helpers::unlock_notes_vault_interactive(|| {
+                    nozy::watch_only::active_profile_viewing_key().map(drop)
+                })?;
+                let fvk = nozy::watch_only::active_profile_viewing_key()?.ok_or_else(|| {
+                    NozyError::InvalidOperation("The active profile has no viewing key".to_string())
+                })?;
+                let spendable_notes =
+                    nozy::scan_notes_for_viewing_key(&fvk, &config.zebra_url).await?;
+                let zebra_client = ZebraClient::from_config(&config);
+                let pilot = nozy::PilotSendOptions {
+                    priority: nozy::NOZY_WALLET_PRIORITY_FEE,
+                    expiry_delta_blocks: nozy::PILOT_EXPIRY_DELTA_BLOCKS,
+                };
+                let memo = memo
+                    .as_ref()
+                    .map(|m| m.trim().as_bytes())
+                    .filter(|b| !b.is_empty());
+                let fee_zatoshis = nozy::cli_helpers::estimate_transaction_fee_for_send(
+                    &zebra_client,
+                    memo,
+                    pilot.priority,
+                )
+                .await;
+                nozy::warm_orchard_proving_key();
+                let build = nozy::build_keystone_send_pczt(
+                    &zebra_client,
+                    &nozy::ZebraJsonRpcOrchardWitnessProvider,
+                    &config.keystone,
+                    &spendable_notes,
+                    &to,
+                    amount_zatoshis,
+                    fee_zatoshis,
+                    memo,
+                    pilot,
+                    network,
+                )
+                .await?;
+                let request = nozy::enqueue_signing_request(nozy::prepared_send_from_build(
+                    &to,
+                    amount_zatoshis,
+                    &build,
+                ))?;
+                println!("🧾 {}", build.summary);
+                println!(
+                    "📥 Queued as {} (expires at height {})",
+                    request.id, request.expiry_height
+                );
+                emit_pczt(&build.pczt_bytes, out.as_deref(), qr).await?;
+                println!("✍️  Sign it offline with `nozy pczt sign`, then run `nozy pczt broadcast` here.");
+            }
+            PcztCommand::Sign {
+                input,
+                out,
+                qr,
+                yes,
+            } => {
+                let network = if config.network == "testnet" {
+                    NetworkType::Test
+                } else {
+                    NetworkType::Main
+                };
+                let pczt_bytes = read_pczt_input(&input)?;
+                let (wallet, _storage) = load_wallet().await?;
+                let spending_key = nozy::orchard_spending_key_from_wallet(&wallet)?;
+                let inspection = nozy::cold_signing::inspect_pczt(
+                    &pczt_bytes,
+                    &orchard::keys::FullViewingKey::from(&spending_key),
+                    network,
+                    &config.pczt_policy,
+                )?;
+                print_pczt_inspection(&inspection);
+                inspection.enforce_policy()?;
+                if !yes
+                    && !Confirm::new()
+                        .with_prompt("Sign this transaction?")
+                        .default(false)
+                        .interact()
+                        .map_err(|e| NozyError::InvalidOperation(format!("Input error: {e}")))?
+                {
+                    println!("❌ Not signed");
+                    return Ok(());
+                }
+                let signed = nozy::sign_pczt_orchard_spends(&pczt_bytes, &spending_key)?;
+                println!("✅ Signed");
+                emit_pczt(&signed, out.as_deref(), qr).await?;
+            }
+            PcztCommand::Broadcast { input, request } => {
+                let pczt_bytes = match (input, request) {
+                    (_, Some(id)) => nozy::scanned_signed_pczt(&id)?,
+                    (Some(input), None) => read_pczt_input(&input)?,
+                    (None, None) => {
+                        return Err(NozyError::InvalidInput(
+                            "Give a signed PCZT or --request <id>".to_string(),
+                        ))
+                    }
+                };
+                // Unlocks the notes vault so the spent notes can be marked; no seed needed.
+                nozy::cli_helpers::unlock_notes_vault_interactive(|| {
+                    nozy::watch_only::active_profile_viewing_key()?;
+                    nozy::load_wallet_notes().map(drop)
+                })?;
+                let txid = nozy::cold_signing::broadcast_signed_pczt(
+                    &ZebraClient::from_config(&config),
+                    &pczt_bytes,
+                )
+                .await?;
+                println!("📡 Broadcast {txid}");
+            }
+            PcztCommand::Queue => {
+                let chain_tip = ZebraClient::from_config(&config).get_block_count().await?;
+                for dropped in nozy::prune_expired_signing_requests(chain_tip)? {
+                    println!(
+                        "⌛ Dropped {} (expired at height {})",
+                        dropped.id, dropped.expiry_height
+                    );
+                }
+                let queue = nozy::load_signing_queue()?;
+                if cli.json {
+                    println!(
+                        "{}",
+                        serde_json::to_string_pretty(&queue.requests).map_err(|e| {
+                            NozyError::InvalidOperation(format!("json encode: {e}"))
+                        })?
+                    );
+                } else if queue.requests.is_empty() {
+                    println!("No PCZTs are waiting for a signature.");
+                } else {
+                    for request in &queue.requests {
+                        let send = &request.send;
+                        println!(
+                            "{}  {:.8} ZEC to {}  expires at {} ({} blocks left)  {} note(s) locked",
+                            request.id,
+                            send.amount_zatoshis as f64 / 100_000_000.0,
+                            send.recipient,
+                            request.expiry_height,
+                            request.expiry_height.saturating_sub(chain_tip),
+                            request.locked_notes.len()
+                        );
+                        if !request.scan.frames.is_empty() {
+                            println!(
+                                "    scanned {} of {} frame(s)",
+                                request.scan.frames.len(),
+                                request
+                                    .scan
+                                    .expected_fragments
+                                    .map(|n| n.to_string())
+                                    .unwrap_or_else(|| "?".to_string())
+                            );
+                        }
+                    }
+                }
+            }
+            PcztCommand::Cancel { id } => {
+                let cancelled = nozy::cancel_signing_request(&id)?;
+                println!(
+                    "🗑️  Cancelled {}; {} note(s) released",
+                    cancelled.id,
+                    cancelled.locked_notes.len()
+                );
+            }
+            PcztCommand::Scan { id, input } => {
+                let text = read_text_input(&input)?;
+                let frames: Vec<String> = text.lines().map(str::to_string).collect();
+                let progress = nozy::record_scanned_frames(&id, &frames)?;
+                if progress.complete {
+                    println!(
+                        "✅ Signed PCZT complete ({} frame(s)); run `nozy pczt broadcast --request {id}`",
+                        progress.frames_received
+                    );
+                } else {
+                    println!(
+                        "📷 {} of {} frame(s) scanned; scan more and run this again",
+                        progress.frames_received,
+                        progress
+                            .expected_fragments
+                            .map(|n| n.to_string())
+                            .unwrap_or_else(|| "?".to_string())
+                    );
+                }
+            }
+            PcztCommand::Inspect { input } => {
+                let network = if config.network == "testnet" {
+                    NetworkType::Test
+                } else {
+                    NetworkType::Main
+                };
+                let pczt_bytes = read_pczt_input(&input)?;
+                let (wallet, _storage) = load_wallet().await?;
+                let inspection = nozy::cold_signing::inspect_pczt_for_wallet(
+                    &pczt_bytes,
+                    &wallet,
+                    network,
+                    &config.pczt_policy,
+                )?;
+                if cli.json {
+                    println!(
+                        "{}",
+                        serde_json::to_string_pretty(&inspection).map_err(|e| {
+                            NozyError::InvalidOperation(format!("json encode: {e}"))
+                        })?
+                    );
+                } else {
+                    print_pczt_inspection(&inspection);
+                }
+            }
+            PcztCommand::Policy {
+                max_amount,
+                max_fee,
+                address_book_only,
+                clear,
+            } => {
+                // Fresh copy so CLI network overrides are not persisted.
+                let mut stored = load_config();
+                let policy = &mut stored.pczt_policy;
+                let changed = max_amount.is_some()
+                    || max_fee.is_some()
+                    || address_book_only.is_some()
+                    || clear;
+                if clear {
+                    *policy = Default::default();
+                }
+                if let Some(zec) = max_amount {
+                    policy.max_amount_zatoshis =
+                        Some(nozy::input_validation::zec_to_zatoshis_exact(zec)?);
+                }
+                if let Some(zec) = max_fee {
+                    policy.max_fee_zatoshis =
+                        Some(nozy::input_validation::zec_to_zatoshis_exact(zec)?);
+                }
+                if let Some(only) = address_book_only {
+                    policy.address_book_only = only;
+                }
+                let limit = |z: Option<u64>| {
+                    z.map(|z| format!("{:.8} ZEC", z as f64 / 100_000_000.0))
+                        .unwrap_or_else(|| "none".to_string())
+                };
+                println!("Max amount: {}", limit(policy.max_amount_zatoshis));
+                println!("Max fee: {}", limit(policy.max_fee_zatoshis));
+                println!(
+                    "Address book recipients only: {}",
+                    if policy.address_book_only {
+                        "yes"
+                    } else {
+                        "no"
+                    }
+                );
+                if changed {
+                    save_config(&stored)?;
+                }
+            }
+        },
+
+        Commands::Frost { command } => match command {
+            FrostCommand::Dealer {
+                threshold,
+                signers,
+                out_dir,
+            } => {
+                let network = if config.network == "testnet" {
+                    NetworkType::Test
+                } else {
+                    NetworkType::Main
+                };
+                let shares = nozy::frost_multisig::dealer_keygen(threshold, signers, network)?;
+                std::fs::create_dir_all(&out_dir).map_err(|e| {
+                    NozyError::Storage(format!("Failed to create {}: {e}", out_dir.display()))
+                })?;
+                for share in &shares {
+                    let path = out_dir.join(format!("frost-share-{}.json", share.identifier));
+                    emit_frost_message(share, Some(&path), false).await?;
+                }
+                println!(
+                    "🔑 {threshold}-of-{signers} group address: {}",
+                    shares[0].group_address()?
+                );
+                println!(
+                    "⚠️  Each file is a secret share: hand it to one participant, then delete it."
+                );
+            }
+            FrostCommand::Import { share, name } => {
+                let share: nozy::frost_multisig::FrostShare = read_frost_input(&share)?;
+                let profile = nozy::create_new_profile(Some(&name))?;
+                nozy::configure_profile_network(
+                    &profile.id,
+                    &share.network,
+                    nozy::default_zebra_url_for_network(&share.network),
+                    true,
+                )?;
+                unlock_frost_vault(true)?;
+                nozy::frost_multisig::save_share(&share)?;
+                println!(
+                    "✅ Share {} of a {}-of-{} key imported into profile {} ({})",
+                    share.identifier,
+                    share.min_signers,
+                    share.max_signers,
+                    profile.name,
+                    profile.id
+                );
+                println!("📍 Group address: {}", share.group_address()?);
+            }
+            FrostCommand::Dkg { command } => match command {
+                FrostDkgCommand::Round1 {
+                    id,
+                    threshold,
+                    signers,
+                    name,
+                    out,
+                } => {
+                    let network = if config.network == "testnet" {
+                        NetworkType::Test
+                    } else {
+                        NetworkType::Main
+                    };
+                    let (state, message) =
+                        nozy::frost_multisig::dkg_round1(id, threshold, signers, network)?;
+                    let profile = nozy::create_new_profile(Some(&name))?;
+                    nozy::configure_profile_network(
+                        &profile.id,
+                        &config.network,
+                        &config.zebra_url,
+                        true,
+                    )?;
+                    unlock_frost_vault(true)?;
+                    nozy::frost_multisig::save_dkg_state(&state)?;
+                    println!(
+                        "🧩 DKG started in profile {} ({})",
+                        profile.name, profile.id
+                    );
+                    emit_frost_message(&message, out.as_deref(), false).await?;
+                    println!("   Send this round 1 message to every other participant.");
+                }
+                FrostDkgCommand::Round2 { round1, out_dir } => {
+                    let round1 = round1
+                        .iter()
+                        .map(|f| read_frost_input(f))
+                        .collect::<NozyResult<Vec<_>>>()?;
+                    unlock_frost_vault(false)?;
+                    let mut state = nozy::frost_multisig::load_dkg_state()?;
+                    let messages = nozy::frost_multisig::dkg_round2(&mut state, &round1)?;
+                    nozy::frost_multisig::save_dkg_state(&state)?;
+                    std::fs::create_dir_all(&out_dir).map_err(|e| {
+                        NozyError::Storage(format!("Failed to create {}: {e}", out_dir.display()))
+                    })?;
+                    for message in &messages {
+                        let path = out_dir.join(format!(
+                            "frost-dkg2-{}-to-{}.json",
+                            message.from, message.to
+                        ));
+                        emit_frost_message(message, Some(&path), false).await?;
+                    }
+                    println!(
+                        "🔒 Give each file only to its recipient, over a private channel: it \
+                         carries your part of the group viewing key."
+                    );
+                }
+                FrostDkgCommand::Finish { round1, round2 } => {
+                    let round1 = round1
+                        .iter()
+                        .map(|f| read_frost_input(f))
+                        .collect::<NozyResult<Vec<_>>>()?;
+                    let round2 = round2
+                        .iter()
+                        .map(|f| read_frost_input(f))
+                        .collect::<NozyResult<Vec<_>>>()?;
+                    unlock_frost_vault(false)?;
+                    let state = nozy::frost_multisig::load_dkg_state()?;
+                    let share = nozy::frost_multisig::dkg_finish(&state, &round1, &round2)?;
+                    nozy::frost_multisig::save_share(&share)?;
+                    nozy::frost_multisig::clear_dkg_state()?;
+                    println!(
+                        "✅ DKG complete: share {} of a {}-of-{} key",
+                        share.identifier, share.min_signers, share.max_signers
+                    );
+                    println!("📍 Group address: {}", share.group_address()?);
+                }
+            },
+            FrostCommand::Info => {
+                unlock_frost_vault(false)?;
+                let share = nozy::frost_multisig::load_share()?;
+                println!(
+                    "🔑 FROST share {} of a {}-of-{} key ({})",
+                    share.identifier, share.min_signers, share.max_signers, share.network
+                );
+                println!("📍 Group address: {}", share.group_address()?);
+                println!("👁️  Group UFVK:    {}", share.group_ufvk()?);
+                println!("🧾 Build group sends in this profile with `nozy pczt create`.");
+            }
+            FrostCommand::Commit { pczt, out, qr } => {
+                let pczt_bytes = read_pczt_input(&pczt)?;
+                unlock_frost_vault(false)?;
+                let share = nozy::frost_multisig::load_share()?;
+                let commitments = nozy::frost_multisig::commit_to_pczt(&share, &pczt_bytes)?;
+                println!(
+                    "🎲 Committed to {} spend(s) as signer {}",
+                    commitments.actions.len(),
+                    share.identifier
+                );
+                emit_frost_message(&commitments, out.as_deref(), qr).await?;
+            }
+            FrostCommand::Request {
+                pczt,
+                commitments,
+                out,
+                qr,
+            } => {
+                let pczt_bytes = read_pczt_input(&pczt)?;
+                let commitments = commitments
+                    .iter()
+                    .map(|f| read_frost_input(f))
+                    .collect::<NozyResult<Vec<_>>>()?;
+                unlock_frost_vault(false)?;
+                let share = nozy::frost_multisig::load_share()?;
+                let request =
+                    nozy::frost_multisig::build_signing_request(&share, &pczt_bytes, commitments)?;
+                emit_frost_message(&request, out.as_deref(), qr).await?;
+            }
+            FrostCommand::Sign {
+                request,
+                out,
+                qr,
+                yes,
+            } => {
+                let request: nozy::frost_multisig::FrostSigningRequest =
+                    read_frost_input(&request)?;
+                unlock_frost_vault(false)?;
+                let share = nozy::frost_multisig::load_share()?;
+                let inspection = nozy::cold_signing::inspect_pczt(
+                    &nozy::frost_multisig::request_pczt(&request)?,
+                    &share.group_fvk()?,
+                    share.network_type(),
+                    &config.pczt_policy,
+                )?;
+                let signers: Vec<String> = request
+                    .commitments
+                    .iter()
+                    .map(|c| c.identifier.to_string())
+                    .collect();
+                println!("👥 Group spend, signers {}", signers.join(", "));
+                print_pczt_inspection(&inspection);
+                inspection.enforce_policy()?;
+                if !yes
+                    && !Confirm::new()
+                        .with_prompt("Sign your share of this transaction?")
+                        .default(false)
+                        .interact()
+                        .map_err(|e| NozyError::InvalidOperation(format!("Input error: {e}")))?
+                {
+                    println!("❌ Not signed");
+                    return Ok(());
+                }
+                let shares = nozy::frost_multisig::sign_request(&share, &request)?;
+                emit_frost_message(&shares, out.as_deref(), qr).await?;
+            }
+            FrostCommand::Aggregate {
+                request,
+                shares,
+                out,
+                qr,
+            } => {
+                let request: nozy::frost_multisig::FrostSigningRequest =
+                    read_frost_input(&request)?;
+                let shares = shares
+                    .iter()
+                    .map(|f| read_frost_input(f))
+                    .collect::<NozyResult<Vec<_>>>()?;
+                unlock_frost_vault(false)?;
+                let share = nozy::frost_multisig::load_share()?;
+                let signed = nozy::frost_multisig::aggregate_signatures(&share, &request, &shares)?;
+                println!("✅ Threshold signature complete");
+                emit_pczt(&signed, out.as_deref(), qr).await?;
+                println!("📡 Broadcast it with `nozy pczt broadcast`.");
+            }
+        },
+
+        Commands::Audit { command } => match command {
+            AuditCommand::Export {
+                from,
+                to,
+                start_height,
+                end_height,
+                account,
+                file,
+            } => {
+                let parse_day = |day: &str| {
+                    chrono::NaiveDate::parse_from_str(day, "%Y-%m-%d").map_err(|_| {
+                        NozyError::InvalidInput(format!(
+                            "Invalid date '{day}' (expected YYYY-MM-DD)"
+                        ))
+                    })
+                };
+                let zebra = ZebraClient::from_config(&config);
+                let range = match (from, to, start_height, end_height) {
+                    (Some(from), Some(to), None, None) => {
+                        nozy::auditor::height_for_date(&zebra, parse_day(&from)?, parse_day(&to)?)
+                            .await?
+                    }
+                    (None, None, Some(start_height), Some(end_height))
+                        if start_height <= end_height =>
+                    {
+                        nozy::auditor::AuditRange {
+                            start_height,
+                            end_height,
+                            start_date: None,
+                            end_date: None,
+                        }
+                    }
+                    _ => {
+                        return Err(NozyError::InvalidInput(
+                            "Give either --from/--to dates or --start-height/--end-height".into(),
+                        ))
+                    }
+                };
+                let (wallet, _storage) = load_wallet().await?;
+                let network = if config.network == "testnet" {
+                    NetworkType::Test
+                } else {
+                    NetworkType::Main
+                };
+                let account = account.unwrap_or(config.active_orchard_account());
+                let package =
+                    nozy::auditor::create_auditor_package(&wallet, &zebra, account, network, range)
+                        .await?;
+                let encoded = serde_json::to_string_pretty(&package)
+                    .map_err(|e| NozyError::InvalidOperation(format!("json encode: {e}")))?;
+                std::fs::write(&file, &encoded).map_err(|e| {
+                    NozyError::Storage(format!("Failed to write {}: {e}", file.display()))
+                })?;
+                let manifest = &package.manifest;
+                println!(
+                    "📦 Auditor package for account {} written to {}",
+                    manifest.account,
+                    file.display()
+                );
+                println!(
+                    "   Blocks {}..={} ({} receipts, {} payments on record)",
+                    manifest.start_height,
+                    manifest.end_height,
+                    manifest.received.len(),
+                    manifest.sent.len()
+                );
+                println!("   Signer key: {}", manifest.signer_key);
+                println!(
+                    "   No viewing key is included; give the auditor the signer key separately."
+                );
+            }
+            AuditCommand::Run { package, out } => {
+                let raw = std::fs::read_to_string(&package).map_err(|e| {
+                    NozyError::Storage(format!("Failed to read {}: {e}", package.display()))
+                })?;
+                let package: nozy::auditor::AuditorPackage = serde_json::from_str(&raw)
+                    .map_err(|e| NozyError::InvalidInput(format!("Not an auditor package: {e}")))?;
+                if package.manifest.network != config.network {
+                    return Err(NozyError::InvalidInput(format!(
+                        "Package is for {} but Nozy is configured for {}",
+                        package.manifest.network, config.network
+                    )));
+                }
+                if !cli.json {
+                    println!(
+                        "🔍 Checking the packaged outputs of blocks {}..={}...",
+                        package.manifest.start_height, package.manifest.end_height
+                    );
+                }
+                let report =
+                    nozy::auditor::run_audit(&ZebraClient::from_config(&config), &package).await?;
+                let encoded = serde_json::to_string_pretty(&report)
+                    .map_err(|e| NozyError::InvalidOperation(format!("json encode: {e}")))?;
+                if let Some(path) = &out {
+                    std::fs::write(path, &encoded).map_err(|e| {
+                        NozyError::Storage(format!("Failed to write {}: {e}", path.display()))
+                    })?;
+                }
+                if cli.json {
+                    println!("{encoded}");
+                } else {
+                    let zec = |z: u64| z as f64 / 100_000_000.0;
+                    for (label, txs) in [("Received", &report.received), ("Sent", &report.sent)] {
+                        println!("{label}:");
+                        for tx in txs {
+                            println!(
+                                "   {:>8}  {}  {:.8} ZEC{}",
+                                tx.block_height,
+                                tx.txid,
+                                zec(tx.value_zatoshis),
+                                tx.counterparty
+                                    .as_deref()
+                                    .map(|a| format!("  → {a}"))
+                                    .unwrap_or_default()
+                            );
+                        }
+                    }
+                    println!(
+                        "Totals: received {:.8} ZEC, sent {:.8} ZEC",
+                        zec(report.total_received_zatoshis),
+                        zec(report.total_sent_zatoshis)
+                    );
+                    if report.verified() {
+                        println!("✅ History matches the signed manifest");
+                    } else {
+                        println!(
+                            "❌ {} discrepancies with the signed manifest:",
+                            report.discrepancies.len()
+                        );
+                        for d in &report.discrepancies {
+                            println!("   - {d}");
+                        }
+                    }
+                    if let Some(path) = &out {
+                        println!("📄 Report written to {}", path.display());
+                    }
+                }
+            }
+        },
+
+        Commands::Disclosure { command } => match command {
+            DisclosureCommand::Export { txid, output, file } => {
+                let (wallet, _storage) = load_wallet().await?;
+                let network = if config.network == "testnet" {
+                    NetworkType::Test
+                } else {
+                    NetworkType::Main
+                };
+                let disclosure = nozy::payment_disclosure::export_payment_disclosure(
+                    &wallet,
+                    &ZebraClient::from_config(&config),
+                    &txid,
+                    output,
+                    network,
+                )
+                .await?;
+                let encoded = serde_json::to_string_pretty(&disclosure)
+                    .map_err(|e| NozyError::InvalidOperation(format!("json encode: {e}")))?;
+                match file {
+                    Some(path) => {
+                        std::fs::write(&path, &encoded).map_err(|e| {
+                            NozyError::Storage(format!("Failed to write {}: {e}", path.display()))
+                        })?;
+                        println!(
+                            "📄 Disclosure for output {} of {} written to {}",
+                            disclosure.output_index,
+                            disclosure.txid,
+                            path.display()
+                        );
+                        println!("   Anyone holding it can see this output's recipient, amount and memo.");
+                    }
+                    None => println!("{encoded}"),
+                }
+            }
+            DisclosureCommand::Verify { disclosure } => {
+                let raw = if std::path::Path::new(&disclosure).is_file() {
+                    std::fs::read_to_string(&disclosure).map_err(|e| {
+                        NozyError::Storage(format!("Failed to read {disclosure}: {e}"))
+                    })?
+                } else {
+                    disclosure
+                };
+                let disclosure: nozy::payment_disclosure::PaymentDisclosure =
+                    serde_json::from_str(&raw).map_err(|e| {
+                        NozyError::InvalidInput(format!("Not a payment disclosure: {e}"))
+                    })?;
+                let verified = nozy::payment_disclosure::verify_payment_disclosure_with_zebra(
+                    &ZebraClient::from_config(&config),
+                    &disclosure,
+                )
+                .await?;
+                if cli.json {
+                    println!(
+                        "{}",
+                        serde_json::to_string_pretty(&verified).map_err(|e| {
+                            NozyError::InvalidOperation(format!("json encode: {e}"))
+                        })?
+                    );
+                } else {
+                    println!("✅ Payment disclosure verified");
+                    println!("   Transaction: {}", verified.txid);
+                    if verified.block_height > 0 {
+                        println!("   Mined at:    {}", verified.block_height);
+                    } else {
+                        println!("   Mined at:    not yet mined (mempool)");
+                    }
+                    println!("   Paid:        {}", verified.recipient_address);
+                    println!(
+                        "   Amount:      {:.8} ZEC",
+                        verified.value_zatoshis as f64 / 100_000_000.0
+                    );
+                    match (&verified.memo_text, &verified.memo) {
+                        (Some(text), _) => println!("   Memo:        {text}"),
+                        (None, Some(memo)) => println!("   Memo (hex):  {memo}"),
+                        (None, None) => {}
+                    }
+                }
+            }
+        },
+
+        Commands::Message { command } => match command {
+            MessageCommand::Sign {
+                message,
+                account,
+                address,
+                sapling,
+                reveal_viewing_key,
+            } => {
+                if !sapling && !reveal_viewing_key {
+                    return Err(NozyError::InvalidInput(format!(
+                        "{} Pass --reveal-viewing-key to sign anyway, or --sapling for a \
+                         ZIP-304 signature that reveals no key.",
+                        nozy::signed_message::VIEWING_KEY_WARNING
+                    )));
+                }
+                let (wallet, _storage) = load_wallet().await?;
+                let account = account.unwrap_or(config.active_orchard_account());
+                let network = if config.network == "testnet" {
+                    NetworkType::Test
+                } else {
+                    NetworkType::Main
+                };
+                let address = match address {
+                    Some(address) => address,
+                    None if sapling => {
+                        wallet.generate_sapling_payment_address(account, 0, network)?
+                    }
+                    None => wallet.generate_orchard_address(account, 0, network)?,
+                };
+                let seed = wallet.get_mnemonic_object().to_seed("");
+                let signature = if sapling {
+                    if !cli.json {
+                        println!("⏳ Building Sapling proof...");
+                    }
+                    nozy::zip304::sign_with_seed(&seed, account, &address, &message)?
+                } else {
+                    nozy::signed_message::sign_for_address(
+                        &seed,
+                        account,
+                        &address,
+                        &message,
+                        reveal_viewing_key,
+                    )?
+                };
+                let warning = (!sapling).then_some(nozy::signed_message::VIEWING_KEY_WARNING);
+                if cli.json {
+                    println!(
+                        "{}",
+                        serde_json::json!({
+                            "address": address,
+                            "signature": signature,
+                            "warning": warning,
+                        })
+                    );
+                } else {
+                    println!("✍️  Signed as {address}");
+                    println!("{signature}");
+                    if let Some(warning) = warning {
+                        println!("⚠️  {warning}");
+                    }
+                }
+            }
+            MessageCommand::Verify {
+                message,
+                signature,
+                address,
+            } => {
+                let valid = nozy::signed_message::verify_any(
+                    signature.trim(),
+                    &message,
+                    address.as_deref(),
+                )?;
+                if cli.json {
+                    println!("{}", serde_json::json!({ "valid": valid }));
+                } else if valid {
+                    match &address {
+                        Some(address)
+                            if signature.starts_with(nozy::signed_message::SCHEME_ID_V2)
+                                || signature.starts_with(nozy::zip304::SIGNATURE_PREFIX) =>
+                        {
+                            println!("✅ Valid signature by the owner of {address}")
+                        }
+                        _ => println!("✅ Valid signature (not bound to an address)"),
+                    }
+                }
+                if !valid {
+                    return Err(NozyError::InvalidInput(
+                        "Signature is not valid".to_string(),
+                    ));
+                }
+            }
+        },
+
+        Commands::PaymentRequest { command } => match command {
+            PaymentRequestCommand::Key => {
+                let (wallet, _storage) = load_wallet().await?;
+                let signer = nozy::PaymentRequestSigner::from_wallet(
+                    &wallet,
+                    nozy::WalletRole::Business.orchard_account(),
+                )?;
+                println!("🔑 Merchant key: {}", signer.merchant_key());
+                println!("   Customers add it with: nozy address-book merchant-key --name <shop> --key <key>");
+            }
+            PaymentRequestCommand::Sign { uri, expires_in } => {
+                let (wallet, _storage) = load_wallet().await?;
+                let signer = nozy::PaymentRequestSigner::from_wallet(
+                    &wallet,
+                    nozy::WalletRole::Business.orchard_account(),
+                )?;
+                let expires = expires_in.map(|minutes| {
+                    (chrono::Utc::now() + chrono::Duration::minutes(minutes)).to_rfc3339()
+                });
+                let (signed, _) = signer.sign_uri(&uri, expires.as_deref())?;
+                println!("{signed}");
+            }
+            PaymentRequestCommand::Verify { uri } => {
+                let contacts = AddressBook::new()?.list_addresses();
+                let (request, check) = nozy::verify_payment_uri(&uri, &contacts)?;
+                if cli.json {
+                    println!(
+                        "{}",
+                        serde_json::to_string_pretty(&check).map_err(|e| {
+                            NozyError::InvalidOperation(format!("json encode: {e}"))
+                        })?
+                    );
+                } else {
+                    println!("{}", check.summary());
+                    println!("   Address: {}", request.address);
+                    if let Some(amount) = request.amount_zec {
+                        println!("   Amount:  {amount} ZEC");
+                    }
+                    if let Some(memo) = &request.memo {
+                        println!("   Memo:    {memo}");
+                    }
+                    if let Some(expires) = &request.expires {
+                        println!("   Expires: {expires}");
+                    }
                 }
-                Err(e) => {
-                    println!("❌ Failed to send: {}", e);
-                    handle_insufficient_funds_error(&e);
+                if !check.is_acceptable(true) {
+                    return Err(NozyError::InvalidInput(
+                        "Payment request failed verification".to_string(),
+                    ));
                 }
             }
-        }
+        },
 
-        Commands::Balance { json } => {
-            let as_json = json || cli.json;
-            if as_json {
-                match nozy::balance_to_json() {
-                    Ok(b) => {
+        Commands::Zns { command } => {
+            use nozy::zns_registration::{ZnsActionKind, ZnsActionStatus};
+
+            let network = network_type_from_config(&config.network);
+            let indexer = nozy::zns::ZnsIndexer::for_network(Some(&config.network))?;
+            let (kind, name, address, dry_run, yes) = match command {
+                ZnsCommand::Register {
+                    name,
+                    address,
+                    dry_run,
+                    yes,
+                } => (ZnsActionKind::Claim, name, address, dry_run, yes),
+                ZnsCommand::Update {
+                    name,
+                    address,
+                    dry_run,
+                    yes,
+                } => (ZnsActionKind::Update, name, address, dry_run, yes),
+                ZnsCommand::Renew { name, dry_run, yes } => {
+                    (ZnsActionKind::Renew, name, None, dry_run, yes)
+                }
+                ZnsCommand::Config {
+                    registry_address,
+                    max_cost,
+                } => {
+                    // Fresh copy so CLI network overrides are not persisted.
+                    let mut stored = load_config();
+                    let zns = &mut stored.zns_registration;
+                    let changed = registry_address.is_some() || max_cost.is_some();
+                    if let Some(address) = registry_address {
+                        let address = nozy::input_validation::normalize_unified_address(&address);
+                        nozy::input_validation::validate_zcash_address(&address)?;
+                        zns.registry_address = Some(address);
+                    }
+                    if let Some(max_cost) = max_cost {
+                        zns.max_cost_zatoshis =
+                            Some(nozy::input_validation::zec_to_zatoshis_exact(max_cost)?);
+                    }
+                    println!(
+                        "Registry address: {}",
+                        zns.registry_address
+                            .as_deref()
+                            .unwrap_or("(not pinned, actions cannot be sent)")
+                    );
+                    println!(
+                        "Max cost: {:.8} ZEC",
+                        zns.max_cost_zatoshis() as f64 / 100_000_000.0
+                    );
+                    if changed {
+                        save_config(&stored)?;
+                    }
+                    return Ok(());
+                }
+                ZnsCommand::Status => {
+                    let zebra_client = ZebraClient::from_config(&config);
+                    let actions =
+                        nozy::zns_registration::refresh_zns_actions(&zebra_client, &indexer)
+                            .await?;
+                    if cli.json {
                         println!(
                             "{}",
-                            serde_json::to_string_pretty(&b).map_err(|e| {
+                            serde_json::to_string_pretty(&actions).map_err(|e| {
                                 NozyError::InvalidOperation(format!("json encode: {e}"))
                             })?
                         );
+                        return Ok(());
                     }
-                    Err(e) => {
-                        eprintln!("{}", e);
-                        std::process::exit(4);
+                    if actions.is_empty() {
+                        println!("No ZNS actions yet. Claim a name with 'zns register <name>'.");
                     }
-                }
-            } else {
-                use nozy::paths::get_wallet_data_dir;
-                use nozy::wallet_balance_snapshot;
-
-                let notes_path = get_wallet_data_dir().join("notes.json");
-                let snapshot = match wallet_balance_snapshot() {
-                    Ok(snapshot) => snapshot,
-                    Err(e) => {
-                        eprintln!("❌ Failed to read wallet balance: {}", e);
-                        return Ok(());
+                    for action in &actions {
+                        let state = match &action.status {
+                            ZnsActionStatus::Submitted => "⏳ waiting to be mined".to_string(),
+                            ZnsActionStatus::Mined { height } => {
+                                format!("⛏️  mined at {height}, indexer not updated yet")
+                            }
+                            ZnsActionStatus::Confirmed { height } => {
+                                format!("✅ confirmed at {height}")
+                            }
+                            ZnsActionStatus::Superseded { height, by_txid } => {
+                                format!("✅ confirmed at {height}, since replaced by {by_txid}")
+                            }
+                            ZnsActionStatus::Mismatch { reason } => format!("❌ {reason}"),
+                            ZnsActionStatus::Expired { expiry_height } => format!(
+                                "⌛ expired unmined at {expiry_height}; the registry was not paid"
+                            ),
+                        };
+                        let state = match &action.last_error {
+                            Some(error) => format!("{state} (not re-checked: {error})"),
+                            None => state,
+                        };
+                        println!(
+                            "{}  {} {}  {}  {}",
+                            action.submitted_at.format("%Y-%m-%d"),
+                            action.kind.as_str(),
+                            action.name,
+                            action.txid,
+                            state
+                        );
                     }
-                };
+                    return Ok(());
+                }
+            };
 
-                println!("💰 Balance Information");
-                println!("{}", "=".repeat(50));
+            let (wallet, _storage) = load_wallet().await?;
+            let prepared = nozy::zns_registration::prepare_zns_action(
+                &wallet,
+                &indexer,
+                kind,
+                &name,
+                address.as_deref(),
+                network,
+            )
+            .await?;
+            println!(
+                "📛 {} {}.zcash → {}",
+                prepared.kind.as_str(),
+                prepared.name,
+                prepared.address
+            );
+            println!(
+                "   Pays {:.8} ZEC + {:.8} ZEC fee to the registry",
+                prepared.cost_zatoshis as f64 / 100_000_000.0,
+                prepared.fee_zatoshis as f64 / 100_000_000.0
+            );
+            println!("   Registry: {}", prepared.registry_address);
+            println!("   Memo: {}", prepared.memo);
+            if !prepared.registry_pinned {
                 println!(
-                    "   Confirmed: {:.8} ZEC ({} unspent notes)",
-                    snapshot.confirmed_zatoshis as f64 / 100_000_000.0,
-                    snapshot.unspent_note_count
+                    "⚠️  This registry address is not pinned. Verify it out of band, then run \
+                     'zns config --registry-address <address>'."
                 );
+            }
+            if dry_run {
+                println!("Dry run: nothing sent.");
+                return Ok(());
+            }
+            if !yes
+                && !Confirm::new()
+                    .with_prompt("Pay the registry?")
+                    .default(false)
+                    .interact()
+                    .map_err(|e| NozyError::InvalidOperation(format!("Input error: {e}")))?
+            {
+                println!("❌ Not sent");
+                return Ok(());
+            }
+            let action =
+                nozy::zns_registration::submit_zns_action(&wallet, &config.zebra_url, &prepared)
+                    .await?;
+            println!("📡 Broadcast {}", action.txid);
+            println!("   Track it with 'zns status'; the indexer picks it up once mined.");
+        }
 
-                if snapshot.pending_zatoshis > 0 {
-                    println!(
-                        "   Pending:   -{:.8} ZEC",
-                        snapshot.pending_zatoshis as f64 / 100_000_000.0
-                    );
-                    println!(
-                        "   Available: {:.8} ZEC",
-                        snapshot.available_zatoshis as f64 / 100_000_000.0
-                    );
-                    println!(
-                        "\n   💡 Pending transactions reduce available balance until confirmed"
-                    );
-                } else {
-                    println!(
-                        "   Available: {:.8} ZEC",
-                        snapshot.available_zatoshis as f64 / 100_000_000.0
-                    );
-                }
+        Commands::Schedule { command } => {
+            use nozy::{load_payment_schedule, PaymentRunOutcome};
 
-                if snapshot.sapling_unspent_zatoshis > 0 {
+            match command {
+                ScheduleCommand::Add {
+                    recipient,
+                    amount,
+                    every,
+                    start,
+                    runs,
+                    memo,
+                    label,
+                    on_insufficient,
+                } => {
+                    let _ = load_wallet().await?;
+                    let new = nozy::NewScheduledPayment {
+                        label,
+                        recipient,
+                        amount_zatoshis: nozy::input_validation::zec_to_zatoshis_exact(amount)?,
+                        memo,
+                        frequency: Some(every.parse()?),
+                        start_at: start
+                            .as_deref()
+                            .map(nozy::parse_schedule_start)
+                            .transpose()?,
+                        runs,
+                        on_insufficient_funds: on_insufficient.parse()?,
+                    };
+                    let payment = nozy::add_scheduled_payment(new)?;
                     println!(
-                        "   Sapling:   {:.8} ZEC (legacy; not spendable yet)",
-                        snapshot.sapling_unspent_zatoshis as f64 / 100_000_000.0
+                        "🗓️  Scheduled {} ({:.8} ZEC {}), first due {}",
+                        payment.id,
+                        payment.amount_zatoshis as f64 / 100_000_000.0,
+                        payment.frequency.as_str(),
+                        payment.next_due.format("%Y-%m-%d %H:%M UTC")
                     );
                 }
-
-                if !notes_path.exists() {
-                    println!("\n   ⚠️  Run 'sync' to update your balance.");
+                ScheduleCommand::List { json } => {
+                    let _ = load_wallet().await?;
+                    let schedule = load_payment_schedule()?;
+                    if json || cli.json {
+                        println!(
+                            "{}",
+                            serde_json::to_string_pretty(&schedule).map_err(|e| {
+                                NozyError::InvalidOperation(format!("json encode: {e}"))
+                            })?
+                        );
+                    } else if schedule.payments.is_empty() {
+                        println!("No scheduled payments. Add one with 'schedule add'.");
+                    } else {
+                        println!("🗓️  Scheduled payments ({})", schedule.payments.len());
+                        println!("{}", "=".repeat(80));
+                        for p in &schedule.payments {
+                            let state = if p.enabled { "active" } else { "paused" };
+                            let runs = p
+                                .remaining_runs
+                                .map(|n| format!(", {n} left"))
+                                .unwrap_or_default();
+                            println!(
+                                "{}  {:.8} ZEC {}  next {}  [{}{}]  {}",
+                                p.id,
+                                p.amount_zatoshis as f64 / 100_000_000.0,
+                                p.frequency.as_str(),
+                                p.next_due.format("%Y-%m-%d"),
+                                state,
+                                runs,
+                                p.label.as_deref().unwrap_or(&p.recipient)
+                            );
+                        }
+                        let recent = schedule.recent_runs(None, 5);
+                        if !recent.is_empty() {
+                            println!("\nRecent runs:");
+                            for run in &recent {
+                                let outcome = match &run.outcome {
+                                    PaymentRunOutcome::Sent { txid } => format!("sent {txid}"),
+                                    PaymentRunOutcome::InsufficientFunds { .. } => {
+                                        "⚠️  insufficient funds".to_string()
+                                    }
+                                    PaymentRunOutcome::Failed { error } => {
+                                        format!("❌ failed: {error}")
+                                    }
+                                };
+                                println!(
+                                    "   {}  {}  {}",
+                                    run.ran_at.format("%Y-%m-%d %H:%M"),
+                                    run.payment_id,
+                                    outcome
+                                );
+                            }
+                        }
+                    }
+                }
+                ScheduleCommand::Remove { id } => {
+                    let _ = load_wallet().await?;
+                    nozy::remove_scheduled_payment(&id)?;
+                    println!("🗑️  Removed scheduled payment {}", id);
+                }
+                ScheduleCommand::Pause { id } => {
+                    let _ = load_wallet().await?;
+                    nozy::set_scheduled_payment_enabled(&id, false)?;
+                    println!("⏸️  Paused scheduled payment {}", id);
+                }
+                ScheduleCommand::Resume { id } => {
+                    let _ = load_wallet().await?;
+                    nozy::set_scheduled_payment_enabled(&id, true)?;
+                    println!("▶️  Resumed scheduled payment {}", id);
+                }
+                ScheduleCommand::Run { zebra_url } => {
+                    if let Some(url) = zebra_url {
+                        config.zebra_url = url;
+                    }
+                    let (wallet, _storage) = load_wallet().await?;
+                    let runs = nozy::run_due_payments(&wallet, &config.zebra_url).await?;
+                    if runs.is_empty() {
+                        println!("No scheduled payments are due.");
+                    }
+                    for run in &runs {
+                        let amount = run.amount_zatoshis as f64 / 100_000_000.0;
+                        match &run.outcome {
+                            PaymentRunOutcome::Sent { txid } => {
+                                println!("✅ {} paid {:.8} ZEC: {}", run.payment_id, amount, txid)
+                            }
+                            PaymentRunOutcome::InsufficientFunds {
+                                available_zatoshis,
+                                needed_zatoshis,
+                            } => eprintln!(
+                                "⚠️  {} skipped: need {:.8} ZEC in one note, wallet has {:.8} ZEC",
+                                run.payment_id,
+                                *needed_zatoshis as f64 / 100_000_000.0,
+                                *available_zatoshis as f64 / 100_000_000.0
+                            ),
+                            PaymentRunOutcome::Failed { error } => {
+                                eprintln!("❌ {} failed: {}", run.payment_id, error)
+                            }
+                        }
+                    }
                 }
             }
         }
@@ -1974,7 +4813,210 @@ async fn execute_command(_command: Commands, mut config: nozy::WalletConfig) ->
             }
         }
 
-        Commands::History => {
+        Commands::History {
+            command:
+                Some(HistoryCommand::Export {
+                    format,
+                    from,
+                    to,
+                    account,
+                    output,
+                    fiat,
+                }),
+        } => {
+            let format: nozy::HistoryExportFormat = format.parse()?;
+            let filter = nozy::HistoryExportFilter::parse(from.as_deref(), to.as_deref(), account)?;
+            let config = load_config();
+            let zebra_client = ZebraClient::from_config(&config);
+            let rendered =
+                nozy::export_wallet_history(&zebra_client, &filter, format, fiat.as_deref())
+                    .await?;
+            match output {
+                Some(path) => {
+                    std::fs::write(&path, rendered.as_bytes()).map_err(|e| {
+                        NozyError::Storage(format!("Failed to write export to {path}: {e}"))
+                    })?;
+                    eprintln!("✅ Exported history ({}) to {}", format.as_str(), path);
+                }
+                None => print!("{rendered}"),
+            }
+        }
+
+        Commands::CostBasis { command } => match command {
+            CostBasisCommand::Report {
+                year,
+                method,
+                currency,
+                format,
+                output,
+            } => {
+                let method: nozy::CostBasisMethod = method.parse()?;
+                let config = load_config();
+                let zebra_client = ZebraClient::from_config(&config);
+                let report = nozy::wallet_cost_basis_report(
+                    &zebra_client,
+                    year,
+                    method,
+                    currency.as_deref(),
+                )
+                .await?;
+                let rendered = match format.as_str() {
+                    "csv" => nozy::render_cost_basis_csv(&report),
+                    "json" => {
+                        serde_json::to_string_pretty(&report)
+                            .map_err(|e| NozyError::InvalidOperation(format!("json encode: {e}")))?
+                            + "\n"
+                    }
+                    "text" => {
+                        let c = &report.currency;
+                        let mut out = format!(
+                            "📊 Realized gains {} ({}, {})\n{}\n",
+                            report.year,
+                            report.method.as_str(),
+                            c,
+                            "=".repeat(50)
+                        );
+                        out.push_str(&format!("   Disposals matched: {}\n", report.entries.len()));
+                        out.push_str(&format!(
+                            "   Proceeds:          {:.2} {c}\n",
+                            report.total_proceeds
+                        ));
+                        out.push_str(&format!(
+                            "   Cost basis:        {:.2} {c}\n",
+                            report.total_cost_basis
+                        ));
+                        out.push_str(&format!(
+                            "   Short-term gain:   {:.2} {c}\n",
+                            report.short_term_gain
+                        ));
+                        out.push_str(&format!(
+                            "   Long-term gain:    {:.2} {c}\n",
+                            report.long_term_gain
+                        ));
+                        out.push_str(&format!(
+                            "   Total gain:        {:.2} {c}\n",
+                            report.total_gain
+                        ));
+                        if report.unpriced_entries > 0 {
+                            out.push_str(&format!(
+                                "   ⚠️  {} entries without a price (set overrides or a price source)\n",
+                                report.unpriced_entries
+                            ));
+                        }
+                        if report.unmatched_zatoshis > 0 {
+                            out.push_str(&format!(
+                                "   ⚠️  {:.8} ZEC disposed with no earlier deposit on record\n",
+                                report.unmatched_zatoshis as f64 / 100_000_000.0
+                            ));
+                        }
+                        out
+                    }
+                    other => {
+                        return Err(NozyError::InvalidInput(format!(
+                            "Unknown format '{other}' (expected text, csv or json)"
+                        )))
+                    }
+                };
+                match output {
+                    Some(path) => {
+                        std::fs::write(&path, rendered.as_bytes()).map_err(|e| {
+                            NozyError::Storage(format!("Failed to write report to {path}: {e}"))
+                        })?;
+                        eprintln!("✅ Wrote cost-basis report to {}", path);
+                    }
+                    None => print!("{rendered}"),
+                }
+            }
+            CostBasisCommand::Override {
+                note_id,
+                cost,
+                currency,
+                acquired,
+                note,
+            } => {
+                let acquired_at = acquired
+                    .as_deref()
+                    .map(nozy::parse_acquired_date)
+                    .transpose()?;
+                let currency = currency.unwrap_or_else(|| load_config().fiat.display_currency);
+                let mut overrides = nozy::CostBasisOverrides::new()?;
+                overrides.set(nozy::CostBasisOverride {
+                    note_id: note_id.clone(),
+                    currency,
+                    cost_basis: cost,
+                    acquired_at,
+                    note,
+                })?;
+                println!("✅ Cost basis for {} set to {:.2}", note_id, cost);
+            }
+            CostBasisCommand::ClearOverride { note_id } => {
+                let mut overrides = nozy::CostBasisOverrides::new()?;
+                if overrides.remove(&note_id)? {
+                    println!("✅ Removed cost-basis override for {}", note_id);
+                } else {
+                    println!("No override for {}", note_id);
+                }
+            }
+            CostBasisCommand::Overrides => {
+                let overrides = nozy::CostBasisOverrides::new()?.list();
+                if overrides.is_empty() {
+                    println!("No cost-basis overrides.");
+                }
+                for o in overrides {
+                    println!(
+                        "{}  {:.2} {}{}{}",
+                        o.note_id,
+                        o.cost_basis,
+                        o.currency,
+                        o.acquired_at
+                            .map(|d| format!("  acquired {}", d.format("%Y-%m-%d")))
+                            .unwrap_or_default(),
+                        o.note.map(|n| format!("  ({n})")).unwrap_or_default()
+                    );
+                }
+            }
+        },
+
+        Commands::History {
+            command: Some(HistoryCommand::Search { query }),
+        } => {
+            use nozy::transaction_history::collect_wallet_transaction_views;
+
+            let _ = load_wallet().await?;
+            let config = load_config();
+            let store = nozy::LabelStore::new()?;
+            let mut views = collect_wallet_transaction_views(config.last_scan_height.unwrap_or(0))?;
+            views.retain(|v| store.view_matches(v, &query));
+            store.annotate_views(&mut views);
+            if views.is_empty() {
+                println!("No transactions match '{query}'");
+            }
+            for view in &views {
+                let sign = if view.net_amount_zatoshis < 0 {
+                    "-"
+                } else {
+                    "+"
+                };
+                println!(
+                    "{} {}{:.8} ZEC  {}",
+                    view.txid,
+                    sign,
+                    view.amount_zec(),
+                    view.transaction_type.label()
+                );
+                if let Some(a) = &view.annotation {
+                    if let Some(label) = &a.label {
+                        println!("   Label: {label}");
+                    }
+                    if !a.tags.is_empty() {
+                        let tags: Vec<String> = a.tags.iter().map(|t| format!("#{t}")).collect();
+                        println!("   Tags:  {}", tags.join(" "));
+                    }
+                }
+            }
+        }
+
+        Commands::History { command: None } => {
             use nozy::load_config;
             use nozy::transaction_history::SentTransactionStorage;
 
@@ -2591,6 +5633,82 @@ async fn execute_command(_command: Commands, mut config: nozy::WalletConfig) ->
             }
         }
 
+        Commands::Label { command } => {
+            use nozy::{AnnotationUpdate, LabelStore, LabelTarget};
+
+            let print_annotation = |target: LabelTarget, id: &str, a: &nozy::Annotation| {
+                let kind = match target {
+                    LabelTarget::Transaction => "tx",
+                    LabelTarget::Note => "note",
+                };
+                println!("{kind} {id}");
+                if let Some(label) = &a.label {
+                    println!("   Label: {label}");
+                }
+                if !a.tags.is_empty() {
+                    let tags: Vec<String> = a.tags.iter().map(|t| format!("#{t}")).collect();
+                    println!("   Tags:  {}", tags.join(" "));
+                }
+                if let Some(note) = &a.note {
+                    println!("   Note:  {note}");
+                }
+            };
+
+            // Labels share the notes vault key; unlock before reading or writing.
+            let _ = load_wallet().await?;
+            let mut store = LabelStore::new()?;
+            match command {
+                LabelCommand::Set {
+                    target,
+                    id,
+                    label,
+                    tags,
+                    untags,
+                    note,
+                } => {
+                    let target: LabelTarget = target.parse()?;
+                    let annotation = store.update(
+                        target,
+                        &id,
+                        AnnotationUpdate {
+                            label,
+                            add_tags: tags,
+                            remove_tags: untags,
+                            note,
+                        },
+                    )?;
+                    if annotation.is_empty() {
+                        println!("✅ Cleared annotation for {id}");
+                    } else {
+                        print_annotation(target, &id, &annotation);
+                    }
+                }
+                LabelCommand::Show { target, id } => {
+                    let target: LabelTarget = target.parse()?;
+                    match store.get(target, &id) {
+                        Some(a) => print_annotation(target, &id, a),
+                        None => println!("No label for {id}"),
+                    }
+                }
+                LabelCommand::Remove { target, id } => {
+                    if store.remove(target.parse()?, &id)? {
+                        println!("✅ Removed label for {id}");
+                    } else {
+                        println!("No label for {id}");
+                    }
+                }
+                LabelCommand::Search { query } => {
+                    let hits = store.search(&query);
+                    if hits.is_empty() {
+                        println!("No labels match '{query}'");
+                    }
+                    for hit in hits {
+                        print_annotation(hit.target, &hit.id, &hit.annotation);
+                    }
+                }
+            }
+        }
+
         Commands::AddressBook { command } => {
             let address_book = AddressBook::new()?;
 
@@ -2611,6 +5729,9 @@ async fn execute_command(_command: Commands, mut config: nozy::WalletConfig) ->
                             if let Some(notes) = &entry.notes {
                                 println!("   Notes: {}", notes);
                             }
+                            if let Some(key) = &entry.merchant_key {
+                                println!("   Merchant key: {}", key);
+                            }
                             println!(
                                 "   Created: {}",
                                 entry.created_at.format("%Y-%m-%d %H:%M:%S UTC")
@@ -2632,7 +5753,13 @@ async fn execute_command(_command: Commands, mut config: nozy::WalletConfig) ->
                     name,
                     address,
                     notes,
-                } => match address_book.add_address(name.clone(), address.clone(), notes) {
+                    merchant_key,
+                } => match address_book
+                    .add_address(name.clone(), address.clone(), notes)
+                    .and_then(|()| match merchant_key {
+                        Some(key) => address_book.set_merchant_key(&name, Some(key)).map(|_| ()),
+                        None => Ok(()),
+                    }) {
                     Ok(()) => {
                         println!("✅ Added '{}' to address book", name);
                         println!("   Address: {}", address);
@@ -2642,6 +5769,18 @@ async fn execute_command(_command: Commands, mut config: nozy::WalletConfig) ->
                     }
                 },
 
+                AddressBookCommand::MerchantKey { name, key, clear } => {
+                    let key = if clear { None } else { key };
+                    match address_book.set_merchant_key(&name, key.clone()) {
+                        Ok(true) if key.is_some() => {
+                            println!("✅ Payment requests signed by this key now show as '{name}'")
+                        }
+                        Ok(true) => println!("✅ Cleared merchant key for '{name}'"),
+                        Ok(false) => eprintln!("❌ Address '{}' not found in address book", name),
+                        Err(e) => eprintln!("❌ Failed to set merchant key: {}", e),
+                    }
+                }
+
                 AddressBookCommand::Remove { name } => match address_book.remove_address(&name) {
                     Ok(true) => {
                         println!("✅ Removed '{}' from address book", name);
@@ -2661,6 +5800,9 @@ async fn execute_command(_command: Commands, mut config: nozy::WalletConfig) ->
                         if let Some(notes) = &entry.notes {
                             println!("   Notes: {}", notes);
                         }
+                        if let Some(key) = &entry.merchant_key {
+                            println!("   Merchant key: {}", key);
+                        }
                         println!(
                             "   Created: {}",
                             entry.created_at.format("%Y-%m-%d %H:%M:%S UTC")
@@ -4187,7 +7329,7 @@ async fn execute_command(_command: Commands, mut config: nozy::WalletConfig) ->
                         }
                     }
                 }
-                IronwoodCommand::Migrate => {
+                IronwoodCommand::Migrate { pczt, out, qr } => {
                     if !ironwood_active {
                         return Err(NozyError::InvalidOperation(format!(
                             "Ironwood (NU6.3) is not active on this network yet (tip {}, activation {}). \
@@ -4216,6 +7358,19 @@ async fn execute_command(_command: Commands, mut config: nozy::WalletConfig) ->
                     println!("🔍 Loading spendable Orchard notes for turnstile prebuild...");
                     let spendable_notes =
                         scan_notes_for_sending(&wallet, &config.zebra_url).await?;
+
+                    if pczt {
+                        let pending = nozy::ironwood::prepare_keystone_migration_pczt(
+                            &config.zebra_url,
+                            ironwood_active,
+                            &wallet,
+                            &config.keystone,
+                            &spendable_notes,
+                        )
+                        .await?;
+                        emit_keystone_migration(&pending, out.as_deref(), qr).await?;
+                        return Ok(());
+                    }
                     let result = execute_orchard_migration(
                         &config.zebra_url,
                         ironwood_active,
@@ -4361,7 +7516,12 @@ async fn execute_command(_command: Commands, mut config: nozy::WalletConfig) ->
                         println!("   Blocker: {blocker}");
                     }
                 }
-                IronwoodCommand::Split { dry_run } => {
+                IronwoodCommand::Split {
+                    dry_run,
+                    pczt,
+                    out,
+                    qr,
+                } => {
                     if !ironwood_active {
                         return Err(NozyError::InvalidOperation(format!(
                             "Ironwood (NU6.3) is not active on this network yet (tip {}, activation {}). \
@@ -4408,6 +7568,16 @@ async fn execute_command(_command: Commands, mut config: nozy::WalletConfig) ->
                             println!("     • #{} {value} zat", index + 1);
                         }
                         println!("   Run without --dry-run to build, prove, and broadcast.");
+                    } else if pczt {
+                        let pending = nozy::ironwood::prepare_keystone_note_split_pczt(
+                            &config.zebra_url,
+                            ironwood_active,
+                            &wallet,
+                            &config.keystone,
+                            &spendable_notes,
+                        )
+                        .await?;
+                        emit_keystone_migration(&pending, out.as_deref(), qr).await?;
                     } else {
                         let result = execute_orchard_note_split(
                             &config.zebra_url,
@@ -4438,6 +7608,38 @@ async fn execute_command(_command: Commands, mut config: nozy::WalletConfig) ->
                         }
                     }
                 }
+                IronwoodCommand::Complete { input } => {
+                    let signed = read_pczt_input(&input)?;
+                    let result = nozy::ironwood::complete_keystone_migration_pczt(
+                        &config.zebra_url,
+                        &signed,
+                    )
+                    .await?;
+                    match result.kind {
+                        nozy::ironwood::KeystoneMigrationKind::NoteSplit => {
+                            println!("✅ Keystone-signed note split broadcast");
+                            println!("   TXID: {}", result.txid);
+                            println!(
+                                "   Next: run `nozy sync --to-tip`, `nozy ironwood plan --save`, then `nozy ironwood preflight`."
+                            );
+                        }
+                        nozy::ironwood::KeystoneMigrationKind::Crossing => {
+                            println!(
+                                "🔒 Keystone-signed turnstile transaction stored as presigned"
+                            );
+                            if let Some(sequence) = result.sequence {
+                                println!("   Sequence: {sequence}");
+                            }
+                            println!("   TXID:     {}", result.txid);
+                            if let Some(path) = result.schedule_path {
+                                println!("   Schedule: {}", path.display());
+                            }
+                            println!(
+                                "   Next: run `nozy ironwood broadcast` when the ZIP 318 bucket window is open"
+                            );
+                        }
+                    }
+                }
             }
         }
 
@@ -2,27 +2,96 @@
 //!
 //! Stored as JSON beside the wallet datadir. Diversifier index advances per invoice
 //! so each sale gets a distinct Orchard UA under Business account index 1.
+//!
+//! Every payment to that address is recorded on the invoice; the status follows the
+//! running total (`PartiallyPaid` / `Detected` / `Confirmed` / `Overpaid`) within the
+//! invoice's tolerance; a full or excess payment stays `Detected` until it is confirmed. Sync finds those payments with the Business viewing key
+//! ([`sync_invoice_payments`]); a payment counts as confirmed once its block is
+//! `confirmations` deep, and loses that again if the block is reorged out. A payment
+//! whose transaction expires unmined is kept as `dropped` and no longer counts.
 
 use crate::error::{NozyError, NozyResult};
+use crate::fee_policy::{estimate_orchard_send_fee_zatoshis, pilot_transaction_expired};
+use crate::notes::WatchedPayment;
 use crate::paths::get_wallet_data_dir;
+use crate::payment_request::PaymentRequestSigner;
+use crate::price_source::PricePoint;
+use crate::zebra_integration::ZebraClient;
 use crate::zip321::build_payment_uri;
-use chrono::{Duration, Utc};
+use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 
 const DEFAULT_TTL_MINUTES: i64 = 60;
+const DEFAULT_CONFIRMATIONS: u32 = 3;
+/// Paying blocks younger than this are re-checked against the chain on every sync.
+const REORG_CHECK_DEPTH: u32 = 100;
+
+fn default_confirmations() -> u32 {
+    DEFAULT_CONFIRMATIONS
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum InvoiceStatus {
     Open,
+    /// Paid less than `amount - tolerance` so far.
+    PartiallyPaid,
     Detected,
     Confirmed,
+    /// Paid more than `amount + tolerance`, all confirmed; see [`refund_guidance`].
+    Overpaid,
     Expired,
     Cancelled,
 }
 
+/// Merchant invoice defaults (`merchant_invoices` in config).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InvoiceConfig {
+    /// Shortfall or excess (zats) still treated as an exact payment.
+    #[serde(default)]
+    pub tolerance_zatoshis: u64,
+    /// Blocks (including the paying one) before a payment counts as confirmed.
+    #[serde(default = "default_confirmations")]
+    pub confirmations: u32,
+    /// Sign invoice URIs with the Business key ([`crate::payment_request`]).
+    #[serde(default)]
+    pub sign_payment_requests: bool,
+}
+
+impl Default for InvoiceConfig {
+    fn default() -> Self {
+        Self {
+            tolerance_zatoshis: 0,
+            confirmations: DEFAULT_CONFIRMATIONS,
+            sign_payment_requests: false,
+        }
+    }
+}
+
+/// One payment received at an invoice address.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct InvoicePayment {
+    pub txid: String,
+    pub amount_zatoshis: u64,
+    /// Mined at least `confirmations` deep in the active chain.
+    pub confirmed: bool,
+    pub detected_at: String,
+    /// `None` while in the mempool or after the paying block was reorged out.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_height: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_hash: Option<String>,
+    /// Last height the paying transaction can be mined at, when it has one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expiry_height: Option<u32>,
+    /// Expired without being mined; not counted towards the invoice.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub dropped: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MerchantInvoice {
     pub invoice_id: String,
@@ -34,6 +103,9 @@ pub struct MerchantInvoice {
     pub amount_fiat: Option<f64>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub fiat_currency: Option<String>,
+    /// Exchange rate the fiat amount was converted at (POS sales).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locked_rate: Option<PricePoint>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub product_name: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -43,10 +115,90 @@ pub struct MerchantInvoice {
     pub created_at: String,
     pub expires_at: String,
     pub zcash_uri: String,
+    /// Merchant signature carried in `zcash_uri` (`sig` parameter), if signed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub detected_txid: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub confirmed_txid: Option<String>,
+    /// Per-invoice webhook endpoint; overrides `merchant_webhooks.url`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub webhook_url: Option<String>,
+    #[serde(default)]
+    pub tolerance_zatoshis: u64,
+    #[serde(default = "default_confirmations")]
+    pub confirmations: u32,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub payments: Vec<InvoicePayment>,
+    #[serde(default)]
+    pub paid_zatoshis: u64,
+    #[serde(default)]
+    pub outstanding_zatoshis: u64,
+}
+
+impl MerchantInvoice {
+    /// Can still take (more) payment: `Open` or `PartiallyPaid`.
+    fn awaits_payment(&self) -> bool {
+        matches!(
+            self.status,
+            InvoiceStatus::Open | InvoiceStatus::PartiallyPaid
+        )
+    }
+
+    fn is_past_deadline(&self, now: DateTime<Utc>) -> bool {
+        chrono::DateTime::parse_from_rfc3339(&self.expires_at)
+            .is_ok_and(|exp| exp.with_timezone(&Utc) <= now)
+    }
+
+    /// Payments that still count: everything not dropped.
+    fn live_payments(&self) -> impl Iterator<Item = &InvoicePayment> {
+        self.payments.iter().filter(|p| !p.dropped)
+    }
+
+    fn refresh_totals(&mut self) {
+        self.paid_zatoshis = self.live_payments().map(|p| p.amount_zatoshis).sum();
+        self.outstanding_zatoshis = self.amount_zatoshis.saturating_sub(self.paid_zatoshis);
+    }
+
+    /// Status implied by the recorded payments (`Open` when there are none).
+    fn payment_status(&self) -> InvoiceStatus {
+        let paid = self.paid_zatoshis;
+        if paid == 0 {
+            InvoiceStatus::Open
+        } else if paid < self.amount_zatoshis.saturating_sub(self.tolerance_zatoshis) {
+            InvoiceStatus::PartiallyPaid
+        } else if !self.live_payments().all(|p| p.confirmed) {
+            InvoiceStatus::Detected
+        } else if paid > self.amount_zatoshis.saturating_add(self.tolerance_zatoshis) {
+            InvoiceStatus::Overpaid
+        } else {
+            InvoiceStatus::Confirmed
+        }
+    }
+
+    /// Paid beyond the invoice amount (zero unless overpaid past tolerance).
+    pub fn overpaid_zatoshis(&self) -> u64 {
+        if self.status == InvoiceStatus::Overpaid {
+            self.paid_zatoshis.saturating_sub(self.amount_zatoshis)
+        } else {
+            0
+        }
+    }
+
+    /// ZIP-321 URI for the remaining amount of a partially paid invoice, else the original.
+    ///
+    /// The remainder URI is unsigned: the merchant signature covers the full amount.
+    pub fn outstanding_payment_uri(&self) -> NozyResult<String> {
+        if self.status != InvoiceStatus::PartiallyPaid {
+            return Ok(self.zcash_uri.clone());
+        }
+        build_payment_uri(
+            &self.payment_address,
+            Some(self.outstanding_zatoshis as f64 / 100_000_000.0),
+            self.memo.as_deref(),
+        )
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -67,8 +219,12 @@ impl InvoiceStore {
         }
         let raw = fs::read_to_string(&path)
             .map_err(|e| NozyError::Storage(format!("Failed to read invoices: {e}")))?;
-        serde_json::from_str(&raw)
-            .map_err(|e| NozyError::Storage(format!("Corrupt invoice store: {e}")))
+        let mut store: Self = serde_json::from_str(&raw)
+            .map_err(|e| NozyError::Storage(format!("Corrupt invoice store: {e}")))?;
+        for inv in &mut store.invoices {
+            inv.refresh_totals();
+        }
+        Ok(store)
     }
 
     fn save(&self) -> NozyResult<()> {
@@ -95,6 +251,14 @@ pub struct CreateInvoiceParams {
     pub ttl_minutes: Option<i64>,
     pub payment_address: String,
     pub diversifier_index: u32,
+    pub webhook_url: Option<String>,
+    /// Defaults to `merchant_invoices.tolerance_zatoshis` from config.
+    pub tolerance_zatoshis: Option<u64>,
+    /// Defaults to `merchant_invoices.confirmations` from config.
+    pub confirmations: Option<u32>,
+    pub locked_rate: Option<PricePoint>,
+    /// Signs the URI (address, amount, memo, expiry) when set.
+    pub signer: Option<PaymentRequestSigner>,
 }
 
 /// Allocate next diversifier index for Business invoices (does not persist until create).
@@ -108,6 +272,13 @@ pub fn create_invoice(params: CreateInvoiceParams) -> NozyResult<MerchantInvoice
             "amount_zec must be a positive finite value".into(),
         ));
     }
+    if let Some(url) = params.webhook_url.as_deref() {
+        if !(url.starts_with("https://") || url.starts_with("http://")) {
+            return Err(NozyError::InvalidInput(
+                "webhook_url must be an http(s) URL".into(),
+            ));
+        }
+    }
     let amount_zatoshis = (params.amount_zec * 100_000_000.0).round() as u64;
     if amount_zatoshis == 0 {
         return Err(NozyError::InvalidInput(
@@ -115,6 +286,20 @@ pub fn create_invoice(params: CreateInvoiceParams) -> NozyResult<MerchantInvoice
         ));
     }
 
+    let defaults = crate::config::load_config().merchant_invoices;
+    let tolerance_zatoshis = params
+        .tolerance_zatoshis
+        .unwrap_or(defaults.tolerance_zatoshis);
+    let confirmations = params
+        .confirmations
+        .unwrap_or(defaults.confirmations)
+        .max(1);
+    if tolerance_zatoshis >= amount_zatoshis {
+        return Err(NozyError::InvalidInput(
+            "tolerance must be smaller than the invoice amount".into(),
+        ));
+    }
+
     let mut store = InvoiceStore::load()?;
     let diversifier_index = params.diversifier_index;
     if diversifier_index >= store.next_diversifier {
@@ -126,11 +311,19 @@ pub fn create_invoice(params: CreateInvoiceParams) -> NozyResult<MerchantInvoice
     let expires = now + Duration::minutes(ttl);
     let invoice_id = format!("inv_{}", now.timestamp_millis());
     let memo = params.memo.or_else(|| Some(invoice_id.clone()));
+    let expires_at = expires.to_rfc3339();
     let zcash_uri = build_payment_uri(
         &params.payment_address,
         Some(params.amount_zec),
         memo.as_deref(),
     )?;
+    let (zcash_uri, signature) = match &params.signer {
+        Some(signer) => {
+            let (uri, signature) = signer.sign_uri(&zcash_uri, Some(&expires_at))?;
+            (uri, Some(signature))
+        }
+        None => (zcash_uri, None),
+    };
 
     let invoice = MerchantInvoice {
         invoice_id,
@@ -140,30 +333,39 @@ pub fn create_invoice(params: CreateInvoiceParams) -> NozyResult<MerchantInvoice
         amount_zec: params.amount_zec,
         amount_fiat: params.amount_fiat,
         fiat_currency: params.fiat_currency,
+        locked_rate: params.locked_rate,
         product_name: params.product_name,
         memo,
         diversifier_index,
         created_at: now.to_rfc3339(),
-        expires_at: expires.to_rfc3339(),
+        expires_at,
         zcash_uri,
+        signature,
         detected_txid: None,
         confirmed_txid: None,
+        webhook_url: params.webhook_url,
+        tolerance_zatoshis,
+        confirmations,
+        payments: Vec::new(),
+        paid_zatoshis: 0,
+        outstanding_zatoshis: amount_zatoshis,
     };
 
     store.invoices.push(invoice.clone());
     store.save()?;
+    notify_webhook(&invoice, None);
     Ok(invoice)
 }
 
 pub fn get_invoice(id: &str) -> NozyResult<Option<MerchantInvoice>> {
     let mut store = InvoiceStore::load()?;
-    expire_open(&mut store)?;
+    expire_overdue(&mut store)?;
     Ok(store.invoices.iter().find(|i| i.invoice_id == id).cloned())
 }
 
 pub fn list_invoices(limit: usize) -> NozyResult<Vec<MerchantInvoice>> {
     let mut store = InvoiceStore::load()?;
-    expire_open(&mut store)?;
+    expire_overdue(&mut store)?;
     let mut items = store.invoices;
     items.reverse();
     items.truncate(limit.max(1));
@@ -177,67 +379,298 @@ pub fn cancel_invoice(id: &str) -> NozyResult<MerchantInvoice> {
         .iter_mut()
         .find(|i| i.invoice_id == id)
         .ok_or_else(|| NozyError::InvalidOperation(format!("Invoice {id} not found")))?;
-    if inv.status != InvoiceStatus::Open && inv.status != InvoiceStatus::Detected {
+    if !matches!(
+        inv.status,
+        InvoiceStatus::Open | InvoiceStatus::PartiallyPaid | InvoiceStatus::Detected
+    ) {
         return Err(NozyError::InvalidOperation(format!(
             "Cannot cancel invoice in status {:?}",
             inv.status
         )));
     }
-    inv.status = InvoiceStatus::Cancelled;
+    let previous = std::mem::replace(&mut inv.status, InvoiceStatus::Cancelled);
     let out = inv.clone();
     store.save()?;
+    notify_webhook(&out, Some(&previous));
     Ok(out)
 }
 
-/// Mark open invoices past `expires_at` as Expired.
-fn expire_open(store: &mut InvoiceStore) -> NozyResult<()> {
+/// Queue a signed webhook for a status transition; failures only warn.
+fn notify_webhook(invoice: &MerchantInvoice, previous: Option<&InvoiceStatus>) {
+    if let Err(e) = crate::merchant_webhooks::enqueue_invoice_webhook(invoice, previous) {
+        eprintln!(
+            "Warning: webhook not queued for invoice {}: {e}",
+            invoice.invoice_id
+        );
+    }
+}
+
+/// Mark open and partially paid invoices past `expires_at` as Expired.
+fn expire_overdue(store: &mut InvoiceStore) -> NozyResult<()> {
     let now = Utc::now();
-    let mut dirty = false;
+    let mut expired = Vec::new();
     for inv in &mut store.invoices {
-        if inv.status == InvoiceStatus::Open {
-            if let Ok(exp) = chrono::DateTime::parse_from_rfc3339(&inv.expires_at) {
-                if exp.with_timezone(&Utc) <= now {
-                    inv.status = InvoiceStatus::Expired;
-                    dirty = true;
-                }
-            }
+        if inv.awaits_payment() && inv.is_past_deadline(now) {
+            let previous = std::mem::replace(&mut inv.status, InvoiceStatus::Expired);
+            expired.push((inv.clone(), previous));
         }
     }
-    if dirty {
+    if !expired.is_empty() {
         store.save()?;
+        for (inv, previous) in &expired {
+            notify_webhook(inv, Some(previous));
+        }
     }
     Ok(())
 }
 
-/// Mark matching open invoice as detected/confirmed when a note arrives.
-pub fn match_incoming_payment(
-    payment_address: &str,
+/// Payments are keyed by txid; seeing the same txid again only updates where it was mined.
+fn record_payment(
+    inv: &mut MerchantInvoice,
     amount_zatoshis: u64,
     txid: &str,
-    confirmed: bool,
-) -> NozyResult<Option<MerchantInvoice>> {
-    let mut store = InvoiceStore::load()?;
-    expire_open(&mut store)?;
-    let addr = payment_address.replace([' ', '\n', '\t'], "");
-    let inv = store.invoices.iter_mut().find(|i| {
-        (i.status == InvoiceStatus::Open || i.status == InvoiceStatus::Detected)
-            && i.payment_address.replace([' ', '\n', '\t'], "") == addr
-            && i.amount_zatoshis == amount_zatoshis
-    });
-    let Some(inv) = inv else {
-        return Ok(None);
-    };
-    if confirmed {
-        inv.status = InvoiceStatus::Confirmed;
-        inv.confirmed_txid = Some(txid.to_string());
-        inv.detected_txid = inv.detected_txid.clone().or(Some(txid.to_string()));
+    block_height: Option<u32>,
+    block_hash: Option<String>,
+    expiry_height: Option<u32>,
+) {
+    match inv.payments.iter_mut().find(|p| p.txid == txid) {
+        Some(existing) => {
+            if block_height.is_some() {
+                existing.block_height = block_height;
+                existing.block_hash = block_hash;
+            }
+            existing.expiry_height = existing.expiry_height.or(expiry_height);
+        }
+        None => inv.payments.push(InvoicePayment {
+            txid: txid.to_string(),
+            amount_zatoshis,
+            confirmed: false,
+            detected_at: Utc::now().to_rfc3339(),
+            block_height,
+            block_hash,
+            expiry_height,
+            dropped: false,
+        }),
+    }
+}
+
+/// Recompute confirmations at `chain_tip`, totals, status and the txid shortcuts.
+///
+/// An unmined payment whose transaction expired before `chain_tip` is dropped.
+fn settle(inv: &mut MerchantInvoice, chain_tip: u32) {
+    let depth = inv.confirmations.max(1);
+    for p in &mut inv.payments {
+        p.confirmed = p
+            .block_height
+            .is_some_and(|h| h <= chain_tip && chain_tip - h + 1 >= depth);
+        p.dropped = p.block_height.is_none()
+            && p.expiry_height
+                .is_some_and(|exp| pilot_transaction_expired(chain_tip, exp));
+    }
+    inv.refresh_totals();
+    if inv.payments.is_empty() {
+        return;
+    }
+    inv.status = inv.payment_status();
+    // A payment short of the amount does not keep an invoice alive past its deadline;
+    // one that completes it still settles it late.
+    if inv.awaits_payment() && inv.is_past_deadline(Utc::now()) {
+        inv.status = InvoiceStatus::Expired;
+    }
+    inv.detected_txid = inv.live_payments().next().map(|p| p.txid.clone());
+    inv.confirmed_txid = if matches!(
+        inv.status,
+        InvoiceStatus::Confirmed | InvoiceStatus::Overpaid
+    ) {
+        inv.live_payments().last().map(|p| p.txid.clone())
     } else {
-        inv.status = InvoiceStatus::Detected;
-        inv.detected_txid = Some(txid.to_string());
+        None
+    };
+}
+
+/// Some invoice can still be paid, so sync should watch the Business account key.
+pub fn has_active_invoices() -> NozyResult<bool> {
+    Ok(InvoiceStore::load()?
+        .invoices
+        .iter()
+        .any(|i| i.awaits_payment()))
+}
+
+/// Some uncancelled invoice has a payment that is not confirmed yet.
+pub fn has_unconfirmed_invoice_payments() -> NozyResult<bool> {
+    Ok(InvoiceStore::load()?
+        .invoices
+        .iter()
+        .any(|i| i.status != InvoiceStatus::Cancelled && i.live_payments().any(|p| !p.confirmed)))
+}
+
+/// Orchard receiver of an invoice UA, for matching scanned notes.
+fn invoice_orchard_receiver(payment_address: &str) -> Option<[u8; 43]> {
+    use zcash_address::unified::{Address, Container, Encoding, Receiver};
+    let (_, ua) = Address::decode(payment_address.trim()).ok()?;
+    ua.items().into_iter().find_map(|item| match item {
+        Receiver::Orchard(data) => Some(data),
+        _ => None,
+    })
+}
+
+/// Sync hook: record `watched` notes (mined or mempool) on their invoices, re-check
+/// recent paying blocks (a payment whose block left the active chain goes back to
+/// unmined) and settle confirmations at `chain_tip`. Returns the invoices whose status
+/// changed.
+pub async fn sync_invoice_payments(
+    zebra_client: &ZebraClient,
+    watched: &[WatchedPayment],
+    chain_tip: u32,
+) -> NozyResult<Vec<MerchantInvoice>> {
+    let mut store = InvoiceStore::load()?;
+    expire_overdue(&mut store)?;
+    let before: Vec<InvoiceStatus> = store.invoices.iter().map(|i| i.status.clone()).collect();
+    let mut hashes: HashMap<u32, Option<String>> = HashMap::new();
+
+    for inv in store
+        .invoices
+        .iter_mut()
+        .filter(|i| i.status != InvoiceStatus::Cancelled)
+    {
+        for p in inv.payments.iter_mut().filter(|p| !p.dropped) {
+            match p.block_height {
+                Some(h) if chain_tip.saturating_sub(h) < REORG_CHECK_DEPTH => {
+                    let current = block_hash_at(zebra_client, &mut hashes, h).await;
+                    match (p.block_hash.clone(), current) {
+                        (_, None) => {}
+                        (None, Some(hash)) => p.block_hash = Some(hash),
+                        (Some(stored), Some(hash)) if stored == hash => {}
+                        (Some(_), Some(_)) => relocate_payment(zebra_client, p).await,
+                    }
+                }
+                Some(_) => {}
+                None => relocate_payment(zebra_client, p).await,
+            }
+        }
     }
-    let out = inv.clone();
+
+    // Several outputs of one transaction to the same invoice count as one payment.
+    let mut by_tx: HashMap<(usize, &str), (u64, Option<u32>)> = HashMap::new();
+    for w in watched {
+        let Some(idx) = store.invoices.iter().position(|i| {
+            i.status != InvoiceStatus::Cancelled
+                && invoice_orchard_receiver(&i.payment_address) == Some(w.orchard_receiver)
+        }) else {
+            continue;
+        };
+        let entry = by_tx
+            .entry((idx, w.txid.as_str()))
+            .or_insert((0, w.block_height));
+        entry.0 = entry.0.saturating_add(w.value_zatoshis);
+    }
+    for ((idx, txid), (amount, height)) in by_tx {
+        let (hash, expiry) = match height {
+            Some(h) => (block_hash_at(zebra_client, &mut hashes, h).await, None),
+            // Learn the expiry now: an evicted transaction can no longer be looked up.
+            None => (
+                None,
+                zebra_client
+                    .get_transaction_info(txid)
+                    .await
+                    .ok()
+                    .and_then(|info| info.expiry_height),
+            ),
+        };
+        record_payment(&mut store.invoices[idx], amount, txid, height, hash, expiry);
+    }
+
+    for inv in store.invoices.iter_mut().filter(|i| !i.payments.is_empty()) {
+        settle(inv, chain_tip);
+    }
+    let changed: Vec<(MerchantInvoice, InvoiceStatus)> = store
+        .invoices
+        .iter()
+        .zip(before)
+        .filter(|(inv, was)| inv.status != *was)
+        .map(|(inv, was)| (inv.clone(), was))
+        .collect();
     store.save()?;
-    Ok(Some(out))
+    for (inv, was) in &changed {
+        notify_webhook(inv, Some(was));
+    }
+    Ok(changed.into_iter().map(|(inv, _)| inv).collect())
+}
+
+async fn block_hash_at(
+    zebra_client: &ZebraClient,
+    cache: &mut HashMap<u32, Option<String>>,
+    height: u32,
+) -> Option<String> {
+    if let Some(hash) = cache.get(&height) {
+        return hash.clone();
+    }
+    let hash = zebra_client.get_block_hash(height).await.ok();
+    cache.insert(height, hash.clone());
+    hash
+}
+
+/// Look the payment up again; unmined (or unknown) payments lose their block, and an
+/// unmined one records its expiry height.
+async fn relocate_payment(zebra_client: &ZebraClient, payment: &mut InvoicePayment) {
+    match zebra_client.get_transaction_info(&payment.txid).await {
+        Ok(info) if info.block_height.is_some() => {
+            payment.block_height = info.block_height;
+            payment.block_hash = info.block_hash;
+        }
+        Ok(info) => {
+            payment.block_height = None;
+            payment.block_hash = None;
+            payment.expiry_height = payment.expiry_height.or(info.expiry_height);
+        }
+        Err(e) => {
+            // A stored hash means the paying block is gone; an RPC error leaves it unmined.
+            if payment.block_hash.is_some() {
+                tracing::warn!(txid = %payment.txid, error = %e, "invoice payment not found after reorg");
+                payment.block_height = None;
+                payment.block_hash = None;
+            }
+        }
+    }
+}
+
+/// How to return the excess of an overpaid invoice.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RefundGuidance {
+    pub invoice_id: String,
+    pub overpaid_zatoshis: u64,
+    pub estimated_fee_zatoshis: u64,
+    /// Excess minus the estimated fee of the refund send.
+    pub refund_zatoshis: u64,
+    pub instructions: String,
+}
+
+/// Refund guidance for an overpaid invoice; `None` for any other status.
+pub fn refund_guidance(invoice: &MerchantInvoice) -> Option<RefundGuidance> {
+    let overpaid = invoice.overpaid_zatoshis();
+    if overpaid == 0 {
+        return None;
+    }
+    let fee = estimate_orchard_send_fee_zatoshis(None, true);
+    let refund = overpaid.saturating_sub(fee);
+    let instructions = if refund == 0 {
+        format!(
+            "Overpayment of {overpaid} zats is below the {fee} zat network fee; a refund would cost more than it returns."
+        )
+    } else {
+        format!(
+            "Shielded payments do not reveal the sender. Ask the customer for a refund address, then send {:.8} ZEC from the Business account (the {overpaid} zat excess minus the {fee} zat fee), quoting invoice {}.",
+            refund as f64 / 100_000_000.0,
+            invoice.invoice_id
+        )
+    };
+    Some(RefundGuidance {
+        invoice_id: invoice.invoice_id.clone(),
+        overpaid_zatoshis: overpaid,
+        estimated_fee_zatoshis: fee,
+        refund_zatoshis: refund,
+        instructions,
+    })
 }
 
 #[cfg(test)]
@@ -264,6 +697,11 @@ mod tests {
                 ttl_minutes: Some(30),
                 payment_address: "u1business".into(),
                 diversifier_index: 1,
+                webhook_url: None,
+                tolerance_zatoshis: Some(0),
+                confirmations: None,
+                locked_rate: None,
+                signer: None,
             })
             .unwrap();
             assert_eq!(inv.status, InvoiceStatus::Open);
@@ -276,4 +714,155 @@ mod tests {
         });
         let _ = fs::remove_dir_all(&dir);
     }
+
+    fn invoice(amount: u64, tolerance: u64) -> MerchantInvoice {
+        MerchantInvoice {
+            invoice_id: "inv_1".into(),
+            status: InvoiceStatus::Open,
+            payment_address: "u1business".into(),
+            amount_zatoshis: amount,
+            amount_zec: amount as f64 / 100_000_000.0,
+            amount_fiat: None,
+            fiat_currency: None,
+            locked_rate: None,
+            product_name: None,
+            memo: None,
+            diversifier_index: 1,
+            created_at: String::new(),
+            expires_at: String::new(),
+            zcash_uri: String::new(),
+            signature: None,
+            detected_txid: None,
+            confirmed_txid: None,
+            webhook_url: None,
+            tolerance_zatoshis: tolerance,
+            confirmations: 3,
+            payments: Vec::new(),
+            paid_zatoshis: 0,
+            outstanding_zatoshis: amount,
+        }
+    }
+
+    fn pay(inv: &mut MerchantInvoice, amount: u64, txid: &str, height: Option<u32>, tip: u32) {
+        record_payment(
+            inv,
+            amount,
+            txid,
+            height,
+            height.map(|h| format!("hash{h}")),
+            None,
+        );
+        settle(inv, tip);
+    }
+
+    #[test]
+    fn payments_accumulate_within_tolerance() {
+        let mut inv = invoice(1_000_000, 100);
+        pay(&mut inv, 600_000, "tx1", None, 100);
+        assert_eq!(inv.status, InvoiceStatus::PartiallyPaid);
+        assert_eq!(inv.outstanding_zatoshis, 400_000);
+
+        // One zat short is within tolerance; a repeated txid only updates its block.
+        pay(&mut inv, 399_999, "tx2", None, 100);
+        assert_eq!(inv.status, InvoiceStatus::Detected);
+        pay(&mut inv, 600_000, "tx1", Some(101), 103);
+        pay(&mut inv, 399_999, "tx2", Some(101), 103);
+        assert_eq!(inv.status, InvoiceStatus::Confirmed);
+        assert_eq!(inv.paid_zatoshis, 999_999);
+        assert!(refund_guidance(&inv).is_none());
+    }
+
+    #[test]
+    fn confirmation_depth_and_reorg_downgrade() {
+        let mut inv = invoice(1_000_000, 0);
+        pay(&mut inv, 1_000_000, "tx1", Some(200), 201);
+        assert_eq!(inv.status, InvoiceStatus::Detected);
+        settle(&mut inv, 202);
+        assert_eq!(inv.status, InvoiceStatus::Confirmed);
+        assert_eq!(inv.confirmed_txid.as_deref(), Some("tx1"));
+
+        // Paying block reorged out: back to unmined until it is mined again.
+        inv.payments[0].block_height = None;
+        inv.payments[0].block_hash = None;
+        settle(&mut inv, 202);
+        assert_eq!(inv.status, InvoiceStatus::Detected);
+        assert!(inv.confirmed_txid.is_none());
+    }
+
+    #[test]
+    fn partial_payment_expires_at_the_deadline() {
+        let mut inv = invoice(1_000_000, 0);
+        inv.expires_at = (Utc::now() - Duration::minutes(1)).to_rfc3339();
+        pay(&mut inv, 400_000, "tx1", Some(10), 20);
+        assert_eq!(inv.status, InvoiceStatus::Expired);
+        assert!(!inv.awaits_payment());
+
+        // The rest arriving late still settles it.
+        pay(&mut inv, 600_000, "tx2", Some(11), 20);
+        assert_eq!(inv.status, InvoiceStatus::Confirmed);
+
+        let mut store = InvoiceStore {
+            invoices: vec![invoice(1_000_000, 0)],
+            ..Default::default()
+        };
+        store.invoices[0].status = InvoiceStatus::PartiallyPaid;
+        store.invoices[0].expires_at = inv.expires_at.clone();
+        let dir = std::env::temp_dir().join(format!("nozy_inv_expire_test_{}", std::process::id()));
+        with_wallet_data_dir(&dir, || expire_overdue(&mut store).unwrap());
+        assert_eq!(store.invoices[0].status, InvoiceStatus::Expired);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn overpayment_gets_refund_guidance() {
+        let mut inv = invoice(1_000_000, 0);
+        pay(&mut inv, 1_500_000, "tx1", Some(10), 20);
+        assert_eq!(inv.status, InvoiceStatus::Overpaid);
+        assert_eq!(inv.confirmed_txid.as_deref(), Some("tx1"));
+        let refund = refund_guidance(&inv).unwrap();
+        assert_eq!(refund.overpaid_zatoshis, 500_000);
+        assert_eq!(
+            refund.refund_zatoshis,
+            500_000 - refund.estimated_fee_zatoshis
+        );
+    }
+
+    #[test]
+    fn overpayment_is_detected_until_confirmed() {
+        let mut inv = invoice(1_000_000, 0);
+        pay(&mut inv, 1_500_000, "tx1", None, 100);
+        assert_eq!(inv.status, InvoiceStatus::Detected);
+        assert!(refund_guidance(&inv).is_none());
+
+        pay(&mut inv, 1_500_000, "tx1", Some(101), 102);
+        assert_eq!(inv.status, InvoiceStatus::Detected);
+        settle(&mut inv, 103);
+        assert_eq!(inv.status, InvoiceStatus::Overpaid);
+        assert_eq!(inv.overpaid_zatoshis(), 500_000);
+        assert_eq!(inv.confirmed_txid.as_deref(), Some("tx1"));
+    }
+
+    #[test]
+    fn payment_expiring_unmined_is_dropped() {
+        let mut inv = invoice(1_000_000, 0);
+        record_payment(&mut inv, 1_500_000, "tx1", None, None, Some(110));
+        settle(&mut inv, 100);
+        assert_eq!(inv.status, InvoiceStatus::Detected);
+
+        // Still minable at its expiry height.
+        settle(&mut inv, 110);
+        assert!(!inv.payments[0].dropped);
+        settle(&mut inv, 111);
+        assert!(inv.payments[0].dropped);
+        assert_eq!(inv.paid_zatoshis, 0);
+        assert_eq!(inv.status, InvoiceStatus::Open);
+        assert!(inv.detected_txid.is_none());
+
+        // A payment mined before its expiry stays.
+        pay(&mut inv, 1_000_000, "tx2", Some(105), 111);
+        inv.payments[1].expiry_height = Some(106);
+        settle(&mut inv, 111);
+        assert_eq!(inv.status, InvoiceStatus::Confirmed);
+        assert_eq!(inv.paid_zatoshis, 1_000_000);
+    }
 }
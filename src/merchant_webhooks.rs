@@ -0,0 +1,592 @@
+//! Signed merchant webhooks for invoice status transitions.
+//!
+//! Every transition recorded by [`crate::merchant_invoices`] queues one delivery per
+//! endpoint (the invoice's own `webhook_url`, else `merchant_webhooks.url` in config).
+//! Bodies are JSON, signed with HMAC-SHA256 over the exact body bytes and sent as
+//! `X-Nozy-Signature: sha256=<hex>`. Failed deliveries back off exponentially; the log
+//! lives in `merchant_webhook_deliveries.json` beside the invoice store.
+//!
+//! Deliveries go through the configured Tor / I2P proxy (loopback endpoints excepted)
+//! unless [`WebhookConfig::allow_direct_http`] is set, like HTTP price lookups.
+
+use crate::config::{load_config, WalletConfig};
+use crate::error::{NozyError, NozyResult};
+use crate::merchant_invoices::{InvoiceStatus, MerchantInvoice};
+use crate::paths::get_wallet_data_dir;
+use crate::zebra_integration::ZebraClient;
+use chrono::{DateTime, Duration, Utc};
+use hmac::{Hmac, Mac};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::fs;
+use std::path::PathBuf;
+
+pub const SIGNATURE_HEADER: &str = "X-Nozy-Signature";
+pub const EVENT_HEADER: &str = "X-Nozy-Event";
+pub const DELIVERY_HEADER: &str = "X-Nozy-Delivery";
+
+const DELIVERY_LOG_FILE: &str = "merchant_webhook_deliveries.json";
+const DEFAULT_MAX_ATTEMPTS: u32 = 8;
+const RETRY_BASE_SECS: i64 = 30;
+const RETRY_MAX_SECS: i64 = 6 * 60 * 60;
+const REQUEST_TIMEOUT_SECS: u64 = 10;
+/// Finished deliveries beyond this many are dropped, oldest first.
+const MAX_FINISHED_DELIVERIES: usize = 1000;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// `merchant_webhooks` section of the wallet config.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WebhookConfig {
+    /// Global endpoint for invoices without their own `webhook_url`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Shared HMAC secret; deliveries are not queued until one is set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_attempts: Option<u32>,
+    /// Deliver without Tor / I2P. Default off.
+    #[serde(default)]
+    pub allow_direct_http: bool,
+}
+
+impl WebhookConfig {
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts.unwrap_or(DEFAULT_MAX_ATTEMPTS).max(1)
+    }
+}
+
+/// HTTP client for deliveries, routed through the configured privacy proxy.
+pub fn webhook_http_client(config: &WalletConfig) -> NozyResult<reqwest::Client> {
+    let mut builder =
+        reqwest::Client::builder().timeout(std::time::Duration::from_secs(REQUEST_TIMEOUT_SECS));
+    match ZebraClient::selected_proxy_from_config(config) {
+        Some(proxy_url) => {
+            let proxy = reqwest::Proxy::all(&proxy_url)
+                .map_err(|e| {
+                    NozyError::NetworkError(format!("Invalid privacy proxy URL '{proxy_url}': {e}"))
+                })?
+                .no_proxy(reqwest::NoProxy::from_string("localhost,127.0.0.1,::1"));
+            builder = builder.proxy(proxy);
+        }
+        None if config.merchant_webhooks.allow_direct_http => {}
+        None => {
+            return Err(NozyError::NetworkError(
+                "Webhook delivery requires Tor or I2P (privacy_network); set \
+                 merchant_webhooks.allow_direct_http = true to allow clearnet"
+                    .to_string(),
+            ))
+        }
+    }
+    builder
+        .build()
+        .map_err(|e| NozyError::NetworkError(format!("Failed to build webhook client: {e}")))
+}
+
+/// Random 32-byte hex secret for `nozy webhooks config --generate-secret`.
+pub fn generate_webhook_secret() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+pub fn sign_webhook_body(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Constant-time check of a `sha256=<hex>` signature header.
+pub fn verify_webhook_signature(secret: &str, body: &[u8], header: &str) -> bool {
+    let Some(sig) = header
+        .trim()
+        .strip_prefix("sha256=")
+        .and_then(|h| hex::decode(h).ok())
+    else {
+        return false;
+    };
+    let mut mac =
+        HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(body);
+    mac.verify_slice(&sig).is_ok()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeliveryStatus {
+    Pending,
+    Delivered,
+    /// Gave up after `max_attempts`; `nozy webhooks retry` re-queues it.
+    Failed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WebhookDelivery {
+    pub id: String,
+    pub invoice_id: String,
+    /// `invoice.open`, `invoice.detected`, `invoice.confirmed`, ...
+    pub event: String,
+    pub url: String,
+    /// Exact signed body, resent unchanged on retries.
+    pub body: String,
+    pub created_at: DateTime<Utc>,
+    pub status: DeliveryStatus,
+    pub attempts: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_attempt_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_attempt_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_response_status: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+}
+
+impl WebhookDelivery {
+    fn is_due(&self, now: DateTime<Utc>) -> bool {
+        self.status == DeliveryStatus::Pending && self.next_attempt_at.is_none_or(|at| at <= now)
+    }
+
+    /// Record one attempt: `Ok(status)` for an HTTP response, `Err` for a transport error.
+    fn record_attempt(
+        &mut self,
+        result: Result<u16, String>,
+        now: DateTime<Utc>,
+        max_attempts: u32,
+    ) {
+        self.attempts += 1;
+        self.last_attempt_at = Some(now);
+        match result {
+            Ok(code) => {
+                self.last_response_status = Some(code);
+                self.last_error = None;
+                if (200..300).contains(&code) {
+                    self.status = DeliveryStatus::Delivered;
+                    self.next_attempt_at = None;
+                    return;
+                }
+                self.last_error = Some(format!("HTTP {code}"));
+            }
+            Err(e) => self.last_error = Some(e),
+        }
+        if self.attempts >= max_attempts {
+            self.status = DeliveryStatus::Failed;
+            self.next_attempt_at = None;
+        } else {
+            self.next_attempt_at = Some(now + retry_delay(self.attempts));
+        }
+    }
+}
+
+/// 30 s, 60 s, 120 s, ... capped at 6 h.
+pub fn retry_delay(attempts: u32) -> Duration {
+    let secs = RETRY_BASE_SECS.saturating_mul(1i64 << attempts.saturating_sub(1).min(20));
+    Duration::seconds(secs.min(RETRY_MAX_SECS))
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct DeliveryLog {
+    deliveries: Vec<WebhookDelivery>,
+}
+
+impl DeliveryLog {
+    fn path() -> PathBuf {
+        get_wallet_data_dir().join(DELIVERY_LOG_FILE)
+    }
+
+    fn load() -> NozyResult<Self> {
+        let path = Self::path();
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let raw = fs::read_to_string(&path)
+            .map_err(|e| NozyError::Storage(format!("Failed to read webhook log: {e}")))?;
+        serde_json::from_str(&raw)
+            .map_err(|e| NozyError::Storage(format!("Corrupt webhook log: {e}")))
+    }
+
+    fn save(&mut self) -> NozyResult<()> {
+        let finished = self
+            .deliveries
+            .iter()
+            .filter(|d| d.status != DeliveryStatus::Pending)
+            .count();
+        if finished > MAX_FINISHED_DELIVERIES {
+            let mut drop = finished - MAX_FINISHED_DELIVERIES;
+            self.deliveries.retain(|d| {
+                if drop > 0 && d.status != DeliveryStatus::Pending {
+                    drop -= 1;
+                    false
+                } else {
+                    true
+                }
+            });
+        }
+        let path = Self::path();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| NozyError::Storage(format!("Failed to create webhook dir: {e}")))?;
+        }
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| NozyError::Storage(format!("Failed to serialize webhook log: {e}")))?;
+        let temp_path = path.with_extension("tmp");
+        fs::write(&temp_path, json)
+            .map_err(|e| NozyError::Storage(format!("Failed to write webhook log: {e}")))?;
+        fs::rename(&temp_path, &path)
+            .map_err(|e| NozyError::Storage(format!("Failed to save webhook log: {e}")))
+    }
+}
+
+fn new_delivery_id() -> String {
+    let mut bytes = [0u8; 8];
+    OsRng.fill_bytes(&mut bytes);
+    format!("whd_{:016x}", u64::from_be_bytes(bytes))
+}
+
+/// Event type for `status`. A confirmed overpayment is `invoice.confirmed` too, with a
+/// non-zero `overpaid_zatoshis` in the body, so shops fulfil on one event.
+pub fn invoice_event_name(status: &InvoiceStatus) -> &'static str {
+    match status {
+        InvoiceStatus::Open => "invoice.open",
+        InvoiceStatus::PartiallyPaid => "invoice.partially_paid",
+        InvoiceStatus::Detected => "invoice.detected",
+        InvoiceStatus::Confirmed | InvoiceStatus::Overpaid => "invoice.confirmed",
+        InvoiceStatus::Expired => "invoice.expired",
+        InvoiceStatus::Cancelled => "invoice.cancelled",
+    }
+}
+
+fn invoice_event_body(
+    delivery_id: &str,
+    invoice: &MerchantInvoice,
+    previous: Option<&InvoiceStatus>,
+    now: DateTime<Utc>,
+) -> NozyResult<String> {
+    serde_json::to_string(&serde_json::json!({
+        "id": delivery_id,
+        "type": invoice_event_name(&invoice.status),
+        "created_at": now.to_rfc3339(),
+        "previous_status": previous,
+        "overpaid_zatoshis": invoice.overpaid_zatoshis(),
+        "invoice": invoice,
+    }))
+    .map_err(|e| NozyError::Storage(format!("Failed to serialize webhook body: {e}")))
+}
+
+/// Queue a delivery for `invoice`'s new status. No-op without an endpoint or secret.
+pub fn enqueue_invoice_webhook(
+    invoice: &MerchantInvoice,
+    previous: Option<&InvoiceStatus>,
+) -> NozyResult<Option<WebhookDelivery>> {
+    let config = load_config().merchant_webhooks;
+    let Some(url) = invoice.webhook_url.clone().or(config.url) else {
+        return Ok(None);
+    };
+    if config.secret.as_deref().unwrap_or("").is_empty() {
+        return Ok(None);
+    }
+    let now = Utc::now();
+    let id = new_delivery_id();
+    let delivery = WebhookDelivery {
+        body: invoice_event_body(&id, invoice, previous, now)?,
+        id,
+        invoice_id: invoice.invoice_id.clone(),
+        event: invoice_event_name(&invoice.status).to_string(),
+        url,
+        created_at: now,
+        status: DeliveryStatus::Pending,
+        attempts: 0,
+        next_attempt_at: None,
+        last_attempt_at: None,
+        last_response_status: None,
+        last_error: None,
+    };
+    let mut log = DeliveryLog::load()?;
+    log.deliveries.push(delivery.clone());
+    log.save()?;
+    Ok(Some(delivery))
+}
+
+/// Newest first, optionally for one invoice.
+pub fn list_webhook_deliveries(
+    invoice_id: Option<&str>,
+    limit: usize,
+) -> NozyResult<Vec<WebhookDelivery>> {
+    Ok(DeliveryLog::load()?
+        .deliveries
+        .into_iter()
+        .rev()
+        .filter(|d| invoice_id.is_none_or(|id| d.invoice_id == id))
+        .take(limit.max(1))
+        .collect())
+}
+
+/// Re-queue a delivery (any status) for immediate sending.
+pub fn retry_webhook_delivery(id: &str) -> NozyResult<WebhookDelivery> {
+    let mut log = DeliveryLog::load()?;
+    let delivery = log
+        .deliveries
+        .iter_mut()
+        .find(|d| d.id == id)
+        .ok_or_else(|| NozyError::InvalidInput(format!("Unknown webhook delivery {id}")))?;
+    delivery.status = DeliveryStatus::Pending;
+    delivery.attempts = 0;
+    delivery.next_attempt_at = None;
+    let out = delivery.clone();
+    log.save()?;
+    Ok(out)
+}
+
+/// Send every due delivery once. Returns the deliveries that were attempted.
+pub async fn deliver_due_webhooks() -> NozyResult<Vec<WebhookDelivery>> {
+    let wallet_config = load_config();
+    let config = wallet_config.merchant_webhooks.clone();
+    let Some(secret) = config.secret.clone().filter(|s| !s.is_empty()) else {
+        return Ok(Vec::new());
+    };
+    let now = Utc::now();
+    let due: Vec<WebhookDelivery> = DeliveryLog::load()?
+        .deliveries
+        .into_iter()
+        .filter(|d| d.is_due(now))
+        .collect();
+    if due.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let client = webhook_http_client(&wallet_config)?;
+    let mut attempted = Vec::with_capacity(due.len());
+    for mut delivery in due {
+        let result = client
+            .post(&delivery.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(
+                SIGNATURE_HEADER,
+                sign_webhook_body(&secret, delivery.body.as_bytes()),
+            )
+            .header(EVENT_HEADER, &delivery.event)
+            .header(DELIVERY_HEADER, &delivery.id)
+            .body(delivery.body.clone())
+            .send()
+            .await
+            .map(|resp| resp.status().as_u16())
+            .map_err(|e| e.to_string());
+        delivery.record_attempt(result, Utc::now(), config.max_attempts());
+        attempted.push(delivery);
+    }
+
+    // Reload so deliveries queued while we were sending are kept.
+    let mut log = DeliveryLog::load()?;
+    for done in &attempted {
+        if let Some(slot) = log.deliveries.iter_mut().find(|d| d.id == done.id) {
+            *slot = done.clone();
+        }
+    }
+    log.save()?;
+    Ok(attempted)
+}
+
+/// One request seen by [`run_webhook_test_receiver`].
+#[derive(Debug, Clone)]
+pub struct ReceivedWebhook {
+    pub event: Option<String>,
+    pub delivery_id: Option<String>,
+    /// `None` when no secret was given to check against.
+    pub signature_valid: Option<bool>,
+    pub body: String,
+}
+
+struct RawRequest {
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl RawRequest {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// Parse a complete HTTP/1.1 request; `None` until headers and body have arrived.
+fn parse_http_request(buf: &[u8]) -> Option<RawRequest> {
+    let split = buf.windows(4).position(|w| w == b"\r\n\r\n")?;
+    let head = std::str::from_utf8(&buf[..split]).ok()?;
+    let headers: Vec<(String, String)> = head
+        .lines()
+        .skip(1)
+        .filter_map(|line| line.split_once(':'))
+        .map(|(k, v)| (k.trim().to_string(), v.trim().to_string()))
+        .collect();
+    let len = headers
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, v)| v.parse::<usize>().ok())
+        .unwrap_or(0);
+    let body = buf.get(split + 4..split + 4 + len)?.to_vec();
+    Some(RawRequest { headers, body })
+}
+
+/// Minimal local endpoint for testing deliveries: answers `200` to every POST and
+/// reports it (with signature check when `secret` is set) to `on_request`.
+pub async fn run_webhook_test_receiver(
+    bind: &str,
+    secret: Option<String>,
+    mut on_request: impl FnMut(ReceivedWebhook),
+) -> NozyResult<()> {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let listener = tokio::net::TcpListener::bind(bind)
+        .await
+        .map_err(|e| NozyError::NetworkError(format!("Failed to bind {bind}: {e}")))?;
+    loop {
+        let (mut socket, _) = listener
+            .accept()
+            .await
+            .map_err(|e| NozyError::NetworkError(format!("Accept failed: {e}")))?;
+        let mut buf = Vec::new();
+        let mut chunk = [0u8; 4096];
+        let request = loop {
+            match socket.read(&mut chunk).await {
+                Ok(0) | Err(_) => break None,
+                Ok(n) => {
+                    buf.extend_from_slice(&chunk[..n]);
+                    if let Some(req) = parse_http_request(&buf) {
+                        break Some(req);
+                    }
+                }
+            }
+        };
+        let Some(request) = request else {
+            continue;
+        };
+        let signature_valid = secret.as_deref().map(|s| {
+            request
+                .header(SIGNATURE_HEADER)
+                .is_some_and(|h| verify_webhook_signature(s, &request.body, h))
+        });
+        let status = if signature_valid == Some(false) {
+            "401 Unauthorized"
+        } else {
+            "200 OK"
+        };
+        let _ = socket
+            .write_all(format!("HTTP/1.1 {status}\r\ncontent-length: 0\r\n\r\n").as_bytes())
+            .await;
+        on_request(ReceivedWebhook {
+            event: request.header(EVENT_HEADER).map(str::to_string),
+            delivery_id: request.header(DELIVERY_HEADER).map(str::to_string),
+            signature_valid,
+            body: String::from_utf8_lossy(&request.body).into_owned(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signature_matches_rfc4231_vector() {
+        let sig = sign_webhook_body("Jefe", b"what do ya want for nothing?");
+        assert_eq!(
+            sig,
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+        assert!(verify_webhook_signature(
+            "Jefe",
+            b"what do ya want for nothing?",
+            &sig
+        ));
+        assert!(!verify_webhook_signature("Jefe", b"tampered", &sig));
+        assert!(!verify_webhook_signature("Jefe", b"x", "md5=00"));
+    }
+
+    #[test]
+    fn client_requires_privacy_proxy_unless_direct_allowed() {
+        let mut config = WalletConfig::default();
+        config.privacy_network.tor_enabled = false;
+        config.privacy_network.i2p_enabled = false;
+        assert!(webhook_http_client(&config).is_err());
+        config.merchant_webhooks.allow_direct_http = true;
+        assert!(webhook_http_client(&config).is_ok());
+    }
+
+    #[test]
+    fn failed_attempts_back_off_then_give_up() {
+        let now = Utc::now();
+        let mut d = WebhookDelivery {
+            id: "whd_1".into(),
+            invoice_id: "inv_1".into(),
+            event: "invoice.confirmed".into(),
+            url: "http://127.0.0.1:1".into(),
+            body: "{}".into(),
+            created_at: now,
+            status: DeliveryStatus::Pending,
+            attempts: 0,
+            next_attempt_at: None,
+            last_attempt_at: None,
+            last_response_status: None,
+            last_error: None,
+        };
+        d.record_attempt(Ok(500), now, 3);
+        assert_eq!(d.next_attempt_at, Some(now + Duration::seconds(30)));
+        d.record_attempt(Err("refused".into()), now, 3);
+        assert_eq!(d.next_attempt_at, Some(now + Duration::seconds(60)));
+        assert!(!d.is_due(now));
+        d.record_attempt(Ok(502), now, 3);
+        assert_eq!(d.status, DeliveryStatus::Failed);
+        assert_eq!(retry_delay(30), Duration::seconds(RETRY_MAX_SECS));
+    }
+
+    #[test]
+    fn parses_request_once_body_is_complete() {
+        let raw =
+            b"POST /hook HTTP/1.1\r\nContent-Length: 2\r\nX-Nozy-Event: invoice.open\r\n\r\n{}";
+        assert!(parse_http_request(&raw[..raw.len() - 1]).is_none());
+        let req = parse_http_request(raw).unwrap();
+        assert_eq!(req.body, b"{}");
+        assert_eq!(req.header("x-nozy-event"), Some("invoice.open"));
+    }
+
+    #[test]
+    fn confirmed_overpayment_is_a_confirmed_event() {
+        let invoice: MerchantInvoice = serde_json::from_value(serde_json::json!({
+            "invoice_id": "inv_1",
+            "status": "overpaid",
+            "payment_address": "u1business",
+            "amount_zatoshis": 1_000_000,
+            "amount_zec": 0.01,
+            "diversifier_index": 1,
+            "created_at": "",
+            "expires_at": "",
+            "zcash_uri": "",
+            "payments": [{
+                "txid": "tx1",
+                "amount_zatoshis": 1_500_000,
+                "confirmed": true,
+                "detected_at": "",
+                "block_height": 10
+            }],
+            "paid_zatoshis": 1_500_000
+        }))
+        .unwrap();
+        let body = invoice_event_body(
+            "whd_1",
+            &invoice,
+            Some(&InvoiceStatus::Detected),
+            Utc::now(),
+        )
+        .unwrap();
+        let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(body["type"], "invoice.confirmed");
+        assert_eq!(body["overpaid_zatoshis"], 500_000);
+        assert_eq!(body["invoice"]["status"], "overpaid");
+    }
+}
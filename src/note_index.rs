@@ -677,6 +677,7 @@ mod tests {
             rseed_bytes: None,
             spent_in_txid: None,
             pool: crate::shielded_pool::ShieldedPool::Orchard,
+            frozen: false,
         }
     }
 
@@ -12,6 +12,7 @@ use crate::note_index::NoteIndex;
 use crate::orchard_tree_codec::orchard_commitment_tree_from_final_state;
 use crate::orchard_tree_codec::OrchardCommitmentTree;
 use crate::orchard_witness::{merkle_hash_from_cmx_bytes, OrchardWitnessTracker};
+use crate::ovk_recovery::{OutgoingRecoveryKeys, RecoveredSentTransaction};
 use crate::scan_log;
 use crate::scan_verbose;
 use crate::shielded_pool::ShieldedPool;
@@ -22,10 +23,11 @@ use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
 use orchard::{
-    keys::{FullViewingKey, IncomingViewingKey, SpendingKey},
+    keys::{FullViewingKey, IncomingViewingKey, SpendAuthorizingKey, SpendingKey},
     note::{Note, Nullifier},
     Address as OrchardAddress,
 };
+use zcash_protocol::consensus::NetworkType;
 use zip32::AccountId;
 
 use zcash_note_encryption::try_compact_note_decryption;
@@ -45,7 +47,10 @@ pub struct OrchardNote {
 #[derive(Debug, Clone)]
 pub struct SpendableNote {
     pub orchard_note: OrchardNote,
-    pub spending_key: SpendingKey,
+    /// `None` for notes found with a viewing key only (watch-only and FROST profiles); those
+    /// can go into a PCZT but must be signed elsewhere.
+    pub spending_key: Option<SpendingKey>,
+    pub fvk: FullViewingKey,
     pub derivation_path: String,
     pub pool: ShieldedPool,
     pub orchard_incremental_witness_hex: Option<String>,
@@ -55,6 +60,20 @@ pub struct SpendableNote {
 }
 
 impl SpendableNote {
+    /// Key authorizing this note's spend; errors for viewing-key-only notes.
+    pub fn spend_authorizing_key(&self) -> NozyResult<SpendAuthorizingKey> {
+        self.spending_key
+            .as_ref()
+            .map(SpendAuthorizingKey::from)
+            .ok_or_else(|| {
+                NozyError::InvalidOperation(
+                    "This profile holds only a viewing key: build a PCZT and sign it with the \
+                     spending wallet"
+                        .to_string(),
+                )
+            })
+    }
+
     pub fn witness_hex_for_pool(&self) -> Option<&String> {
         match self.pool {
             ShieldedPool::Orchard => self.orchard_incremental_witness_hex.as_ref(),
@@ -147,6 +166,9 @@ pub struct SerializableOrchardNote {
     /// Shielded value pool (Orchard legacy vs Ironwood NU6.3+). Defaults to Orchard for v2 notes.
     #[serde(default)]
     pub pool: ShieldedPool,
+    /// Coin control: frozen notes are never selected for spends (see [`crate::coin_control`]).
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub frozen: bool,
 }
 
 impl SerializableOrchardNote {
@@ -231,12 +253,10 @@ pub fn load_spendable_notes_from_wallet(wallet: &HDWallet) -> NozyResult<Vec<Spe
     use crate::key_management::{zeroize_bytes, SecureSeed};
     use zip32::AccountId;
 
-    let cached = load_wallet_notes()?;
-    let unspent: Vec<&SerializableOrchardNote> = cached
+    if !load_wallet_notes()?
         .iter()
-        .filter(|n| !n.spent && n.witness_hex_for_pool().is_some_and(|w| !w.is_empty()))
-        .collect();
-    if unspent.is_empty() {
+        .any(|n| !n.spent && n.witness_hex_for_pool().is_some_and(|w| !w.is_empty()))
+    {
         return Ok(Vec::new());
     }
 
@@ -251,6 +271,30 @@ pub fn load_spendable_notes_from_wallet(wallet: &HDWallet) -> NozyResult<Vec<Spe
         })?;
     zeroize_bytes(&mut seed_bytes);
 
+    let fvk = FullViewingKey::from(&orchard_sk);
+    spendable_notes_from_cache(Some(orchard_sk), fvk)
+}
+
+/// [`load_spendable_notes_from_wallet`] for a watch-only or FROST profile: the notes carry
+/// `fvk` and no spending key.
+#[cfg(feature = "native")]
+pub fn load_spendable_notes_for_viewing_key(
+    fvk: &FullViewingKey,
+) -> NozyResult<Vec<SpendableNote>> {
+    spendable_notes_from_cache(None, fvk.clone())
+}
+
+#[cfg(feature = "native")]
+fn spendable_notes_from_cache(
+    orchard_sk: Option<SpendingKey>,
+    fvk: FullViewingKey,
+) -> NozyResult<Vec<SpendableNote>> {
+    let cached = load_wallet_notes()?;
+    let unspent: Vec<&SerializableOrchardNote> = cached
+        .iter()
+        .filter(|n| !n.spent && n.witness_hex_for_pool().is_some_and(|w| !w.is_empty()))
+        .collect();
+
     let mut spendable = Vec::with_capacity(unspent.len());
     for sn in unspent {
         let orchard_note = sn.to_wallet_orchard_note().ok_or_else(|| {
@@ -262,6 +306,7 @@ pub fn load_spendable_notes_from_wallet(wallet: &HDWallet) -> NozyResult<Vec<Spe
         let mut spendable_note = SpendableNote {
             orchard_note,
             spending_key: orchard_sk.clone(),
+            fvk: fvk.clone(),
             derivation_path: "m/32'/133'/0'".to_string(),
             pool: sn.pool,
             orchard_incremental_witness_hex: None,
@@ -315,6 +360,7 @@ impl From<&OrchardNote> for SerializableOrchardNote {
             rseed_bytes: Some(note.note.rseed().as_bytes().to_vec()),
             spent_in_txid: None,
             pool: ShieldedPool::Orchard,
+            frozen: false,
         }
     }
 }
@@ -343,7 +389,7 @@ pub fn mark_wallet_notes_spent_from_spendables(
     let mut marked = 0usize;
 
     for sn in spent {
-        let fvk = FullViewingKey::from(&sn.spending_key);
+        let fvk = sn.fvk.clone();
         let nf = sn.orchard_note.note.nullifier(&fvk).to_bytes();
 
         if index.mark_note_spent_on_chain(&nf, &fvk) {
@@ -762,22 +808,69 @@ pub struct NoteScanResult {
     pub spendable_count: usize,
 }
 
+/// A note to a watch-only key (e.g. a Business invoice address); never added to the wallet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WatchedPayment {
+    pub txid: String,
+    /// `None` for a mempool transaction.
+    pub block_height: Option<u32>,
+    pub value_zatoshis: u64,
+    pub orchard_receiver: [u8; 43],
+    pub pool: ShieldedPool,
+}
+
+/// Orchard spending key and Sapling viewing key of account 0, derived from the seed.
+fn wallet_scanning_keys(
+    wallet: &HDWallet,
+) -> NozyResult<(
+    SpendingKey,
+    Option<sapling::zip32::DiversifiableFullViewingKey>,
+)> {
+    let account_id = AccountId::try_from(0)
+        .map_err(|e| NozyError::KeyDerivation(format!("Invalid account ID: {:?}", e)))?;
+    let mnemonic = wallet.get_mnemonic_object();
+
+    let mut seed_bytes = mnemonic.to_seed("").to_vec();
+    let secure_seed = SecureSeed::new(seed_bytes.clone());
+
+    let orchard_sk = SpendingKey::from_zip32_seed(secure_seed.as_bytes(), 133, account_id)
+        .map_err(|e| {
+            NozyError::KeyDerivation(format!("Failed to derive Orchard spending key: {:?}", e))
+        })?;
+    let sapling_dfvk = crate::sapling_keys::derive_sapling_extsk(secure_seed.as_bytes(), 0)
+        .ok()
+        .map(|extsk| extsk.to_diversifiable_full_viewing_key());
+    zeroize_bytes(&mut seed_bytes);
+    Ok((orchard_sk, sapling_dfvk))
+}
+
 pub struct NoteScanner<'a> {
-    wallet: &'a HDWallet,
+    /// Source of the scanning keys; `None` scans with [`Self::viewing_key`] alone.
+    wallet: Option<&'a HDWallet>,
+    viewing_key: Option<FullViewingKey>,
     zebra_client: ZebraClient,
     note_index: Option<NoteIndex>,
     block_cache: Option<Arc<SimpleCache<Vec<ParsedTransaction>>>>,
     parallel_blocks: usize,
+    /// Outgoing payments decrypted with our OVKs during the last [`Self::scan_notes`].
+    recovered_sent: Vec<RecoveredSentTransaction>,
+    /// Extra external-scope key whose notes are reported via [`Self::take_watched_payments`].
+    watch_fvk: Option<FullViewingKey>,
+    watched_payments: Vec<WatchedPayment>,
 }
 
 impl<'a> NoteScanner<'a> {
     pub fn new(wallet: &'a HDWallet, zebra_client: ZebraClient) -> Self {
         Self {
-            wallet,
+            wallet: Some(wallet),
+            viewing_key: None,
             zebra_client,
             note_index: None,
             block_cache: None,
             parallel_blocks: 5,
+            recovered_sent: Vec::new(),
+            watch_fvk: None,
+            watched_payments: Vec::new(),
         }
     }
 
@@ -793,11 +886,15 @@ impl<'a> NoteScanner<'a> {
 
     pub fn with_index(wallet: &'a HDWallet, zebra_client: ZebraClient, index: NoteIndex) -> Self {
         Self {
-            wallet,
+            wallet: Some(wallet),
+            viewing_key: None,
             zebra_client,
             note_index: Some(index),
             block_cache: None,
             parallel_blocks: 5,
+            recovered_sent: Vec::new(),
+            watch_fvk: None,
+            watched_payments: Vec::new(),
         }
     }
 
@@ -808,14 +905,39 @@ impl<'a> NoteScanner<'a> {
     ) -> NozyResult<Self> {
         let index = NoteIndex::load_from_file(index_path)?;
         Ok(Self {
-            wallet,
+            wallet: Some(wallet),
+            viewing_key: None,
             zebra_client,
             note_index: Some(index),
             block_cache: None,
             parallel_blocks: 5,
+            recovered_sent: Vec::new(),
+            watch_fvk: None,
+            watched_payments: Vec::new(),
         })
     }
 
+    /// Scan with a full viewing key instead of a seed (watch-only and FROST profiles).
+    ///
+    /// Found notes carry no spending key, and OVK recovery covers Orchard only.
+    pub fn for_viewing_key(
+        fvk: FullViewingKey,
+        zebra_client: ZebraClient,
+        index: Option<NoteIndex>,
+    ) -> Self {
+        Self {
+            wallet: None,
+            viewing_key: Some(fvk),
+            zebra_client,
+            note_index: index,
+            block_cache: None,
+            parallel_blocks: 5,
+            recovered_sent: Vec::new(),
+            watch_fvk: None,
+            watched_payments: Vec::new(),
+        }
+    }
+
     pub fn get_index(&self) -> Option<&NoteIndex> {
         self.note_index.as_ref()
     }
@@ -828,6 +950,54 @@ impl<'a> NoteScanner<'a> {
         self.note_index
     }
 
+    /// Sent transactions recovered via OVK decryption during the last scan.
+    pub fn take_recovered_sent_transactions(&mut self) -> Vec<RecoveredSentTransaction> {
+        std::mem::take(&mut self.recovered_sent)
+    }
+
+    /// Also trial-decrypt with `fvk` (external scope) and report its notes separately.
+    pub fn set_watch_fvk(&mut self, fvk: FullViewingKey) {
+        self.watch_fvk = Some(fvk);
+    }
+
+    /// Notes to the watch key found during the last scan.
+    pub fn take_watched_payments(&mut self) -> Vec<WatchedPayment> {
+        std::mem::take(&mut self.watched_payments)
+    }
+
+    /// Trial-decrypt mempool transactions not in `seen` with the watch key.
+    ///
+    /// Returned payments have no block height. A txid joins `seen` only once it was fetched
+    /// and decrypted, so later polls skip it but retry transactions that failed to load.
+    pub async fn scan_mempool_watched(
+        &self,
+        seen: &mut std::collections::HashSet<String>,
+    ) -> NozyResult<Vec<WatchedPayment>> {
+        let Some(fvk) = self.watch_fvk.as_ref() else {
+            return Ok(Vec::new());
+        };
+        let ivk = fvk.to_ivk(orchard::keys::Scope::External);
+        let mut txs = Vec::new();
+        let mut fetched = Vec::new();
+        for txid in self.zebra_client.get_raw_mempool().await? {
+            if seen.contains(&txid) {
+                continue;
+            }
+            // Transactions can leave the mempool between the two calls.
+            if let Ok(tx) = self.zebra_client.get_raw_transaction_verbose(&txid).await {
+                txs.push(tx);
+                fetched.push(txid);
+            }
+        }
+        if txs.is_empty() {
+            return Ok(Vec::new());
+        }
+        let parsed = Self::parse_block_data(&serde_json::json!({ "tx": txs }), 0)?;
+        let payments = self.decrypt_watched_actions(&parsed, 0, fvk, &ivk);
+        seen.extend(fetched);
+        Ok(payments)
+    }
+
     pub fn save_index(&self, path: &std::path::PathBuf) -> NozyResult<()> {
         if let Some(ref index) = self.note_index {
             index.save_to_file(path)
@@ -887,28 +1057,40 @@ impl<'a> NoteScanner<'a> {
             ProgressBar::hidden()
         };
 
-        let account_id = AccountId::try_from(0)
-            .map_err(|e| NozyError::KeyDerivation(format!("Invalid account ID: {:?}", e)))?;
-        let mnemonic = self.wallet.get_mnemonic_object();
-
-        let mut seed_bytes = mnemonic.to_seed("").to_vec();
-        let secure_seed = SecureSeed::new(seed_bytes.clone());
-
-        let orchard_sk = SpendingKey::from_zip32_seed(secure_seed.as_bytes(), 133, account_id)
-            .map_err(|e| {
-                NozyError::KeyDerivation(format!("Failed to derive Orchard spending key: {:?}", e))
-            })?;
-
-        let orchard_fvk = FullViewingKey::from(&orchard_sk);
+        let (orchard_sk, orchard_fvk, sapling_dfvk) = match (self.wallet, &self.viewing_key) {
+            (Some(wallet), _) => {
+                let (orchard_sk, sapling_dfvk) = wallet_scanning_keys(wallet)?;
+                let orchard_fvk = FullViewingKey::from(&orchard_sk);
+                (Some(orchard_sk), orchard_fvk, sapling_dfvk)
+            }
+            (None, Some(fvk)) => (None, fvk.clone(), None),
+            (None, None) => {
+                return Err(NozyError::KeyDerivation(
+                    "Note scanner has neither a wallet nor a viewing key".to_string(),
+                ))
+            }
+        };
         let orchard_ivk_external = orchard_fvk.to_ivk(orchard::keys::Scope::External);
         let orchard_ivk_internal = orchard_fvk.to_ivk(orchard::keys::Scope::Internal);
-
-        zeroize_bytes(&mut seed_bytes);
+        let network = if crate::config::load_config().network == "testnet" {
+            NetworkType::Test
+        } else {
+            NetworkType::Main
+        };
+        let recovery_keys = OutgoingRecoveryKeys::new(network, &orchard_fvk, sapling_dfvk);
+        let mut recovered_sent = Vec::new();
+        let watch_ivk = self
+            .watch_fvk
+            .as_ref()
+            .map(|fvk| fvk.to_ivk(orchard::keys::Scope::External));
+        let mut watched_payments = Vec::new();
 
         if scan_log::scan_progress_enabled() {
-            pb.println(
-                "Generated scanning keys from wallet mnemonic (Orchard only; External and Internal scopes)",
-            );
+            pb.println(if orchard_sk.is_some() {
+                "Generated scanning keys from wallet mnemonic (Orchard only; External and Internal scopes)"
+            } else {
+                "Scanning with a full viewing key (Orchard only; External and Internal scopes)"
+            });
         }
 
         let mut note_index = self.note_index.take().unwrap_or_else(NoteIndex::new);
@@ -972,6 +1154,7 @@ impl<'a> NoteScanner<'a> {
             None
         };
 
+        let mut reported_percent = None;
         while current_height <= end_height {
             let batch_end = (current_height + batch_size as u32 - 1).min(end_height);
 
@@ -1045,7 +1228,7 @@ impl<'a> NoteScanner<'a> {
                             &orchard_fvk,
                             &orchard_ivk_external,
                             &orchard_ivk_internal,
-                            &orchard_sk,
+                            orchard_sk.as_ref(),
                             witness_tracker.as_mut(),
                             &mut note_index,
                             &mut all_notes,
@@ -1063,7 +1246,7 @@ impl<'a> NoteScanner<'a> {
                             &orchard_fvk,
                             &orchard_ivk_external,
                             &orchard_ivk_internal,
-                            &orchard_sk,
+                            orchard_sk.as_ref(),
                             ironwood_witness_tracker.as_mut(),
                             &mut note_index,
                             &mut all_notes,
@@ -1075,6 +1258,19 @@ impl<'a> NoteScanner<'a> {
                                 detail: e.to_string(),
                             });
                         }
+                        recovered_sent.extend(
+                            transactions.iter().filter_map(|tx| {
+                                recovery_keys.recover_transaction(tx, &note_index)
+                            }),
+                        );
+                        if let (Some(fvk), Some(ivk)) = (self.watch_fvk.as_ref(), &watch_ivk) {
+                            watched_payments.extend(self.decrypt_watched_actions(
+                                &transactions,
+                                height,
+                                fvk,
+                                ivk,
+                            ));
+                        }
                     }
                     Err(e) => {
                         return Err(NozyError::ScanAtBlock {
@@ -1087,6 +1283,18 @@ impl<'a> NoteScanner<'a> {
                 pb.inc(1);
             }
 
+            let scanned = (batch_end - start_height + 1) as u64;
+            let percent = (scanned * 100 / total_blocks) as u8;
+            if reported_percent != Some(percent) {
+                reported_percent = Some(percent);
+                crate::daemon::publish_wallet_event(crate::daemon::WalletEvent::ScanProgress {
+                    height: batch_end,
+                    start_height,
+                    end_height,
+                    percent,
+                });
+            }
+
             current_height = batch_end + 1;
         }
 
@@ -1136,6 +1344,14 @@ impl<'a> NoteScanner<'a> {
         let serializable_notes: Vec<SerializableOrchardNote> = note_index.get_all_notes().to_vec();
 
         self.note_index = Some(note_index);
+        if !recovered_sent.is_empty() {
+            tracing::info!(
+                count = recovered_sent.len(),
+                "Recovered outgoing transactions via OVK"
+            );
+        }
+        self.recovered_sent = recovered_sent;
+        self.watched_payments = watched_payments;
 
         let result = NoteScanResult {
             notes: serializable_notes,
@@ -1163,6 +1379,44 @@ impl<'a> NoteScanner<'a> {
         Ok((result, spendable_notes))
     }
 
+    fn decrypt_watched_actions(
+        &self,
+        transactions: &[ParsedTransaction],
+        block_height: u32,
+        fvk: &FullViewingKey,
+        ivk: &IncomingViewingKey,
+    ) -> Vec<WatchedPayment> {
+        let mut found = Vec::new();
+        for tx in transactions {
+            let pools = [
+                (ShieldedPool::Orchard, &tx.orchard_actions),
+                (ShieldedPool::Ironwood, &tx.ironwood_actions),
+            ];
+            for (pool, actions) in pools {
+                for action in actions {
+                    if let Ok(Some(note)) = self.decrypt_shielded_action(
+                        action,
+                        pool,
+                        fvk,
+                        ivk,
+                        block_height,
+                        &tx.txid,
+                        orchard::keys::Scope::External,
+                    ) {
+                        found.push(WatchedPayment {
+                            txid: tx.txid.clone(),
+                            block_height: (block_height > 0).then_some(block_height),
+                            value_zatoshis: note.value,
+                            orchard_receiver: note.address.to_raw_address_bytes(),
+                            pool,
+                        });
+                    }
+                }
+            }
+        }
+        found
+    }
+
     fn process_block_orchard_actions(
         &self,
         transactions: &[ParsedTransaction],
@@ -1170,7 +1424,7 @@ impl<'a> NoteScanner<'a> {
         orchard_fvk: &FullViewingKey,
         orchard_ivk_external: &IncomingViewingKey,
         orchard_ivk_internal: &IncomingViewingKey,
-        orchard_sk: &SpendingKey,
+        orchard_sk: Option<&SpendingKey>,
         mut witness_tracker: Option<&mut OrchardWitnessTracker>,
         note_index: &mut NoteIndex,
         all_notes: &mut Vec<OrchardNote>,
@@ -1186,7 +1440,7 @@ impl<'a> NoteScanner<'a> {
                 orchard_fvk,
                 orchard_ivk_external,
                 orchard_ivk_internal,
-                Some(orchard_sk),
+                orchard_sk,
                 witness_tracker.as_mut().map(|tr| &mut **tr),
                 note_index,
                 all_notes,
@@ -1204,7 +1458,7 @@ impl<'a> NoteScanner<'a> {
         orchard_fvk: &FullViewingKey,
         orchard_ivk_external: &IncomingViewingKey,
         orchard_ivk_internal: &IncomingViewingKey,
-        orchard_sk: &SpendingKey,
+        orchard_sk: Option<&SpendingKey>,
         mut witness_tracker: Option<&mut IronwoodWitnessTracker>,
         note_index: &mut NoteIndex,
         all_notes: &mut Vec<OrchardNote>,
@@ -1220,7 +1474,7 @@ impl<'a> NoteScanner<'a> {
                 orchard_fvk,
                 orchard_ivk_external,
                 orchard_ivk_internal,
-                Some(orchard_sk),
+                orchard_sk,
                 witness_tracker.as_mut().map(|tr| &mut **tr),
                 note_index,
                 all_notes,
@@ -1320,29 +1574,28 @@ impl<'a> NoteScanner<'a> {
 
                 all_notes.push(orchard_note.clone());
 
-                if let Some(orchard_sk) = orchard_sk {
-                    let mut spendable = SpendableNote {
-                        orchard_note,
-                        spending_key: orchard_sk.clone(),
-                        derivation_path: format!("m/32'/133'/0'/0/{}", action_idx),
-                        pool,
-                        orchard_incremental_witness_hex: None,
-                        orchard_witness_tip_height: None,
-                        ironwood_incremental_witness_hex: None,
-                        ironwood_witness_tip_height: None,
-                    };
-                    match pool {
-                        ShieldedPool::Orchard => {
-                            spendable.orchard_incremental_witness_hex = witness_hex.clone();
-                            spendable.orchard_witness_tip_height = Some(block_height);
-                        }
-                        ShieldedPool::Ironwood => {
-                            spendable.ironwood_incremental_witness_hex = witness_hex.clone();
-                            spendable.ironwood_witness_tip_height = Some(block_height);
-                        }
+                let mut spendable = SpendableNote {
+                    orchard_note,
+                    spending_key: orchard_sk.cloned(),
+                    fvk: orchard_fvk.clone(),
+                    derivation_path: format!("m/32'/133'/0'/0/{}", action_idx),
+                    pool,
+                    orchard_incremental_witness_hex: None,
+                    orchard_witness_tip_height: None,
+                    ironwood_incremental_witness_hex: None,
+                    ironwood_witness_tip_height: None,
+                };
+                match pool {
+                    ShieldedPool::Orchard => {
+                        spendable.orchard_incremental_witness_hex = witness_hex.clone();
+                        spendable.orchard_witness_tip_height = Some(block_height);
+                    }
+                    ShieldedPool::Ironwood => {
+                        spendable.ironwood_incremental_witness_hex = witness_hex.clone();
+                        spendable.ironwood_witness_tip_height = Some(block_height);
                     }
-                    spendable_notes.push(spendable);
                 }
+                spendable_notes.push(spendable);
             }
         }
         Ok(())
@@ -1465,7 +1718,16 @@ impl<'a> NoteScanner<'a> {
                         .transpose()?
                         .unwrap_or_default();
 
-                    if !orchard_actions.is_empty() || !ironwood_actions.is_empty() {
+                    // Sapling outputs are only kept for OVK recovery from the raw transaction.
+                    let has_sapling_outputs = tx
+                        .get("vShieldedOutput")
+                        .and_then(|v| v.as_array())
+                        .is_some_and(|outputs| !outputs.is_empty());
+
+                    if !orchard_actions.is_empty()
+                        || !ironwood_actions.is_empty()
+                        || has_sapling_outputs
+                    {
                         let raw_hex = tx.get("hex").and_then(|v| v.as_str()).unwrap_or("");
                         let parsed_tx = ParsedTransaction {
                             txid: txid.to_string(),
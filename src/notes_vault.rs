@@ -17,6 +17,12 @@ use zeroize::Zeroize;
 const NOTES_MAGIC: &[u8; 4] = b"NZN1";
 /// Ironwood migration schedule on-disk magic (F-13 residual).
 const SCHEDULE_MAGIC: &[u8; 4] = b"NZS1";
+/// Transaction / note labels and tags.
+const LABELS_MAGIC: &[u8; 4] = b"NZL1";
+/// FROST key share, DKG state and signing nonces.
+const FROST_MAGIC: &[u8; 4] = b"NZF1";
+/// UFVK of a watch-only profile.
+const WATCH_MAGIC: &[u8; 4] = b"NZW1";
 const SALT_FILE: &str = "notes.salt";
 
 static NOTES_AES_KEY: Mutex<Option<[u8; 32]>> = Mutex::new(None);
@@ -183,6 +189,36 @@ pub fn decrypt_schedule_file_content(content: &str) -> NozyResult<String> {
     decrypt_file_content_with_magic(content, SCHEDULE_MAGIC, "ironwood migration schedule")
 }
 
+/// Encrypt labels / tags JSON (NZL1, same session key as notes).
+pub fn encrypt_labels_json(plaintext_json: &str) -> NozyResult<String> {
+    encrypt_json_with_magic(plaintext_json, LABELS_MAGIC)
+}
+
+/// Decode labels file: NZL1 hex blob or legacy plaintext JSON.
+pub fn decrypt_labels_file_content(content: &str) -> NozyResult<String> {
+    decrypt_file_content_with_magic(content, LABELS_MAGIC, "labels.json")
+}
+
+/// Encrypt FROST share / nonce JSON (NZF1, same session key as notes).
+pub fn encrypt_frost_json(plaintext_json: &str) -> NozyResult<String> {
+    encrypt_json_with_magic(plaintext_json, FROST_MAGIC)
+}
+
+/// Decode a FROST file: NZF1 hex blob or plaintext JSON.
+pub fn decrypt_frost_file_content(content: &str, file_label: &str) -> NozyResult<String> {
+    decrypt_file_content_with_magic(content, FROST_MAGIC, file_label)
+}
+
+/// Encrypt a watch-only profile's viewing key JSON (NZW1, same session key as notes).
+pub fn encrypt_watch_json(plaintext_json: &str) -> NozyResult<String> {
+    encrypt_json_with_magic(plaintext_json, WATCH_MAGIC)
+}
+
+/// Decode the viewing key file: NZW1 hex blob or plaintext JSON.
+pub fn decrypt_watch_file_content(content: &str) -> NozyResult<String> {
+    decrypt_file_content_with_magic(content, WATCH_MAGIC, "viewing_key.json")
+}
+
 #[cfg(test)]
 static NOTES_VAULT_TEST_LOCK: Mutex<()> = Mutex::new(());
 
@@ -18,8 +18,7 @@ use orchard::{
     builder::BundleType,
     bundle::BundleVersion,
     circuit::{OrchardCircuitVersion, ProvingKey},
-    keys::FullViewingKey,
-    keys::SpendAuthorizingKey,
+    keys::Scope,
     tree::Anchor,
     tree::MerklePath,
     value::NoteValue,
@@ -341,7 +340,11 @@ impl OrchardTransactionBuilder {
                 spendable_note.orchard_note.value
             );
 
-            let fvk = FullViewingKey::from(&spendable_note.spending_key);
+            let fvk = spendable_note.fvk.clone();
+            // Outputs are encrypted to our own OVKs so sent history can be
+            // recovered from chain after a seed restore (see `ovk_recovery`).
+            let external_ovk = fvk.to_ovk(Scope::External);
+            let internal_ovk = fvk.to_ovk(Scope::Internal);
 
             let (anchor, merkle_path) = witness_provider
                 .prepare_spend_anchor_and_path(zebra_client, spendable_note, tip_height)
@@ -365,7 +368,7 @@ impl OrchardTransactionBuilder {
 
             builder
                 .add_output(
-                    None,
+                    Some(external_ovk),
                     recipient_orchard_address.clone(),
                     recipient_note_value,
                     recipient_memo,
@@ -380,7 +383,12 @@ impl OrchardTransactionBuilder {
                 let change_memo = [0u8; 512];
 
                 builder
-                    .add_output(None, change_orchard_address, change_note_value, change_memo)
+                    .add_output(
+                        Some(internal_ovk),
+                        change_orchard_address,
+                        change_note_value,
+                        change_memo,
+                    )
                     .map_err(|e| {
                         NozyError::InvalidOperation(format!("Failed to add change output: {}", e))
                     })?;
@@ -456,7 +464,7 @@ impl OrchardTransactionBuilder {
                 .apply_signatures(
                     &mut rng,
                     sighash,
-                    &[SpendAuthorizingKey::from(&spendable_note.spending_key)],
+                    &[spendable_note.spend_authorizing_key()?],
                 )
                 .map_err(|e| {
                     NozyError::InvalidOperation(format!(
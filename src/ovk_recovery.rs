@@ -0,0 +1,476 @@
+//! Recover sent transaction details from chain with outgoing viewing keys.
+//!
+//! [`SentTransactionStorage`] only knows about sends made on this device. Nozy
+//! encrypts every output it creates to the account OVK (external scope for the
+//! recipient, internal scope for change), so after a restore from seed the
+//! scanner can decrypt those outputs again. [`OutgoingRecoveryKeys`] runs on each
+//! scanned transaction; [`persist_recovered_sent_transactions`] folds the result
+//! back into [`SentTransactionRecord`]s without touching records that already exist.
+
+use crate::block_parser::ParsedTransaction;
+use crate::error::{NozyError, NozyResult};
+use crate::note_index::NoteIndex;
+use crate::notes::OrchardActionData;
+use crate::shielded_pool::ShieldedPool;
+use crate::transaction_history::{
+    SentTransactionRecord, SentTransactionStorage, TransactionStatus,
+};
+use crate::zebra_integration::ZebraClient;
+use chrono::{DateTime, Utc};
+use orchard::keys::{FullViewingKey, OutgoingViewingKey, Scope};
+use orchard::Address as OrchardAddress;
+use sapling::zip32::DiversifiableFullViewingKey;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use zcash_address::unified::{self, Encoding};
+use zcash_protocol::consensus::{BlockHeight, BranchId, NetworkType, MAIN_NETWORK, TEST_NETWORK};
+
+/// Memo lead byte for "no memo" (ZIP 302).
+const EMPTY_MEMO_LEAD_BYTE: u8 = 0xF6;
+
+/// Shielded pool an output was recovered from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RecoveredOutputPool {
+    Orchard,
+    Ironwood,
+    Sapling,
+}
+
+impl From<ShieldedPool> for RecoveredOutputPool {
+    fn from(pool: ShieldedPool) -> Self {
+        match pool {
+            ShieldedPool::Orchard => RecoveredOutputPool::Orchard,
+            ShieldedPool::Ironwood => RecoveredOutputPool::Ironwood,
+        }
+    }
+}
+
+/// One output decrypted with our OVK.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecoveredOutput {
+    pub pool: RecoveredOutputPool,
+    /// Action / output index inside the pool's bundle.
+    pub output_index: usize,
+    pub recipient_address: String,
+    pub value_zatoshis: u64,
+    pub memo: Option<Vec<u8>>,
+    /// Output pays one of this wallet's own addresses (change or self-transfer).
+    pub is_change: bool,
+}
+
+/// All OVK-recoverable outputs of one mined transaction.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecoveredSentTransaction {
+    pub txid: String,
+    pub block_height: u32,
+    pub outputs: Vec<RecoveredOutput>,
+    /// Hex nullifiers of wallet notes spent by this transaction.
+    pub spent_note_ids: Vec<String>,
+    /// Sum of wallet note values spent by this transaction (0 when unknown).
+    pub spent_input_zatoshis: u64,
+}
+
+impl RecoveredSentTransaction {
+    /// Outputs that left the wallet.
+    pub fn payment_outputs(&self) -> impl Iterator<Item = &RecoveredOutput> {
+        self.outputs.iter().filter(|o| !o.is_change)
+    }
+
+    pub fn payment_total_zatoshis(&self) -> u64 {
+        self.payment_outputs().map(|o| o.value_zatoshis).sum()
+    }
+
+    /// Fee implied by our spent inputs minus every recovered output.
+    ///
+    /// Returns 0 when the inputs are unknown (e.g. Sapling or transparent spends) or do
+    /// not cover the recovered outputs.
+    pub fn fee_zatoshis(&self) -> u64 {
+        let outputs: u64 = self.outputs.iter().map(|o| o.value_zatoshis).sum();
+        if self.spent_input_zatoshis >= outputs {
+            self.spent_input_zatoshis - outputs
+        } else {
+            0
+        }
+    }
+
+    /// Rebuild the local sent record; `None` for pure self-transfers.
+    ///
+    /// Multi-recipient transactions keep the first recipient's address and memo and the
+    /// total paid out, matching how single-recipient sends are stored today.
+    pub fn to_sent_record(
+        &self,
+        block_time: Option<DateTime<Utc>>,
+        current_height: u32,
+    ) -> Option<SentTransactionRecord> {
+        let first = self.payment_outputs().next()?;
+        let mut record = SentTransactionRecord::new(
+            self.txid.clone(),
+            first.recipient_address.clone(),
+            self.payment_total_zatoshis(),
+            self.fee_zatoshis(),
+            first.memo.clone(),
+            self.spent_note_ids.clone(),
+        );
+        record.status = TransactionStatus::Confirmed;
+        record.block_height = Some(self.block_height);
+        record.block_time = block_time;
+        record.confirmations = current_height.saturating_sub(self.block_height) + 1;
+        if let Some(t) = block_time {
+            record.created_at = t;
+            record.broadcast_at = Some(t);
+        }
+        Some(record)
+    }
+}
+
+/// Account 0 outgoing viewing keys used by the scanner.
+pub struct OutgoingRecoveryKeys {
+    network: NetworkType,
+    orchard_fvk: FullViewingKey,
+    orchard_ovks: [OutgoingViewingKey; 2],
+    sapling_dfvk: Option<DiversifiableFullViewingKey>,
+}
+
+impl OutgoingRecoveryKeys {
+    pub fn new(
+        network: NetworkType,
+        orchard_fvk: &FullViewingKey,
+        sapling_dfvk: Option<DiversifiableFullViewingKey>,
+    ) -> Self {
+        Self {
+            network,
+            orchard_fvk: orchard_fvk.clone(),
+            orchard_ovks: [
+                orchard_fvk.to_ovk(Scope::External),
+                orchard_fvk.to_ovk(Scope::Internal),
+            ],
+            sapling_dfvk,
+        }
+    }
+
+    /// Try every shielded output of `tx`; `None` when nothing decrypts with our OVKs.
+    ///
+    /// Call after the transaction's spends were applied to `note_index` so spent note
+    /// values resolve through their canonical nullifiers.
+    pub fn recover_transaction(
+        &self,
+        tx: &ParsedTransaction,
+        note_index: &NoteIndex,
+    ) -> Option<RecoveredSentTransaction> {
+        let mut outputs = Vec::new();
+        self.recover_shielded_actions(&tx.orchard_actions, ShieldedPool::Orchard, &mut outputs);
+        self.recover_shielded_actions(&tx.ironwood_actions, ShieldedPool::Ironwood, &mut outputs);
+        self.recover_sapling_outputs(&tx.raw_data, tx.height, &mut outputs);
+        if outputs.is_empty() {
+            return None;
+        }
+
+        let mut spent_note_ids = Vec::new();
+        let mut spent_input_zatoshis = 0u64;
+        for action in tx.orchard_actions.iter().chain(tx.ironwood_actions.iter()) {
+            if let Some(note) = note_index.get_note_by_nullifier(&action.nullifier) {
+                spent_note_ids.push(hex::encode(action.nullifier));
+                spent_input_zatoshis = spent_input_zatoshis.saturating_add(note.value);
+            }
+        }
+
+        Some(RecoveredSentTransaction {
+            txid: tx.txid.clone(),
+            block_height: tx.height,
+            outputs,
+            spent_note_ids,
+            spent_input_zatoshis,
+        })
+    }
+
+    fn recover_shielded_actions(
+        &self,
+        actions: &[OrchardActionData],
+        pool: ShieldedPool,
+        outputs: &mut Vec<RecoveredOutput>,
+    ) {
+        for (output_index, action) in actions.iter().enumerate() {
+            let recovered = self
+                .orchard_ovks
+                .iter()
+                .find_map(|ovk| recover_shielded_action(action, pool, ovk));
+            let Some((value, address, memo)) = recovered else {
+                continue;
+            };
+            let recipient_address = match encode_orchard_receiver(&address, self.network) {
+                Ok(a) => a,
+                Err(e) => {
+                    tracing::warn!(error = %e, "Skipping OVK-recovered output with unencodable address");
+                    continue;
+                }
+            };
+            outputs.push(RecoveredOutput {
+                pool: pool.into(),
+                output_index,
+                recipient_address,
+                value_zatoshis: value,
+                memo: normalize_memo(&memo),
+                is_change: self.orchard_fvk.scope_for_address(&address).is_some(),
+            });
+        }
+    }
+
+    fn recover_sapling_outputs(
+        &self,
+        raw_tx: &[u8],
+        height: u32,
+        outputs: &mut Vec<RecoveredOutput>,
+    ) {
+        use sapling::note_encryption::{try_sapling_output_recovery, Zip212Enforcement};
+        use zcash_primitives::transaction::Transaction;
+
+        let Some(dfvk) = self.sapling_dfvk.as_ref() else {
+            return;
+        };
+        if raw_tx.is_empty() {
+            return;
+        }
+        let branch_id = match self.network {
+            NetworkType::Main => BranchId::for_height(&MAIN_NETWORK, BlockHeight::from_u32(height)),
+            NetworkType::Test | NetworkType::Regtest => {
+                BranchId::for_height(&TEST_NETWORK, BlockHeight::from_u32(height))
+            }
+        };
+        let tx = match Transaction::read(raw_tx, branch_id) {
+            Ok(tx) => tx,
+            Err(e) => {
+                tracing::debug!(height, error = %e, "Raw transaction not parseable for Sapling OVK recovery");
+                return;
+            }
+        };
+        let Some(bundle) = tx.sapling_bundle() else {
+            return;
+        };
+        let ovks = [
+            dfvk.to_ovk(zip32::Scope::External),
+            dfvk.to_ovk(zip32::Scope::Internal),
+        ];
+        for (output_index, output) in bundle.shielded_outputs().iter().enumerate() {
+            let recovered = ovks
+                .iter()
+                .find_map(|ovk| try_sapling_output_recovery(ovk, output, Zip212Enforcement::On));
+            let Some((note, address, memo)) = recovered else {
+                continue;
+            };
+            let recipient_address =
+                match crate::sapling_keys::encode_sapling_payment_address(&address, self.network) {
+                    Ok(a) => a,
+                    Err(_) => continue,
+                };
+            outputs.push(RecoveredOutput {
+                pool: RecoveredOutputPool::Sapling,
+                output_index,
+                recipient_address,
+                value_zatoshis: note.value().inner(),
+                memo: normalize_memo(&memo),
+                is_change: dfvk.decrypt_diversifier(&address).is_some(),
+            });
+        }
+    }
+}
+
+/// Rebuild an [`orchard::Action`] from scanned action bytes; `None` if any field is invalid.
+pub(crate) fn action_from_data(action: &OrchardActionData) -> Option<orchard::Action<()>> {
+    use orchard::{
+        note::{ExtractedNoteCommitment, Nullifier, TransmittedNoteCiphertext},
+        primitives::redpallas::{SpendAuth, VerificationKey},
+        value::ValueCommitment,
+        Action,
+    };
+
+    let nf = Nullifier::from_bytes(&action.nullifier).into_option()?;
+    let rk = VerificationKey::<SpendAuth>::try_from(action.rk).ok()?;
+    let cmx = ExtractedNoteCommitment::from_bytes(&action.cmx).into_option()?;
+    let cv_net = ValueCommitment::from_bytes(&action.cv).into_option()?;
+    let encrypted_note = TransmittedNoteCiphertext {
+        epk_bytes: action.ephemeral_key,
+        enc_ciphertext: action.encrypted_note,
+        // `OrchardActionData::enc_ciphertext` carries the 80-byte outCiphertext.
+        out_ciphertext: action.enc_ciphertext,
+    };
+    Some(Action::from_parts(nf, rk, cmx, encrypted_note, cv_net, ()))
+}
+
+/// Decrypt one Orchard / Ironwood action with `ovk`: `(value, recipient, memo)`.
+fn recover_shielded_action(
+    action: &OrchardActionData,
+    pool: ShieldedPool,
+    ovk: &OutgoingViewingKey,
+) -> Option<(u64, OrchardAddress, [u8; 512])> {
+    use orchard::note_encryption::{IronwoodDomain, OrchardDomain};
+    use zcash_note_encryption::try_output_recovery_with_ovk;
+
+    let full_action = action_from_data(action)?;
+
+    let (note, address, memo) = match pool {
+        ShieldedPool::Orchard => try_output_recovery_with_ovk(
+            &OrchardDomain::for_action(&full_action),
+            ovk,
+            &full_action,
+            full_action.cv_net(),
+            &full_action.encrypted_note().out_ciphertext,
+        )?,
+        ShieldedPool::Ironwood => try_output_recovery_with_ovk(
+            &IronwoodDomain::for_action(&full_action),
+            ovk,
+            &full_action,
+            full_action.cv_net(),
+            &full_action.encrypted_note().out_ciphertext,
+        )?,
+    };
+    Some((note.value().inner(), address, memo))
+}
+
+/// Encode an Orchard receiver as an Orchard-only Unified Address.
+pub fn encode_orchard_receiver(
+    address: &OrchardAddress,
+    network: NetworkType,
+) -> NozyResult<String> {
+    let ua = unified::Address::try_from_items(vec![unified::Receiver::Orchard(
+        address.to_raw_address_bytes(),
+    )])
+    .map_err(|e| NozyError::AddressParsing(format!("Failed to encode Orchard receiver: {e:?}")))?;
+    Ok(ua.encode(&network))
+}
+
+/// Convert a ZIP 302 memo field into the form stored on [`SentTransactionRecord::memo`].
+///
+/// Empty memos become `None`; text memos drop their zero padding; other memo types are
+/// kept verbatim.
+pub fn normalize_memo(memo: &[u8; 512]) -> Option<Vec<u8>> {
+    match memo[0] {
+        EMPTY_MEMO_LEAD_BYTE if memo[1..].iter().all(|b| *b == 0) => None,
+        lead if lead <= 0xF4 => {
+            let end = memo
+                .iter()
+                .rposition(|b| *b != 0)
+                .map(|i| i + 1)
+                .unwrap_or(0);
+            if end == 0 {
+                None
+            } else {
+                Some(memo[..end].to_vec())
+            }
+        }
+        _ => Some(memo.to_vec()),
+    }
+}
+
+/// Save recovered sends that the local history does not know yet.
+///
+/// Returns how many records were written. Block times are fetched best-effort so restored
+/// history sorts by when the transaction was mined rather than when the rescan ran.
+pub async fn persist_recovered_sent_transactions(
+    zebra_client: &ZebraClient,
+    recovered: &[RecoveredSentTransaction],
+    current_height: u32,
+) -> NozyResult<usize> {
+    if recovered.is_empty() {
+        return Ok(0);
+    }
+    let storage = SentTransactionStorage::new()?;
+    let mut block_times: HashMap<u32, Option<DateTime<Utc>>> = HashMap::new();
+    let mut written = 0usize;
+
+    for tx in recovered {
+        if storage.get_transaction(&tx.txid).is_some() {
+            continue;
+        }
+        let block_time = match block_times.get(&tx.block_height) {
+            Some(t) => *t,
+            None => {
+                let t = fetch_block_time(zebra_client, tx.block_height).await;
+                block_times.insert(tx.block_height, t);
+                t
+            }
+        };
+        if let Some(record) = tx.to_sent_record(block_time, current_height) {
+            storage.save_transaction(record)?;
+            written += 1;
+        }
+    }
+    Ok(written)
+}
+
+async fn fetch_block_time(zebra_client: &ZebraClient, height: u32) -> Option<DateTime<Utc>> {
+    let hash = zebra_client.get_block_hash(height).await.ok()?;
+    let block = zebra_client.get_block_by_hash(&hash, 1).await.ok()?;
+    let secs = block.get("time").and_then(|v| v.as_i64())?;
+    DateTime::<Utc>::from_timestamp(secs, 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn output(value: u64, is_change: bool) -> RecoveredOutput {
+        RecoveredOutput {
+            pool: RecoveredOutputPool::Orchard,
+            output_index: 0,
+            recipient_address: if is_change { "u1self" } else { "u1recipient" }.to_string(),
+            value_zatoshis: value,
+            memo: if is_change {
+                None
+            } else {
+                Some(b"rent".to_vec())
+            },
+            is_change,
+        }
+    }
+
+    fn recovered(outputs: Vec<RecoveredOutput>, inputs: u64) -> RecoveredSentTransaction {
+        RecoveredSentTransaction {
+            txid: "ab".repeat(32),
+            block_height: 3_100_000,
+            outputs,
+            spent_note_ids: vec!["cd".repeat(32)],
+            spent_input_zatoshis: inputs,
+        }
+    }
+
+    #[test]
+    fn sent_record_excludes_change_and_derives_fee() {
+        let tx = recovered(vec![output(70_000, false), output(20_000, true)], 100_000);
+        let record = tx.to_sent_record(None, 3_100_009).expect("payment present");
+        assert_eq!(record.recipient_address, "u1recipient");
+        assert_eq!(record.amount_zatoshis, 70_000);
+        assert_eq!(record.fee_zatoshis, 10_000);
+        assert_eq!(record.memo.as_deref(), Some(&b"rent"[..]));
+        assert_eq!(record.status, TransactionStatus::Confirmed);
+        assert_eq!(record.block_height, Some(3_100_000));
+        assert_eq!(record.confirmations, 10);
+    }
+
+    #[test]
+    fn self_transfer_does_not_become_sent_record() {
+        let tx = recovered(vec![output(90_000, true)], 100_000);
+        assert!(tx.to_sent_record(None, 3_100_000).is_none());
+    }
+
+    #[test]
+    fn unknown_inputs_leave_fee_zero() {
+        let tx = recovered(vec![output(70_000, false)], 0);
+        assert_eq!(tx.fee_zatoshis(), 0);
+    }
+
+    #[test]
+    fn normalize_memo_handles_empty_text_and_binary() {
+        let mut empty = [0u8; 512];
+        empty[0] = EMPTY_MEMO_LEAD_BYTE;
+        assert_eq!(normalize_memo(&empty), None);
+
+        let mut text = [0u8; 512];
+        text[..5].copy_from_slice(b"hello");
+        assert_eq!(normalize_memo(&text), Some(b"hello".to_vec()));
+
+        let mut binary = [0u8; 512];
+        binary[0] = 0xFF;
+        assert_eq!(normalize_memo(&binary).map(|m| m.len()), Some(512));
+    }
+}
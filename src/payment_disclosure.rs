@@ -0,0 +1,479 @@
+//! Payment disclosures: prove that one output of a sent transaction paid an address.
+//!
+//! A disclosure (`nozy-pd-v1`) carries the outgoing cipher key (`ock`) of a single
+//! Orchard / Ironwood output. With it a verifier decrypts that output of the mined
+//! transaction fetched from Zebra, which also checks the note commitment, and compares
+//! recipient, value and memo with what is claimed. No wallet key is revealed and the
+//! other outputs stay private. Nozy derives each `ock` from the account OVK the first
+//! time a send is disclosed and stores it on the [`SentTransactionRecord`].
+
+use crate::block_parser::ParsedTransaction;
+use crate::config::{load_config, WalletRole};
+use crate::error::{NozyError, NozyResult};
+use crate::hd_wallet::HDWallet;
+use crate::notes::{NoteScanner, OrchardActionData};
+use crate::ovk_recovery::{
+    action_from_data, encode_orchard_receiver, normalize_memo, RecoveredOutputPool,
+};
+use crate::signed_message::orchard_receiver;
+use crate::transaction_history::{SentTransactionRecord, SentTransactionStorage};
+use crate::zebra_integration::ZebraClient;
+use orchard::keys::{FullViewingKey, Scope};
+use orchard::note_encryption::{IronwoodDomain, OrchardDomain};
+use orchard::Address as OrchardAddress;
+use serde::{Deserialize, Serialize};
+use zcash_note_encryption::{
+    try_output_recovery_with_ock, Domain, EphemeralKeyBytes, OutgoingCipherKey,
+};
+use zcash_protocol::consensus::NetworkType;
+
+pub const DISCLOSURE_SCHEME: &str = "nozy-pd-v1";
+
+/// Stored secret that lets this wallet disclose one output of a sent transaction.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OutputDisclosureKey {
+    pub pool: RecoveredOutputPool,
+    pub output_index: usize,
+    /// Hex outgoing cipher key of the output.
+    pub ock: String,
+    /// Output pays one of this wallet's own addresses.
+    pub is_change: bool,
+}
+
+/// Exported proof that `txid` paid `recipient_address` `value_zatoshis` with `memo`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PaymentDisclosure {
+    pub scheme: String,
+    pub txid: String,
+    pub pool: RecoveredOutputPool,
+    pub output_index: usize,
+    pub recipient_address: String,
+    pub value_zatoshis: u64,
+    /// Hex memo as stored on sent records (`None` for an empty memo).
+    pub memo: Option<String>,
+    pub ock: String,
+}
+
+/// What a verifier learned from a valid disclosure.
+#[derive(Debug, Clone, Serialize)]
+pub struct VerifiedPayment {
+    pub txid: String,
+    /// 0 while the transaction is still in the mempool.
+    pub block_height: u32,
+    pub recipient_address: String,
+    pub value_zatoshis: u64,
+    pub memo: Option<String>,
+    pub memo_text: Option<String>,
+}
+
+pub(crate) fn pool_actions(
+    tx: &ParsedTransaction,
+    pool: RecoveredOutputPool,
+) -> NozyResult<&[OrchardActionData]> {
+    match pool {
+        RecoveredOutputPool::Orchard => Ok(&tx.orchard_actions),
+        RecoveredOutputPool::Ironwood => Ok(&tx.ironwood_actions),
+        RecoveredOutputPool::Sapling => Err(NozyError::InvalidInput(
+            "Payment disclosures cover Orchard and Ironwood outputs only".into(),
+        )),
+    }
+}
+
+fn derive_ock(
+    action: &OrchardActionData,
+    pool: RecoveredOutputPool,
+    ovk: &orchard::keys::OutgoingViewingKey,
+) -> Option<OutgoingCipherKey> {
+    let full_action = action_from_data(action)?;
+    let epk = EphemeralKeyBytes(action.ephemeral_key);
+    match pool {
+        RecoveredOutputPool::Orchard => Some(OrchardDomain::derive_ock(
+            ovk,
+            full_action.cv_net(),
+            &action.cmx,
+            &epk,
+        )),
+        RecoveredOutputPool::Ironwood => Some(IronwoodDomain::derive_ock(
+            ovk,
+            full_action.cv_net(),
+            &action.cmx,
+            &epk,
+        )),
+        RecoveredOutputPool::Sapling => None,
+    }
+}
+
+/// Decrypt one action with its `ock`: `(value, recipient, memo)`.
+pub(crate) fn decrypt_with_ock(
+    action: &OrchardActionData,
+    pool: RecoveredOutputPool,
+    ock: &OutgoingCipherKey,
+) -> Option<(u64, OrchardAddress, [u8; 512])> {
+    let full_action = action_from_data(action)?;
+    let out_ciphertext = &full_action.encrypted_note().out_ciphertext;
+    let (note, address, memo) = match pool {
+        RecoveredOutputPool::Orchard => try_output_recovery_with_ock(
+            &OrchardDomain::for_action(&full_action),
+            ock,
+            &full_action,
+            out_ciphertext,
+        )?,
+        RecoveredOutputPool::Ironwood => try_output_recovery_with_ock(
+            &IronwoodDomain::for_action(&full_action),
+            ock,
+            &full_action,
+            out_ciphertext,
+        )?,
+        RecoveredOutputPool::Sapling => return None,
+    };
+    Some((note.value().inner(), address, memo))
+}
+
+pub(crate) fn parse_ock(hex_ock: &str) -> NozyResult<OutgoingCipherKey> {
+    let bytes = hex::decode(hex_ock.trim())
+        .ok()
+        .and_then(|b| <[u8; 32]>::try_from(b).ok())
+        .ok_or_else(|| NozyError::InvalidInput("Disclosure key must be 32 hex bytes".into()))?;
+    Ok(OutgoingCipherKey(bytes))
+}
+
+/// Disclosure keys for every output of `tx` that one of `fvks` encrypted to its OVK.
+pub fn derive_output_keys(
+    tx: &ParsedTransaction,
+    fvks: &[FullViewingKey],
+) -> Vec<OutputDisclosureKey> {
+    let mut keys = Vec::new();
+    for pool in [RecoveredOutputPool::Orchard, RecoveredOutputPool::Ironwood] {
+        let actions = pool_actions(tx, pool).unwrap_or_default();
+        for (output_index, action) in actions.iter().enumerate() {
+            let found = fvks.iter().find_map(|fvk| {
+                [Scope::External, Scope::Internal].iter().find_map(|scope| {
+                    let ock = derive_ock(action, pool, &fvk.to_ovk(*scope))?;
+                    let (_, address, _) = decrypt_with_ock(action, pool, &ock)?;
+                    Some((ock, fvk.scope_for_address(&address).is_some()))
+                })
+            });
+            if let Some((ock, is_change)) = found {
+                keys.push(OutputDisclosureKey {
+                    pool,
+                    output_index,
+                    ock: hex::encode(ock.0),
+                    is_change,
+                });
+            }
+        }
+    }
+    keys
+}
+
+/// Fetch `txid` from Zebra in the shape the scanner uses (mempool transactions get height 0).
+pub async fn fetch_transaction(zebra: &ZebraClient, txid: &str) -> NozyResult<ParsedTransaction> {
+    let verbose = zebra.get_raw_transaction_verbose(txid).await?;
+    let height = verbose.get("height").and_then(|h| h.as_u64()).unwrap_or(0) as u32;
+    NoteScanner::parse_block_data(&serde_json::json!({ "tx": [verbose] }), height)?
+        .into_iter()
+        .find(|tx| tx.txid == txid)
+        .ok_or_else(|| {
+            NozyError::InvalidOperation(format!("Transaction {txid} has no shielded outputs"))
+        })
+}
+
+/// Build a disclosure for `output_index` (default: the first non-change output).
+pub fn create_payment_disclosure(
+    record: &SentTransactionRecord,
+    tx: &ParsedTransaction,
+    output_index: Option<usize>,
+    network: NetworkType,
+) -> NozyResult<PaymentDisclosure> {
+    let key = match output_index {
+        Some(index) => record
+            .disclosure_keys
+            .iter()
+            .find(|k| k.output_index == index && !k.is_change)
+            .or_else(|| {
+                record
+                    .disclosure_keys
+                    .iter()
+                    .find(|k| k.output_index == index)
+            }),
+        None => record.disclosure_keys.iter().find(|k| !k.is_change),
+    }
+    .ok_or_else(|| {
+        NozyError::InvalidInput(format!(
+            "No disclosable output found for transaction {}",
+            record.txid
+        ))
+    })?;
+
+    let ock = parse_ock(&key.ock)?;
+    let action = pool_actions(tx, key.pool)?
+        .get(key.output_index)
+        .ok_or_else(|| NozyError::InvalidOperation("Output index out of range".into()))?;
+    let (value, address, memo) = decrypt_with_ock(action, key.pool, &ock).ok_or_else(|| {
+        NozyError::InvalidOperation("Stored disclosure key does not open this output".into())
+    })?;
+
+    // Keep the address the recipient knows when it contains this Orchard receiver.
+    let recipient_address = match orchard_receiver(&record.recipient_address) {
+        Ok(receiver) if receiver == address.to_raw_address_bytes() => {
+            record.recipient_address.clone()
+        }
+        _ => encode_orchard_receiver(&address, network)?,
+    };
+
+    Ok(PaymentDisclosure {
+        scheme: DISCLOSURE_SCHEME.to_string(),
+        txid: record.txid.clone(),
+        pool: key.pool,
+        output_index: key.output_index,
+        recipient_address,
+        value_zatoshis: value,
+        memo: normalize_memo(&memo).map(hex::encode),
+        ock: key.ock.clone(),
+    })
+}
+
+/// Check `disclosure` against the transaction it names.
+pub fn verify_payment_disclosure(
+    disclosure: &PaymentDisclosure,
+    tx: &ParsedTransaction,
+) -> NozyResult<VerifiedPayment> {
+    let fail = |reason: &str| {
+        Err(NozyError::InvalidInput(format!(
+            "Disclosure invalid: {reason}"
+        )))
+    };
+    if disclosure.scheme != DISCLOSURE_SCHEME {
+        return fail("unknown scheme");
+    }
+    if disclosure.txid != tx.txid {
+        return fail("transaction id does not match");
+    }
+    let ock = parse_ock(&disclosure.ock)?;
+    let Some(action) = pool_actions(tx, disclosure.pool)?.get(disclosure.output_index) else {
+        return fail("output index out of range");
+    };
+    let Some((value, address, memo)) = decrypt_with_ock(action, disclosure.pool, &ock) else {
+        return fail("key does not open this output");
+    };
+    if orchard_receiver(&disclosure.recipient_address)? != address.to_raw_address_bytes() {
+        return fail("output pays a different address");
+    }
+    if value != disclosure.value_zatoshis {
+        return fail("amount does not match");
+    }
+    let memo = normalize_memo(&memo);
+    if memo.as_ref().map(hex::encode) != disclosure.memo {
+        return fail("memo does not match");
+    }
+
+    Ok(VerifiedPayment {
+        txid: tx.txid.clone(),
+        block_height: tx.height,
+        recipient_address: disclosure.recipient_address.clone(),
+        value_zatoshis: value,
+        memo_text: memo
+            .as_ref()
+            .filter(|m| m.first().is_some_and(|b| *b <= 0xF4))
+            .and_then(|m| String::from_utf8(m.clone()).ok()),
+        memo: memo.map(hex::encode),
+    })
+}
+
+/// Export a disclosure for a send in local history, deriving and saving its keys if needed.
+///
+/// Keys are looked up under the active, default and Business accounts.
+pub async fn export_payment_disclosure(
+    wallet: &HDWallet,
+    zebra: &ZebraClient,
+    txid: &str,
+    output_index: Option<usize>,
+    network: NetworkType,
+) -> NozyResult<PaymentDisclosure> {
+    let storage = SentTransactionStorage::new()?;
+    let mut record = storage
+        .get_transaction(txid)
+        .ok_or_else(|| NozyError::InvalidInput(format!("No sent transaction {txid}")))?;
+    let tx = fetch_transaction(zebra, txid).await?;
+
+    if record.disclosure_keys.is_empty() {
+        let mut accounts = vec![
+            load_config().active_orchard_account(),
+            0,
+            WalletRole::Business.orchard_account(),
+        ];
+        accounts.sort_unstable();
+        accounts.dedup();
+        let fvks = accounts
+            .into_iter()
+            .map(|account| wallet.orchard_full_viewing_key(account))
+            .collect::<NozyResult<Vec<_>>>()?;
+        record.disclosure_keys = derive_output_keys(&tx, &fvks);
+        if record.disclosure_keys.is_empty() {
+            return Err(NozyError::InvalidOperation(format!(
+                "No output of {txid} was encrypted to this wallet's outgoing viewing keys"
+            )));
+        }
+        storage.save_transaction(record.clone())?;
+    }
+
+    let disclosure = create_payment_disclosure(&record, &tx, output_index, network)?;
+    verify_payment_disclosure(&disclosure, &tx)?;
+    Ok(disclosure)
+}
+
+/// Fetch the named transaction from Zebra and verify `disclosure` against it.
+pub async fn verify_payment_disclosure_with_zebra(
+    zebra: &ZebraClient,
+    disclosure: &PaymentDisclosure,
+) -> NozyResult<VerifiedPayment> {
+    let tx = fetch_transaction(zebra, &disclosure.txid).await?;
+    verify_payment_disclosure(disclosure, &tx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn empty_tx() -> ParsedTransaction {
+        ParsedTransaction {
+            txid: "ab".repeat(32),
+            height: 100,
+            index: 0,
+            raw_data: Vec::new(),
+            orchard_actions: Vec::new(),
+            ironwood_actions: Vec::new(),
+        }
+    }
+
+    fn disclosure() -> PaymentDisclosure {
+        PaymentDisclosure {
+            scheme: DISCLOSURE_SCHEME.to_string(),
+            txid: "ab".repeat(32),
+            pool: RecoveredOutputPool::Orchard,
+            output_index: 0,
+            recipient_address: "u1example".to_string(),
+            value_zatoshis: 50_000,
+            memo: None,
+            ock: "00".repeat(32),
+        }
+    }
+
+    #[test]
+    fn disclosure_must_name_the_fetched_transaction() {
+        let mut other = disclosure();
+        other.txid = "cd".repeat(32);
+        let err = verify_payment_disclosure(&other, &empty_tx()).unwrap_err();
+        assert!(err.to_string().contains("transaction id"));
+
+        let mut unknown = disclosure();
+        unknown.scheme = "nozy-pd-v0".into();
+        assert!(verify_payment_disclosure(&unknown, &empty_tx()).is_err());
+
+        let err = verify_payment_disclosure(&disclosure(), &empty_tx()).unwrap_err();
+        assert!(err.to_string().contains("out of range"));
+    }
+
+    #[test]
+    fn default_output_skips_change() {
+        let mut record = SentTransactionRecord::new(
+            "ab".repeat(32),
+            "u1example".into(),
+            50_000,
+            10_000,
+            None,
+            Vec::new(),
+        );
+        record.disclosure_keys = vec![OutputDisclosureKey {
+            pool: RecoveredOutputPool::Orchard,
+            output_index: 0,
+            ock: "00".repeat(32),
+            is_change: true,
+        }];
+        let err =
+            create_payment_disclosure(&record, &empty_tx(), None, NetworkType::Main).unwrap_err();
+        assert!(err.to_string().contains("No disclosable output"));
+    }
+
+    fn action_data(action: &pczt::orchard::Action) -> OrchardActionData {
+        OrchardActionData {
+            nullifier: *action.spend().nullifier(),
+            cmx: *action.output().cmx(),
+            ephemeral_key: *action.output().ephemeral_key(),
+            encrypted_note: action
+                .output()
+                .enc_ciphertext()
+                .as_slice()
+                .try_into()
+                .unwrap(),
+            enc_ciphertext: action
+                .output()
+                .out_ciphertext()
+                .as_slice()
+                .try_into()
+                .unwrap(),
+            cv: *action.cv_net(),
+            rk: *action.spend().rk(),
+        }
+    }
+
+    #[test]
+    fn exported_disclosure_verifies_against_the_real_output() {
+        use orchard::keys::SpendingKey;
+
+        let fvk = FullViewingKey::from(&SpendingKey::from_bytes([13u8; 32]).unwrap());
+        let recipient = FullViewingKey::from(&SpendingKey::from_bytes([14u8; 32]).unwrap())
+            .address_at(0u32, Scope::External);
+        let change = fvk.address_at(0u32, Scope::Internal);
+        let (pczt, _) = crate::tests::unproven_test_pczt(
+            &fvk,
+            200_000,
+            &[(recipient, 150_000), (change, 40_000)],
+            &[],
+        );
+        let tx = ParsedTransaction {
+            orchard_actions: pczt.orchard().actions().iter().map(action_data).collect(),
+            ..empty_tx()
+        };
+
+        let mut record = SentTransactionRecord::new(
+            "ab".repeat(32),
+            encode_orchard_receiver(&recipient, NetworkType::Test).unwrap(),
+            150_000,
+            10_000,
+            None,
+            Vec::new(),
+        );
+        record.disclosure_keys = derive_output_keys(&tx, std::slice::from_ref(&fvk));
+        assert_eq!(record.disclosure_keys.len(), 2);
+        assert_eq!(
+            record
+                .disclosure_keys
+                .iter()
+                .filter(|k| k.is_change)
+                .count(),
+            1
+        );
+
+        let disclosure = create_payment_disclosure(&record, &tx, None, NetworkType::Test).unwrap();
+        assert_eq!(disclosure.value_zatoshis, 150_000);
+        assert_eq!(disclosure.recipient_address, record.recipient_address);
+        let verified = verify_payment_disclosure(&disclosure, &tx).unwrap();
+        assert_eq!(verified.value_zatoshis, 150_000);
+        assert_eq!(verified.memo, disclosure.memo);
+
+        let mut inflated = disclosure.clone();
+        inflated.value_zatoshis = 160_000;
+        let err = verify_payment_disclosure(&inflated, &tx).unwrap_err();
+        assert!(err.to_string().contains("amount"));
+
+        let mut redirected = disclosure.clone();
+        redirected.recipient_address = encode_orchard_receiver(&change, NetworkType::Test).unwrap();
+        let err = verify_payment_disclosure(&redirected, &tx).unwrap_err();
+        assert!(err.to_string().contains("different address"));
+
+        let mut wrong_output = disclosure;
+        wrong_output.output_index = 1 - wrong_output.output_index;
+        assert!(verify_payment_disclosure(&wrong_output, &tx).is_err());
+    }
+}
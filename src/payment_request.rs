@@ -0,0 +1,275 @@
+//! Signed payment requests: merchant signatures over ZIP-321 invoices.
+//!
+//! The merchant signs `(address, amount, memo, expiry)` with the `nozy-sm-v1` scheme
+//! ([`crate::signed_message`]) using its Business account key, and the URI carries the
+//! result as `expires=…&sig=…`. Both parameters are optional, so wallets that do not
+//! know them still pay the plain request. When paying, Nozy verifies the signature and
+//! names the signer if its key is saved as a `merchant_key` in the address book.
+
+use crate::address_book::AddressEntry;
+use crate::error::{NozyError, NozyResult};
+use crate::hd_wallet::HDWallet;
+use crate::signed_message::{
+    sign_with_key, signer_key_hex, spend_authorizing_key, verify, verifying_key_hex,
+};
+use crate::zip321::{append_query_param, parse_payment_uri, PaymentRequest};
+use chrono::{DateTime, Utc};
+use orchard::keys::SpendAuthorizingKey;
+use serde::Serialize;
+
+const MESSAGE_DOMAIN: &str = "nozy-payreq-v1";
+
+fn amount_zatoshis(amount_zec: Option<f64>) -> Option<u64> {
+    amount_zec.map(|zec| (zec * 100_000_000.0).round() as u64)
+}
+
+/// Canonical text a payment-request signature covers.
+pub fn payment_request_message(
+    address: &str,
+    amount_zatoshis: Option<u64>,
+    memo: Option<&str>,
+    expires: Option<&str>,
+) -> String {
+    format!(
+        "{MESSAGE_DOMAIN}\naddress={}\namount={}\nmemo={}\nexpires={}",
+        address.trim(),
+        amount_zatoshis.map(|z| z.to_string()).unwrap_or_default(),
+        memo.unwrap_or_default(),
+        expires.unwrap_or_default()
+    )
+}
+
+fn request_message(request: &PaymentRequest) -> String {
+    payment_request_message(
+        &request.address,
+        amount_zatoshis(request.amount_zec),
+        request.memo.as_deref(),
+        request.expires.as_deref(),
+    )
+}
+
+/// Signs payment requests with one account's Orchard SpendAuth key.
+#[derive(Clone)]
+pub struct PaymentRequestSigner {
+    ask: SpendAuthorizingKey,
+}
+
+impl std::fmt::Debug for PaymentRequestSigner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PaymentRequestSigner")
+            .field("merchant_key", &self.merchant_key())
+            .finish()
+    }
+}
+
+impl PaymentRequestSigner {
+    pub fn from_seed(seed_bytes: &[u8], account: u32) -> NozyResult<Self> {
+        Ok(Self {
+            ask: spend_authorizing_key(seed_bytes, account)?,
+        })
+    }
+
+    pub fn from_wallet(wallet: &HDWallet, account: u32) -> NozyResult<Self> {
+        Self::from_seed(&wallet.get_mnemonic_object().to_seed(""), account)
+    }
+
+    /// Public key payers save in their address book (hex `ak`).
+    pub fn merchant_key(&self) -> String {
+        verifying_key_hex(&self.ask)
+    }
+
+    /// Sign `uri`, adding `expires` (when given) and `sig`; returns the URI and signature.
+    pub fn sign_uri(&self, uri: &str, expires: Option<&str>) -> NozyResult<(String, String)> {
+        let mut request = parse_payment_uri(uri)?;
+        if request.signature.is_some() || request.expires.is_some() {
+            return Err(NozyError::InvalidInput(
+                "Payment request is already signed".into(),
+            ));
+        }
+        request.expires = expires.map(str::to_string);
+        let signature = sign_with_key(&self.ask, &request_message(&request));
+
+        let mut signed = uri.trim().to_string();
+        if let Some(expires) = expires {
+            signed = append_query_param(&signed, "expires", expires);
+        }
+        Ok((append_query_param(&signed, "sig", &signature), signature))
+    }
+}
+
+/// Who signed a payment request, as far as the payer can tell.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RequestAuthenticity {
+    Unsigned,
+    /// A signature is attached but does not match the request.
+    Invalid,
+    Signed {
+        merchant_key: String,
+        /// Address-book name of the key, if it is saved there.
+        merchant: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PaymentRequestCheck {
+    pub authenticity: RequestAuthenticity,
+    /// Signed expiry has passed.
+    pub expired: bool,
+}
+
+impl PaymentRequestCheck {
+    /// Safe to pay: unsigned (nothing claimed) or validly signed and current. A valid
+    /// signature from a key not in the address book only passes with `allow_unknown_signer`.
+    pub fn is_acceptable(&self, allow_unknown_signer: bool) -> bool {
+        self.authenticity != RequestAuthenticity::Invalid
+            && !self.expired
+            && (allow_unknown_signer || !self.signed_by_unknown_key())
+    }
+
+    /// Validly signed by a key that is not a known merchant.
+    pub fn signed_by_unknown_key(&self) -> bool {
+        matches!(
+            self.authenticity,
+            RequestAuthenticity::Signed { merchant: None, .. }
+        )
+    }
+
+    pub fn summary(&self) -> String {
+        let mut line = match &self.authenticity {
+            RequestAuthenticity::Unsigned => "Unsigned payment request".to_string(),
+            RequestAuthenticity::Invalid => {
+                "Signature does NOT match this payment request".to_string()
+            }
+            RequestAuthenticity::Signed {
+                merchant: Some(name),
+                ..
+            } => format!("Signed by known merchant {name}"),
+            RequestAuthenticity::Signed { merchant_key, .. } => {
+                format!("Signed by unknown key {}…", &merchant_key[..16])
+            }
+        };
+        if self.expired {
+            line.push_str(" (expired)");
+        }
+        line
+    }
+}
+
+/// Check `request`'s signature and expiry; `contacts` supplies known merchant keys.
+pub fn verify_payment_request(
+    request: &PaymentRequest,
+    contacts: &[AddressEntry],
+) -> PaymentRequestCheck {
+    let Some(signature) = request.signature.as_deref() else {
+        return PaymentRequestCheck {
+            authenticity: RequestAuthenticity::Unsigned,
+            expired: false,
+        };
+    };
+
+    let merchant_key = signer_key_hex(signature).map(str::to_ascii_lowercase);
+    let authenticity = match merchant_key {
+        Some(key) if verify(signature, &request_message(request)).unwrap_or(false) => {
+            let merchant = contacts
+                .iter()
+                .find(|c| c.merchant_key.as_deref() == Some(key.as_str()))
+                .map(|c| c.name.clone());
+            RequestAuthenticity::Signed {
+                merchant_key: key,
+                merchant,
+            }
+        }
+        _ => RequestAuthenticity::Invalid,
+    };
+
+    // An unparseable expiry cannot be honoured, so treat it as already passed.
+    let expired = request.expires.as_deref().is_some_and(|raw| {
+        DateTime::parse_from_rfc3339(raw)
+            .map(|at| at.with_timezone(&Utc) <= Utc::now())
+            .unwrap_or(true)
+    });
+
+    PaymentRequestCheck {
+        authenticity,
+        expired,
+    }
+}
+
+/// Parse and check a `zcash:` URI in one step.
+pub fn verify_payment_uri(
+    uri: &str,
+    contacts: &[AddressEntry],
+) -> NozyResult<(PaymentRequest, PaymentRequestCheck)> {
+    let request = parse_payment_uri(uri)?;
+    let check = verify_payment_request(&request, contacts);
+    Ok((request, check))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::zip321::build_payment_uri;
+    use bip39::{Language, Mnemonic};
+    use chrono::Duration;
+
+    fn signer() -> PaymentRequestSigner {
+        let mnemonic = Mnemonic::from_entropy_in(Language::English, &[9u8; 16]).unwrap();
+        PaymentRequestSigner::from_seed(&mnemonic.to_seed(""), 1).unwrap()
+    }
+
+    #[test]
+    fn signed_request_names_known_merchant() {
+        let signer = signer();
+        let uri = build_payment_uri("u1merchant", Some(0.25), Some("inv_1")).unwrap();
+        let expires = (Utc::now() + Duration::minutes(30)).to_rfc3339();
+        let (signed, _) = signer.sign_uri(&uri, Some(&expires)).unwrap();
+
+        let (_, check) = verify_payment_uri(&signed, &[]).unwrap();
+        assert!(check.signed_by_unknown_key());
+        assert!(!check.is_acceptable(false));
+        assert!(check.is_acceptable(true));
+        assert_eq!(
+            check.authenticity,
+            RequestAuthenticity::Signed {
+                merchant_key: signer.merchant_key(),
+                merchant: None
+            }
+        );
+
+        let mut shop = AddressEntry::new("Coffee Shop".into(), "u1merchant".into(), None);
+        shop.merchant_key = Some(signer.merchant_key());
+        let (_, check) = verify_payment_uri(&signed, &[shop]).unwrap();
+        assert_eq!(check.summary(), "Signed by known merchant Coffee Shop");
+        assert!(check.is_acceptable(false));
+    }
+
+    #[test]
+    fn substituted_address_or_amount_fails() {
+        let uri = build_payment_uri("u1merchant", Some(0.25), Some("inv_1")).unwrap();
+        let (signed, _) = signer().sign_uri(&uri, None).unwrap();
+
+        for forged in [
+            signed.replace("u1merchant", "u1phisher"),
+            signed.replace("amount=0.25", "amount=2.5"),
+        ] {
+            let (_, check) = verify_payment_uri(&forged, &[]).unwrap();
+            assert_eq!(check.authenticity, RequestAuthenticity::Invalid);
+            assert!(!check.is_acceptable(true));
+        }
+    }
+
+    #[test]
+    fn expired_request_is_not_acceptable() {
+        let uri = build_payment_uri("u1merchant", Some(1.0), None).unwrap();
+        let expires = (Utc::now() - Duration::minutes(1)).to_rfc3339();
+        let (signed, _) = signer().sign_uri(&uri, Some(&expires)).unwrap();
+        let (_, check) = verify_payment_uri(&signed, &[]).unwrap();
+        assert!(matches!(
+            check.authenticity,
+            RequestAuthenticity::Signed { .. }
+        ));
+        assert!(check.expired);
+        assert!(!check.is_acceptable(true));
+    }
+}
@@ -0,0 +1,324 @@
+//! Point-of-sale mode: fiat-priced invoices at a locked rate, shown as a QR code.
+//!
+//! A sale converts the fiat amount at one spot price (or a cashier-entered rate) and
+//! stores that [`PricePoint`] on the invoice, so later price moves do not change what
+//! the customer owes. [`PosWatcher`] then follows the invoice through the mempool and
+//! new blocks until it is paid, expired or cancelled.
+
+use crate::config::{WalletConfig, WalletRole};
+use crate::error::{NozyError, NozyResult};
+use crate::hd_wallet::HDWallet;
+use crate::merchant_invoices::{
+    create_invoice, get_invoice, peek_next_diversifier, sync_invoice_payments, CreateInvoiceParams,
+    InvoiceStatus, MerchantInvoice,
+};
+use crate::notes::NoteScanner;
+use crate::payment_request::PaymentRequestSigner;
+use crate::price_source::{configured_price_source, normalize_currency_code, PricePoint};
+use crate::wallet_sync::{sync_wallet_notes, WalletSyncOptions};
+use crate::zebra_integration::ZebraClient;
+use chrono::Utc;
+use orchard::keys::FullViewingKey;
+use qrcode::{Color, QrCode};
+use std::collections::HashSet;
+use std::path::Path;
+use zcash_protocol::consensus::NetworkType;
+
+const ZATOSHIS_PER_ZEC: f64 = 100_000_000.0;
+/// Light modules around the code; scanners need at least a few.
+const QR_QUIET_ZONE: usize = 2;
+
+#[derive(Debug, Clone, Default)]
+pub struct PosSaleRequest {
+    pub amount_fiat: f64,
+    /// Defaults to `fiat.display_currency`.
+    pub currency: Option<String>,
+    pub product_name: Option<String>,
+    pub memo: Option<String>,
+    pub ttl_minutes: Option<i64>,
+    /// Cashier-entered ZEC price; skips the configured price source.
+    pub price_per_zec: Option<f64>,
+}
+
+/// Fiat amount to zatoshis at `price_per_zec` (rounded to the nearest zat).
+pub fn fiat_to_zatoshis(amount_fiat: f64, price_per_zec: f64) -> NozyResult<u64> {
+    if !(amount_fiat.is_finite() && amount_fiat > 0.0) {
+        return Err(NozyError::InvalidInput(
+            "Fiat amount must be a positive number".into(),
+        ));
+    }
+    if !(price_per_zec.is_finite() && price_per_zec > 0.0) {
+        return Err(NozyError::InvalidInput(
+            "ZEC price must be a positive number".into(),
+        ));
+    }
+    let zatoshis = (amount_fiat / price_per_zec * ZATOSHIS_PER_ZEC).round() as u64;
+    if zatoshis == 0 {
+        return Err(NozyError::InvalidInput(
+            "Fiat amount is too small (rounds to 0 zats)".into(),
+        ));
+    }
+    Ok(zatoshis)
+}
+
+/// Spot price to lock for a sale: `manual` when given, else the configured source.
+pub async fn lock_exchange_rate(
+    config: &WalletConfig,
+    currency: &str,
+    manual: Option<f64>,
+) -> NozyResult<PricePoint> {
+    let currency = normalize_currency_code(currency)?;
+    if let Some(price_per_zec) = manual {
+        return Ok(PricePoint {
+            currency,
+            price_per_zec,
+            at: Utc::now(),
+            source: "manual".to_string(),
+        });
+    }
+    let source = configured_price_source(config)?.ok_or_else(|| {
+        NozyError::InvalidOperation(
+            "No price source configured; set fiat.source or enter a rate".into(),
+        )
+    })?;
+    source
+        .spot_price(&currency)
+        .await?
+        .ok_or_else(|| NozyError::InvalidOperation(format!("No {currency} price available")))
+}
+
+/// Create a Business invoice for a fiat amount at a locked rate.
+pub async fn create_pos_sale(
+    wallet: &HDWallet,
+    config: &WalletConfig,
+    request: PosSaleRequest,
+) -> NozyResult<MerchantInvoice> {
+    if config.active_role != WalletRole::Business {
+        return Err(NozyError::InvalidOperation(
+            "Switch to the Business profile before selling".into(),
+        ));
+    }
+    let currency = request
+        .currency
+        .as_deref()
+        .unwrap_or(&config.fiat.display_currency);
+    let rate = lock_exchange_rate(config, currency, request.price_per_zec).await?;
+    let amount_zatoshis = fiat_to_zatoshis(request.amount_fiat, rate.price_per_zec)?;
+
+    let diversifier_index = peek_next_diversifier()?;
+    let network = if config.network == "testnet" {
+        NetworkType::Test
+    } else {
+        NetworkType::Main
+    };
+    let payment_address = wallet.generate_orchard_address(
+        WalletRole::Business.orchard_account(),
+        diversifier_index,
+        network,
+    )?;
+    let signer = config
+        .merchant_invoices
+        .sign_payment_requests
+        .then(|| PaymentRequestSigner::from_wallet(wallet, WalletRole::Business.orchard_account()))
+        .transpose()?;
+
+    create_invoice(CreateInvoiceParams {
+        amount_zec: amount_zatoshis as f64 / ZATOSHIS_PER_ZEC,
+        amount_fiat: Some(request.amount_fiat),
+        fiat_currency: Some(rate.currency.clone()),
+        product_name: request.product_name,
+        memo: request.memo,
+        ttl_minutes: request.ttl_minutes,
+        payment_address,
+        diversifier_index,
+        webhook_url: None,
+        tolerance_zatoshis: None,
+        confirmations: None,
+        locked_rate: Some(rate),
+        signer,
+    })
+}
+
+/// Nothing more will happen to the sale (`zero_conf`: a detected payment is enough).
+pub fn pos_sale_finished(invoice: &MerchantInvoice, zero_conf: bool) -> bool {
+    match invoice.status {
+        InvoiceStatus::Confirmed
+        | InvoiceStatus::Overpaid
+        | InvoiceStatus::Expired
+        | InvoiceStatus::Cancelled => true,
+        InvoiceStatus::Detected => zero_conf,
+        InvoiceStatus::Open | InvoiceStatus::PartiallyPaid => false,
+    }
+}
+
+/// One-line status for the POS screen.
+pub fn pos_status_line(invoice: &MerchantInvoice) -> String {
+    let zec = |zats: u64| zats as f64 / ZATOSHIS_PER_ZEC;
+    match invoice.status {
+        InvoiceStatus::Open => format!("Waiting for {:.8} ZEC", zec(invoice.amount_zatoshis)),
+        InvoiceStatus::PartiallyPaid => format!(
+            "Partially paid: {:.8} ZEC received, {:.8} ZEC outstanding",
+            zec(invoice.paid_zatoshis),
+            zec(invoice.outstanding_zatoshis)
+        ),
+        InvoiceStatus::Detected => "Payment detected, waiting for confirmations".to_string(),
+        InvoiceStatus::Confirmed => "PAID".to_string(),
+        InvoiceStatus::Overpaid => format!(
+            "PAID (overpaid by {:.8} ZEC, refund due)",
+            zec(invoice.overpaid_zatoshis())
+        ),
+        InvoiceStatus::Expired => "EXPIRED".to_string(),
+        InvoiceStatus::Cancelled => "CANCELLED".to_string(),
+    }
+}
+
+/// Follows one invoice: mempool payments on every poll, a sync whenever a block arrives.
+pub struct PosWatcher<'a> {
+    wallet: &'a HDWallet,
+    zebra_client: ZebraClient,
+    zebra_url: Option<String>,
+    invoice_id: String,
+    business_fvk: FullViewingKey,
+    last_tip: Option<u32>,
+    seen_mempool: HashSet<String>,
+}
+
+impl<'a> PosWatcher<'a> {
+    pub fn new(
+        wallet: &'a HDWallet,
+        config: &WalletConfig,
+        invoice_id: &str,
+        zebra_url: Option<String>,
+    ) -> NozyResult<Self> {
+        Ok(Self {
+            wallet,
+            zebra_client: ZebraClient::from_config_with_url(config, zebra_url.as_deref()),
+            zebra_url,
+            invoice_id: invoice_id.to_string(),
+            business_fvk: wallet
+                .orchard_full_viewing_key(WalletRole::Business.orchard_account())?,
+            last_tip: None,
+            seen_mempool: HashSet::new(),
+        })
+    }
+
+    /// Check the mempool (and sync on a new block); returns the current invoice.
+    pub async fn poll(&mut self) -> NozyResult<MerchantInvoice> {
+        let tip = self.zebra_client.get_block_count().await?;
+        if self.last_tip != Some(tip) {
+            // Sync matches mined payments and advances confirmations.
+            let options = WalletSyncOptions {
+                zebra_url: self.zebra_url.clone(),
+                ..WalletSyncOptions::to_tip()
+            };
+            sync_wallet_notes(self.wallet, options)
+                .await
+                .map_err(|e| NozyError::InvalidOperation(e.to_string()))?;
+            self.last_tip = Some(tip);
+        }
+
+        let mut scanner = NoteScanner::new(self.wallet, self.zebra_client.clone());
+        scanner.set_watch_fvk(self.business_fvk.clone());
+        // Only remember mempool txids once their payments are recorded, so a failed poll
+        // looks at them again.
+        let mut seen = self.seen_mempool.clone();
+        let pending = scanner.scan_mempool_watched(&mut seen).await?;
+        if !pending.is_empty() {
+            sync_invoice_payments(&self.zebra_client, &pending, tip).await?;
+        }
+        self.seen_mempool = seen;
+
+        get_invoice(&self.invoice_id)?.ok_or_else(|| {
+            NozyError::InvalidInput(format!("Invoice {} not found", self.invoice_id))
+        })
+    }
+}
+
+fn qr_modules(data: &str) -> NozyResult<(usize, Vec<bool>)> {
+    let code = QrCode::new(data.as_bytes())
+        .map_err(|e| NozyError::InvalidOperation(format!("QR encode failed: {e}")))?;
+    let width = code.width();
+    let size = width + 2 * QR_QUIET_ZONE;
+    let mut dark = vec![false; size * size];
+    for (i, color) in code.to_colors().into_iter().enumerate() {
+        let (x, y) = (i % width + QR_QUIET_ZONE, i / width + QR_QUIET_ZONE);
+        dark[y * size + x] = color == Color::Dark;
+    }
+    Ok((size, dark))
+}
+
+/// QR code as half-block text (two module rows per line), light modules drawn solid so
+/// it scans on dark terminal backgrounds.
+pub fn render_qr_terminal(data: &str) -> NozyResult<String> {
+    let (size, dark) = qr_modules(data)?;
+    let light = |x: usize, y: usize| y >= size || !dark[y * size + x];
+    let mut out = String::new();
+    for y in (0..size).step_by(2) {
+        for x in 0..size {
+            out.push(match (light(x, y), light(x, y + 1)) {
+                (true, true) => '█',
+                (true, false) => '▀',
+                (false, true) => '▄',
+                (false, false) => ' ',
+            });
+        }
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+/// QR code as a grayscale PNG, `scale` pixels per module.
+pub fn render_qr_png(data: &str, scale: u32) -> NozyResult<Vec<u8>> {
+    let (size, dark) = qr_modules(data)?;
+    let scale = scale.clamp(1, 32) as usize;
+    let side = size * scale;
+    let mut pixels = vec![0u8; side * side];
+    for py in 0..side {
+        for px in 0..side {
+            let module = dark[(py / scale) * size + px / scale];
+            pixels[py * side + px] = if module { 0x00 } else { 0xff };
+        }
+    }
+
+    let encode_err =
+        |e: png::EncodingError| NozyError::InvalidOperation(format!("PNG encode failed: {e}"));
+    let mut out = Vec::new();
+    {
+        let mut encoder = png::Encoder::new(&mut out, side as u32, side as u32);
+        encoder.set_color(png::ColorType::Grayscale);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().map_err(encode_err)?;
+        writer.write_image_data(&pixels).map_err(encode_err)?;
+    }
+    Ok(out)
+}
+
+/// Write [`render_qr_png`] to `path`.
+pub fn write_qr_png(data: &str, path: &Path, scale: u32) -> NozyResult<()> {
+    std::fs::write(path, render_qr_png(data, scale)?)
+        .map_err(|e| NozyError::Storage(format!("Failed to write {}: {e}", path.display())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fiat_converts_at_locked_rate() {
+        assert_eq!(fiat_to_zatoshis(29.99, 40.0).unwrap(), 74_975_000);
+        assert!(fiat_to_zatoshis(0.0, 40.0).is_err());
+        assert!(fiat_to_zatoshis(10.0, 0.0).is_err());
+    }
+
+    #[test]
+    fn qr_renders_terminal_and_png() {
+        let uri = "zcash:u1example?amount=0.05&memo=aW52XzE";
+        let text = render_qr_terminal(uri).unwrap();
+        let (size, _) = qr_modules(uri).unwrap();
+        assert_eq!(text.lines().count(), size.div_ceil(2));
+        assert!(text.lines().all(|l| l.chars().count() == size));
+
+        let png = render_qr_png(uri, 4).unwrap();
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+    }
+}
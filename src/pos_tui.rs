@@ -0,0 +1,166 @@
+//! Point-of-sale screen (`nozy pos sell --tui`): invoice QR plus live payment status.
+
+use crate::config::WalletConfig;
+use crate::error::{NozyError, NozyResult};
+use crate::hd_wallet::HDWallet;
+use crate::merchant_invoices::MerchantInvoice;
+use crate::pos::{pos_sale_finished, pos_status_line, render_qr_terminal, PosWatcher};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use crossterm::execute;
+use crossterm::terminal::{
+    disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
+};
+use ratatui::backend::CrosstermBackend;
+use ratatui::layout::{Alignment, Constraint, Direction, Layout};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Paragraph};
+use ratatui::Terminal;
+use std::io::{self, Stdout};
+use std::time::{Duration, Instant};
+
+fn tui_err(e: impl std::fmt::Display) -> NozyError {
+    NozyError::InvalidOperation(e.to_string())
+}
+
+fn sale_heading(invoice: &MerchantInvoice) -> String {
+    let fiat = match (invoice.amount_fiat, &invoice.fiat_currency) {
+        (Some(amount), Some(currency)) => format!("{amount:.2} {currency} = "),
+        _ => String::new(),
+    };
+    let rate = invoice
+        .locked_rate
+        .as_ref()
+        .map(|r| {
+            format!(
+                "  (1 ZEC = {:.2} {}, {})",
+                r.price_per_zec, r.currency, r.source
+            )
+        })
+        .unwrap_or_default();
+    format!("{fiat}{:.8} ZEC{rate}", invoice.amount_zec)
+}
+
+fn draw(
+    terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+    invoice: &MerchantInvoice,
+    qr: &str,
+    note: &str,
+) {
+    let _ = terminal.draw(|f| {
+        let qr_height = qr.lines().count() as u16 + 2;
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .margin(1)
+            .constraints([
+                Constraint::Length(4),
+                Constraint::Length(qr_height),
+                Constraint::Length(4),
+                Constraint::Min(3),
+            ])
+            .split(f.area());
+
+        let header = Paragraph::new(vec![
+            Line::from(Span::styled(
+                invoice
+                    .product_name
+                    .clone()
+                    .unwrap_or_else(|| "Point of sale".to_string()),
+                Style::default().add_modifier(Modifier::BOLD),
+            )),
+            Line::from(sale_heading(invoice)),
+        ])
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(invoice.invoice_id.as_str()),
+        );
+        f.render_widget(header, chunks[0]);
+
+        let code = Paragraph::new(qr.lines().map(Line::from).collect::<Vec<_>>())
+            .alignment(Alignment::Center)
+            .block(Block::default().borders(Borders::ALL).title("scan to pay"));
+        f.render_widget(code, chunks[1]);
+
+        let status = Paragraph::new(vec![
+            Line::from(Span::styled(
+                pos_status_line(invoice),
+                Style::default().add_modifier(Modifier::BOLD),
+            )),
+            Line::from(format!("expires {}", invoice.expires_at)),
+        ])
+        .block(Block::default().borders(Borders::ALL).title("status"));
+        f.render_widget(status, chunks[2]);
+
+        let help = Paragraph::new(format!("q / Esc close · {note}"))
+            .block(Block::default().borders(Borders::ALL).title("keys"));
+        f.render_widget(help, chunks[3]);
+    });
+}
+
+/// Show `invoice` as a QR code and poll it until it finishes or the cashier quits.
+pub async fn run_pos_tui(
+    wallet: &HDWallet,
+    config: &WalletConfig,
+    invoice: MerchantInvoice,
+    poll_secs: u64,
+    zero_conf: bool,
+    zebra_url: Option<String>,
+) -> NozyResult<MerchantInvoice> {
+    let interval = Duration::from_secs(poll_secs.max(1));
+    let qr = render_qr_terminal(&invoice.outstanding_payment_uri()?)?;
+    let mut watcher = PosWatcher::new(wallet, config, &invoice.invoice_id, zebra_url)?;
+
+    enable_raw_mode().map_err(tui_err)?;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen).map_err(tui_err)?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(stdout)).map_err(tui_err)?;
+
+    let result = async {
+        let mut current = invoice;
+        let mut qr = qr;
+        let mut note = format!("checking every {}s", interval.as_secs());
+        draw(&mut terminal, &current, &qr, &note);
+        let mut last = Instant::now();
+
+        loop {
+            let timeout = interval
+                .checked_sub(last.elapsed())
+                .unwrap_or(Duration::from_millis(50));
+            if event::poll(timeout).map_err(tui_err)? {
+                if let Event::Key(key) = event::read().map_err(tui_err)? {
+                    if key.kind == KeyEventKind::Press
+                        && matches!(key.code, KeyCode::Char('q') | KeyCode::Esc)
+                    {
+                        break;
+                    }
+                }
+            }
+
+            if last.elapsed() >= interval && !pos_sale_finished(&current, zero_conf) {
+                match watcher.poll().await {
+                    Ok(inv) => {
+                        if inv.outstanding_zatoshis != current.outstanding_zatoshis {
+                            qr = render_qr_terminal(&inv.outstanding_payment_uri()?)?;
+                        }
+                        current = inv;
+                        note = format!("checking every {}s", interval.as_secs());
+                    }
+                    Err(e) => note = format!("last check failed: {e}"),
+                }
+                if pos_sale_finished(&current, zero_conf) {
+                    note = "sale finished, press q to close".to_string();
+                }
+                draw(&mut terminal, &current, &qr, &note);
+                last = Instant::now();
+            }
+        }
+        Ok(current)
+    }
+    .await;
+
+    disable_raw_mode().ok();
+    execute!(terminal.backend_mut(), LeaveAlternateScreen).ok();
+    terminal.show_cursor().ok();
+    result
+}
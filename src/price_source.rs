@@ -0,0 +1,518 @@
+//! Fiat valuation: ZEC exchange rates from a local CSV table or an HTTP provider.
+//!
+//! The CSV table (`prices.csv` in the wallet data dir by default) works offline and in
+//! tests. HTTP providers are opt-in and only run through the configured Tor / I2P proxy
+//! unless [`FiatConfig::allow_direct_http`] is set, so rate lookups do not leak the
+//! user's IP or the dates of their transactions.
+
+use crate::config::WalletConfig;
+use crate::error::{NozyError, NozyResult};
+use crate::paths::get_wallet_data_dir;
+use crate::transaction_history::TransactionView;
+use crate::zebra_integration::ZebraClient;
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+pub const PRICE_TABLE_FILE: &str = "prices.csv";
+
+/// Historical lookups fall back to the latest earlier price within this window.
+pub const MAX_HISTORICAL_PRICE_GAP_DAYS: i64 = 3;
+
+const COINGECKO_API_URL: &str = "https://api.coingecko.com/api/v3";
+
+const ZATOSHIS_PER_ZEC: f64 = 100_000_000.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum PriceSourceKind {
+    None,
+    #[default]
+    Csv,
+    Coingecko,
+}
+
+/// `[fiat]` section of the wallet config.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FiatConfig {
+    /// ISO 4217 code used when the UI / CLI do not ask for a specific currency.
+    #[serde(default = "default_display_currency")]
+    pub display_currency: String,
+
+    #[serde(default)]
+    pub source: PriceSourceKind,
+
+    /// Override for the CSV price table (default: `<wallet data dir>/prices.csv`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub csv_path: Option<String>,
+
+    /// Allow HTTP price providers without Tor / I2P. Default off.
+    #[serde(default)]
+    pub allow_direct_http: bool,
+}
+
+fn default_display_currency() -> String {
+    "USD".to_string()
+}
+
+impl Default for FiatConfig {
+    fn default() -> Self {
+        Self {
+            display_currency: default_display_currency(),
+            source: PriceSourceKind::default(),
+            csv_path: None,
+            allow_direct_http: false,
+        }
+    }
+}
+
+/// Price of 1 ZEC in `currency` at `at`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PricePoint {
+    pub currency: String,
+    pub price_per_zec: f64,
+    pub at: DateTime<Utc>,
+    pub source: String,
+}
+
+/// Fiat value of a ZEC amount at a given price.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FiatValue {
+    pub currency: String,
+    /// Signed like the ZEC amount it values.
+    pub amount: f64,
+    pub price_per_zec: f64,
+    pub priced_at: DateTime<Utc>,
+    pub source: String,
+}
+
+impl FiatValue {
+    pub fn from_zatoshis(zatoshis: i64, price: &PricePoint) -> Self {
+        let amount = (zatoshis as f64 / ZATOSHIS_PER_ZEC) * price.price_per_zec;
+        Self {
+            currency: price.currency.clone(),
+            amount: (amount * 100.0).round() / 100.0,
+            price_per_zec: price.price_per_zec,
+            priced_at: price.at,
+            source: price.source.clone(),
+        }
+    }
+}
+
+/// Normalise and validate a three-letter currency code (`eur` → `EUR`).
+pub fn normalize_currency_code(code: &str) -> NozyResult<String> {
+    let code = code.trim().to_ascii_uppercase();
+    if code.len() == 3 && code.chars().all(|c| c.is_ascii_alphabetic()) {
+        Ok(code)
+    } else {
+        Err(NozyError::InvalidInput(format!(
+            "Invalid currency code '{code}' (expected ISO 4217, e.g. USD)"
+        )))
+    }
+}
+
+#[async_trait]
+pub trait PriceSource: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Most recent known price.
+    async fn spot_price(&self, currency: &str) -> NozyResult<Option<PricePoint>>;
+
+    /// Price at `at`, or `None` when the source has no data close enough.
+    async fn historical_price(
+        &self,
+        currency: &str,
+        at: DateTime<Utc>,
+    ) -> NozyResult<Option<PricePoint>>;
+}
+
+/// Local price table: `date,currency,price` rows (date as `YYYY-MM-DD` or RFC 3339).
+#[derive(Debug, Clone, Default)]
+pub struct CsvPriceTable {
+    /// Currency → points sorted by time.
+    points: HashMap<String, Vec<PricePoint>>,
+}
+
+impl CsvPriceTable {
+    pub fn default_path() -> PathBuf {
+        get_wallet_data_dir().join(PRICE_TABLE_FILE)
+    }
+
+    /// Load `path`; a missing file is an empty table.
+    pub fn load(path: &Path) -> NozyResult<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            NozyError::Storage(format!(
+                "Failed to read price table {}: {e}",
+                path.display()
+            ))
+        })?;
+        Self::parse(&content)
+    }
+
+    pub fn parse(content: &str) -> NozyResult<Self> {
+        let mut points: HashMap<String, Vec<PricePoint>> = HashMap::new();
+        for (line_no, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            if fields.len() != 3 {
+                return Err(NozyError::InvalidInput(format!(
+                    "price table line {}: expected date,currency,price",
+                    line_no + 1
+                )));
+            }
+            if line_no == 0 && fields[0].eq_ignore_ascii_case("date") {
+                continue;
+            }
+            let at = parse_price_time(fields[0]).ok_or_else(|| {
+                NozyError::InvalidInput(format!(
+                    "price table line {}: invalid date '{}'",
+                    line_no + 1,
+                    fields[0]
+                ))
+            })?;
+            let currency = normalize_currency_code(fields[1])?;
+            let price_per_zec: f64 = fields[2]
+                .parse()
+                .ok()
+                .filter(|p: &f64| *p > 0.0)
+                .ok_or_else(|| {
+                    NozyError::InvalidInput(format!(
+                        "price table line {}: invalid price '{}'",
+                        line_no + 1,
+                        fields[2]
+                    ))
+                })?;
+            points
+                .entry(currency.clone())
+                .or_default()
+                .push(PricePoint {
+                    currency,
+                    price_per_zec,
+                    at,
+                    source: "csv".to_string(),
+                });
+        }
+        for series in points.values_mut() {
+            series.sort_by_key(|p| p.at);
+        }
+        Ok(Self { points })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    fn latest(&self, currency: &str) -> Option<PricePoint> {
+        self.points.get(currency)?.last().cloned()
+    }
+
+    fn at_or_before(&self, currency: &str, at: DateTime<Utc>) -> Option<PricePoint> {
+        let series = self.points.get(currency)?;
+        let idx = series.partition_point(|p| p.at <= at);
+        let point = series.get(idx.checked_sub(1)?)?;
+        (at - point.at <= Duration::days(MAX_HISTORICAL_PRICE_GAP_DAYS)).then(|| point.clone())
+    }
+}
+
+fn parse_price_time(s: &str) -> Option<DateTime<Utc>> {
+    if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+        return Some(dt.with_timezone(&Utc));
+    }
+    NaiveDate::parse_from_str(s, "%Y-%m-%d")
+        .ok()?
+        .and_hms_opt(0, 0, 0)
+        .map(|d| d.and_utc())
+}
+
+#[async_trait]
+impl PriceSource for CsvPriceTable {
+    fn name(&self) -> &'static str {
+        "csv"
+    }
+
+    async fn spot_price(&self, currency: &str) -> NozyResult<Option<PricePoint>> {
+        Ok(self.latest(&normalize_currency_code(currency)?))
+    }
+
+    async fn historical_price(
+        &self,
+        currency: &str,
+        at: DateTime<Utc>,
+    ) -> NozyResult<Option<PricePoint>> {
+        Ok(self.at_or_before(&normalize_currency_code(currency)?, at))
+    }
+}
+
+/// HTTP client for price providers, routed through the configured privacy proxy.
+pub fn price_http_client(config: &WalletConfig) -> NozyResult<reqwest::Client> {
+    let mut builder = reqwest::Client::builder().timeout(std::time::Duration::from_secs(30));
+    match ZebraClient::selected_proxy_from_config(config) {
+        Some(proxy_url) => {
+            let proxy = reqwest::Proxy::all(&proxy_url).map_err(|e| {
+                NozyError::NetworkError(format!("Invalid privacy proxy URL '{proxy_url}': {e}"))
+            })?;
+            builder = builder.proxy(proxy);
+        }
+        None if config.fiat.allow_direct_http => {}
+        None => {
+            return Err(NozyError::NetworkError(
+                "HTTP price lookups require Tor or I2P (privacy_network); set \
+                 fiat.allow_direct_http = true to allow clearnet, or use the CSV price table"
+                    .to_string(),
+            ))
+        }
+    }
+    builder
+        .build()
+        .map_err(|e| NozyError::NetworkError(format!("Failed to create HTTP client: {e}")))
+}
+
+/// CoinGecko public API. Historical prices are daily; lookups are cached per day.
+pub struct CoinGeckoPriceSource {
+    client: reqwest::Client,
+    base_url: String,
+    daily_cache: Mutex<HashMap<(String, NaiveDate), Option<f64>>>,
+}
+
+impl CoinGeckoPriceSource {
+    pub fn new(client: reqwest::Client) -> Self {
+        Self::with_base_url(client, COINGECKO_API_URL)
+    }
+
+    pub fn with_base_url(client: reqwest::Client, base_url: impl Into<String>) -> Self {
+        Self {
+            client,
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            daily_cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    async fn get_json(&self, url: &str) -> NozyResult<serde_json::Value> {
+        let res = self
+            .client
+            .get(url)
+            .send()
+            .await
+            .map_err(|e| NozyError::NetworkError(format!("Price provider unreachable: {e}")))?;
+        if !res.status().is_success() {
+            return Err(NozyError::NetworkError(format!(
+                "Price provider HTTP {}",
+                res.status()
+            )));
+        }
+        res.json()
+            .await
+            .map_err(|e| NozyError::NetworkError(format!("Invalid price provider response: {e}")))
+    }
+}
+
+#[async_trait]
+impl PriceSource for CoinGeckoPriceSource {
+    fn name(&self) -> &'static str {
+        "coingecko"
+    }
+
+    async fn spot_price(&self, currency: &str) -> NozyResult<Option<PricePoint>> {
+        let currency = normalize_currency_code(currency)?;
+        let key = currency.to_ascii_lowercase();
+        let url = format!(
+            "{}/simple/price?ids=zcash&vs_currencies={key}",
+            self.base_url
+        );
+        let body = self.get_json(&url).await?;
+        Ok(body["zcash"][&key]
+            .as_f64()
+            .map(|price_per_zec| PricePoint {
+                currency,
+                price_per_zec,
+                at: Utc::now(),
+                source: self.name().to_string(),
+            }))
+    }
+
+    async fn historical_price(
+        &self,
+        currency: &str,
+        at: DateTime<Utc>,
+    ) -> NozyResult<Option<PricePoint>> {
+        let currency = normalize_currency_code(currency)?;
+        let day = at.date_naive();
+        let cache_key = (currency.clone(), day);
+        let cached = self
+            .daily_cache
+            .lock()
+            .ok()
+            .and_then(|cache| cache.get(&cache_key).copied());
+        let price = match cached {
+            Some(p) => p,
+            None => {
+                let url = format!(
+                    "{}/coins/zcash/history?date={}&localization=false",
+                    self.base_url,
+                    day.format("%d-%m-%Y")
+                );
+                let body = self.get_json(&url).await?;
+                let p =
+                    body["market_data"]["current_price"][currency.to_ascii_lowercase()].as_f64();
+                if let Ok(mut cache) = self.daily_cache.lock() {
+                    cache.insert(cache_key, p);
+                }
+                p
+            }
+        };
+        Ok(price.map(|price_per_zec| PricePoint {
+            currency,
+            price_per_zec,
+            at: day.and_hms_opt(0, 0, 0).map(|d| d.and_utc()).unwrap_or(at),
+            source: self.name().to_string(),
+        }))
+    }
+}
+
+/// Price source selected by `config.fiat`, or `None` when valuation is disabled.
+pub fn configured_price_source(config: &WalletConfig) -> NozyResult<Option<Box<dyn PriceSource>>> {
+    match config.fiat.source {
+        PriceSourceKind::None => Ok(None),
+        PriceSourceKind::Csv => {
+            let path = config
+                .fiat
+                .csv_path
+                .as_ref()
+                .map(PathBuf::from)
+                .unwrap_or_else(CsvPriceTable::default_path);
+            Ok(Some(Box::new(CsvPriceTable::load(&path)?)))
+        }
+        PriceSourceKind::Coingecko => Ok(Some(Box::new(CoinGeckoPriceSource::new(
+            price_http_client(config)?,
+        )))),
+    }
+}
+
+/// Fill [`TransactionView::fiat_value`] at each transaction's block time.
+///
+/// Views without a block time are valued at spot when `spot_for_undated` is set (e.g.
+/// pending sends), otherwise left empty. Returns how many views were valued.
+pub async fn apply_fiat_values(
+    views: &mut [TransactionView],
+    source: &dyn PriceSource,
+    currency: &str,
+    spot_for_undated: bool,
+) -> NozyResult<usize> {
+    let currency = normalize_currency_code(currency)?;
+    let mut spot: Option<Option<PricePoint>> = None;
+    let mut valued = 0usize;
+    for view in views.iter_mut() {
+        let price = match view.block_time {
+            Some(t) => source.historical_price(&currency, t).await?,
+            None if spot_for_undated => {
+                if spot.is_none() {
+                    spot = Some(source.spot_price(&currency).await?);
+                }
+                spot.clone().flatten()
+            }
+            None => None,
+        };
+        if let Some(price) = price {
+            view.fiat_value = Some(FiatValue::from_zatoshis(view.net_amount_zatoshis, &price));
+            valued += 1;
+        }
+    }
+    Ok(valued)
+}
+
+/// [`apply_fiat_values`] with the configured source; no-op when valuation is disabled.
+pub async fn apply_configured_fiat_values(
+    views: &mut [TransactionView],
+    currency: &str,
+) -> NozyResult<usize> {
+    let config = crate::config::load_config();
+    match configured_price_source(&config)? {
+        Some(source) => apply_fiat_values(views, source.as_ref(), currency, true).await,
+        None => Ok(0),
+    }
+}
+
+/// Spot fiat value of `zatoshis` with the configured source.
+pub async fn fiat_value_at_spot(
+    config: &WalletConfig,
+    currency: Option<&str>,
+    zatoshis: u64,
+) -> NozyResult<Option<FiatValue>> {
+    let currency = currency.unwrap_or(&config.fiat.display_currency);
+    let Some(source) = configured_price_source(config)? else {
+        return Ok(None);
+    };
+    Ok(source
+        .spot_price(currency)
+        .await?
+        .map(|p| FiatValue::from_zatoshis(zatoshis as i64, &p)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    const TABLE: &str = "date,currency,price\n\
+        2026-03-01,USD,40.00\n\
+        2026-03-02,usd,42.50\n\
+        2026-03-02T12:00:00Z,EUR,39.00\n";
+
+    #[test]
+    fn csv_table_parses_and_sorts() {
+        let table = CsvPriceTable::parse(TABLE).unwrap();
+        assert_eq!(table.latest("USD").unwrap().price_per_zec, 42.5);
+        assert_eq!(table.latest("EUR").unwrap().price_per_zec, 39.0);
+        assert!(table.latest("GBP").is_none());
+    }
+
+    #[test]
+    fn csv_table_uses_latest_earlier_price_within_gap() {
+        let table = CsvPriceTable::parse(TABLE).unwrap();
+        let t = Utc.with_ymd_and_hms(2026, 3, 1, 18, 0, 0).unwrap();
+        assert_eq!(table.at_or_before("USD", t).unwrap().price_per_zec, 40.0);
+        let before = Utc.with_ymd_and_hms(2026, 2, 28, 0, 0, 0).unwrap();
+        assert!(table.at_or_before("USD", before).is_none());
+        let stale = Utc.with_ymd_and_hms(2026, 3, 10, 0, 0, 0).unwrap();
+        assert!(table.at_or_before("USD", stale).is_none());
+    }
+
+    #[test]
+    fn csv_table_rejects_bad_rows() {
+        assert!(CsvPriceTable::parse("2026-03-01,USD").is_err());
+        assert!(CsvPriceTable::parse("2026-03-01,USD,-1").is_err());
+        assert!(CsvPriceTable::parse("yesterday,USD,40").is_err());
+        assert!(CsvPriceTable::parse("2026-03-01,DOLLARS,40").is_err());
+    }
+
+    #[test]
+    fn fiat_value_is_signed_and_rounded() {
+        let price = PricePoint {
+            currency: "USD".into(),
+            price_per_zec: 42.5,
+            at: Utc::now(),
+            source: "csv".into(),
+        };
+        assert_eq!(FiatValue::from_zatoshis(150_000_000, &price).amount, 63.75);
+        assert_eq!(FiatValue::from_zatoshis(-33_333_333, &price).amount, -14.17);
+    }
+
+    #[test]
+    fn http_client_requires_privacy_proxy_by_default() {
+        let mut config = WalletConfig::default();
+        config.privacy_network.tor_enabled = false;
+        config.privacy_network.i2p_enabled = false;
+        assert!(price_http_client(&config).is_err());
+        config.fiat.allow_direct_http = true;
+        assert!(price_http_client(&config).is_ok());
+    }
+}
@@ -0,0 +1,760 @@
+//! Recurring payroll-style payments stored in the encrypted schedule vault.
+//!
+//! Payments live in `scheduled_payments.json`, encrypted with the same vault key as the
+//! Ironwood migration schedule (`NZS1`). [`run_due_payments`] is the runner used by
+//! `nozy schedule run`, `POST /api/schedule/run` and the background daemon: it sends each
+//! due payment once, records a [`PaymentRun`], and advances the payment past `now`, so a
+//! wallet that was offline for several periods pays once rather than catching up.
+//!
+//! The CLI and the api-server daemon are separate processes, so runners take a lock file
+//! next to the schedule, and each payment is marked in flight on disk before it is sent.
+
+use crate::cli_helpers::scan_notes_for_sending;
+use crate::config::load_config;
+use crate::error::{NozyError, NozyResult};
+use crate::fee_policy::{estimate_orchard_send_fee_zatoshis, PilotSendOptions};
+use crate::hd_wallet::HDWallet;
+use crate::input_validation::{normalize_unified_address, validate_memo, validate_zcash_address};
+use crate::notes::mark_wallet_notes_spent_by_nullifier_hex;
+use crate::notes_vault::{decrypt_schedule_file_content, encrypt_schedule_json};
+use crate::paths::get_wallet_data_dir;
+use crate::transaction_builder::ZcashTransactionBuilder;
+use crate::transaction_history::{SentTransactionRecord, SentTransactionStorage};
+use crate::zebra_integration::ZebraClient;
+use chrono::{DateTime, Datelike, Duration, Months, Utc};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+pub const SCHEDULED_PAYMENTS_FILE: &str = "scheduled_payments.json";
+pub const SCHEDULED_PAYMENTS_VERSION: u32 = 1;
+pub const SCHEDULED_PAYMENTS_LOCK_FILE: &str = "scheduled_payments.lock";
+
+/// Oldest run log entries are dropped past this many.
+const MAX_RUN_LOG: usize = 500;
+
+/// A runner lock older than this was left behind by a crashed process.
+const STALE_RUN_LOCK_SECS: u64 = 30 * 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PaymentFrequency {
+    Weekly,
+    Monthly,
+}
+
+impl PaymentFrequency {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Weekly => "weekly",
+            Self::Monthly => "monthly",
+        }
+    }
+
+    /// Next occurrence after `from`. Monthly payments keep `anchor_day` where the month
+    /// has it and fall back to the last day otherwise (31st → Feb 28th → Mar 31st).
+    pub fn next_occurrence(self, from: DateTime<Utc>, anchor_day: u32) -> DateTime<Utc> {
+        match self {
+            Self::Weekly => from + Duration::weeks(1),
+            Self::Monthly => {
+                let first = from
+                    .with_day(1)
+                    .and_then(|d| d.checked_add_months(Months::new(1)))
+                    .unwrap_or(from + Duration::days(30));
+                let last_day = days_in_month(first.year(), first.month());
+                first
+                    .with_day(anchor_day.clamp(1, last_day))
+                    .unwrap_or(first)
+            }
+        }
+    }
+}
+
+impl FromStr for PaymentFrequency {
+    type Err = NozyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "weekly" | "week" => Ok(Self::Weekly),
+            "monthly" | "month" => Ok(Self::Monthly),
+            other => Err(NozyError::InvalidInput(format!(
+                "Unknown frequency '{other}' (expected weekly or monthly)"
+            ))),
+        }
+    }
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    let (next_year, next_month) = if month == 12 {
+        (year + 1, 1)
+    } else {
+        (year, month + 1)
+    };
+    chrono::NaiveDate::from_ymd_opt(next_year, next_month, 1)
+        .and_then(|d| d.pred_opt())
+        .map(|d| d.day())
+        .unwrap_or(28)
+}
+
+/// What the runner does when a due payment cannot be covered or fails to send.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InsufficientFundsPolicy {
+    /// Record an alert and move on to the next period.
+    #[default]
+    Skip,
+    /// Record an alert and try again on the next run.
+    Retry,
+}
+
+impl FromStr for InsufficientFundsPolicy {
+    type Err = NozyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "skip" => Ok(Self::Skip),
+            "retry" => Ok(Self::Retry),
+            other => Err(NozyError::InvalidInput(format!(
+                "Unknown insufficient-funds policy '{other}' (expected skip or retry)"
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScheduledPayment {
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    pub recipient: String,
+    pub amount_zatoshis: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+    pub frequency: PaymentFrequency,
+    pub next_due: DateTime<Utc>,
+    /// Day of month monthly payments aim for (from the first due date).
+    pub anchor_day: u32,
+    /// Payments left to send; `None` = repeat until removed. Skipped periods do not count.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remaining_runs: Option<u32>,
+    #[serde(default)]
+    pub on_insufficient_funds: InsufficientFundsPolicy,
+    pub enabled: bool,
+    pub created_at: DateTime<Utc>,
+    /// Set (and saved) before a send starts and cleared when its run is recorded. A payment
+    /// still marked on the next run was interrupted mid-send and is not paid again.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub in_flight_since: Option<DateTime<Utc>>,
+}
+
+impl ScheduledPayment {
+    pub fn is_due(&self, now: DateTime<Utc>) -> bool {
+        self.enabled && self.remaining_runs != Some(0) && self.next_due <= now
+    }
+
+    /// Move `next_due` to the first occurrence after `now`; sent runs count down `remaining_runs`.
+    fn advance(&mut self, now: DateTime<Utc>, sent: bool) {
+        while self.next_due <= now {
+            self.next_due = self
+                .frequency
+                .next_occurrence(self.next_due, self.anchor_day);
+        }
+        if !sent {
+            return;
+        }
+        if let Some(left) = self.remaining_runs.as_mut() {
+            *left = left.saturating_sub(1);
+            if *left == 0 {
+                self.enabled = false;
+            }
+        }
+    }
+}
+
+/// Input for [`PaymentSchedule::add`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NewScheduledPayment {
+    #[serde(default)]
+    pub label: Option<String>,
+    pub recipient: String,
+    pub amount_zatoshis: u64,
+    #[serde(default)]
+    pub memo: Option<String>,
+    pub frequency: Option<PaymentFrequency>,
+    /// First due date; defaults to now.
+    #[serde(default)]
+    pub start_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub runs: Option<u32>,
+    #[serde(default)]
+    pub on_insufficient_funds: InsufficientFundsPolicy,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum PaymentRunOutcome {
+    Sent {
+        txid: String,
+    },
+    InsufficientFunds {
+        available_zatoshis: u64,
+        needed_zatoshis: u64,
+    },
+    Failed {
+        error: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PaymentRun {
+    pub payment_id: String,
+    pub due_at: DateTime<Utc>,
+    pub ran_at: DateTime<Utc>,
+    pub recipient: String,
+    pub amount_zatoshis: u64,
+    #[serde(flatten)]
+    pub outcome: PaymentRunOutcome,
+}
+
+impl PaymentRun {
+    /// Runs that need the user's attention (insufficient funds or a failed send).
+    pub fn is_alert(&self) -> bool {
+        !matches!(self.outcome, PaymentRunOutcome::Sent { .. })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PaymentSchedule {
+    pub version: u32,
+    #[serde(default)]
+    pub payments: Vec<ScheduledPayment>,
+    #[serde(default)]
+    pub runs: Vec<PaymentRun>,
+}
+
+impl Default for PaymentSchedule {
+    fn default() -> Self {
+        Self {
+            version: SCHEDULED_PAYMENTS_VERSION,
+            payments: Vec::new(),
+            runs: Vec::new(),
+        }
+    }
+}
+
+impl PaymentSchedule {
+    pub fn add(
+        &mut self,
+        new: NewScheduledPayment,
+        now: DateTime<Utc>,
+    ) -> NozyResult<ScheduledPayment> {
+        let recipient = normalize_unified_address(&new.recipient);
+        validate_zcash_address(&recipient)?;
+        if new.amount_zatoshis == 0 {
+            return Err(NozyError::InvalidInput(
+                "Scheduled amount must be greater than zero".into(),
+            ));
+        }
+        if let Some(memo) = new.memo.as_deref() {
+            validate_memo(memo)?;
+        }
+        if new.runs == Some(0) {
+            return Err(NozyError::InvalidInput("runs must be at least 1".into()));
+        }
+        let next_due = new.start_at.unwrap_or(now);
+        let payment = ScheduledPayment {
+            id: new_payment_id(),
+            label: new.label.filter(|l| !l.trim().is_empty()),
+            recipient,
+            amount_zatoshis: new.amount_zatoshis,
+            memo: new.memo.filter(|m| !m.is_empty()),
+            frequency: new.frequency.unwrap_or(PaymentFrequency::Monthly),
+            next_due,
+            anchor_day: next_due.day(),
+            remaining_runs: new.runs,
+            on_insufficient_funds: new.on_insufficient_funds,
+            enabled: true,
+            created_at: now,
+            in_flight_since: None,
+        };
+        self.payments.push(payment.clone());
+        Ok(payment)
+    }
+
+    pub fn get(&self, id: &str) -> Option<&ScheduledPayment> {
+        self.payments.iter().find(|p| p.id == id)
+    }
+
+    pub fn remove(&mut self, id: &str) -> NozyResult<ScheduledPayment> {
+        let pos = self
+            .payments
+            .iter()
+            .position(|p| p.id == id)
+            .ok_or_else(|| unknown_payment(id))?;
+        Ok(self.payments.remove(pos))
+    }
+
+    pub fn set_enabled(&mut self, id: &str, enabled: bool) -> NozyResult<()> {
+        let payment = self
+            .payments
+            .iter_mut()
+            .find(|p| p.id == id)
+            .ok_or_else(|| unknown_payment(id))?;
+        if enabled && payment.remaining_runs == Some(0) {
+            return Err(NozyError::InvalidOperation(format!(
+                "Scheduled payment {id} has no runs left"
+            )));
+        }
+        payment.enabled = enabled;
+        Ok(())
+    }
+
+    /// Ids of enabled payments due at `now`, oldest due first.
+    pub fn due_ids(&self, now: DateTime<Utc>) -> Vec<String> {
+        let mut due: Vec<&ScheduledPayment> =
+            self.payments.iter().filter(|p| p.is_due(now)).collect();
+        due.sort_by_key(|p| p.next_due);
+        due.into_iter().map(|p| p.id.clone()).collect()
+    }
+
+    /// Mark `id` as being sent; saved before broadcasting so another runner skips it.
+    pub fn mark_in_flight(&mut self, id: &str, now: DateTime<Utc>) {
+        if let Some(payment) = self.payments.iter_mut().find(|p| p.id == id) {
+            payment.in_flight_since = Some(now);
+        }
+    }
+
+    /// Log `run` and advance its payment unless it should be retried next time.
+    pub fn record_run(&mut self, run: PaymentRun, now: DateTime<Utc>) {
+        if let Some(payment) = self.payments.iter_mut().find(|p| p.id == run.payment_id) {
+            payment.in_flight_since = None;
+            let sent = !run.is_alert();
+            if sent || payment.on_insufficient_funds == InsufficientFundsPolicy::Skip {
+                payment.advance(now, sent);
+            }
+        }
+        self.push_run(run);
+    }
+
+    /// Log an interrupted send and move past its period whatever the policy: the
+    /// transaction may have been broadcast, so retrying could pay twice.
+    pub fn record_interrupted_run(&mut self, run: PaymentRun, now: DateTime<Utc>) {
+        if let Some(payment) = self.payments.iter_mut().find(|p| p.id == run.payment_id) {
+            payment.in_flight_since = None;
+            payment.advance(now, false);
+        }
+        self.push_run(run);
+    }
+
+    fn push_run(&mut self, run: PaymentRun) {
+        self.runs.push(run);
+        if self.runs.len() > MAX_RUN_LOG {
+            let excess = self.runs.len() - MAX_RUN_LOG;
+            self.runs.drain(..excess);
+        }
+    }
+
+    /// Most recent runs first, optionally for one payment.
+    pub fn recent_runs(&self, payment_id: Option<&str>, limit: usize) -> Vec<PaymentRun> {
+        self.runs
+            .iter()
+            .rev()
+            .filter(|r| payment_id.is_none_or(|id| r.payment_id == id))
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+}
+
+/// First due time from `YYYY-MM-DD` (midnight UTC) or RFC 3339.
+pub fn parse_schedule_start(value: &str) -> NozyResult<DateTime<Utc>> {
+    let value = value.trim();
+    if let Ok(at) = DateTime::parse_from_rfc3339(value) {
+        return Ok(at.with_timezone(&Utc));
+    }
+    crate::cost_basis::parse_acquired_date(value).map_err(|_| {
+        NozyError::InvalidInput(format!(
+            "Invalid start '{value}' (expected YYYY-MM-DD or RFC 3339)"
+        ))
+    })
+}
+
+fn unknown_payment(id: &str) -> NozyError {
+    NozyError::InvalidInput(format!(
+        "Unknown scheduled payment {id} (see `nozy schedule list`)"
+    ))
+}
+
+fn new_payment_id() -> String {
+    let mut bytes = [0u8; 8];
+    OsRng.fill_bytes(&mut bytes);
+    format!("{:016x}", u64::from_be_bytes(bytes))
+}
+
+pub fn scheduled_payments_path() -> PathBuf {
+    get_wallet_data_dir().join(SCHEDULED_PAYMENTS_FILE)
+}
+
+pub fn load_payment_schedule() -> NozyResult<PaymentSchedule> {
+    let path = scheduled_payments_path();
+    if !path.exists() {
+        return Ok(PaymentSchedule::default());
+    }
+    let content = fs::read_to_string(&path)
+        .map_err(|e| NozyError::Storage(format!("Failed to read scheduled payments: {e}")))?;
+    let plaintext = decrypt_schedule_file_content(&content)?;
+    serde_json::from_str(&plaintext)
+        .map_err(|e| NozyError::Storage(format!("Failed to parse scheduled payments: {e}")))
+}
+
+pub fn save_payment_schedule(schedule: &PaymentSchedule) -> NozyResult<()> {
+    let path = scheduled_payments_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| {
+            NozyError::Storage(format!(
+                "Failed to create scheduled payments directory: {e}"
+            ))
+        })?;
+    }
+    let serialized = serde_json::to_string_pretty(schedule)
+        .map_err(|e| NozyError::Storage(format!("Failed to serialize scheduled payments: {e}")))?;
+    let encrypted = encrypt_schedule_json(&serialized)?;
+    let temp_path = path.with_extension("tmp");
+    fs::write(&temp_path, encrypted)
+        .map_err(|e| NozyError::Storage(format!("Failed to write scheduled payments: {e}")))?;
+    fs::rename(&temp_path, &path)
+        .map_err(|e| NozyError::Storage(format!("Failed to save scheduled payments: {e}")))?;
+    Ok(())
+}
+
+/// Add a payment due from `new.start_at` (or now) and persist it.
+pub fn add_scheduled_payment(new: NewScheduledPayment) -> NozyResult<ScheduledPayment> {
+    let mut schedule = load_payment_schedule()?;
+    let payment = schedule.add(new, Utc::now())?;
+    save_payment_schedule(&schedule)?;
+    Ok(payment)
+}
+
+pub fn remove_scheduled_payment(id: &str) -> NozyResult<ScheduledPayment> {
+    let mut schedule = load_payment_schedule()?;
+    let removed = schedule.remove(id)?;
+    save_payment_schedule(&schedule)?;
+    Ok(removed)
+}
+
+/// Pause (`false`) or resume (`true`) a payment.
+pub fn set_scheduled_payment_enabled(id: &str, enabled: bool) -> NozyResult<()> {
+    let mut schedule = load_payment_schedule()?;
+    schedule.set_enabled(id, enabled)?;
+    save_payment_schedule(&schedule)
+}
+
+/// Exclusive lock file held while [`run_due_payments`] sends; removed on drop.
+struct RunLock {
+    path: PathBuf,
+}
+
+impl RunLock {
+    fn acquire() -> NozyResult<Self> {
+        let path = get_wallet_data_dir().join(SCHEDULED_PAYMENTS_LOCK_FILE);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| {
+                NozyError::Storage(format!(
+                    "Failed to create scheduled payments directory: {e}"
+                ))
+            })?;
+        }
+        for _ in 0..2 {
+            match fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&path)
+            {
+                Ok(mut file) => {
+                    let _ = writeln!(file, "{}", std::process::id());
+                    return Ok(Self { path });
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                    let stale = fs::metadata(&path)
+                        .and_then(|m| m.modified())
+                        .ok()
+                        .and_then(|t| t.elapsed().ok())
+                        .is_some_and(|age| age.as_secs() > STALE_RUN_LOCK_SECS);
+                    if !stale {
+                        break;
+                    }
+                    let _ = fs::remove_file(&path);
+                }
+                Err(e) => {
+                    return Err(NozyError::Storage(format!(
+                        "Failed to lock scheduled payments: {e}"
+                    )))
+                }
+            }
+        }
+        Err(NozyError::InvalidOperation(
+            "Scheduled payments are already being run by another Nozy process".into(),
+        ))
+    }
+}
+
+impl Drop for RunLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Send every payment due now and persist the outcomes. Requires an unlocked vault.
+pub async fn run_due_payments(wallet: &HDWallet, zebra_url: &str) -> NozyResult<Vec<PaymentRun>> {
+    let _lock = RunLock::acquire()?;
+    let now = Utc::now();
+    let mut schedule = load_payment_schedule()?;
+    let due = schedule.due_ids(now);
+    let mut runs = Vec::with_capacity(due.len());
+    for id in due {
+        let Some(payment) = schedule.get(&id).cloned() else {
+            continue;
+        };
+        let run_with = |outcome| PaymentRun {
+            payment_id: payment.id.clone(),
+            due_at: payment.next_due,
+            ran_at: Utc::now(),
+            recipient: payment.recipient.clone(),
+            amount_zatoshis: payment.amount_zatoshis,
+            outcome,
+        };
+        if let Some(since) = payment.in_flight_since {
+            let run = run_with(PaymentRunOutcome::Failed {
+                error: format!(
+                    "A send started at {} was interrupted and may have been broadcast; \
+                     check `nozy history` before paying this period by hand",
+                    since.to_rfc3339()
+                ),
+            });
+            schedule.record_interrupted_run(run.clone(), now);
+            save_payment_schedule(&schedule)?;
+            runs.push(run);
+            continue;
+        }
+
+        schedule.mark_in_flight(&id, Utc::now());
+        save_payment_schedule(&schedule)?;
+        let outcome = match send_scheduled_payment(wallet, zebra_url, &payment).await {
+            Ok(outcome) => outcome,
+            Err(e) => PaymentRunOutcome::Failed {
+                error: e.to_string(),
+            },
+        };
+        let run = run_with(outcome);
+        schedule.record_run(run.clone(), now);
+        // Save after every send so a crash mid-batch cannot pay the same period twice.
+        save_payment_schedule(&schedule)?;
+        runs.push(run);
+    }
+    Ok(runs)
+}
+
+async fn send_scheduled_payment(
+    wallet: &HDWallet,
+    zebra_url: &str,
+    payment: &ScheduledPayment,
+) -> NozyResult<PaymentRunOutcome> {
+    let mut config = load_config();
+    if config.zebra_url != zebra_url {
+        config.zebra_url = zebra_url.to_string();
+    }
+    let zebra_client = ZebraClient::from_config(&config);
+
+    let spendable_notes = scan_notes_for_sending(wallet, zebra_url).await?;
+    let memo = payment.memo.as_ref().map(|m| m.as_bytes().to_vec());
+    let pilot = PilotSendOptions::for_send();
+    let fee_zatoshis = estimate_orchard_send_fee_zatoshis(memo.as_deref(), pilot.priority);
+    let needed = payment.amount_zatoshis.saturating_add(fee_zatoshis);
+    let available: u64 = spendable_notes.iter().map(|n| n.orchard_note.value).sum();
+    // Sends spend a single note, so the largest note has to cover the payment.
+    let largest = spendable_notes
+        .iter()
+        .map(|n| n.orchard_note.value)
+        .max()
+        .unwrap_or(0);
+    if largest < needed {
+        return Ok(PaymentRunOutcome::InsufficientFunds {
+            available_zatoshis: available,
+            needed_zatoshis: needed,
+        });
+    }
+
+    let mut tx_builder = ZcashTransactionBuilder::new();
+    tx_builder.set_zebra_url(zebra_url);
+    tx_builder.enable_mainnet_broadcast();
+    let transaction = tx_builder
+        .build_and_broadcast_send_transaction(
+            &zebra_client,
+            &spendable_notes,
+            &payment.recipient,
+            payment.amount_zatoshis,
+            fee_zatoshis,
+            memo.as_deref(),
+            pilot,
+        )
+        .await?;
+    let txid = transaction.txid.clone();
+
+    // The payment is on the network from here on: bookkeeping failures are warnings, never
+    // a failed run, or the retry policy would send it again.
+    let spent_note_ids: Vec<String> = transaction.spent_nullifier_hex.iter().cloned().collect();
+    if spent_note_ids.is_empty() {
+        eprintln!("Warning: scheduled payment {txid} did not report its spent note");
+    } else if let Err(e) = mark_wallet_notes_spent_by_nullifier_hex(&spent_note_ids, Some(&txid)) {
+        eprintln!("Warning: could not mark spent notes locally after scheduled payment: {e}");
+    }
+
+    let mut record = SentTransactionRecord::new_pilot(
+        txid.clone(),
+        payment.recipient.clone(),
+        payment.amount_zatoshis,
+        fee_zatoshis,
+        memo,
+        spent_note_ids,
+        pilot.priority,
+        transaction.expiry_height,
+    );
+    record.mark_broadcast();
+    if let Err(e) = SentTransactionStorage::new().and_then(|s| s.save_transaction(record)) {
+        eprintln!("Warning: could not save scheduled payment {txid} to history: {e}");
+    }
+
+    Ok(PaymentRunOutcome::Sent { txid })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(y: i32, m: u32, d: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, m, d, 9, 0, 0).unwrap()
+    }
+
+    fn payment(frequency: PaymentFrequency, next_due: DateTime<Utc>) -> ScheduledPayment {
+        ScheduledPayment {
+            id: "p1".to_string(),
+            label: None,
+            recipient: "u1test".to_string(),
+            amount_zatoshis: 1_000_000,
+            memo: None,
+            frequency,
+            next_due,
+            anchor_day: next_due.day(),
+            remaining_runs: None,
+            on_insufficient_funds: InsufficientFundsPolicy::Skip,
+            enabled: true,
+            created_at: next_due,
+            in_flight_since: None,
+        }
+    }
+
+    #[test]
+    fn monthly_keeps_anchor_day_across_short_months() {
+        let f = PaymentFrequency::Monthly;
+        let feb = f.next_occurrence(at(2026, 1, 31), 31);
+        assert_eq!(feb, at(2026, 2, 28));
+        assert_eq!(f.next_occurrence(feb, 31), at(2026, 3, 31));
+        assert_eq!(f.next_occurrence(at(2026, 12, 15), 15), at(2027, 1, 15));
+        assert_eq!(
+            PaymentFrequency::Weekly.next_occurrence(at(2026, 3, 1), 1),
+            at(2026, 3, 8)
+        );
+    }
+
+    #[test]
+    fn missed_periods_advance_past_now_once() {
+        let mut schedule = PaymentSchedule {
+            payments: vec![payment(PaymentFrequency::Weekly, at(2026, 3, 1))],
+            ..PaymentSchedule::default()
+        };
+        let now = at(2026, 3, 20);
+        assert_eq!(schedule.due_ids(now), vec!["p1".to_string()]);
+        schedule.record_run(
+            PaymentRun {
+                payment_id: "p1".to_string(),
+                due_at: at(2026, 3, 1),
+                ran_at: now,
+                recipient: "u1test".to_string(),
+                amount_zatoshis: 1_000_000,
+                outcome: PaymentRunOutcome::Sent { txid: "aa".into() },
+            },
+            now,
+        );
+        assert_eq!(schedule.payments[0].next_due, at(2026, 3, 22));
+        assert!(schedule.due_ids(now).is_empty());
+    }
+
+    #[test]
+    fn retry_policy_keeps_payment_due_and_runs_count_down() {
+        let mut p = payment(PaymentFrequency::Monthly, at(2026, 3, 1));
+        p.on_insufficient_funds = InsufficientFundsPolicy::Retry;
+        p.remaining_runs = Some(1);
+        let mut schedule = PaymentSchedule {
+            payments: vec![p],
+            ..PaymentSchedule::default()
+        };
+        let now = at(2026, 3, 2);
+        let run = |outcome| PaymentRun {
+            payment_id: "p1".to_string(),
+            due_at: at(2026, 3, 1),
+            ran_at: now,
+            recipient: "u1test".to_string(),
+            amount_zatoshis: 1_000_000,
+            outcome,
+        };
+        schedule.record_run(
+            run(PaymentRunOutcome::InsufficientFunds {
+                available_zatoshis: 0,
+                needed_zatoshis: 1_010_000,
+            }),
+            now,
+        );
+        assert_eq!(schedule.due_ids(now).len(), 1);
+        assert!(schedule.runs[0].is_alert());
+
+        schedule.record_run(run(PaymentRunOutcome::Sent { txid: "bb".into() }), now);
+        assert_eq!(schedule.payments[0].remaining_runs, Some(0));
+        assert!(!schedule.payments[0].enabled);
+        assert!(schedule.due_ids(at(2026, 5, 1)).is_empty());
+    }
+
+    #[test]
+    fn interrupted_send_is_not_retried() {
+        let mut p = payment(PaymentFrequency::Weekly, at(2026, 3, 1));
+        p.on_insufficient_funds = InsufficientFundsPolicy::Retry;
+        let mut schedule = PaymentSchedule {
+            payments: vec![p],
+            ..PaymentSchedule::default()
+        };
+        let now = at(2026, 3, 2);
+        schedule.mark_in_flight("p1", now);
+        assert_eq!(schedule.payments[0].in_flight_since, Some(now));
+
+        schedule.record_interrupted_run(
+            PaymentRun {
+                payment_id: "p1".to_string(),
+                due_at: at(2026, 3, 1),
+                ran_at: now,
+                recipient: "u1test".to_string(),
+                amount_zatoshis: 1_000_000,
+                outcome: PaymentRunOutcome::Failed {
+                    error: "interrupted".into(),
+                },
+            },
+            now,
+        );
+        assert_eq!(schedule.payments[0].in_flight_since, None);
+        assert!(schedule.due_ids(now).is_empty());
+        assert_eq!(schedule.payments[0].next_due, at(2026, 3, 8));
+    }
+}
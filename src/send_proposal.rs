@@ -0,0 +1,454 @@
+//! Two-phase send: a [`SendProposal`] describes the exact transaction shape before Halo 2 proving.
+//!
+//! `propose` selects notes, change, fee and expiry without touching the prover, so the desktop,
+//! extension and CLI can show (or serialize) the details and ask for confirmation. Executing the
+//! proposal proves and signs against exactly the notes it lists.
+
+use crate::error::{NozyError, NozyResult};
+use crate::notes::SpendableNote;
+use crate::shielded_pool::ShieldedPool;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Bump when the serialized proposal layout changes incompatibly.
+pub const SEND_PROPOSAL_VERSION: u32 = 1;
+
+/// Blocks after `proposed_at_height` before a proposal must be rebuilt (~50 minutes at 75s/block).
+pub const SEND_PROPOSAL_MAX_AGE_BLOCKS: u32 = 40;
+
+/// One note the proposal will spend.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProposedInput {
+    /// Canonical nullifier hex (same identifier recorded in `SentTransactionRecord::spent_note_ids`).
+    pub nullifier_hex: String,
+    pub value_zatoshis: u64,
+    pub pool: ShieldedPool,
+    pub block_height: u32,
+    pub txid: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProposedOutputKind {
+    Recipient,
+    Change,
+}
+
+/// One shielded output the proposal will create.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProposedOutput {
+    pub kind: ProposedOutputKind,
+    /// Recipient unified address; `None` for change back to the spent note's own address.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<String>,
+    pub value_zatoshis: u64,
+    pub pool: ShieldedPool,
+    /// Memo byte length on this output (0 when empty).
+    pub memo_len: usize,
+}
+
+/// Value moving between shielded pools (ZIP 318 turnstile crossings).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PoolCrossing {
+    pub from_pool: ShieldedPool,
+    pub to_pool: ShieldedPool,
+    pub value_zatoshis: u64,
+}
+
+/// Fully-determined send shape, produced before proving and consumed by `execute_proposal`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SendProposal {
+    pub version: u32,
+    pub network: String,
+    pub recipient_address: String,
+    pub amount_zatoshis: u64,
+    pub fee_zatoshis: u64,
+    pub change_zatoshis: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memo_hex: Option<String>,
+    pub inputs: Vec<ProposedInput>,
+    pub outputs: Vec<ProposedOutput>,
+    #[serde(default)]
+    pub pool_crossings: Vec<PoolCrossing>,
+    /// Chain tip observed when the proposal was built.
+    pub proposed_at_height: u32,
+    pub expiry_delta_blocks: u32,
+    /// Expected on-chain expiry; re-derived from the tip at proving time, so it may move forward.
+    pub expiry_height: u32,
+    pub priority: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Canonical nullifier hex for a spendable note (matches the spend builder).
+pub fn spendable_note_nullifier_hex(note: &SpendableNote) -> String {
+    let fvk = note.fvk.clone();
+    hex::encode(note.orchard_note.note.nullifier(&fvk).to_bytes())
+}
+
+/// Pool crossings implied by spending `inputs` into outputs of `output_pool`.
+pub fn pool_crossings_for(
+    inputs: &[ProposedInput],
+    output_pool: ShieldedPool,
+) -> Vec<PoolCrossing> {
+    let mut crossings: Vec<PoolCrossing> = Vec::new();
+    for input in inputs.iter().filter(|i| i.pool != output_pool) {
+        match crossings.iter_mut().find(|c| c.from_pool == input.pool) {
+            Some(c) => c.value_zatoshis = c.value_zatoshis.saturating_add(input.value_zatoshis),
+            None => crossings.push(PoolCrossing {
+                from_pool: input.pool,
+                to_pool: output_pool,
+                value_zatoshis: input.value_zatoshis,
+            }),
+        }
+    }
+    crossings
+}
+
+impl SendProposal {
+    /// Build a proposal from already-selected notes (no network or prover access).
+    #[allow(clippy::too_many_arguments)]
+    pub fn from_selection(
+        network: &str,
+        selected: &[&SpendableNote],
+        output_pool: ShieldedPool,
+        recipient_address: &str,
+        amount_zatoshis: u64,
+        fee_zatoshis: u64,
+        memo: Option<&[u8]>,
+        proposed_at_height: u32,
+        expiry_delta_blocks: u32,
+        priority: bool,
+    ) -> NozyResult<Self> {
+        if selected.is_empty() {
+            return Err(NozyError::InvalidOperation(
+                "Send proposal needs at least one input note".to_string(),
+            ));
+        }
+        let inputs: Vec<ProposedInput> = selected
+            .iter()
+            .map(|n| ProposedInput {
+                nullifier_hex: spendable_note_nullifier_hex(n),
+                value_zatoshis: n.orchard_note.value,
+                pool: n.pool,
+                block_height: n.orchard_note.block_height,
+                txid: n.orchard_note.txid.clone(),
+            })
+            .collect();
+        let total_in: u64 = inputs.iter().map(|i| i.value_zatoshis).sum();
+        let needed = amount_zatoshis.saturating_add(fee_zatoshis);
+        if total_in < needed {
+            return Err(NozyError::InsufficientFunds(format!(
+                "Selected notes hold {total_in} zats; proposal needs {needed} zats"
+            )));
+        }
+        let change_zatoshis = total_in - needed;
+        let memo_len = memo.map(|m| m.len()).unwrap_or(0);
+
+        let mut outputs = vec![ProposedOutput {
+            kind: ProposedOutputKind::Recipient,
+            address: Some(recipient_address.to_string()),
+            value_zatoshis: amount_zatoshis,
+            pool: output_pool,
+            memo_len,
+        }];
+        if change_zatoshis > 0 {
+            outputs.push(ProposedOutput {
+                kind: ProposedOutputKind::Change,
+                address: None,
+                value_zatoshis: change_zatoshis,
+                pool: output_pool,
+                memo_len: 0,
+            });
+        }
+
+        let pool_crossings = pool_crossings_for(&inputs, output_pool);
+        Ok(Self {
+            version: SEND_PROPOSAL_VERSION,
+            network: network.to_string(),
+            recipient_address: recipient_address.to_string(),
+            amount_zatoshis,
+            fee_zatoshis,
+            change_zatoshis,
+            memo_hex: memo.filter(|m| !m.is_empty()).map(hex::encode),
+            inputs,
+            outputs,
+            pool_crossings,
+            proposed_at_height,
+            expiry_delta_blocks,
+            expiry_height: crate::fee_policy::pilot_expiry_height(
+                proposed_at_height,
+                expiry_delta_blocks,
+            ),
+            priority,
+            created_at: Utc::now(),
+        })
+    }
+
+    pub fn total_input_zatoshis(&self) -> u64 {
+        self.inputs.iter().map(|i| i.value_zatoshis).sum()
+    }
+
+    pub fn total_output_zatoshis(&self) -> u64 {
+        self.outputs.iter().map(|o| o.value_zatoshis).sum()
+    }
+
+    /// Decoded memo bytes, if the proposal carries one.
+    pub fn memo_bytes(&self) -> NozyResult<Option<Vec<u8>>> {
+        self.memo_hex
+            .as_deref()
+            .map(|h| {
+                hex::decode(h).map_err(|e| {
+                    NozyError::InvalidInput(format!("Invalid memo_hex in send proposal: {e}"))
+                })
+            })
+            .transpose()
+    }
+
+    /// Structural checks a tampered or hand-edited proposal must pass before proving.
+    pub fn validate(&self) -> NozyResult<()> {
+        if self.version != SEND_PROPOSAL_VERSION {
+            return Err(NozyError::InvalidInput(format!(
+                "Unsupported send proposal version {} (expected {})",
+                self.version, SEND_PROPOSAL_VERSION
+            )));
+        }
+        if self.inputs.is_empty() {
+            return Err(NozyError::InvalidInput(
+                "Send proposal lists no input notes".to_string(),
+            ));
+        }
+        let balance = self
+            .total_output_zatoshis()
+            .saturating_add(self.fee_zatoshis);
+        if self.total_input_zatoshis() != balance {
+            return Err(NozyError::InvalidInput(format!(
+                "Send proposal does not balance: inputs {} zats, outputs + fee {} zats",
+                self.total_input_zatoshis(),
+                balance
+            )));
+        }
+        let recipient_total: u64 = self
+            .outputs
+            .iter()
+            .filter(|o| o.kind == ProposedOutputKind::Recipient)
+            .map(|o| o.value_zatoshis)
+            .sum();
+        if recipient_total != self.amount_zatoshis {
+            return Err(NozyError::InvalidInput(
+                "Send proposal recipient output does not match amount".to_string(),
+            ));
+        }
+        self.memo_bytes()?;
+        Ok(())
+    }
+
+    /// Reject a proposal made for another network, or for an output pool the chain no longer
+    /// uses (an Ironwood activation between proposing and executing).
+    pub fn check_chain(&self, network: &str, output_pool: ShieldedPool) -> NozyResult<()> {
+        if self.network != network {
+            return Err(NozyError::InvalidInput(format!(
+                "Send proposal is for {}, but the connected node is on {network}",
+                self.network
+            )));
+        }
+        if let Some(output) = self.outputs.iter().find(|o| o.pool != output_pool) {
+            return Err(NozyError::InvalidOperation(format!(
+                "Send proposal pays into the {} pool, but new outputs now go to {output_pool}; \
+                 create a new proposal",
+                output.pool
+            )));
+        }
+        Ok(())
+    }
+
+    /// True once the chain has moved too far past `proposed_at_height` to trust the selection.
+    pub fn is_stale(&self, tip_height: u32) -> bool {
+        tip_height
+            > self
+                .proposed_at_height
+                .saturating_add(SEND_PROPOSAL_MAX_AGE_BLOCKS)
+    }
+
+    /// Pick exactly the proposal's notes out of a fresh scan; fails if any was spent meanwhile.
+    pub fn notes_for_execution(
+        &self,
+        spendable_notes: &[SpendableNote],
+    ) -> NozyResult<Vec<SpendableNote>> {
+        let mut selected = Vec::with_capacity(self.inputs.len());
+        for input in &self.inputs {
+            let note = spendable_notes
+                .iter()
+                .filter(|n| !n.orchard_note.spent)
+                .find(|n| spendable_note_nullifier_hex(n) == input.nullifier_hex)
+                .ok_or_else(|| {
+                    NozyError::InvalidOperation(format!(
+                        "Proposed note {}… is no longer spendable; create a new proposal",
+                        &input.nullifier_hex[..input.nullifier_hex.len().min(16)]
+                    ))
+                })?;
+            if note.orchard_note.value != input.value_zatoshis || note.pool != input.pool {
+                return Err(NozyError::InvalidOperation(
+                    "Proposed note no longer matches wallet state; create a new proposal"
+                        .to_string(),
+                ));
+            }
+            selected.push(note.clone());
+        }
+        Ok(selected)
+    }
+
+    pub fn to_json_pretty(&self) -> NozyResult<String> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| NozyError::Storage(format!("Serialize send proposal: {e}")))
+    }
+
+    pub fn from_json(json: &str) -> NozyResult<Self> {
+        let proposal: Self = serde_json::from_str(json)
+            .map_err(|e| NozyError::InvalidInput(format!("Invalid send proposal JSON: {e}")))?;
+        proposal.validate()?;
+        Ok(proposal)
+    }
+}
+
+/// Mark the proposal's notes spent and save a `SentTransactionRecord` after broadcast.
+pub fn record_executed_proposal(
+    proposal: &SendProposal,
+    transaction: &crate::transaction_builder::SignedTransaction,
+) -> NozyResult<()> {
+    use crate::transaction_history::{SentTransactionRecord, SentTransactionStorage};
+
+    let spent_note_ids: Vec<String> = proposal
+        .inputs
+        .iter()
+        .map(|i| i.nullifier_hex.clone())
+        .collect();
+    if let Err(e) = crate::notes::mark_wallet_notes_spent_by_nullifier_hex(
+        &spent_note_ids,
+        Some(&transaction.txid),
+    ) {
+        eprintln!("Warning: could not mark spent notes locally: {e}");
+    }
+
+    let mut tx_record = SentTransactionRecord::new_pilot(
+        transaction.txid.clone(),
+        proposal.recipient_address.clone(),
+        proposal.amount_zatoshis,
+        proposal.fee_zatoshis,
+        proposal.memo_bytes()?,
+        spent_note_ids,
+        proposal.priority,
+        transaction.expiry_height,
+    );
+    tx_record.mark_broadcast();
+    SentTransactionStorage::new()?.save_transaction(tx_record)?;
+    if proposal.priority {
+        crate::pilot_metrics::record_priority_send();
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input(pool: ShieldedPool, value: u64) -> ProposedInput {
+        ProposedInput {
+            nullifier_hex: "00".repeat(32),
+            value_zatoshis: value,
+            pool,
+            block_height: 1,
+            txid: "aa".repeat(32),
+        }
+    }
+
+    fn sample_proposal() -> SendProposal {
+        SendProposal {
+            version: SEND_PROPOSAL_VERSION,
+            network: "testnet".to_string(),
+            recipient_address: "utest1example".to_string(),
+            amount_zatoshis: 100_000,
+            fee_zatoshis: 40_000,
+            change_zatoshis: 60_000,
+            memo_hex: Some(hex::encode(b"hi")),
+            inputs: vec![input(ShieldedPool::Orchard, 200_000)],
+            outputs: vec![
+                ProposedOutput {
+                    kind: ProposedOutputKind::Recipient,
+                    address: Some("utest1example".to_string()),
+                    value_zatoshis: 100_000,
+                    pool: ShieldedPool::Orchard,
+                    memo_len: 2,
+                },
+                ProposedOutput {
+                    kind: ProposedOutputKind::Change,
+                    address: None,
+                    value_zatoshis: 60_000,
+                    pool: ShieldedPool::Orchard,
+                    memo_len: 0,
+                },
+            ],
+            pool_crossings: vec![],
+            proposed_at_height: 1_000,
+            expiry_delta_blocks: 5,
+            expiry_height: 1_006,
+            priority: true,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn balanced_proposal_round_trips_through_json() {
+        let proposal = sample_proposal();
+        let json = proposal.to_json_pretty().unwrap();
+        let parsed = SendProposal::from_json(&json).unwrap();
+        assert_eq!(parsed, proposal);
+        assert_eq!(parsed.memo_bytes().unwrap().as_deref(), Some(&b"hi"[..]));
+    }
+
+    #[test]
+    fn unbalanced_proposal_is_rejected() {
+        let mut proposal = sample_proposal();
+        proposal.outputs[1].value_zatoshis += 1;
+        assert!(proposal.validate().is_err());
+    }
+
+    #[test]
+    fn proposal_for_other_network_or_pool_is_rejected() {
+        let proposal = sample_proposal();
+        assert!(proposal
+            .check_chain("testnet", ShieldedPool::Orchard)
+            .is_ok());
+        assert!(proposal
+            .check_chain("mainnet", ShieldedPool::Orchard)
+            .is_err());
+        assert!(proposal
+            .check_chain("testnet", ShieldedPool::Ironwood)
+            .is_err());
+    }
+
+    #[test]
+    fn proposal_goes_stale_after_max_age() {
+        let proposal = sample_proposal();
+        assert!(!proposal.is_stale(1_000 + SEND_PROPOSAL_MAX_AGE_BLOCKS));
+        assert!(proposal.is_stale(1_001 + SEND_PROPOSAL_MAX_AGE_BLOCKS));
+    }
+
+    #[test]
+    fn crossings_sum_inputs_from_other_pools() {
+        let inputs = vec![
+            input(ShieldedPool::Orchard, 10),
+            input(ShieldedPool::Orchard, 5),
+            input(ShieldedPool::Ironwood, 7),
+        ];
+        let crossings = pool_crossings_for(&inputs, ShieldedPool::Ironwood);
+        assert_eq!(
+            crossings,
+            vec![PoolCrossing {
+                from_pool: ShieldedPool::Orchard,
+                to_pool: ShieldedPool::Ironwood,
+                value_zatoshis: 15,
+            }]
+        );
+        assert!(pool_crossings_for(&inputs[..2], ShieldedPool::Orchard).is_empty());
+    }
+}
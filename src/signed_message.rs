@@ -1,21 +1,42 @@
-//! Domain-separated Orchard SpendAuth message signatures (`nozy-sm-v1`).
+//! Domain-separated Orchard SpendAuth message signatures (`nozy-sm-v1`, `nozy-sm-v2`).
 //!
 //! This is **not** a ZIP-standardized message-signing scheme. It uses the wallet's
-//! Orchard `SpendAuthorizingKey` (ZIP-32 coin type 133, account 0) to produce a
-//! RedDSA SpendAuth signature over `SHA256("NozyWallet_SignedMessage_v1" || 0x00 || msg)`.
-//! Encoding: `nozy-sm-v1:<ak_hex32>:<sig_hex64>`.
+//! Orchard `SpendAuthorizingKey` (ZIP-32 coin type 133) to produce a RedDSA SpendAuth
+//! signature.
+//!
+//! - v1 (account 0): over `SHA256("NozyWallet_SignedMessage_v1" || 0x00 || msg)`.
+//!   Encoding: `nozy-sm-v1:<ak_hex32>:<sig_hex64>`.
+//! - v2 (any account, bound to an address): over
+//!   `SHA256("NozyWallet_SignedMessage_v2" || 0x00 || d || pk_d || msg)` for the Orchard
+//!   receiver `(d, pk_d)` of the signing address. Encoding:
+//!   `nozy-sm-v2:<fvk_hex96>:<sig_hex64>`. The verifier checks that the full viewing key
+//!   derives that receiver and that its `ak` made the signature, so a v2 signature
+//!   **reveals the account's full viewing key**. Signing therefore needs an explicit
+//!   opt-in, and every surface shows [`VIEWING_KEY_WARNING`] with the result. Only the
+//!   Orchard receiver of a unified address is bound.
+//!
+//! Sapling addresses use standard ZIP-304 signatures ([`crate::zip304`]); [`verify_any`]
+//! accepts those too on native builds.
 
 use crate::error::{NozyError, NozyResult};
 use group::ff::Field;
-use orchard::keys::{SpendAuthorizingKey, SpendingKey};
+use orchard::keys::{FullViewingKey, Scope, SpendAuthorizingKey, SpendingKey};
 use orchard::primitives::redpallas::{self, SpendAuth};
+use orchard::Address as OrchardAddress;
 use pasta_curves::pallas;
 use rand::rngs::OsRng;
 use sha2::{Digest, Sha256};
+use zcash_address::unified::{self, Container, Encoding};
 use zip32::AccountId;
 
 pub const SCHEME_ID: &str = "nozy-sm-v1";
 const DOMAIN: &[u8] = b"NozyWallet_SignedMessage_v1";
+pub const SCHEME_ID_V2: &str = "nozy-sm-v2";
+const DOMAIN_V2: &[u8] = b"NozyWallet_SignedMessage_v2";
+
+/// Shown wherever a `nozy-sm-v2` signature is made or requested.
+pub const VIEWING_KEY_WARNING: &str = "A nozy-sm-v2 signature contains the account's full \
+     viewing key: anyone who sees it can follow every payment to and from this account.";
 
 fn digest_message(message: &str) -> [u8; 32] {
     let mut hasher = Sha256::new();
@@ -25,32 +46,54 @@ fn digest_message(message: &str) -> [u8; 32] {
     hasher.finalize().into()
 }
 
-fn spending_key_from_seed(seed_bytes: &[u8]) -> NozyResult<SpendingKey> {
-    let account_id = AccountId::try_from(0u32)
+fn spending_key_from_seed(seed_bytes: &[u8], account: u32) -> NozyResult<SpendingKey> {
+    let account_id = AccountId::try_from(account)
         .map_err(|e| NozyError::KeyDerivation(format!("Invalid ZIP32 account id: {e}")))?;
     SpendingKey::from_zip32_seed(seed_bytes, 133, account_id).map_err(|e| {
         NozyError::KeyDerivation(format!("Failed to derive Orchard spending key: {e:?}"))
     })
 }
 
-/// Sign `message` with the Orchard SpendAuth key for account 0.
-///
-/// `seed_bytes` is the BIP-39 seed (`mnemonic.to_seed("")`).
-pub fn sign_with_seed(seed_bytes: &[u8], message: &str) -> NozyResult<String> {
-    let spending_key = spending_key_from_seed(seed_bytes)?;
-    let ask = SpendAuthorizingKey::from(&spending_key);
-    let msg = digest_message(message);
+/// Orchard SpendAuth key for `account`, for repeated [`sign_with_key`] calls.
+pub fn spend_authorizing_key(seed_bytes: &[u8], account: u32) -> NozyResult<SpendAuthorizingKey> {
+    let spending_key = spending_key_from_seed(seed_bytes, account)?;
+    Ok(SpendAuthorizingKey::from(&spending_key))
+}
+
+/// Hex `ak` that [`sign_with_key`] signatures made with `ask` carry.
+pub fn verifying_key_hex(ask: &SpendAuthorizingKey) -> String {
     let signing_key = ask.randomize(&pallas::Scalar::ZERO);
     let verifying_key = redpallas::VerificationKey::<SpendAuth>::from(&signing_key);
     let ak_bytes: [u8; 32] = (&verifying_key).into();
+    hex::encode(ak_bytes)
+}
+
+/// Sign `message` with an already derived SpendAuth key.
+pub fn sign_with_key(ask: &SpendAuthorizingKey, message: &str) -> String {
+    let msg = digest_message(message);
+    let signing_key = ask.randomize(&pallas::Scalar::ZERO);
     let signature = signing_key.sign(&mut OsRng, &msg);
     let sig_bytes: [u8; 64] = (&signature).into();
-    Ok(format!(
+    format!(
         "{}:{}:{}",
         SCHEME_ID,
-        hex::encode(ak_bytes),
+        verifying_key_hex(ask),
         hex::encode(sig_bytes)
-    ))
+    )
+}
+
+/// Sign `message` with the Orchard SpendAuth key for account 0.
+///
+/// `seed_bytes` is the BIP-39 seed (`mnemonic.to_seed("")`).
+pub fn sign_with_seed(seed_bytes: &[u8], message: &str) -> NozyResult<String> {
+    let ask = spend_authorizing_key(seed_bytes, 0)?;
+    Ok(sign_with_key(&ask, message))
+}
+
+/// Hex `ak` of the signer, if `signature` is a well-formed `nozy-sm-v1` string.
+pub fn signer_key_hex(signature: &str) -> Option<&str> {
+    let parts: Vec<&str> = signature.split(':').collect();
+    (parts.len() == 3 && parts[0] == SCHEME_ID && parts[1].len() == 64).then_some(parts[1])
 }
 
 /// Verify a `sign_with_seed` output.
@@ -80,6 +123,132 @@ pub fn verify(signature: &str, message: &str) -> NozyResult<bool> {
     Ok(vk.verify(&msg, &sig).is_ok())
 }
 
+fn digest_message_v2(receiver: &[u8; 43], message: &str) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(DOMAIN_V2);
+    hasher.update([0u8]);
+    hasher.update(receiver);
+    hasher.update(message.as_bytes());
+    hasher.finalize().into()
+}
+
+/// Orchard receiver bytes (`d || pk_d`) of a unified address.
+pub fn orchard_receiver(address: &str) -> NozyResult<[u8; 43]> {
+    let (_, ua) = unified::Address::decode(address.trim())
+        .map_err(|e| NozyError::AddressParsing(format!("Invalid unified address: {e}")))?;
+    ua.items()
+        .into_iter()
+        .find_map(|item| match item {
+            unified::Receiver::Orchard(raw) => Some(raw),
+            _ => None,
+        })
+        .ok_or_else(|| NozyError::AddressParsing("Address has no Orchard receiver".into()))
+}
+
+/// Sign `message` as the owner of `address`, an external address of `account`.
+///
+/// The signature reveals the account's full viewing key, so this fails unless
+/// `reveal_viewing_key` is set; show [`VIEWING_KEY_WARNING`] before asking for it.
+pub fn sign_for_address(
+    seed_bytes: &[u8],
+    account: u32,
+    address: &str,
+    message: &str,
+    reveal_viewing_key: bool,
+) -> NozyResult<String> {
+    if !reveal_viewing_key {
+        return Err(NozyError::InvalidInput(format!(
+            "{VIEWING_KEY_WARNING} Confirm that the viewing key may be revealed to sign."
+        )));
+    }
+    let spending_key = spending_key_from_seed(seed_bytes, account)?;
+    let fvk = FullViewingKey::from(&spending_key);
+    let receiver = orchard_receiver(address)?;
+    let owned = Option::<OrchardAddress>::from(OrchardAddress::from_raw_address_bytes(&receiver))
+        .and_then(|addr| fvk.scope_for_address(&addr))
+        == Some(Scope::External);
+    if !owned {
+        return Err(NozyError::InvalidInput(format!(
+            "Address is not an external address of account {account}"
+        )));
+    }
+
+    let ask = SpendAuthorizingKey::from(&spending_key);
+    let signing_key = ask.randomize(&pallas::Scalar::ZERO);
+    let signature = signing_key.sign(&mut OsRng, &digest_message_v2(&receiver, message));
+    let sig_bytes: [u8; 64] = (&signature).into();
+    Ok(format!(
+        "{}:{}:{}",
+        SCHEME_ID_V2,
+        hex::encode(fvk.to_bytes()),
+        hex::encode(sig_bytes)
+    ))
+}
+
+/// Verify a [`sign_for_address`] output against the claimed `address`.
+pub fn verify_for_address(address: &str, message: &str, signature: &str) -> NozyResult<bool> {
+    let parts: Vec<&str> = signature.split(':').collect();
+    if parts.len() != 3 || parts[0] != SCHEME_ID_V2 {
+        return Ok(false);
+    }
+    let fvk_vec = hex::decode(parts[1])
+        .map_err(|e| NozyError::KeyDerivation(format!("Invalid fvk hex: {e}")))?;
+    let sig_vec = hex::decode(parts[2])
+        .map_err(|e| NozyError::KeyDerivation(format!("Invalid sig hex: {e}")))?;
+    let (Ok(fvk_bytes), Ok(sig_bytes)) = (
+        <[u8; 96]>::try_from(fvk_vec.as_slice()),
+        <[u8; 64]>::try_from(sig_vec.as_slice()),
+    ) else {
+        return Ok(false);
+    };
+    let Some(fvk) = FullViewingKey::from_bytes(&fvk_bytes) else {
+        return Ok(false);
+    };
+
+    let receiver = orchard_receiver(address)?;
+    let Some(addr) =
+        Option::<OrchardAddress>::from(OrchardAddress::from_raw_address_bytes(&receiver))
+    else {
+        return Ok(false);
+    };
+    if fvk.scope_for_address(&addr) != Some(Scope::External) {
+        return Ok(false);
+    }
+
+    // The FVK encoding starts with `ak`.
+    let mut ak = [0u8; 32];
+    ak.copy_from_slice(&fvk_bytes[..32]);
+    let vk = match redpallas::VerificationKey::<SpendAuth>::try_from(ak) {
+        Ok(v) => v,
+        Err(_) => return Ok(false),
+    };
+    let sig = redpallas::Signature::<SpendAuth>::from(sig_bytes);
+    Ok(vk
+        .verify(&digest_message_v2(&receiver, message), &sig)
+        .is_ok())
+}
+
+/// Verify any supported scheme; `address` is required for v2 and ZIP-304 and ignored by v1.
+pub fn verify_any(signature: &str, message: &str, address: Option<&str>) -> NozyResult<bool> {
+    #[cfg(feature = "native")]
+    if signature.starts_with(crate::zip304::SIGNATURE_PREFIX) {
+        let address = address.ok_or_else(|| {
+            NozyError::InvalidInput(
+                "A Sapling address is required to verify a ZIP-304 signature".into(),
+            )
+        })?;
+        return crate::zip304::verify(address, message, signature);
+    }
+    if signature.starts_with(SCHEME_ID_V2) {
+        let address = address.ok_or_else(|| {
+            NozyError::InvalidInput("An address is required to verify a v2 signature".into())
+        })?;
+        verify_for_address(address, message, signature)
+    } else {
+        verify(signature, message)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -96,4 +265,29 @@ mod tests {
         assert!(verify(&sig, msg).expect("verify"));
         assert!(!verify(&sig, "tampered").expect("verify tampered"));
     }
+
+    #[test]
+    fn v2_binds_signature_to_address() {
+        use crate::hd_wallet::HDWallet;
+        use zcash_protocol::consensus::NetworkType;
+
+        let mnemonic = Mnemonic::from_entropy_in(Language::English, &[7u8; 16]).expect("mnemonic");
+        let wallet = HDWallet::from_mnemonic(&mnemonic.to_string()).expect("wallet");
+        let seed = mnemonic.to_seed("");
+        let ours = wallet
+            .generate_orchard_address(1, 4, NetworkType::Main)
+            .expect("address");
+        let other = wallet
+            .generate_orchard_address(0, 4, NetworkType::Main)
+            .expect("address");
+
+        assert!(sign_for_address(&seed, 1, &ours, "prove it", false).is_err());
+        let sig = sign_for_address(&seed, 1, &ours, "prove it", true).expect("sign");
+        assert!(sig.starts_with("nozy-sm-v2:"));
+        assert!(verify_for_address(&ours, "prove it", &sig).expect("verify"));
+        assert!(verify_any(&sig, "prove it", Some(&ours)).expect("verify any"));
+        assert!(!verify_for_address(&ours, "tampered", &sig).expect("tampered"));
+        assert!(!verify_for_address(&other, "prove it", &sig).expect("other address"));
+        assert!(sign_for_address(&seed, 0, &ours, "prove it", true).is_err());
+    }
 }
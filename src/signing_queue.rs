@@ -0,0 +1,438 @@
+//! Persistent queue of PCZTs waiting for a hardware-wallet signature.
+//!
+//! Every prepared Keystone / offline-signer send is queued under its own id together with
+//! the expiry height of its PCZT. A queued request locks the notes it spends:
+//! [`crate::cli_helpers::scan_notes_for_sending`] drops them from send candidates until the
+//! request is completed, cancelled or expires. UR frames scanned back from the device are
+//! saved per request, so an interrupted animated-QR scan resumes where it stopped.
+
+use crate::error::{NozyError, NozyResult};
+use crate::keystone::{decode_pczt_ur_frames, KeystonePreparedSend};
+use crate::notes::SpendableNote;
+use crate::paths::get_wallet_data_dir;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const SIGNING_QUEUE_FILE: &str = "keystone_signing_queue.json";
+pub const SIGNING_QUEUE_VERSION: u32 = 1;
+
+/// Single-request file written before the queue existed; imported on first load.
+const LEGACY_PENDING_SEND_FILE: &str = "keystone_pending_send.json";
+
+/// Signed UR frames received so far for one request.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UrScanState {
+    /// Distinct frames in arrival order.
+    #[serde(default)]
+    pub frames: Vec<String>,
+    /// Fragment count announced by multi-part frames (`ur:zcash-pczt/<seq>-<count>/…`).
+    #[serde(default)]
+    pub expected_fragments: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SigningRequest {
+    pub id: String,
+    pub send: KeystonePreparedSend,
+    /// Last block height the transaction can be mined at; `0` means it never expires.
+    pub expiry_height: u32,
+    /// Nullifier hex of every note the PCZT spends.
+    pub locked_notes: Vec<String>,
+    #[serde(default)]
+    pub scan: UrScanState,
+}
+
+impl SigningRequest {
+    pub fn is_expired(&self, chain_tip: u32) -> bool {
+        self.expiry_height != 0 && chain_tip >= self.expiry_height
+    }
+}
+
+/// Progress of a resumable UR scan, returned after every batch of frames.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UrScanProgress {
+    pub request_id: String,
+    pub frames_received: usize,
+    pub expected_fragments: Option<u32>,
+    /// The frames so far decode to a whole PCZT.
+    pub complete: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SigningQueue {
+    pub version: u32,
+    #[serde(default)]
+    pub requests: Vec<SigningRequest>,
+}
+
+impl Default for SigningQueue {
+    fn default() -> Self {
+        Self {
+            version: SIGNING_QUEUE_VERSION,
+            requests: Vec::new(),
+        }
+    }
+}
+
+impl SigningQueue {
+    /// Queue `send`, reading its expiry height and spent notes from the PCZT.
+    pub fn add(&mut self, send: KeystonePreparedSend) -> NozyResult<SigningRequest> {
+        let pczt_bytes = hex::decode(&send.pczt_hex)
+            .map_err(|e| NozyError::InvalidInput(format!("Invalid PCZT hex: {e}")))?;
+        let pczt = pczt::Pczt::parse(&pczt_bytes)
+            .map_err(|e| NozyError::InvalidOperation(format!("PCZT parse failed: {e:?}")))?;
+        let expiry_height = *pczt.global().expiry_height();
+        let locked_notes = crate::cold_signing::pczt_spend_nullifiers(&pczt_bytes)?
+            .iter()
+            .map(hex::encode)
+            .collect();
+        let request = SigningRequest {
+            id: new_request_id(),
+            send,
+            expiry_height,
+            locked_notes,
+            scan: UrScanState::default(),
+        };
+        self.requests.push(request.clone());
+        Ok(request)
+    }
+
+    pub fn get(&self, id: &str) -> Option<&SigningRequest> {
+        self.requests.iter().find(|r| r.id == id)
+    }
+
+    pub fn remove(&mut self, id: &str) -> NozyResult<SigningRequest> {
+        let pos = self
+            .requests
+            .iter()
+            .position(|r| r.id == id)
+            .ok_or_else(|| unknown_request(id))?;
+        Ok(self.requests.remove(pos))
+    }
+
+    /// The request whose notes `spent_nullifiers` spends, if any.
+    pub fn find_matching(&self, spent_nullifiers: &[String]) -> Option<&SigningRequest> {
+        self.requests.iter().find(|r| {
+            r.locked_notes
+                .iter()
+                .any(|nf| spent_nullifiers.contains(nf))
+        })
+    }
+
+    /// Drop requests whose PCZT can no longer be mined, releasing their notes.
+    pub fn remove_expired(&mut self, chain_tip: u32) -> Vec<SigningRequest> {
+        let (expired, live) = std::mem::take(&mut self.requests)
+            .into_iter()
+            .partition(|r| r.is_expired(chain_tip));
+        self.requests = live;
+        expired
+    }
+
+    /// Notes locked by requests that have not expired at `chain_tip`.
+    pub fn locked_notes(&self, chain_tip: u32) -> HashSet<String> {
+        self.requests
+            .iter()
+            .filter(|r| !r.is_expired(chain_tip))
+            .flat_map(|r| r.locked_notes.iter().cloned())
+            .collect()
+    }
+
+    /// Add newly scanned frames to `id` and report whether the PCZT is complete.
+    pub fn record_frames(&mut self, id: &str, frames: &[String]) -> NozyResult<UrScanProgress> {
+        let request = self
+            .requests
+            .iter_mut()
+            .find(|r| r.id == id)
+            .ok_or_else(|| unknown_request(id))?;
+        let scan = &mut request.scan;
+        for frame in frames.iter().map(|f| f.trim()).filter(|f| !f.is_empty()) {
+            let frame = frame.to_ascii_lowercase();
+            if scan.expected_fragments.is_none() {
+                scan.expected_fragments = announced_fragment_count(&frame);
+            }
+            if !scan.frames.contains(&frame) {
+                scan.frames.push(frame);
+            }
+        }
+        Ok(UrScanProgress {
+            request_id: request.id.clone(),
+            frames_received: scan.frames.len(),
+            expected_fragments: scan.expected_fragments,
+            complete: !scan.frames.is_empty() && decode_pczt_ur_frames(&scan.frames).is_ok(),
+        })
+    }
+}
+
+/// `count` from a multi-part `ur:<type>/<seq>-<count>/<payload>` frame.
+fn announced_fragment_count(frame: &str) -> Option<u32> {
+    let mut parts = frame.split('/');
+    let _ur_type = parts.next()?;
+    let sequence = parts.next()?;
+    parts.next()?;
+    let (_, count) = sequence.split_once('-')?;
+    count.parse().ok()
+}
+
+fn unknown_request(id: &str) -> NozyError {
+    NozyError::InvalidInput(format!(
+        "Unknown signing request {id} (see `nozy pczt queue`)"
+    ))
+}
+
+fn new_request_id() -> String {
+    let mut bytes = [0u8; 8];
+    OsRng.fill_bytes(&mut bytes);
+    format!("{:016x}", u64::from_be_bytes(bytes))
+}
+
+pub fn signing_queue_path() -> PathBuf {
+    get_wallet_data_dir().join(SIGNING_QUEUE_FILE)
+}
+
+pub fn load_signing_queue() -> NozyResult<SigningQueue> {
+    let path = signing_queue_path();
+    let mut queue = if path.exists() {
+        let content = fs::read_to_string(&path)
+            .map_err(|e| NozyError::Storage(format!("Failed to read signing queue: {e}")))?;
+        serde_json::from_str(&content)
+            .map_err(|e| NozyError::Storage(format!("Failed to parse signing queue: {e}")))?
+    } else {
+        SigningQueue::default()
+    };
+
+    let legacy_path = get_wallet_data_dir().join(LEGACY_PENDING_SEND_FILE);
+    if legacy_path.exists() {
+        import_legacy_pending_send(&mut queue, &legacy_path);
+    }
+    Ok(queue)
+}
+
+/// Move the pre-queue pending send into `queue`. A file that cannot be imported is kept
+/// as `.bak` so it never blocks loading the queue again.
+fn import_legacy_pending_send(queue: &mut SigningQueue, legacy_path: &Path) {
+    let imported = fs::read_to_string(legacy_path)
+        .map_err(|e| NozyError::Storage(format!("Failed to read legacy pending send: {e}")))
+        .and_then(|content| {
+            serde_json::from_str::<KeystonePreparedSend>(&content).map_err(|e| {
+                NozyError::Storage(format!("Failed to parse legacy pending send: {e}"))
+            })
+        })
+        .and_then(|send| {
+            let mut updated = queue.clone();
+            updated.add(send)?;
+            save_signing_queue(&updated)?;
+            *queue = updated;
+            Ok(())
+        });
+    match imported {
+        Ok(()) => {
+            if let Err(e) = fs::remove_file(legacy_path) {
+                eprintln!("Warning: could not remove imported legacy pending send: {e}");
+            }
+        }
+        Err(e) => {
+            let backup = legacy_path.with_extension("json.bak");
+            eprintln!(
+                "Warning: could not import {LEGACY_PENDING_SEND_FILE} ({e}); moved it to {}",
+                backup.display()
+            );
+            if let Err(e) = fs::rename(legacy_path, &backup) {
+                eprintln!("Warning: could not move legacy pending send aside: {e}");
+            }
+        }
+    }
+}
+
+pub fn save_signing_queue(queue: &SigningQueue) -> NozyResult<()> {
+    let path = signing_queue_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| {
+            NozyError::Storage(format!("Failed to create signing queue directory: {e}"))
+        })?;
+    }
+    let serialized = serde_json::to_string_pretty(queue)
+        .map_err(|e| NozyError::Storage(format!("Failed to serialize signing queue: {e}")))?;
+    let temp_path = path.with_extension("tmp");
+    fs::write(&temp_path, serialized)
+        .map_err(|e| NozyError::Storage(format!("Failed to write signing queue: {e}")))?;
+    fs::rename(&temp_path, &path)
+        .map_err(|e| NozyError::Storage(format!("Failed to save signing queue: {e}")))
+}
+
+/// Queue a prepared hardware send and lock the notes it spends.
+pub fn enqueue_signing_request(send: KeystonePreparedSend) -> NozyResult<SigningRequest> {
+    let mut queue = load_signing_queue()?;
+    let request = queue.add(send)?;
+    save_signing_queue(&queue)?;
+    Ok(request)
+}
+
+/// Cancel a request; its notes become spendable again.
+pub fn cancel_signing_request(id: &str) -> NozyResult<SigningRequest> {
+    let mut queue = load_signing_queue()?;
+    let removed = queue.remove(id)?;
+    save_signing_queue(&queue)?;
+    Ok(removed)
+}
+
+/// Drop expired requests and return them.
+pub fn prune_expired_signing_requests(chain_tip: u32) -> NozyResult<Vec<SigningRequest>> {
+    let mut queue = load_signing_queue()?;
+    let expired = queue.remove_expired(chain_tip);
+    if !expired.is_empty() {
+        save_signing_queue(&queue)?;
+    }
+    Ok(expired)
+}
+
+/// Save scanned frames for `id`; see [`SigningQueue::record_frames`].
+pub fn record_scanned_frames(id: &str, frames: &[String]) -> NozyResult<UrScanProgress> {
+    let mut queue = load_signing_queue()?;
+    let progress = queue.record_frames(id, frames)?;
+    save_signing_queue(&queue)?;
+    Ok(progress)
+}
+
+/// Signed PCZT bytes assembled from the frames saved for `id`.
+pub fn scanned_signed_pczt(id: &str) -> NozyResult<Vec<u8>> {
+    let queue = load_signing_queue()?;
+    let request = queue.get(id).ok_or_else(|| unknown_request(id))?;
+    if request.scan.frames.is_empty() {
+        return Err(NozyError::InvalidOperation(format!(
+            "No signed UR frames have been scanned for request {id}"
+        )));
+    }
+    decode_pczt_ur_frames(&request.scan.frames)
+}
+
+/// The queued request a signed PCZT completes, if any.
+pub fn find_signing_request_for_pczt(pczt_bytes: &[u8]) -> NozyResult<Option<SigningRequest>> {
+    let spent: Vec<String> = crate::cold_signing::pczt_spend_nullifiers(pczt_bytes)?
+        .iter()
+        .map(hex::encode)
+        .collect();
+    Ok(load_signing_queue()?.find_matching(&spent).cloned())
+}
+
+/// Remove a request whose transaction was broadcast; a request already gone is fine.
+pub fn complete_signing_request(id: &str) -> NozyResult<()> {
+    let mut queue = load_signing_queue()?;
+    if queue.remove(id).is_ok() {
+        save_signing_queue(&queue)?;
+    }
+    Ok(())
+}
+
+/// Drop notes locked by live signing requests from send candidates.
+pub fn exclude_locked_notes(
+    spendable: Vec<SpendableNote>,
+    chain_tip: u32,
+) -> NozyResult<Vec<SpendableNote>> {
+    let locked = load_signing_queue()?.locked_notes(chain_tip);
+    if locked.is_empty() {
+        return Ok(spendable);
+    }
+    Ok(spendable
+        .into_iter()
+        .filter(|n| !locked.contains(&hex::encode(n.orchard_note.nullifier.to_bytes())))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(id: &str, expiry_height: u32, notes: &[&str]) -> SigningRequest {
+        SigningRequest {
+            id: id.to_string(),
+            send: KeystonePreparedSend {
+                recipient: "u1test".to_string(),
+                amount_zatoshis: 1_000,
+                fee_zatoshis: 10_000,
+                summary: String::new(),
+                action_count: 2,
+                pczt_hex: String::new(),
+                created_at: String::new(),
+            },
+            expiry_height,
+            locked_notes: notes.iter().map(|n| n.to_string()).collect(),
+            scan: UrScanState::default(),
+        }
+    }
+
+    #[test]
+    fn locks_follow_expiry_cancellation_and_completion() {
+        let mut queue = SigningQueue {
+            version: SIGNING_QUEUE_VERSION,
+            requests: vec![
+                request("a", 100, &["n1", "n2"]),
+                request("b", 200, &["n3"]),
+                request("c", 0, &["n4"]),
+            ],
+        };
+        assert_eq!(queue.locked_notes(99).len(), 4);
+        assert!(!queue.locked_notes(100).contains("n1"));
+
+        assert_eq!(queue.remove("b").unwrap().id, "b");
+        assert!(queue.remove("b").is_err());
+        assert!(!queue.locked_notes(0).contains("n3"));
+
+        assert!(queue.find_matching(&["zz".to_string()]).is_none());
+        assert_eq!(queue.find_matching(&["n2".to_string()]).unwrap().id, "a");
+        queue.remove("a").unwrap();
+
+        queue.requests.push(request("d", 150, &["n5"]));
+        let expired = queue.remove_expired(150);
+        assert_eq!(expired.len(), 1);
+        assert_eq!(queue.requests.len(), 1, "never-expiring request stays");
+    }
+
+    #[test]
+    fn scan_state_dedupes_frames_and_reads_fragment_count() {
+        let mut queue = SigningQueue {
+            version: SIGNING_QUEUE_VERSION,
+            requests: vec![request("a", 0, &[])],
+        };
+        let frames = vec![
+            "UR:ZCASH-PCZT/1-3/LPADAX".to_string(),
+            "ur:zcash-pczt/1-3/lpadax".to_string(),
+            " ".to_string(),
+        ];
+        let progress = queue.record_frames("a", &frames).unwrap();
+        assert_eq!(progress.frames_received, 1);
+        assert_eq!(progress.expected_fragments, Some(3));
+        assert!(!progress.complete);
+        assert!(queue.record_frames("missing", &frames).is_err());
+        assert_eq!(announced_fragment_count("ur:zcash-pczt/payload"), None);
+    }
+
+    #[test]
+    fn unreadable_legacy_pending_send_is_moved_aside() {
+        let dir = std::env::temp_dir().join(format!(
+            "nozy-signing-queue-legacy-test-{}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        crate::paths::with_wallet_data_dir(&dir, || {
+            let legacy = dir.join(LEGACY_PENDING_SEND_FILE);
+            let backup = dir.join("keystone_pending_send.json.bak");
+
+            fs::write(&legacy, "{ not json").unwrap();
+            assert!(load_signing_queue().unwrap().requests.is_empty());
+            assert!(!legacy.exists());
+            assert_eq!(fs::read_to_string(&backup).unwrap(), "{ not json");
+
+            let mut send = request("x", 0, &[]).send;
+            send.pczt_hex = "00".to_string();
+            fs::write(&legacy, serde_json::to_string(&send).unwrap()).unwrap();
+            assert!(load_signing_queue().unwrap().requests.is_empty());
+            assert!(!legacy.exists());
+            assert!(load_signing_queue().is_ok());
+        });
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -9,6 +9,142 @@ use std::path::PathBuf;
 #[path = "tests/deterministic_scanning_tests.rs"]
 mod deterministic_scanning_tests;
 
+/// Unproven, unsigned testnet PCZT spending one `spend_value` note owned by `fvk`, returned
+/// with that note.
+///
+/// The note sits in a made-up tree, so the transaction never verifies on chain; it exercises
+/// preview, signing and bookkeeping code against real PCZT encodings. Whatever the outputs
+/// leave over is the fee. Outputs are encrypted to `fvk`'s external OVK, as a wallet sending
+/// from it would. IO is finalized, so the PCZT is ready for a signer.
+pub(crate) fn unproven_test_pczt(
+    fvk: &orchard::keys::FullViewingKey,
+    spend_value: u64,
+    orchard_outputs: &[(orchard::Address, u64)],
+    transparent_outputs: &[(zcash_transparent::address::TransparentAddress, u64)],
+) -> (pczt::Pczt, orchard::Note) {
+    unproven_pool_test_pczt(
+        zcash_protocol::consensus::TestNetwork,
+        3_000_000,
+        crate::shielded_pool::ShieldedPool::Orchard,
+        fvk,
+        spend_value,
+        orchard_outputs,
+        transparent_outputs,
+    )
+}
+
+/// [`unproven_test_pczt`] for the Ironwood pool: a V6 mainnet PCZT just past NU6.3
+/// activation whose spend and outputs are all Ironwood.
+pub(crate) fn unproven_ironwood_test_pczt(
+    fvk: &orchard::keys::FullViewingKey,
+    spend_value: u64,
+    ironwood_outputs: &[(orchard::Address, u64)],
+) -> (pczt::Pczt, orchard::Note) {
+    unproven_pool_test_pczt(
+        crate::ironwood::IronwoodAwareMainNetwork,
+        crate::ironwood::NU6_3_MAINNET_ACTIVATION_HEIGHT + 1,
+        crate::shielded_pool::ShieldedPool::Ironwood,
+        fvk,
+        spend_value,
+        ironwood_outputs,
+        &[],
+    )
+}
+
+fn unproven_pool_test_pczt<P: zcash_protocol::consensus::Parameters>(
+    params: P,
+    target_height: u32,
+    pool: crate::shielded_pool::ShieldedPool,
+    fvk: &orchard::keys::FullViewingKey,
+    spend_value: u64,
+    shielded_outputs: &[(orchard::Address, u64)],
+    transparent_outputs: &[(zcash_transparent::address::TransparentAddress, u64)],
+) -> (pczt::Pczt, orchard::Note) {
+    use crate::keystone::FixedFeeRule;
+    use crate::shielded_pool::ShieldedPool;
+    use orchard::keys::Scope;
+    use orchard::note::{NoteVersion, RandomSeed, Rho};
+    use orchard::tree::{MerkleHashOrchard, MerklePath};
+    use orchard::value::NoteValue;
+    use zcash_primitives::transaction::builder::{BuildConfig, Builder};
+    use zcash_protocol::consensus::BlockHeight;
+    use zcash_protocol::memo::MemoBytes;
+    use zcash_protocol::value::Zatoshis;
+
+    let rho = Rho::from_bytes(&[0u8; 32]).unwrap();
+    let rseed = RandomSeed::from_bytes([7u8; 32], &rho).unwrap();
+    let note = orchard::Note::from_parts(
+        fvk.address_at(0u32, Scope::External),
+        NoteValue::from_raw(spend_value),
+        rho,
+        rseed,
+        match pool {
+            ShieldedPool::Orchard => NoteVersion::V2,
+            ShieldedPool::Ironwood => NoteVersion::V3,
+        },
+    )
+    .unwrap();
+    let merkle_path =
+        MerklePath::from_parts(0, [MerkleHashOrchard::from_bytes(&[0u8; 32]).unwrap(); 32]);
+    let anchor = merkle_path.root(note.commitment().into());
+
+    let paid: u64 = shielded_outputs
+        .iter()
+        .map(|(_, v)| v)
+        .chain(transparent_outputs.iter().map(|(_, v)| v))
+        .sum();
+    let fee_rule = FixedFeeRule {
+        fee: Zatoshis::from_u64(spend_value - paid).unwrap(),
+    };
+    let (orchard_anchor, ironwood_anchor) = match pool {
+        ShieldedPool::Orchard => (Some(anchor), None),
+        ShieldedPool::Ironwood => (None, Some(anchor)),
+    };
+    let mut builder = Builder::new(
+        params,
+        BlockHeight::from_u32(target_height),
+        BuildConfig::Standard {
+            sapling_anchor: None,
+            orchard_anchor,
+            ironwood_anchor,
+        },
+    );
+    match pool {
+        ShieldedPool::Orchard => builder
+            .add_orchard_spend::<NozyError>(fvk.clone(), note.clone(), merkle_path)
+            .unwrap(),
+        ShieldedPool::Ironwood => builder
+            .add_ironwood_spend::<NozyError>(fvk.clone(), note.clone(), merkle_path)
+            .unwrap(),
+    }
+    let ovk = Some(fvk.to_ovk(Scope::External));
+    for (address, value) in shielded_outputs {
+        let value = Zatoshis::from_u64(*value).unwrap();
+        match pool {
+            ShieldedPool::Orchard => builder
+                .add_orchard_output::<NozyError>(ovk.clone(), *address, value, MemoBytes::empty())
+                .unwrap(),
+            ShieldedPool::Ironwood => builder
+                .add_ironwood_output::<NozyError>(ovk.clone(), *address, value, MemoBytes::empty())
+                .unwrap(),
+        }
+    }
+    for (address, value) in transparent_outputs {
+        builder
+            .add_transparent_output(address, Zatoshis::from_u64(*value).unwrap())
+            .unwrap();
+    }
+    let parts = builder
+        .build_for_pczt(rand::rngs::OsRng, &fee_rule)
+        .unwrap()
+        .pczt_parts;
+    let pczt = pczt::roles::creator::Creator::build_from_parts(parts).unwrap();
+    let pczt = pczt::roles::io_finalizer::IoFinalizer::new(pczt)
+        .finalize_io()
+        .unwrap();
+    (pczt, note)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
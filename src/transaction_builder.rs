@@ -11,6 +11,7 @@ use crate::notes::SpendableNote;
 use crate::orchard_tx::{
     select_single_spend_note, OrchardTransactionBuilder, ZebraJsonRpcOrchardWitnessProvider,
 };
+use crate::send_proposal::SendProposal;
 use crate::shielded_pool::ShieldedPool;
 use crate::zebra_integration::ZebraClient;
 use zcash_address::unified::{Container, Encoding};
@@ -30,6 +31,24 @@ pub struct ZcashTransactionBuilder {
     pub zebra_url: String,
 }
 
+/// Network Zebra reports (`mainnet` / `testnet`) and the pool new outputs use at `chain_tip`.
+async fn chain_network_and_output_pool(
+    zebra_client: &ZebraClient,
+    chain_tip: u32,
+) -> NozyResult<(&'static str, ShieldedPool)> {
+    let info = zebra_client.get_blockchain_info().await?;
+    let testnet = info
+        .get("chain")
+        .and_then(|v| v.as_str())
+        .is_some_and(|chain| matches!(chain, "test" | "testnet" | "regtest"));
+    let pool = if crate::ironwood::is_ironwood_active(chain_tip, testnet) {
+        ShieldedPool::Ironwood
+    } else {
+        ShieldedPool::Orchard
+    };
+    Ok((if testnet { "testnet" } else { "mainnet" }, pool))
+}
+
 impl ZcashTransactionBuilder {
     pub fn new() -> Self {
         Self {
@@ -143,7 +162,7 @@ impl ZcashTransactionBuilder {
             )
             .await?;
 
-            let fvk = orchard::keys::FullViewingKey::from(&spend_note.spending_key);
+            let fvk = spend_note.fvk.clone();
             let spent_nullifier_hex = Some(hex::encode(
                 spend_note.orchard_note.note.nullifier(&fvk).to_bytes(),
             ));
@@ -203,7 +222,7 @@ impl ZcashTransactionBuilder {
             )
             .await?;
 
-        let fvk = orchard::keys::FullViewingKey::from(&spend_note.spending_key);
+        let fvk = spend_note.fvk.clone();
         let spent_nullifier_hex = Some(hex::encode(
             spend_note.orchard_note.note.nullifier(&fvk).to_bytes(),
         ));
@@ -216,6 +235,122 @@ impl ZcashTransactionBuilder {
         })
     }
 
+    /// Select notes, change, fee and expiry for a send without proving (phase one of two).
+    ///
+    /// Mirrors the selection in [`Self::build_send_transaction`] so that executing the
+    /// proposal spends exactly the notes shown to the user.
+    pub async fn propose_send_transaction(
+        &self,
+        zebra_client: &ZebraClient,
+        spendable_notes: &[SpendableNote],
+        recipient_address: &str,
+        amount_zatoshis: u64,
+        fee_zatoshis: u64,
+        memo: Option<&[u8]>,
+        pilot: PilotSendOptions,
+    ) -> NozyResult<SendProposal> {
+        use crate::privacy::validate_shielded_address;
+        validate_shielded_address(recipient_address)?;
+
+        let (_, decoded) =
+            zcash_address::unified::Address::decode(recipient_address).map_err(|e| {
+                NozyError::InvalidOperation(format!("Invalid recipient address: {}", e))
+            })?;
+        if !decoded
+            .items()
+            .iter()
+            .any(|i| matches!(i, zcash_address::unified::Receiver::Orchard(_)))
+        {
+            return Err(NozyError::InvalidOperation(
+                "Recipient must include an Orchard receiver (ZIP-316). Sapling-only addresses are not supported."
+                    .to_string(),
+            ));
+        }
+
+        let chain_tip = zebra_client.get_best_block_height().await?;
+        let (network, pool) = chain_network_and_output_pool(zebra_client, chain_tip).await?;
+
+        let pool_notes: Vec<SpendableNote> = spendable_notes
+            .iter()
+            .filter(|n| !n.orchard_note.spent && n.pool == pool)
+            .cloned()
+            .collect();
+        let spend_note = match pool {
+            ShieldedPool::Ironwood => {
+                select_single_ironwood_spend_note(&pool_notes, amount_zatoshis, fee_zatoshis)?
+            }
+            ShieldedPool::Orchard => {
+                select_single_spend_note(&pool_notes, amount_zatoshis, fee_zatoshis)?
+            }
+        };
+        crate::send_readiness::ensure_witness_fresh_for_send(spend_note, chain_tip)?;
+
+        let has_change =
+            spend_note.orchard_note.value > amount_zatoshis.saturating_add(fee_zatoshis);
+        let shape = OrchardSendFeeShape::single_spend_send(has_change, memo);
+        let expected_fee = crate::fee_policy::fee_zatoshis(&shape, pilot.priority);
+        if fee_zatoshis < expected_fee {
+            return Err(NozyError::InvalidOperation(format!(
+                "Fee {} zats is below ZIP-317 minimum {} zats for this transaction shape",
+                fee_zatoshis, expected_fee
+            )));
+        }
+
+        SendProposal::from_selection(
+            network,
+            &[spend_note],
+            pool,
+            recipient_address,
+            amount_zatoshis,
+            fee_zatoshis,
+            memo,
+            chain_tip,
+            pilot.expiry_delta_blocks,
+            pilot.priority,
+        )
+    }
+
+    /// Prove, sign and broadcast a previously built [`SendProposal`] (phase two of two).
+    ///
+    /// Only the proposal's notes are handed to the builder, so a note spent or changed since
+    /// the proposal was shown fails here instead of silently picking a different input.
+    pub async fn execute_proposal(
+        &self,
+        zebra_client: &ZebraClient,
+        spendable_notes: &[SpendableNote],
+        proposal: &SendProposal,
+    ) -> NozyResult<SignedTransaction> {
+        proposal.validate()?;
+
+        let tip = zebra_client.get_best_block_height().await?;
+        if proposal.is_stale(tip) {
+            return Err(NozyError::InvalidOperation(format!(
+                "Send proposal from height {} is stale at tip {tip}; create a new proposal",
+                proposal.proposed_at_height
+            )));
+        }
+        let (network, pool) = chain_network_and_output_pool(zebra_client, tip).await?;
+        proposal.check_chain(network, pool)?;
+
+        let selected = proposal.notes_for_execution(spendable_notes)?;
+        let memo = proposal.memo_bytes()?;
+        let pilot = PilotSendOptions {
+            priority: proposal.priority,
+            expiry_delta_blocks: proposal.expiry_delta_blocks,
+        };
+
+        self.build_and_broadcast_send_transaction(
+            zebra_client,
+            &selected,
+            &proposal.recipient_address,
+            proposal.amount_zatoshis,
+            proposal.fee_zatoshis,
+            memo.as_deref(),
+            pilot,
+        )
+        .await
+    }
+
     /// Build, then broadcast with automatic rebuild when the pilot expiry window is exceeded.
     pub async fn build_and_broadcast_send_transaction(
         &self,
@@ -1,6 +1,9 @@
 use crate::error::{NozyError, NozyResult};
+use crate::labels::Annotation;
 use crate::notes::OrchardNote;
 use crate::paths::get_wallet_data_dir;
+use crate::payment_disclosure::OutputDisclosureKey;
+use crate::price_source::FiatValue;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -51,6 +54,10 @@ pub struct SentTransactionRecord {
     /// When this tx is a speed-up replacement, the original txid it replaces.
     #[serde(default)]
     pub speed_up_of_txid: Option<String>,
+
+    /// Per-output keys for payment disclosures, filled on first export.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub disclosure_keys: Vec<OutputDisclosureKey>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -80,6 +87,14 @@ pub struct TransactionView {
     pub notes_involved: Vec<String>,
 
     pub created_at: DateTime<Utc>,
+
+    /// Historical fiat value at block time (see [`crate::price_source::apply_fiat_values`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fiat_value: Option<FiatValue>,
+
+    /// User label / tags / note (see [`crate::labels::LabelStore::annotate_views`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub annotation: Option<Annotation>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -155,6 +170,7 @@ impl SentTransactionRecord {
             priority: false,
             expiry_height: None,
             speed_up_of_txid: None,
+            disclosure_keys: Vec::new(),
         }
     }
 
@@ -184,6 +200,7 @@ impl SentTransactionRecord {
             priority,
             expiry_height: Some(expiry_height),
             speed_up_of_txid: None,
+            disclosure_keys: Vec::new(),
         }
     }
 
@@ -210,6 +227,7 @@ impl SentTransactionRecord {
             priority: true,
             expiry_height: Some(expiry_height),
             speed_up_of_txid: Some(original.txid.clone()),
+            disclosure_keys: Vec::new(),
         }
     }
 
@@ -286,6 +304,8 @@ impl TransactionView {
             memo,
             notes_involved: notes.iter().map(|n| n.id.clone()).collect(),
             created_at: DateTime::UNIX_EPOCH,
+            fiat_value: None,
+            annotation: None,
         })
     }
 
@@ -307,6 +327,8 @@ impl TransactionView {
                 .and_then(|m| String::from_utf8(m.clone()).ok()),
             notes_involved: record.spent_note_ids.clone(),
             created_at: record.created_at,
+            fiat_value: None,
+            annotation: None,
         }
     }
 
@@ -399,6 +421,8 @@ pub fn collect_wallet_transaction_views(current_height: u32) -> NozyResult<Vec<T
                 memo: None,
                 notes_involved: vec![hex::encode(&sn.nullifier_bytes)],
                 created_at: DateTime::UNIX_EPOCH,
+                fiat_value: None,
+                annotation: None,
             });
             known_sent.insert(spend_txid.to_string());
         }
@@ -558,6 +582,10 @@ pub fn transaction_view_to_history_json(view: &TransactionView) -> serde_json::V
         "created_at": optional_history_timestamp(view.created_at),
         "memo": view.memo,
         "is_change": view.transaction_type == TransactionType::Change,
+        "fiat_value": view.fiat_value,
+        "label": view.annotation.as_ref().and_then(|a| a.label.clone()),
+        "tags": view.annotation.as_ref().map(|a| a.tags.clone()).unwrap_or_default(),
+        "user_note": view.annotation.as_ref().and_then(|a| a.note.clone()),
     })
 }
 
@@ -653,6 +681,8 @@ mod tests {
             memo: None,
             notes_involved: vec!["note1".to_string()],
             created_at: Utc::now(),
+            fiat_value: None,
+            annotation: None,
         };
         let received_json = transaction_view_to_history_json(&received);
         assert_eq!(received_json["transaction_type"], "Received");
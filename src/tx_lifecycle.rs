@@ -11,7 +11,6 @@ use crate::transaction_history::{
     SentTransactionRecord, SentTransactionStorage, TransactionStatus,
 };
 use crate::zebra_integration::ZebraClient;
-use orchard::keys::FullViewingKey;
 
 /// Expire unmined pilot txs past `expiry_height` and release their pending note locks.
 pub async fn expire_stale_pending_transactions(zebra_client: &ZebraClient) -> NozyResult<usize> {
@@ -132,7 +131,7 @@ pub async fn speed_up_transaction(
         refreshed.amount_zatoshis,
         fee_zatoshis,
     )?;
-    let fvk = FullViewingKey::from(&spent_note.spending_key);
+    let fvk = spent_note.fvk.clone();
     let spent_note_ids = vec![hex::encode(
         spent_note.orchard_note.note.nullifier(&fvk).to_bytes(),
     )];
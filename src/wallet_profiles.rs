@@ -24,6 +24,8 @@ const PROFILE_SCOPED_FILES: &[&str] = &[
     "lwd_compact.sqlite",
     "analytics.json",
     "keystone_pending_send.json",
+    "keystone_signing_queue.json",
+    "zns_actions.json",
     "address_book.json",
     "sent_transactions.json",
     "wallet_current_backup.dat",
@@ -45,6 +47,21 @@ pub struct WalletProfile {
     /// Last Orchard scan height for this profile (mirrors config while active).
     #[serde(default)]
     pub last_scan_height: Option<u32>,
+    #[serde(default)]
+    pub kind: WalletProfileKind,
+}
+
+/// What a profile holds its spend authority in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WalletProfileKind {
+    /// A seed in `wallet.dat`.
+    #[default]
+    Seed,
+    /// One FROST share of a threshold Orchard key (`frost_share.json`).
+    FrostShare,
+    /// Only the UFVK of a wallet that signs elsewhere (`viewing_key.json`).
+    ViewingKey,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -441,6 +458,7 @@ pub fn ensure_profiles_initialized() -> NozyResult<()> {
             network: None,
             zebra_url: None,
             last_scan_height: None,
+            kind: WalletProfileKind::Seed,
         };
         let dest = profile_dir(&base, &profile.id);
         migrate_legacy_wallet_to_profile(&base, &dest)?;
@@ -519,6 +537,21 @@ pub fn active_profile_id() -> Option<String> {
     load_manifest(&base).ok().and_then(|m| m.active_id)
 }
 
+/// What the active profile holds its spend authority in; [`WalletProfileKind::Seed`] when
+/// no profile is active.
+pub fn active_profile_kind() -> WalletProfileKind {
+    ensure_initialized_once();
+    let base = get_wallet_base_dir();
+    load_manifest(&base)
+        .ok()
+        .and_then(|m| {
+            let id = m.active_id?;
+            m.profiles.into_iter().find(|p| p.id == id)
+        })
+        .map(|p| p.kind)
+        .unwrap_or_default()
+}
+
 /// Active profile data directory (wallet.dat, notes, sync DB, etc.).
 pub fn active_profile_data_dir() -> PathBuf {
     ensure_initialized_once();
@@ -530,6 +563,19 @@ pub fn active_profile_data_dir() -> PathBuf {
     profiles_root(&base).join("_inactive")
 }
 
+/// Data directory of profile `id`, or an error when no such profile exists.
+pub fn profile_data_dir(id: &str) -> NozyResult<PathBuf> {
+    ensure_initialized_once();
+    let base = get_wallet_base_dir();
+    let manifest = load_manifest(&base)?;
+    if !manifest.profiles.iter().any(|p| p.id == id) {
+        return Err(NozyError::InvalidInput(format!(
+            "Unknown wallet profile: {id}"
+        )));
+    }
+    Ok(profile_dir(&base, id))
+}
+
 pub fn active_wallet_exists() -> bool {
     ensure_initialized_once();
     active_profile_data_dir().join("wallet.dat").exists()
@@ -563,6 +609,7 @@ pub fn create_new_profile(name: Option<&str>) -> NozyResult<WalletProfile> {
         network: None,
         zebra_url: None,
         last_scan_height: None,
+        kind: WalletProfileKind::Seed,
     };
 
     fs::create_dir_all(profile_dir(&base, &profile.id)).map_err(|e| {
@@ -581,6 +628,20 @@ pub fn create_new_profile(name: Option<&str>) -> NozyResult<WalletProfile> {
     Ok(profile)
 }
 
+/// Record what profile `id` holds its spend authority in.
+pub fn set_profile_kind(id: &str, kind: WalletProfileKind) -> NozyResult<()> {
+    ensure_initialized_once();
+    let base = get_wallet_base_dir();
+    let mut manifest = load_manifest(&base)?;
+    let profile = manifest
+        .profiles
+        .iter_mut()
+        .find(|profile| profile.id == id)
+        .ok_or_else(|| NozyError::Storage(format!("Wallet profile not found: {id}")))?;
+    profile.kind = kind;
+    save_manifest(&base, &manifest)
+}
+
 pub fn set_active_wallet_profile(id: &str) -> NozyResult<()> {
     ensure_initialized_once();
     let _ = snapshot_active_profile_from_config();
@@ -647,6 +708,7 @@ mod tests {
                 network: None,
                 zebra_url: None,
                 last_scan_height: None,
+                kind: WalletProfileKind::Seed,
             };
             fs::create_dir_all(profile_dir(&base, &p.id)).unwrap();
             fs::write(profile_dir(&base, &p.id).join("wallet.dat"), b"wallet-1").unwrap();
@@ -664,6 +726,7 @@ mod tests {
             network: None,
             zebra_url: None,
             last_scan_height: None,
+            kind: WalletProfileKind::Seed,
         };
         fs::create_dir_all(profile_dir(&base, &profile2.id)).unwrap();
         manifest.profiles.push(profile2.clone());
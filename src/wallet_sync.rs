@@ -3,7 +3,7 @@
 //! See [`docs/rfcs/WALLET_SYNC_UNIFIED_ARCHITECTURE.md`](../docs/rfcs/WALLET_SYNC_UNIFIED_ARCHITECTURE.md).
 
 use crate::config::{
-    load_config, update_last_scan_height, update_last_tip_sync_unix, WalletConfig,
+    load_config, update_last_scan_height, update_last_tip_sync_unix, WalletConfig, WalletRole,
 };
 use crate::error::{NozyError, NozyResult};
 use crate::hd_wallet::HDWallet;
@@ -12,6 +12,7 @@ use crate::notes::{
     NoteScanner,
 };
 use crate::orchard_tx::refresh_cached_witnesses_to_tip;
+use crate::ovk_recovery::persist_recovered_sent_transactions;
 use crate::send_readiness::{max_serialized_witness_lag_blocks, MAX_SEND_WITNESS_LAG_BLOCKS};
 use crate::zebra_integration::ZebraClient;
 use serde::{Deserialize, Serialize};
@@ -410,6 +411,10 @@ pub struct WalletSyncResult {
     pub last_scan_height: u32,
     /// True when the wallet is already caught up to chain tip (no blocks scanned).
     pub already_synced: bool,
+    /// Sent transactions rebuilt from chain via OVK decryption (missing from local history).
+    pub recovered_sent_transactions: usize,
+    /// Merchant invoices whose status changed (payment seen, confirmed or reorged out).
+    pub invoices_updated: usize,
 }
 
 /// Resolve inclusive scan bounds from config + options (no network I/O).
@@ -503,6 +508,13 @@ pub async fn sync_wallet_notes(
     } else {
         NoteScanner::new(wallet, zebra_client.clone())
     };
+    // Invoice addresses live on the Business account, which the wallet scan does not cover.
+    if crate::merchant_invoices::has_active_invoices().unwrap_or(false) {
+        match wallet.orchard_full_viewing_key(WalletRole::Business.orchard_account()) {
+            Ok(fvk) => note_scanner.set_watch_fvk(fvk),
+            Err(e) => tracing::warn!(error = %e, "cannot watch Business account for invoices"),
+        }
+    }
     let (scan_result, _spendable) = note_scanner
         .scan_notes(Some(range.scan_start), Some(range.scan_end))
         .await
@@ -574,6 +586,21 @@ pub async fn sync_wallet_notes(
         )
     })?;
 
+    // Sent history is best-effort: a storage failure here must not fail the note sync.
+    let recovered = note_scanner.take_recovered_sent_transactions();
+    let recovered_sent_transactions =
+        match persist_recovered_sent_transactions(&zebra_client, &recovered, range.chain_tip).await
+        {
+            Ok(n) => n,
+            Err(e) => {
+                tracing::warn!(error = %e, "Failed to persist OVK-recovered sent transactions");
+                0
+            }
+        };
+
+    let watched = note_scanner.take_watched_payments();
+    let invoices_updated = match_invoices(&zebra_client, &watched, range.chain_tip).await;
+
     update_last_scan_height(range.scan_end).map_err(|e| {
         WalletSyncError::with_range(
             WalletSyncPhase::Checkpoint,
@@ -610,9 +637,32 @@ pub async fn sync_wallet_notes(
         blocks_scanned,
         last_scan_height: checkpoint,
         already_synced: false,
+        recovered_sent_transactions,
+        invoices_updated,
     })
 }
 
+/// Match invoice payments and advance confirmations; best-effort like sent-history recovery.
+async fn match_invoices(
+    zebra_client: &ZebraClient,
+    watched: &[crate::notes::WatchedPayment],
+    chain_tip: u32,
+) -> usize {
+    if watched.is_empty()
+        && !crate::merchant_invoices::has_active_invoices().unwrap_or(false)
+        && !crate::merchant_invoices::has_unconfirmed_invoice_payments().unwrap_or(false)
+    {
+        return 0;
+    }
+    match crate::merchant_invoices::sync_invoice_payments(zebra_client, watched, chain_tip).await {
+        Ok(changed) => changed.len(),
+        Err(e) => {
+            tracing::warn!(error = %e, "Failed to match merchant invoice payments");
+            0
+        }
+    }
+}
+
 async fn refresh_and_persist_witnesses(
     zebra_client: &ZebraClient,
     notes: &mut [crate::notes::SerializableOrchardNote],
@@ -708,6 +758,7 @@ async fn finish_caught_up_sync(
         let _ = crate::wallet_profiles::touch_active_profile_scan_height(checkpoint);
         checkpoint
     };
+    let invoices_updated = match_invoices(zebra_client, &[], range.chain_tip).await;
     let witness_lag_after = max_serialized_witness_lag_blocks(&notes_after_repair, range.chain_tip);
     let scan_caught_up = last_scan_height >= range.chain_tip;
     let already_synced = scan_caught_up && witness_lag_after <= MAX_SEND_WITNESS_LAG_BLOCKS;
@@ -726,6 +777,8 @@ async fn finish_caught_up_sync(
         blocks_scanned,
         last_scan_height,
         already_synced,
+        recovered_sent_transactions: 0,
+        invoices_updated,
     })
 }
 
@@ -822,6 +875,7 @@ mod tests {
             rseed_bytes: None,
             spent_in_txid: None,
             pool: crate::shielded_pool::ShieldedPool::Orchard,
+            frozen: false,
         }];
         let chain_tip = 3_389_822;
         let target = witness_catchup_target_height(&notes, chain_tip);
@@ -850,6 +904,7 @@ mod tests {
             rseed_bytes: None,
             spent_in_txid: None,
             pool: crate::shielded_pool::ShieldedPool::Orchard,
+            frozen: false,
         }];
         let chain_tip = 3_389_822;
         assert_eq!(witness_catchup_target_height(&notes, chain_tip), chain_tip);
@@ -901,6 +956,7 @@ mod tests {
             rseed_bytes: None,
             spent_in_txid: None,
             pool: crate::shielded_pool::ShieldedPool::Orchard,
+            frozen: false,
         }];
         apply_empty_cache_backfill(&mut range, &config, &opts, &cached);
         assert!(range.scan_start > range.scan_end);
@@ -931,6 +987,7 @@ mod tests {
             rseed_bytes: None,
             spent_in_txid: None,
             pool: crate::shielded_pool::ShieldedPool::Orchard,
+            frozen: false,
         }];
         apply_cached_notes_resume(&mut range, &config, &opts, &cached);
         assert_eq!(range.scan_start, 4_120_001);
@@ -962,6 +1019,7 @@ mod tests {
             rseed_bytes: None,
             spent_in_txid: None,
             pool: crate::shielded_pool::ShieldedPool::Ironwood,
+            frozen: false,
         }];
         apply_cached_notes_resume(&mut range, &config, &opts, &cached);
         assert_eq!(range.scan_start, 4_143_641);
@@ -989,6 +1047,7 @@ mod tests {
             rseed_bytes: None,
             spent_in_txid: None,
             pool: crate::shielded_pool::ShieldedPool::Orchard,
+            frozen: false,
         }];
 
         let scanned: Vec<SerializableOrchardNote> = vec![];
@@ -1019,6 +1078,7 @@ mod tests {
                 rseed_bytes: None,
                 spent_in_txid: None,
                 pool: crate::shielded_pool::ShieldedPool::Orchard,
+                frozen: false,
             }
         }
 
@@ -1060,6 +1120,7 @@ mod tests {
                 rseed_bytes: None,
                 spent_in_txid: None,
                 pool: crate::shielded_pool::ShieldedPool::Orchard,
+                frozen: false,
             }
         }
 
@@ -1092,6 +1153,7 @@ mod tests {
                 rseed_bytes: None,
                 spent_in_txid: None,
                 pool: crate::shielded_pool::ShieldedPool::Orchard,
+                frozen: false,
             }
         }
 
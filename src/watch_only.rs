@@ -0,0 +1,156 @@
+//! Watch-only profiles for the online half of offline signing.
+//!
+//! A profile of kind [`WalletProfileKind::ViewingKey`] keeps the UFVK of a wallet whose seed
+//! lives on another machine, in `viewing_key.json` encrypted with the notes vault key (NZW1).
+//! It scans with that key, builds PCZTs ([`crate::build_keystone_send_pczt`]) and broadcasts
+//! the signed result, and never holds a seed. A FROST profile works the same way with its
+//! group viewing key ([`crate::frost_multisig::FrostShare::group_fvk`]).
+
+use crate::error::{NozyError, NozyResult};
+use crate::notes_vault::{decrypt_watch_file_content, encrypt_watch_json};
+use crate::paths::get_wallet_data_dir;
+use crate::wallet_profiles::{active_profile_kind, WalletProfileKind};
+use orchard::keys::FullViewingKey;
+use serde::{Deserialize, Serialize};
+use zcash_keys::keys::UnifiedFullViewingKey;
+use zcash_protocol::consensus::{MainNetwork, NetworkType, TestNetwork};
+
+const VIEWING_KEY_FILE: &str = "viewing_key.json";
+
+/// The UFVK a watch-only profile follows.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredViewingKey {
+    /// `mainnet` or `testnet`.
+    pub network: String,
+    pub ufvk: String,
+}
+
+impl StoredViewingKey {
+    /// Checks that `ufvk` is for `network` and has an Orchard component.
+    pub fn new(ufvk: &str, network: NetworkType) -> NozyResult<Self> {
+        decode_orchard_fvk(ufvk, network)?;
+        Ok(Self {
+            network: match network {
+                NetworkType::Main => "mainnet",
+                NetworkType::Test | NetworkType::Regtest => "testnet",
+            }
+            .to_string(),
+            ufvk: ufvk.trim().to_string(),
+        })
+    }
+
+    pub fn network_type(&self) -> NetworkType {
+        if self.network == "testnet" {
+            NetworkType::Test
+        } else {
+            NetworkType::Main
+        }
+    }
+
+    pub fn orchard_fvk(&self) -> NozyResult<FullViewingKey> {
+        decode_orchard_fvk(&self.ufvk, self.network_type())
+    }
+}
+
+fn decode_orchard_fvk(ufvk: &str, network: NetworkType) -> NozyResult<FullViewingKey> {
+    let ufvk = match network {
+        NetworkType::Main => UnifiedFullViewingKey::decode(&MainNetwork, ufvk.trim()),
+        NetworkType::Test | NetworkType::Regtest => {
+            UnifiedFullViewingKey::decode(&TestNetwork, ufvk.trim())
+        }
+    }
+    .map_err(|e| NozyError::InvalidInput(format!("Invalid UFVK: {e}")))?;
+    ufvk.orchard()
+        .cloned()
+        .ok_or_else(|| NozyError::InvalidInput("UFVK has no Orchard component".to_string()))
+}
+
+/// Store `ufvk` in the active profile and mark the profile as watch-only.
+///
+/// The notes vault must be unlocked with the password this profile will use.
+pub fn import_viewing_key(ufvk: &str, network: NetworkType) -> NozyResult<StoredViewingKey> {
+    let stored = StoredViewingKey::new(ufvk, network)?;
+    save_viewing_key(&stored)?;
+    if let Some(id) = crate::wallet_profiles::active_profile_id() {
+        crate::wallet_profiles::set_profile_kind(&id, WalletProfileKind::ViewingKey)?;
+    }
+    Ok(stored)
+}
+
+pub fn save_viewing_key(stored: &StoredViewingKey) -> NozyResult<()> {
+    let json = serde_json::to_string(stored)
+        .map_err(|e| NozyError::Storage(format!("Failed to encode {VIEWING_KEY_FILE}: {e}")))?;
+    let path = get_wallet_data_dir().join(VIEWING_KEY_FILE);
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    let tmp = path.with_extension("tmp");
+    std::fs::write(&tmp, encrypt_watch_json(&json)?)
+        .map_err(|e| NozyError::Storage(format!("Failed to write {VIEWING_KEY_FILE}: {e}")))?;
+    std::fs::rename(&tmp, &path)
+        .map_err(|e| NozyError::Storage(format!("Failed to replace {VIEWING_KEY_FILE}: {e}")))
+}
+
+pub fn load_viewing_key() -> NozyResult<StoredViewingKey> {
+    let path = get_wallet_data_dir().join(VIEWING_KEY_FILE);
+    if !path.exists() {
+        return Err(NozyError::InvalidOperation(
+            "The active profile holds no viewing key (see `nozy pczt watch`)".into(),
+        ));
+    }
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| NozyError::Storage(format!("Failed to read {VIEWING_KEY_FILE}: {e}")))?;
+    let json = decrypt_watch_file_content(&content)?;
+    serde_json::from_str(&json)
+        .map_err(|e| NozyError::Storage(format!("Failed to parse {VIEWING_KEY_FILE}: {e}")))
+}
+
+/// Viewing key the active profile builds PCZTs with: a watch-only profile's UFVK or a FROST
+/// profile's group key. `None` for seed profiles.
+pub fn active_profile_viewing_key() -> NozyResult<Option<FullViewingKey>> {
+    match active_profile_kind() {
+        WalletProfileKind::Seed => Ok(None),
+        WalletProfileKind::ViewingKey => load_viewing_key()?.orchard_fvk().map(Some),
+        WalletProfileKind::FrostShare => crate::frost_multisig::load_share()?.group_fvk().map(Some),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notes_vault::{clear_notes_vault, unlock_notes_vault};
+    use crate::paths::with_wallet_data_dir;
+
+    #[test]
+    fn viewing_key_is_stored_encrypted() {
+        let _g = crate::notes_vault::lock_notes_vault_for_test();
+        let dir = std::env::temp_dir().join(format!("nozy-watch-only-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let ufvk = crate::frost_multisig::dealer_keygen(2, 2, NetworkType::Test).unwrap()[0]
+            .group_ufvk()
+            .unwrap();
+        with_wallet_data_dir(&dir, || {
+            clear_notes_vault();
+            unlock_notes_vault("watch").unwrap();
+            assert!(StoredViewingKey::new("uviewtest1garbage", NetworkType::Test).is_err());
+            assert!(StoredViewingKey::new(&ufvk, NetworkType::Main).is_err());
+            let stored = StoredViewingKey::new(&ufvk, NetworkType::Test).unwrap();
+            save_viewing_key(&stored).unwrap();
+
+            let on_disk = std::fs::read_to_string(dir.join(VIEWING_KEY_FILE)).unwrap();
+            assert!(!on_disk.contains(&ufvk));
+            let loaded = load_viewing_key().unwrap();
+            assert_eq!(loaded.ufvk, stored.ufvk);
+            assert_eq!(
+                loaded.orchard_fvk().unwrap().to_bytes(),
+                stored.orchard_fvk().unwrap().to_bytes()
+            );
+
+            unlock_notes_vault("wrong").unwrap();
+            assert!(load_viewing_key().is_err());
+            clear_notes_vault();
+        });
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
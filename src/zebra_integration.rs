@@ -227,7 +227,7 @@ impl ZebraClient {
             .map_err(|e| format!("Failed to create HTTP client: {}", e))
     }
 
-    fn selected_proxy_from_config(config: &WalletConfig) -> Option<String> {
+    pub(crate) fn selected_proxy_from_config(config: &WalletConfig) -> Option<String> {
         let privacy = &config.privacy_network;
         let preferred = privacy.preferred_network.to_lowercase();
 
@@ -747,6 +747,50 @@ Add the node to trusted_zebra_urls for operator infrastructure, or enable Tor."
             .ok_or_else(|| NozyError::InvalidOperation("Invalid mempool response".to_string()))
     }
 
+    /// Txids currently in the mempool (`getrawmempool`).
+    pub async fn get_raw_mempool(&self) -> NozyResult<Vec<String>> {
+        let request = serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "getrawmempool",
+            "params": [],
+            "id": 1
+        });
+
+        let response: ZebraResponse<Vec<String>> = self.make_request(request).await?;
+
+        if let Some(error) = response.error {
+            return Err(NozyError::InvalidOperation(format!(
+                "Zebra RPC error: {} (code: {})",
+                error.message, error.code
+            )));
+        }
+
+        Ok(response.result.unwrap_or_default())
+    }
+
+    /// Verbose `getrawtransaction` object (same shape as transactions in `getblock` verbosity 2).
+    pub async fn get_raw_transaction_verbose(&self, txid: &str) -> NozyResult<Value> {
+        let request = serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "getrawtransaction",
+            "params": [txid, 1],
+            "id": 1
+        });
+
+        let response: ZebraResponse<Value> = self.make_request(request).await?;
+
+        if let Some(error) = response.error {
+            return Err(NozyError::InvalidOperation(format!(
+                "Zebra RPC error: {} (code: {})",
+                error.message, error.code
+            )));
+        }
+
+        response.result.ok_or_else(|| {
+            NozyError::InvalidOperation("No transaction data in response".to_string())
+        })
+    }
+
     pub async fn get_network_info(&self) -> NozyResult<HashMap<String, Value>> {
         let request = serde_json::json!({
             "jsonrpc": "2.0",
@@ -1356,6 +1400,7 @@ This blocks remote RPC only; localhost RPC remains allowed.",
             block_hash,
             confirmations,
             in_mempool: block_height.is_none(),
+            expiry_height: parse_tx_expiry_height(&tx_data),
         })
     }
 
@@ -1448,6 +1493,15 @@ fn parse_tx_block_height(tx_data: &serde_json::Value) -> Option<u32> {
         .map(|h| h as u32)
 }
 
+/// `None` for transactions without an expiry (`expiryheight` 0 or absent).
+fn parse_tx_expiry_height(tx_data: &serde_json::Value) -> Option<u32> {
+    tx_data
+        .get("expiryheight")
+        .and_then(|v| v.as_u64())
+        .filter(|h| *h > 0)
+        .map(|h| h as u32)
+}
+
 fn parse_tx_block_hash(tx_data: &serde_json::Value) -> Option<String> {
     tx_data
         .get("blockhash")
@@ -1463,6 +1517,8 @@ pub struct TransactionInfo {
     pub block_hash: Option<String>,
     pub confirmations: u32,
     pub in_mempool: bool,
+    /// Last height the transaction can be mined at.
+    pub expiry_height: Option<u32>,
 }
 
 #[derive(Debug, Clone)]
@@ -0,0 +1,256 @@
+//! ZIP-304 Sapling address signatures.
+//!
+//! A signature proves control of a Sapling payment address by producing a Spend proof
+//! for a fixed note (`v = 1`, `rcm = 0`, `rcv = 0`) sent to that address, placed at
+//! position 0 of an otherwise empty commitment tree, plus a SpendAuth signature over
+//! `BLAKE2b-256("ZIP304Signed" || coin_type, zkproof || message)`. The verifier rebuilds
+//! the note, anchor and value commitment from the address alone, so nothing beyond the
+//! signature is disclosed. Signatures by the same address share a nullifier and are
+//! therefore linkable.
+//!
+//! Encoding: `zip304:` followed by base64url of `nf || rk || zkproof || spendAuthSig`
+//! (320 bytes).
+
+use crate::error::{NozyError, NozyResult};
+use crate::sapling_keys::derive_sapling_extsk;
+use crate::sapling_tree_codec::SaplingCommitmentTree;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use bellman::groth16::Proof;
+use bls12_381::Bls12;
+use group::ff::Field;
+use incrementalmerkletree::witness::IncrementalWitness;
+use rand::rngs::OsRng;
+use redjubjub::SpendAuth;
+use sapling::prover::SpendProver;
+use sapling::value::{NoteValue, ValueCommitTrapdoor, ValueCommitment};
+use sapling::verifier::SaplingVerificationContext;
+use sapling::zip32::ExtendedSpendingKey;
+use sapling::{MerklePath, Node, Note, PaymentAddress, Rseed};
+use zcash_address::unified::{self, Container, Encoding};
+use zcash_keys::encoding::decode_payment_address;
+use zcash_proofs::prover::LocalTxProver;
+use zcash_protocol::consensus::{NetworkConstants, NetworkType};
+use zcash_protocol::constants::{mainnet, testnet};
+use zip32::Scope;
+
+pub const SIGNATURE_PREFIX: &str = "zip304:";
+const PERSONALIZATION_PREFIX: &[u8; 12] = b"ZIP304Signed";
+const NOTE_VALUE: u64 = 1;
+const SIGNATURE_LEN: usize = 32 + 32 + 192 + 64;
+
+/// Sapling payment address and its network, from a `zs1…` / `ztestsapling…` address or
+/// the Sapling receiver of a unified address.
+pub fn sapling_payment_address(address: &str) -> NozyResult<(PaymentAddress, NetworkType)> {
+    let address = address.trim();
+    let (hrp, network) = if address.starts_with(testnet::HRP_SAPLING_PAYMENT_ADDRESS) {
+        (testnet::HRP_SAPLING_PAYMENT_ADDRESS, NetworkType::Test)
+    } else if address.starts_with(mainnet::HRP_SAPLING_PAYMENT_ADDRESS) {
+        (mainnet::HRP_SAPLING_PAYMENT_ADDRESS, NetworkType::Main)
+    } else {
+        let (network, ua) = unified::Address::decode(address).map_err(|e| {
+            NozyError::AddressParsing(format!("Not a Sapling or unified address: {e}"))
+        })?;
+        let receiver = ua
+            .items()
+            .into_iter()
+            .find_map(|item| match item {
+                unified::Receiver::Sapling(raw) => Some(raw),
+                _ => None,
+            })
+            .ok_or_else(|| NozyError::AddressParsing("Address has no Sapling receiver".into()))?;
+        let payment_address = PaymentAddress::from_bytes(&receiver)
+            .ok_or_else(|| NozyError::AddressParsing("Invalid Sapling receiver".into()))?;
+        return Ok((payment_address, network));
+    };
+    let payment_address = decode_payment_address(hrp, address)
+        .map_err(|e| NozyError::AddressParsing(format!("Invalid Sapling address: {e}")))?;
+    Ok((payment_address, network))
+}
+
+/// The note, anchor and Merkle path every signature for `address` commits to.
+fn signed_note(address: &PaymentAddress) -> NozyResult<(Note, bls12_381::Scalar, MerklePath)> {
+    let note = address.create_note(
+        NoteValue::from_raw(NOTE_VALUE),
+        Rseed::BeforeZip212(jubjub::Fr::ZERO),
+    );
+    let mut tree = SaplingCommitmentTree::empty();
+    tree.append(Node::from_cmu(&note.cmu()))
+        .map_err(|_| NozyError::InvalidOperation("Sapling tree is full".into()))?;
+    let witness = IncrementalWitness::from_tree(tree)
+        .ok_or_else(|| NozyError::InvalidOperation("Empty Sapling tree".into()))?;
+    let path = witness
+        .path()
+        .ok_or_else(|| NozyError::InvalidOperation("Sapling witness has no Merkle path".into()))?;
+    let anchor = bls12_381::Scalar::from_bytes(&witness.root().to_bytes())
+        .into_option()
+        .ok_or_else(|| NozyError::InvalidOperation("Invalid Sapling anchor".into()))?;
+    Ok((note, anchor, path))
+}
+
+fn zero_trapdoor() -> ValueCommitTrapdoor {
+    ValueCommitTrapdoor::from_bytes([0u8; 32])
+        .into_option()
+        .expect("zero is a valid trapdoor")
+}
+
+fn digest(network: NetworkType, zkproof: &[u8], message: &str) -> [u8; 32] {
+    let mut personal = [0u8; 16];
+    personal[..12].copy_from_slice(PERSONALIZATION_PREFIX);
+    personal[12..].copy_from_slice(&network.coin_type().to_le_bytes());
+    let hash = blake2b_simd::Params::new()
+        .hash_length(32)
+        .personal(&personal)
+        .to_state()
+        .update(zkproof)
+        .update(message.as_bytes())
+        .finalize();
+    let mut out = [0u8; 32];
+    out.copy_from_slice(hash.as_bytes());
+    out
+}
+
+/// Sign `message` as `address`, which must be an external address of `extsk`.
+pub fn sign_message(
+    prover: &LocalTxProver,
+    extsk: &ExtendedSpendingKey,
+    address: &str,
+    message: &str,
+) -> NozyResult<String> {
+    let (payment_address, network) = sapling_payment_address(address)?;
+    let dfvk = extsk.to_diversifiable_full_viewing_key();
+    if !matches!(
+        dfvk.decrypt_diversifier(&payment_address),
+        Some((_, Scope::External))
+    ) {
+        return Err(NozyError::InvalidInput(
+            "Address does not belong to this Sapling key".into(),
+        ));
+    }
+
+    let (note, anchor, path) = signed_note(&payment_address)?;
+    let proof_generation_key = extsk.expsk.proof_generation_key();
+    let viewing_key = proof_generation_key.to_viewing_key();
+    let nf = note.nf(&viewing_key.nk, 0);
+
+    let mut rng = OsRng;
+    let alpha = jubjub::Fr::random(&mut rng);
+    let rk = viewing_key.rk(alpha);
+    let circuit = LocalTxProver::prepare_circuit(
+        proof_generation_key,
+        *payment_address.diversifier(),
+        *note.rseed(),
+        note.value(),
+        alpha,
+        zero_trapdoor(),
+        anchor,
+        path,
+    )
+    .ok_or_else(|| NozyError::InvalidOperation("Could not build the Spend circuit".into()))?;
+    let zkproof = LocalTxProver::encode_proof(prover.create_proof(circuit, &mut rng));
+
+    let rsk = extsk.expsk.ask.randomize(&alpha);
+    let spend_auth_sig = rsk.sign(&mut rng, &digest(network, &zkproof, message));
+
+    let mut bytes = Vec::with_capacity(SIGNATURE_LEN);
+    bytes.extend_from_slice(&nf.0);
+    bytes.extend_from_slice(&<[u8; 32]>::from(rk));
+    bytes.extend_from_slice(&zkproof);
+    bytes.extend_from_slice(&<[u8; 64]>::from(spend_auth_sig));
+    Ok(format!(
+        "{SIGNATURE_PREFIX}{}",
+        URL_SAFE_NO_PAD.encode(bytes)
+    ))
+}
+
+/// Sign with the Sapling key of `account` (see [`crate::derive_sapling_account_keys`]).
+pub fn sign_with_seed(
+    seed_bytes: &[u8],
+    account: u32,
+    address: &str,
+    message: &str,
+) -> NozyResult<String> {
+    let extsk = derive_sapling_extsk(seed_bytes, account)?;
+    sign_message(&LocalTxProver::bundled(), &extsk, address, message)
+}
+
+/// Verify a `zip304:` signature against the claimed Sapling `address`.
+pub fn verify_message(
+    prover: &LocalTxProver,
+    address: &str,
+    message: &str,
+    signature: &str,
+) -> NozyResult<bool> {
+    let Some(encoded) = signature.trim().strip_prefix(SIGNATURE_PREFIX) else {
+        return Ok(false);
+    };
+    let Ok(bytes) = URL_SAFE_NO_PAD.decode(encoded.trim_end_matches('=')) else {
+        return Ok(false);
+    };
+    if bytes.len() != SIGNATURE_LEN {
+        return Ok(false);
+    }
+    let (payment_address, network) = sapling_payment_address(address)?;
+
+    let nf: [u8; 32] = bytes[..32].try_into().expect("length checked");
+    let rk_bytes: [u8; 32] = bytes[32..64].try_into().expect("length checked");
+    let zkproof = &bytes[64..256];
+    let sig_bytes: [u8; 64] = bytes[256..].try_into().expect("length checked");
+
+    let Ok(rk) = redjubjub::VerificationKey::<SpendAuth>::try_from(rk_bytes) else {
+        return Ok(false);
+    };
+    let Ok(proof) = Proof::<Bls12>::read(zkproof) else {
+        return Ok(false);
+    };
+    let spend_auth_sig = redjubjub::Signature::<SpendAuth>::from(sig_bytes);
+
+    let (_, anchor, _) = signed_note(&payment_address)?;
+    let cv = ValueCommitment::derive(NoteValue::from_raw(NOTE_VALUE), zero_trapdoor());
+    let (spend_vk, _) = prover.verifying_keys();
+
+    let mut ctx = SaplingVerificationContext::new();
+    Ok(ctx.check_spend(
+        &cv,
+        anchor,
+        &nf,
+        rk,
+        &digest(network, zkproof, message),
+        spend_auth_sig,
+        proof,
+        &spend_vk,
+    ))
+}
+
+/// [`verify_message`] with the bundled Sapling parameters.
+pub fn verify(address: &str, message: &str, signature: &str) -> NozyResult<bool> {
+    verify_message(&LocalTxProver::bundled(), address, message, signature)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sapling_keys::{derive_sapling_account_keys, encode_sapling_payment_address};
+    use bip39::{Language, Mnemonic};
+
+    #[test]
+    fn sign_and_verify_sapling_address() {
+        let seed = Mnemonic::from_entropy_in(Language::English, &[7u8; 16])
+            .unwrap()
+            .to_seed("");
+        let keys = derive_sapling_account_keys(&seed, 0, 0).unwrap();
+        let ours =
+            encode_sapling_payment_address(&keys.payment_address, NetworkType::Main).unwrap();
+        let other_keys = derive_sapling_account_keys(&seed, 1, 0).unwrap();
+        let other =
+            encode_sapling_payment_address(&other_keys.payment_address, NetworkType::Main).unwrap();
+
+        let prover = LocalTxProver::bundled();
+        let sig = sign_message(&prover, &keys.extsk, &ours, "zip304 test").unwrap();
+        assert!(sig.starts_with(SIGNATURE_PREFIX));
+        assert!(verify_message(&prover, &ours, "zip304 test", &sig).unwrap());
+        assert!(!verify_message(&prover, &ours, "tampered", &sig).unwrap());
+        assert!(!verify_message(&prover, &other, "zip304 test", &sig).unwrap());
+        assert!(sign_message(&prover, &keys.extsk, &other, "zip304 test").is_err());
+    }
+}
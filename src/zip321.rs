@@ -12,6 +12,10 @@ pub struct PaymentRequest {
     pub memo: Option<String>,
     pub message: Option<String>,
     pub label: Option<String>,
+    /// Merchant-signed requests: expiry covered by `signature` (RFC 3339).
+    pub expires: Option<String>,
+    /// `nozy-sm-v1` signature, see [`crate::payment_request`].
+    pub signature: Option<String>,
 }
 
 /// Build `zcash:<address>?amount=…&memo=…` (amount in ZEC decimal).
@@ -48,6 +52,12 @@ pub fn build_payment_uri(
     Ok(uri)
 }
 
+/// Append `key=value` (percent-encoded) to a `zcash:` URI.
+pub fn append_query_param(uri: &str, key: &str, value: &str) -> String {
+    let sep = if uri.contains('?') { '&' } else { '?' };
+    format!("{uri}{sep}{key}={}", percent_encode(value))
+}
+
 /// Parse a `zcash:` URI into address + optional amount/memo.
 pub fn parse_payment_uri(raw: &str) -> NozyResult<PaymentRequest> {
     let s = raw.trim();
@@ -69,6 +79,8 @@ pub fn parse_payment_uri(raw: &str) -> NozyResult<PaymentRequest> {
     let mut memo = None;
     let mut message = None;
     let mut label = None;
+    let mut expires = None;
+    let mut signature = None;
 
     if let Some(q) = query {
         for pair in q.split('&') {
@@ -88,6 +100,8 @@ pub fn parse_payment_uri(raw: &str) -> NozyResult<PaymentRequest> {
                 "memo" => memo = Some(val),
                 "message" => message = Some(val),
                 "label" => label = Some(val),
+                "expires" => expires = Some(val),
+                "sig" => signature = Some(val),
                 _ => {}
             }
         }
@@ -99,6 +113,8 @@ pub fn parse_payment_uri(raw: &str) -> NozyResult<PaymentRequest> {
         memo,
         message,
         label,
+        expires,
+        signature,
     })
 }
 
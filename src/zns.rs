@@ -1,7 +1,8 @@
 //! Zcash Name Service (ZNS) client helpers shared by CLI and companion surfaces.
 //!
-//! Indexer JSON-RPC: https://www.zcashnames.com/docs (resolve method).
+//! Indexer JSON-RPC: https://www.zcashnames.com/docs (`resolve`, `status`).
 //! Optional `.zcash` / `.zec` suffixes are stripped; they are never required.
+//! Registration and updates from the wallet live in [`crate::zns_registration`].
 
 use crate::error::{NozyError, NozyResult};
 use serde::{Deserialize, Serialize};
@@ -25,6 +26,23 @@ pub struct ZnsRegistration {
     pub nonce: Option<u64>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub last_action: Option<String>,
+    /// Hex Ed25519 key that signed the claim; updates must be signed by the same key.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pubkey: Option<String>,
+}
+
+/// Registry parameters from the indexer `status` method.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct ZnsRegistryInfo {
+    /// Shielded address that claim and update transactions pay.
+    pub registry_address: String,
+    #[serde(default)]
+    pub claim_cost_zatoshis: u64,
+    #[serde(default)]
+    pub update_cost_zatoshis: u64,
+    /// Last block the indexer has processed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub synced_height: Option<u64>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -115,86 +133,135 @@ pub fn verify_indexer_url(url: &str) -> NozyResult<()> {
     )))
 }
 
-/// Resolve a Zcash name via the public indexer JSON-RPC `resolve` method.
-pub async fn resolve_name(raw_name: &str, network: Option<&str>) -> NozyResult<ResolveResult> {
-    let name = normalize_zns_name(raw_name);
-    if !is_valid_zns_name(&name) {
-        return Err(NozyError::InvalidInput(
-            "Invalid Zcash name. Use lowercase letters, digits, and hyphens (e.g. alice).".into(),
-        ));
+/// JSON-RPC client for one ZNS indexer.
+#[derive(Debug, Clone)]
+pub struct ZnsIndexer {
+    url: String,
+}
+
+impl ZnsIndexer {
+    /// Indexer at `url` without the allowlist check (local or self-hosted indexers).
+    pub fn new(url: impl Into<String>) -> Self {
+        Self { url: url.into() }
     }
 
-    let url = indexer_url(network);
-    verify_indexer_url(&url)?;
-
-    let body = serde_json::json!({
-        "jsonrpc": "2.0",
-        "id": 1,
-        "method": "resolve",
-        "params": [&name],
-    });
-
-    let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(15))
-        .build()
-        .map_err(|e| NozyError::NetworkError(format!("Failed to build HTTP client: {e}")))?;
-
-    let res = client
-        .post(&url)
-        .json(&body)
-        .send()
-        .await
-        .map_err(|e| NozyError::NetworkError(format!("ZNS indexer unreachable: {e}")))?;
-
-    if !res.status().is_success() {
-        return Err(NozyError::NetworkError(format!(
-            "ZNS indexer HTTP {}",
-            res.status()
-        )));
-    }
-
-    let rpc: serde_json::Value = res
-        .json()
-        .await
-        .map_err(|e| NozyError::NetworkError(format!("Invalid ZNS indexer response: {e}")))?;
-
-    if let Some(err) = rpc.get("error") {
-        let msg = err
-            .get("message")
-            .and_then(|m| m.as_str())
-            .unwrap_or("ZNS resolve failed");
-        return Err(NozyError::NetworkError(msg.to_string()));
-    }
-
-    let result = rpc.get("result");
-    if result.is_none() || result == Some(&serde_json::Value::Null) {
-        return Ok(ResolveResult {
-            name,
-            found: false,
-            registration: None,
-        });
+    /// Configured indexer for `network`, rejected unless allowlisted.
+    pub fn for_network(network: Option<&str>) -> NozyResult<Self> {
+        let url = indexer_url(network);
+        verify_indexer_url(&url)?;
+        Ok(Self { url })
     }
 
-    let reg: ZnsRegistration = serde_json::from_value(result.cloned().unwrap_or_default())
-        .map_err(|e| NozyError::NetworkError(format!("Unexpected ZNS registration shape: {e}")))?;
+    pub fn url(&self) -> &str {
+        &self.url
+    }
 
-    if reg.address.is_empty() {
-        return Ok(ResolveResult {
-            name,
-            found: false,
-            registration: None,
+    /// Call `method`; a missing or `null` result is returned as `Value::Null`.
+    async fn call(&self, method: &str, params: serde_json::Value) -> NozyResult<serde_json::Value> {
+        let body = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params,
         });
+
+        let client = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(15))
+            .build()
+            .map_err(|e| NozyError::NetworkError(format!("Failed to build HTTP client: {e}")))?;
+
+        let res = client
+            .post(&self.url)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| NozyError::NetworkError(format!("ZNS indexer unreachable: {e}")))?;
+
+        if !res.status().is_success() {
+            return Err(NozyError::NetworkError(format!(
+                "ZNS indexer HTTP {}",
+                res.status()
+            )));
+        }
+
+        let rpc: serde_json::Value = res
+            .json()
+            .await
+            .map_err(|e| NozyError::NetworkError(format!("Invalid ZNS indexer response: {e}")))?;
+
+        if let Some(err) = rpc.get("error").filter(|e| !e.is_null()) {
+            let msg = err
+                .get("message")
+                .and_then(|m| m.as_str())
+                .unwrap_or("ZNS indexer request failed");
+            return Err(NozyError::NetworkError(format!("ZNS {method}: {msg}")));
+        }
+
+        Ok(rpc.get("result").cloned().unwrap_or_default())
     }
 
-    Ok(ResolveResult {
-        name: if reg.name.is_empty() {
-            name
-        } else {
-            reg.name.clone()
-        },
-        found: true,
-        registration: Some(reg),
-    })
+    /// Look up an already normalized, valid name.
+    pub async fn resolve(&self, name: &str) -> NozyResult<ResolveResult> {
+        let result = self.call("resolve", serde_json::json!([name])).await?;
+        if result.is_null() {
+            return Ok(ResolveResult {
+                name: name.to_string(),
+                found: false,
+                registration: None,
+            });
+        }
+
+        let reg: ZnsRegistration = serde_json::from_value(result).map_err(|e| {
+            NozyError::NetworkError(format!("Unexpected ZNS registration shape: {e}"))
+        })?;
+
+        if reg.address.is_empty() {
+            return Ok(ResolveResult {
+                name: name.to_string(),
+                found: false,
+                registration: None,
+            });
+        }
+
+        Ok(ResolveResult {
+            name: if reg.name.is_empty() {
+                name.to_string()
+            } else {
+                reg.name.clone()
+            },
+            found: true,
+            registration: Some(reg),
+        })
+    }
+
+    pub async fn registry_info(&self) -> NozyResult<ZnsRegistryInfo> {
+        let result = self.call("status", serde_json::json!([])).await?;
+        let info: ZnsRegistryInfo = serde_json::from_value(result)
+            .map_err(|e| NozyError::NetworkError(format!("Unexpected ZNS status shape: {e}")))?;
+        if info.registry_address.trim().is_empty() {
+            return Err(NozyError::NetworkError(
+                "ZNS indexer did not report a registry address".to_string(),
+            ));
+        }
+        Ok(info)
+    }
+}
+
+/// Normalize `raw` and reject names the registry would refuse.
+pub fn parse_zns_name(raw: &str) -> NozyResult<String> {
+    let name = normalize_zns_name(raw);
+    if !is_valid_zns_name(&name) {
+        return Err(NozyError::InvalidInput(
+            "Invalid Zcash name. Use lowercase letters, digits, and hyphens (e.g. alice).".into(),
+        ));
+    }
+    Ok(name)
+}
+
+/// Resolve a Zcash name via the public indexer JSON-RPC `resolve` method.
+pub async fn resolve_name(raw_name: &str, network: Option<&str>) -> NozyResult<ResolveResult> {
+    let name = parse_zns_name(raw_name)?;
+    ZnsIndexer::for_network(network)?.resolve(&name).await
 }
 
 #[cfg(test)]
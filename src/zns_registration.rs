@@ -0,0 +1,1093 @@
+//! In-wallet ZNS registration, renewal and update.
+//!
+//! Every action is a shielded payment to the registry address, carrying a signed memo:
+//!
+//! - `ZNS:CLAIM:<name>:<ua>:<pubkey>:<sig>`
+//! - `ZNS:UPDATE:<name>:<ua>:<nonce>:<sig>`; a renewal re-sends the current UA
+//!
+//! `<pubkey>` is the wallet's hex Ed25519 ZNS key (SLIP-0010 from the seed, never used
+//! for spending) and `<sig>` its hex signature over everything before the final `:`.
+//! Submitted actions are kept in `zns_actions.json` until the indexer's record matches
+//! the mined transaction; [`refresh_zns_actions`] does that check.
+//!
+//! The indexer is not trusted with where or how much to pay: the registry address it
+//! reports must match the one pinned in the `zns_registration` config section, and the
+//! cost must stay under `max_cost_zatoshis`.
+
+use crate::cli_helpers::scan_notes_for_sending;
+use crate::config::{load_config, save_config, WalletRole};
+use crate::error::{NozyError, NozyResult};
+use crate::fee_policy::{
+    estimate_orchard_send_fee_zatoshis, pilot_transaction_expired, PilotSendOptions,
+};
+use crate::hd_wallet::HDWallet;
+use crate::input_validation::{normalize_unified_address, validate_zcash_address};
+use crate::notes::mark_wallet_notes_spent_by_nullifier_hex;
+use crate::paths::get_wallet_data_dir;
+use crate::transaction_builder::ZcashTransactionBuilder;
+use crate::transaction_history::{SentTransactionRecord, SentTransactionStorage};
+use crate::zebra_integration::ZebraClient;
+use crate::zns::{parse_zns_name, ZnsIndexer, ZnsRegistration};
+use chrono::{DateTime, Utc};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use hmac::{Hmac, Mac};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::Sha512;
+use std::fs;
+use std::path::PathBuf;
+use zcash_protocol::consensus::NetworkType;
+use zeroize::Zeroize;
+
+pub const ZNS_ACTIONS_FILE: &str = "zns_actions.json";
+pub const ZNS_ACTIONS_VERSION: u32 = 1;
+
+/// A name needs a claim or update at least this often (180 days of 75 s blocks).
+pub const ZNS_ACTIVITY_WINDOW_BLOCKS: u64 = 180 * 1152;
+
+/// SLIP-0010 path of the ZNS key, all hardened: `m/44'/133'/1'/0'/0'` (Business account).
+const ZNS_KEY_PATH: [u32; 5] = [44, 133, 1, 0, 0];
+
+const MAX_MEMO_BYTES: usize = 512;
+
+/// Default ceiling on what one claim or update may cost (0.01 ZEC).
+pub const DEFAULT_MAX_COST_ZATOSHIS: u64 = 1_000_000;
+
+/// `zns_registration` section of the wallet config.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ZnsRegistrationConfig {
+    /// Registry UA, verified out of band. Nothing is paid until it is set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub registry_address: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_cost_zatoshis: Option<u64>,
+}
+
+impl ZnsRegistrationConfig {
+    pub fn max_cost_zatoshis(&self) -> u64 {
+        self.max_cost_zatoshis.unwrap_or(DEFAULT_MAX_COST_ZATOSHIS)
+    }
+
+    /// Reject a registry address that differs from the pinned one, or a cost over the
+    /// ceiling. Returns whether the address is pinned at all.
+    fn check_indexer_terms(&self, registry_address: &str, cost_zatoshis: u64) -> NozyResult<bool> {
+        if cost_zatoshis > self.max_cost_zatoshis() {
+            return Err(NozyError::InvalidOperation(format!(
+                "ZNS indexer asks {:.8} ZEC, above the {:.8} ZEC ceiling \
+                 (raise it with 'zns config --max-cost')",
+                cost_zatoshis as f64 / 100_000_000.0,
+                self.max_cost_zatoshis() as f64 / 100_000_000.0
+            )));
+        }
+        let Some(pinned) = self.registry_address.as_deref() else {
+            return Ok(false);
+        };
+        let pinned = normalize_unified_address(pinned);
+        if pinned != registry_address {
+            return Err(NozyError::InvalidOperation(format!(
+                "ZNS indexer reports registry {registry_address}, but the pinned registry is \
+                 {pinned}; refusing to pay it"
+            )));
+        }
+        Ok(true)
+    }
+
+    /// Like [`Self::check_indexer_terms`], but an unpinned registry is an error too.
+    fn check_payment(&self, registry_address: &str, cost_zatoshis: u64) -> NozyResult<()> {
+        if self.check_indexer_terms(registry_address, cost_zatoshis)? {
+            return Ok(());
+        }
+        Err(NozyError::InvalidOperation(format!(
+            "No ZNS registry address is pinned. Verify {registry_address} out of band, \
+             then pin it with 'zns config --registry-address'"
+        )))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ZnsActionKind {
+    Claim,
+    Update,
+    /// Update to the address the name already has, to keep it active.
+    Renew,
+}
+
+impl ZnsActionKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Claim => "claim",
+            Self::Update => "update",
+            Self::Renew => "renew",
+        }
+    }
+
+    fn memo_verb(self) -> &'static str {
+        match self {
+            Self::Claim => "CLAIM",
+            Self::Update | Self::Renew => "UPDATE",
+        }
+    }
+}
+
+/// The wallet's ZNS signing key.
+pub struct ZnsSigningKey(SigningKey);
+
+impl ZnsSigningKey {
+    pub fn from_wallet(wallet: &HDWallet) -> Self {
+        let mut seed = wallet.get_mnemonic_object().to_seed("");
+        let key = Self::from_seed(&seed);
+        seed.zeroize();
+        key
+    }
+
+    pub fn from_seed(seed: &[u8]) -> Self {
+        let mut secret = slip10_ed25519(seed, &ZNS_KEY_PATH);
+        let key = SigningKey::from_bytes(&secret);
+        secret.zeroize();
+        Self(key)
+    }
+
+    pub fn public_key_hex(&self) -> String {
+        hex::encode(self.0.verifying_key().to_bytes())
+    }
+
+    fn sign_hex(&self, message: &str) -> String {
+        hex::encode(self.0.sign(message.as_bytes()).to_bytes())
+    }
+}
+
+/// SLIP-0010 ed25519 derivation; every index is hardened.
+fn slip10_ed25519(seed: &[u8], path: &[u32]) -> [u8; 32] {
+    let hmac = |key: &[u8], data: &[u8]| {
+        let mut mac = Hmac::<Sha512>::new_from_slice(key).expect("HMAC accepts any key length");
+        mac.update(data);
+        let mut out = [0u8; 64];
+        out.copy_from_slice(&mac.finalize().into_bytes());
+        out
+    };
+    let mut i = hmac(b"ed25519 seed", seed);
+    for index in path {
+        let mut data = Vec::with_capacity(37);
+        data.push(0);
+        data.extend_from_slice(&i[..32]);
+        data.extend_from_slice(&(index | 0x8000_0000).to_be_bytes());
+        let next = hmac(&i[32..], &data);
+        data.zeroize();
+        i.zeroize();
+        i = next;
+    }
+    let mut secret = [0u8; 32];
+    secret.copy_from_slice(&i[..32]);
+    i.zeroize();
+    secret
+}
+
+/// Signed memo for `kind`; fails if it would not fit in a 512-byte memo.
+pub fn build_zns_memo(
+    key: &ZnsSigningKey,
+    kind: ZnsActionKind,
+    name: &str,
+    address: &str,
+    nonce: u64,
+) -> NozyResult<String> {
+    let payload = match kind {
+        ZnsActionKind::Claim => format!("ZNS:CLAIM:{name}:{address}:{}", key.public_key_hex()),
+        ZnsActionKind::Update | ZnsActionKind::Renew => {
+            format!("ZNS:{}:{name}:{address}:{nonce}", kind.memo_verb())
+        }
+    };
+    let memo = format!("{payload}:{}", key.sign_hex(&payload));
+    if memo.len() > MAX_MEMO_BYTES {
+        return Err(NozyError::InvalidInput(format!(
+            "ZNS memo is {} bytes (maximum {MAX_MEMO_BYTES}); use a shorter address",
+            memo.len()
+        )));
+    }
+    Ok(memo)
+}
+
+/// Check a ZNS memo's signature against `pubkey_hex`.
+pub fn verify_zns_memo(memo: &str, pubkey_hex: &str) -> NozyResult<()> {
+    let invalid = |why: &str| NozyError::InvalidInput(format!("Invalid ZNS memo: {why}"));
+    let (payload, sig_hex) = memo
+        .rsplit_once(':')
+        .ok_or_else(|| invalid("no signature"))?;
+    if !payload.starts_with("ZNS:CLAIM:") && !payload.starts_with("ZNS:UPDATE:") {
+        return Err(invalid("unknown action"));
+    }
+    let pubkey: [u8; 32] = hex::decode(pubkey_hex)
+        .ok()
+        .and_then(|b| b.try_into().ok())
+        .ok_or_else(|| invalid("bad public key"))?;
+    let sig: [u8; 64] = hex::decode(sig_hex)
+        .ok()
+        .and_then(|b| b.try_into().ok())
+        .ok_or_else(|| invalid("bad signature encoding"))?;
+    VerifyingKey::from_bytes(&pubkey)
+        .map_err(|_| invalid("bad public key"))?
+        .verify(payload.as_bytes(), &Signature::from_bytes(&sig))
+        .map_err(|_| invalid("signature does not match"))
+}
+
+/// A signed action ready to send to the registry.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ZnsPreparedAction {
+    pub kind: ZnsActionKind,
+    pub name: String,
+    pub address: String,
+    pub nonce: u64,
+    pub memo: String,
+    pub registry_address: String,
+    /// Whether `registry_address` matched the pinned registry; unpinned actions are never sent.
+    pub registry_pinned: bool,
+    pub cost_zatoshis: u64,
+    pub fee_zatoshis: u64,
+    /// Link the name in config once confirmed (it points at the Business UA).
+    pub link_on_confirm: bool,
+    /// This wallet's ZNS public key, used to recognise its own later updates.
+    pub pubkey: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum ZnsActionStatus {
+    /// Broadcast, not mined yet.
+    Submitted,
+    /// Mined; the indexer has not caught up.
+    Mined { height: u32 },
+    /// Mined and the indexer's record matches it.
+    Confirmed { height: u32 },
+    /// Mined, then replaced by a later action signed with this wallet's key.
+    Superseded { height: u32, by_txid: String },
+    /// The indexer's record disagrees with the action or the chain.
+    Mismatch { reason: String },
+    /// Not mined by its expiry height; the registry was never paid.
+    Expired { expiry_height: u32 },
+}
+
+impl ZnsActionStatus {
+    pub fn is_final(&self) -> bool {
+        matches!(
+            self,
+            Self::Confirmed { .. }
+                | Self::Superseded { .. }
+                | Self::Mismatch { .. }
+                | Self::Expired { .. }
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ZnsNameAction {
+    pub id: String,
+    pub kind: ZnsActionKind,
+    pub name: String,
+    pub address: String,
+    pub nonce: u64,
+    pub txid: String,
+    pub cost_zatoshis: u64,
+    #[serde(default)]
+    pub link_on_confirm: bool,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub pubkey: String,
+    pub submitted_at: DateTime<Utc>,
+    /// Last height the transaction can be mined at.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expiry_height: Option<u32>,
+    pub status: ZnsActionStatus,
+    /// Why the last refresh could not check this action (indexer unreachable, …).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ZnsActionLog {
+    pub version: u32,
+    #[serde(default)]
+    pub actions: Vec<ZnsNameAction>,
+}
+
+impl ZnsActionLog {
+    pub fn get(&self, id: &str) -> Option<&ZnsNameAction> {
+        self.actions.iter().find(|a| a.id == id)
+    }
+
+    /// Actions still waiting on the chain or the indexer.
+    pub fn pending(&self) -> impl Iterator<Item = &ZnsNameAction> {
+        self.actions.iter().filter(|a| !a.status.is_final())
+    }
+}
+
+fn new_action_id() -> String {
+    let mut bytes = [0u8; 8];
+    OsRng.fill_bytes(&mut bytes);
+    format!("{:016x}", u64::from_be_bytes(bytes))
+}
+
+pub fn zns_actions_path() -> PathBuf {
+    get_wallet_data_dir().join(ZNS_ACTIONS_FILE)
+}
+
+pub fn load_zns_actions() -> NozyResult<ZnsActionLog> {
+    let path = zns_actions_path();
+    if !path.exists() {
+        return Ok(ZnsActionLog {
+            version: ZNS_ACTIONS_VERSION,
+            actions: Vec::new(),
+        });
+    }
+    let content = fs::read_to_string(&path)
+        .map_err(|e| NozyError::Storage(format!("Failed to read ZNS actions: {e}")))?;
+    serde_json::from_str(&content)
+        .map_err(|e| NozyError::Storage(format!("Failed to parse ZNS actions: {e}")))
+}
+
+pub fn save_zns_actions(log: &ZnsActionLog) -> NozyResult<()> {
+    let path = zns_actions_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| {
+            NozyError::Storage(format!("Failed to create ZNS actions directory: {e}"))
+        })?;
+    }
+    let serialized = serde_json::to_string_pretty(log)
+        .map_err(|e| NozyError::Storage(format!("Failed to serialize ZNS actions: {e}")))?;
+    let temp_path = path.with_extension("tmp");
+    fs::write(&temp_path, serialized)
+        .map_err(|e| NozyError::Storage(format!("Failed to write ZNS actions: {e}")))?;
+    fs::rename(&temp_path, &path)
+        .map_err(|e| NozyError::Storage(format!("Failed to save ZNS actions: {e}")))
+}
+
+/// Build and sign `kind` for `raw_name`. `address` defaults to the Business UA
+/// (account 1); a renewal always keeps the current address. Fails if the indexer's
+/// registry address or cost disagree with the `zns_registration` config.
+pub async fn prepare_zns_action(
+    wallet: &HDWallet,
+    indexer: &ZnsIndexer,
+    kind: ZnsActionKind,
+    raw_name: &str,
+    address: Option<&str>,
+    network: NetworkType,
+) -> NozyResult<ZnsPreparedAction> {
+    let business_address =
+        wallet.generate_orchard_address(WalletRole::Business.orchard_account(), 0, network)?;
+    prepare_with_key(
+        indexer,
+        &load_config().zns_registration,
+        &ZnsSigningKey::from_wallet(wallet),
+        kind,
+        raw_name,
+        address,
+        &business_address,
+    )
+    .await
+}
+
+async fn prepare_with_key(
+    indexer: &ZnsIndexer,
+    settings: &ZnsRegistrationConfig,
+    key: &ZnsSigningKey,
+    kind: ZnsActionKind,
+    raw_name: &str,
+    address: Option<&str>,
+    business_address: &str,
+) -> NozyResult<ZnsPreparedAction> {
+    let name = parse_zns_name(raw_name)?;
+    let current = indexer.resolve(&name).await?.registration;
+    let address = address.map(normalize_unified_address);
+
+    let (address, nonce) = match (kind, current) {
+        (ZnsActionKind::Claim, Some(reg)) => {
+            return Err(NozyError::InvalidOperation(format!(
+                "“{name}” is already registered to {}",
+                reg.address
+            )))
+        }
+        (ZnsActionKind::Claim, None) => {
+            (address.unwrap_or_else(|| business_address.to_string()), 0)
+        }
+        (_, None) => {
+            return Err(NozyError::InvalidOperation(format!(
+                "“{name}” is not registered; claim it first"
+            )))
+        }
+        (_, Some(reg)) => {
+            if reg
+                .pubkey
+                .as_deref()
+                .is_some_and(|k| !k.eq_ignore_ascii_case(&key.public_key_hex()))
+            {
+                return Err(NozyError::InvalidOperation(format!(
+                    "“{name}” was claimed with a different ZNS key than this wallet's"
+                )));
+            }
+            let current_address = normalize_unified_address(&reg.address);
+            let address = if kind == ZnsActionKind::Renew {
+                if address.as_ref().is_some_and(|a| *a != current_address) {
+                    return Err(NozyError::InvalidInput(
+                        "A renewal keeps the current address; use update to change it".into(),
+                    ));
+                }
+                current_address
+            } else {
+                address.unwrap_or_else(|| business_address.to_string())
+            };
+            (address, reg.nonce.unwrap_or(0) + 1)
+        }
+    };
+    validate_zcash_address(&address)?;
+
+    let info = indexer.registry_info().await?;
+    let cost_zatoshis = match kind {
+        ZnsActionKind::Claim => info.claim_cost_zatoshis,
+        ZnsActionKind::Update | ZnsActionKind::Renew => info.update_cost_zatoshis,
+    };
+    let registry_address = normalize_unified_address(&info.registry_address);
+    let registry_pinned = settings.check_indexer_terms(&registry_address, cost_zatoshis)?;
+    let memo = build_zns_memo(key, kind, &name, &address, nonce)?;
+    let fee_zatoshis = estimate_orchard_send_fee_zatoshis(Some(memo.as_bytes()), true);
+    Ok(ZnsPreparedAction {
+        kind,
+        link_on_confirm: address == business_address,
+        name,
+        address,
+        nonce,
+        memo,
+        registry_address,
+        registry_pinned,
+        cost_zatoshis,
+        fee_zatoshis,
+        pubkey: key.public_key_hex(),
+    })
+}
+
+/// Pay the registry with the signed memo and record the action as submitted.
+pub async fn submit_zns_action(
+    wallet: &HDWallet,
+    zebra_url: &str,
+    prepared: &ZnsPreparedAction,
+) -> NozyResult<ZnsNameAction> {
+    let mut config = load_config();
+    config
+        .zns_registration
+        .check_payment(&prepared.registry_address, prepared.cost_zatoshis)?;
+    if config.zebra_url != zebra_url {
+        config.zebra_url = zebra_url.to_string();
+    }
+    let zebra_client = ZebraClient::from_config(&config);
+
+    let spendable_notes = scan_notes_for_sending(wallet, zebra_url).await?;
+    let memo = prepared.memo.as_bytes();
+    let pilot = PilotSendOptions::for_send();
+    let fee_zatoshis = estimate_orchard_send_fee_zatoshis(Some(memo), pilot.priority);
+    let needed = prepared.cost_zatoshis.saturating_add(fee_zatoshis);
+    // Sends spend a single note, so the largest note has to cover cost and fee.
+    let largest = spendable_notes
+        .iter()
+        .map(|n| n.orchard_note.value)
+        .max()
+        .unwrap_or(0);
+    if largest < needed {
+        return Err(NozyError::InsufficientFunds(format!(
+            "ZNS {} needs {:.8} ZEC in one note; the largest has {:.8} ZEC",
+            prepared.kind.as_str(),
+            needed as f64 / 100_000_000.0,
+            largest as f64 / 100_000_000.0
+        )));
+    }
+
+    let mut tx_builder = ZcashTransactionBuilder::new();
+    tx_builder.set_zebra_url(zebra_url);
+    tx_builder.enable_mainnet_broadcast();
+    let transaction = tx_builder
+        .build_and_broadcast_send_transaction(
+            &zebra_client,
+            &spendable_notes,
+            &prepared.registry_address,
+            prepared.cost_zatoshis,
+            fee_zatoshis,
+            Some(memo),
+            pilot,
+        )
+        .await?;
+    let txid = transaction.txid.clone();
+
+    // The registry has been paid: record the action first so a retry sees it, and only
+    // warn from here on.
+    let action = ZnsNameAction {
+        id: new_action_id(),
+        kind: prepared.kind,
+        name: prepared.name.clone(),
+        address: prepared.address.clone(),
+        nonce: prepared.nonce,
+        txid: txid.clone(),
+        cost_zatoshis: prepared.cost_zatoshis,
+        link_on_confirm: prepared.link_on_confirm,
+        pubkey: prepared.pubkey.clone(),
+        submitted_at: Utc::now(),
+        expiry_height: Some(transaction.expiry_height),
+        status: ZnsActionStatus::Submitted,
+        last_error: None,
+    };
+    let saved = load_zns_actions().and_then(|mut log| {
+        log.version = ZNS_ACTIONS_VERSION;
+        log.actions.push(action.clone());
+        save_zns_actions(&log)
+    });
+    if let Err(e) = saved {
+        eprintln!("Warning: could not record ZNS action {txid}: {e}");
+    }
+
+    let spent_note_ids: Vec<String> = transaction.spent_nullifier_hex.iter().cloned().collect();
+    if let Err(e) = mark_wallet_notes_spent_by_nullifier_hex(&spent_note_ids, Some(&txid)) {
+        eprintln!("Warning: could not mark spent notes locally after ZNS action: {e}");
+    }
+
+    let mut record = SentTransactionRecord::new_pilot(
+        txid.clone(),
+        prepared.registry_address.clone(),
+        prepared.cost_zatoshis,
+        fee_zatoshis,
+        Some(memo.to_vec()),
+        spent_note_ids,
+        pilot.priority,
+        transaction.expiry_height,
+    );
+    record.mark_broadcast();
+    if let Err(e) = SentTransactionStorage::new().and_then(|s| s.save_transaction(record)) {
+        eprintln!("Warning: could not save ZNS action {txid} to history: {e}");
+    }
+    Ok(action)
+}
+
+/// Compare the indexer's record for a name with one of our actions and the height its
+/// transaction was mined at (`None` while unmined).
+pub fn check_indexer_record(
+    action: &ZnsNameAction,
+    record: Option<&ZnsRegistration>,
+    mined_height: Option<u32>,
+) -> ZnsActionStatus {
+    let Some(height) = mined_height else {
+        return ZnsActionStatus::Submitted;
+    };
+    let Some(record) = record else {
+        return ZnsActionStatus::Mined { height };
+    };
+    let record_height = record.height.unwrap_or(0);
+    if record.txid.as_deref() != Some(action.txid.as_str()) {
+        let ours = !action.pubkey.is_empty()
+            && record
+                .pubkey
+                .as_deref()
+                .is_some_and(|k| k.eq_ignore_ascii_case(&action.pubkey));
+        if ours
+            && record_height > u64::from(height)
+            && record.nonce.is_some_and(|n| n > action.nonce)
+        {
+            return ZnsActionStatus::Superseded {
+                height,
+                by_txid: record.txid.clone().unwrap_or_default(),
+            };
+        }
+        return if record_height >= u64::from(height) {
+            ZnsActionStatus::Mismatch {
+                reason: format!(
+                    "indexer record for “{}” comes from {} at height {record_height}, not this transaction",
+                    action.name,
+                    record.txid.as_deref().unwrap_or("an unknown transaction")
+                ),
+            }
+        } else {
+            ZnsActionStatus::Mined { height }
+        };
+    }
+    let mismatch = |reason: String| ZnsActionStatus::Mismatch { reason };
+    if record_height != u64::from(height) {
+        return mismatch(format!(
+            "indexer says height {record_height}, but the transaction was mined at {height}"
+        ));
+    }
+    if normalize_unified_address(&record.address) != action.address {
+        return mismatch(format!(
+            "indexer resolves “{}” to {}, not {}",
+            action.name, record.address, action.address
+        ));
+    }
+    if record.nonce.is_some_and(|n| n != action.nonce) {
+        return mismatch(format!(
+            "indexer nonce {} does not match {}",
+            record.nonce.unwrap_or_default(),
+            action.nonce
+        ));
+    }
+    ZnsActionStatus::Confirmed { height }
+}
+
+/// Re-check every pending action against the chain and the indexer, save the results
+/// and link confirmed Business names in config.
+pub async fn refresh_zns_actions(
+    zebra_client: &ZebraClient,
+    indexer: &ZnsIndexer,
+) -> NozyResult<Vec<ZnsNameAction>> {
+    let mut log = load_zns_actions()?;
+    if recheck_zns_actions(&mut log.actions, zebra_client, indexer).await? {
+        save_zns_actions(&log)?;
+    }
+    Ok(log.actions)
+}
+
+/// Update the status of every pending action in `actions`; true if any changed.
+///
+/// An action still unmined once the tip passes its expiry height is `Expired`.
+async fn recheck_zns_actions(
+    actions: &mut [ZnsNameAction],
+    zebra_client: &ZebraClient,
+    indexer: &ZnsIndexer,
+) -> NozyResult<bool> {
+    let mut pending = actions
+        .iter_mut()
+        .filter(|a| !a.status.is_final())
+        .peekable();
+    let chain_tip = match pending.peek() {
+        Some(_) => zebra_client.get_block_count().await.ok(),
+        None => None,
+    };
+    let mut changed = false;
+    for action in pending {
+        let mined_height = match zebra_client.get_transaction_info(&action.txid).await {
+            Ok(info) => info.block_height,
+            Err(_) => None,
+        };
+        let expired = action.expiry_height.filter(|expiry| {
+            mined_height.is_none()
+                && chain_tip.is_some_and(|tip| pilot_transaction_expired(tip, *expiry))
+        });
+        if let Some(expiry_height) = expired {
+            action.status = ZnsActionStatus::Expired { expiry_height };
+            action.last_error = None;
+            changed = true;
+            continue;
+        }
+        let record = if mined_height.is_some() {
+            match indexer.resolve(&action.name).await {
+                Ok(resolved) => resolved.registration,
+                Err(e) => {
+                    let error = e.to_string();
+                    if action.last_error.as_deref() != Some(error.as_str()) {
+                        action.last_error = Some(error);
+                        changed = true;
+                    }
+                    continue;
+                }
+            }
+        } else {
+            None
+        };
+        if action.last_error.take().is_some() {
+            changed = true;
+        }
+        let status = check_indexer_record(action, record.as_ref(), mined_height);
+        if status != action.status {
+            if matches!(status, ZnsActionStatus::Confirmed { .. }) && action.link_on_confirm {
+                let mut config = load_config();
+                config.linked_zns_name = Some(action.name.clone());
+                save_config(&config)?;
+            }
+            action.status = status;
+            changed = true;
+        }
+    }
+    Ok(changed)
+}
+
+/// Height by which the name needs another claim or update to stay active.
+pub fn renewal_due_height(record: &ZnsRegistration) -> Option<u64> {
+    record.height.map(|h| h + ZNS_ACTIVITY_WINDOW_BLOCKS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    const UA: &str =
+        "u1business000000000000000000000000000000000000000000000000000000000000000000000000";
+    const CURRENT: &str =
+        "u1current0000000000000000000000000000000000000000000000000000000000000000000000000";
+
+    fn test_key() -> ZnsSigningKey {
+        ZnsSigningKey::from_seed(&[7u8; 64])
+    }
+
+    /// Local JSON-RPC indexer answering `resolve` with `record` and `status` with a registry.
+    async fn mock_indexer(record: serde_json::Value) -> ZnsIndexer {
+        ZnsIndexer::new(mock_rpc(record).await)
+    }
+
+    /// URL of a local JSON-RPC server for both the indexer and Zebra: the tip is 3,000,000
+    /// and every transaction is unmined in the mempool.
+    async fn mock_rpc(record: serde_json::Value) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            loop {
+                let Ok((mut socket, _)) = listener.accept().await else {
+                    return;
+                };
+                let mut buf = Vec::new();
+                let mut chunk = [0u8; 4096];
+                let body = loop {
+                    let n = socket.read(&mut chunk).await.unwrap_or(0);
+                    if n == 0 {
+                        break None;
+                    }
+                    buf.extend_from_slice(&chunk[..n]);
+                    let Some(split) = buf.windows(4).position(|w| w == b"\r\n\r\n") else {
+                        continue;
+                    };
+                    let head = String::from_utf8_lossy(&buf[..split]).to_ascii_lowercase();
+                    let len = head
+                        .lines()
+                        .find_map(|l| l.strip_prefix("content-length:"))
+                        .and_then(|v| v.trim().parse::<usize>().ok())
+                        .unwrap_or(0);
+                    if buf.len() >= split + 4 + len {
+                        break Some(buf[split + 4..split + 4 + len].to_vec());
+                    }
+                };
+                let Some(body) = body else { continue };
+                let request: serde_json::Value = serde_json::from_slice(&body).unwrap();
+                let result = match request["method"].as_str() {
+                    Some("resolve") => record.clone(),
+                    Some("status") => serde_json::json!({
+                        "registry_address": "u1registry",
+                        "claim_cost_zatoshis": 100_000,
+                        "update_cost_zatoshis": 10_000,
+                        "synced_height": 3_000_000,
+                    }),
+                    Some("getblockcount") => serde_json::json!(3_000_000),
+                    Some("getrawtransaction") => serde_json::json!({
+                        "txid": request["params"][0],
+                        "expiryheight": 2_999_999,
+                    }),
+                    _ => serde_json::Value::Null,
+                };
+                let reply =
+                    serde_json::json!({ "jsonrpc": "2.0", "id": 1, "result": result }).to_string();
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{reply}",
+                    reply.len()
+                );
+                let _ = socket.write_all(response.as_bytes()).await;
+            }
+        });
+        url
+    }
+
+    fn action(txid: &str) -> ZnsNameAction {
+        ZnsNameAction {
+            id: "a1".to_string(),
+            kind: ZnsActionKind::Update,
+            name: "alice".to_string(),
+            address: UA.to_string(),
+            nonce: 3,
+            txid: txid.to_string(),
+            cost_zatoshis: 10_000,
+            link_on_confirm: false,
+            pubkey: test_key().public_key_hex(),
+            submitted_at: Utc::now(),
+            expiry_height: None,
+            status: ZnsActionStatus::Submitted,
+            last_error: None,
+        }
+    }
+
+    fn record(txid: &str, height: u64, address: &str, nonce: u64) -> ZnsRegistration {
+        ZnsRegistration {
+            name: "alice".to_string(),
+            address: address.to_string(),
+            txid: Some(txid.to_string()),
+            height: Some(height),
+            nonce: Some(nonce),
+            last_action: Some("UPDATE".to_string()),
+            pubkey: None,
+        }
+    }
+
+    #[test]
+    fn slip10_matches_reference_vector() {
+        let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+        assert_eq!(
+            hex::encode(slip10_ed25519(&seed, &[])),
+            "2b4be7f19ee27bbf30c667b642d5f4aa69fd169872f8fc3059c08ebae2eb19e7"
+        );
+        assert_eq!(
+            hex::encode(slip10_ed25519(&seed, &[0])),
+            "68e0fe46dfb67e368c75379acec591dad19df3cde26e63b93a8e704f1dade7a3"
+        );
+    }
+
+    #[test]
+    fn memos_are_signed_and_fit() {
+        let key = test_key();
+        let claim = build_zns_memo(&key, ZnsActionKind::Claim, "alice", UA, 0).unwrap();
+        assert!(claim.starts_with(&format!("ZNS:CLAIM:alice:{UA}:{}:", key.public_key_hex())));
+        verify_zns_memo(&claim, &key.public_key_hex()).unwrap();
+
+        let renew = build_zns_memo(&key, ZnsActionKind::Renew, "alice", UA, 4).unwrap();
+        assert!(renew.starts_with(&format!("ZNS:UPDATE:alice:{UA}:4:")));
+        verify_zns_memo(&renew, &key.public_key_hex()).unwrap();
+
+        let tampered = renew.replacen(":4:", ":5:", 1);
+        assert!(verify_zns_memo(&tampered, &key.public_key_hex()).is_err());
+        let other = ZnsSigningKey::from_seed(&[8u8; 64]);
+        assert!(verify_zns_memo(&renew, &other.public_key_hex()).is_err());
+
+        let long_name = "a".repeat(63);
+        let long_ua = format!("u1{}", "q".repeat(400));
+        assert!(build_zns_memo(&key, ZnsActionKind::Claim, &long_name, &long_ua, 0).is_err());
+    }
+
+    #[test]
+    fn indexer_record_is_checked_against_the_chain() {
+        let ours = action("tx-ours");
+        assert_eq!(
+            check_indexer_record(&ours, None, None),
+            ZnsActionStatus::Submitted
+        );
+        assert_eq!(
+            check_indexer_record(&ours, None, Some(100)),
+            ZnsActionStatus::Mined { height: 100 }
+        );
+        let lagging = record("tx-old", 90, "u1old", 2);
+        assert_eq!(
+            check_indexer_record(&ours, Some(&lagging), Some(100)),
+            ZnsActionStatus::Mined { height: 100 }
+        );
+        let matched = record("tx-ours", 100, UA, 3);
+        assert_eq!(
+            check_indexer_record(&ours, Some(&matched), Some(100)),
+            ZnsActionStatus::Confirmed { height: 100 }
+        );
+        for bad in [
+            record("tx-other", 120, UA, 4),
+            record("tx-ours", 101, UA, 3),
+            record("tx-ours", 100, "u1elsewhere", 3),
+            record("tx-ours", 100, UA, 9),
+        ] {
+            assert!(matches!(
+                check_indexer_record(&ours, Some(&bad), Some(100)),
+                ZnsActionStatus::Mismatch { .. }
+            ));
+        }
+
+        let mut claim = ours.clone();
+        claim.kind = ZnsActionKind::Claim;
+        claim.nonce = 0;
+        let mut newer = record("tx-later", 120, "u1elsewhere", 1);
+        assert!(matches!(
+            check_indexer_record(&claim, Some(&newer), Some(100)),
+            ZnsActionStatus::Mismatch { .. }
+        ));
+        newer.pubkey = Some(test_key().public_key_hex());
+        assert_eq!(
+            check_indexer_record(&claim, Some(&newer), Some(100)),
+            ZnsActionStatus::Superseded {
+                height: 100,
+                by_txid: "tx-later".to_string()
+            }
+        );
+        assert!(matches!(
+            check_indexer_record(&ours, Some(&newer), Some(100)),
+            ZnsActionStatus::Mismatch { .. }
+        ));
+        assert_eq!(
+            renewal_due_height(&matched),
+            Some(100 + ZNS_ACTIVITY_WINDOW_BLOCKS)
+        );
+    }
+
+    #[tokio::test]
+    async fn unmined_action_expires_once_the_tip_passes_its_expiry() {
+        let url = mock_rpc(serde_json::Value::Null).await;
+        let zebra = ZebraClient::new(url.clone());
+        let indexer = ZnsIndexer::new(url);
+        let mut expired = action("aa");
+        expired.expiry_height = Some(2_999_999);
+        let mut minable = action("bb");
+        minable.expiry_height = Some(3_000_000);
+        // Recorded before actions stored their expiry height.
+        let unknown = action("cc");
+        let mut actions = vec![expired, minable, unknown];
+
+        assert!(recheck_zns_actions(&mut actions, &zebra, &indexer)
+            .await
+            .unwrap());
+        assert_eq!(
+            actions[0].status,
+            ZnsActionStatus::Expired {
+                expiry_height: 2_999_999
+            }
+        );
+        assert!(actions[0].status.is_final());
+        assert_eq!(actions[1].status, ZnsActionStatus::Submitted);
+        assert_eq!(actions[2].status, ZnsActionStatus::Submitted);
+        assert!(!recheck_zns_actions(&mut actions, &zebra, &indexer)
+            .await
+            .unwrap());
+    }
+
+    #[tokio::test]
+    async fn prepares_actions_against_mock_indexer() {
+        let key = test_key();
+        let pinned = ZnsRegistrationConfig {
+            registry_address: Some("u1registry".to_string()),
+            max_cost_zatoshis: None,
+        };
+
+        let free = mock_indexer(serde_json::Value::Null).await;
+        let claim = prepare_with_key(
+            &free,
+            &pinned,
+            &key,
+            ZnsActionKind::Claim,
+            "Alice.zcash",
+            None,
+            UA,
+        )
+        .await
+        .unwrap();
+        assert_eq!(claim.name, "alice");
+        assert_eq!(claim.address, UA);
+        assert_eq!(claim.registry_address, "u1registry");
+        assert!(claim.registry_pinned);
+        assert_eq!(claim.cost_zatoshis, 100_000);
+        assert!(claim.link_on_confirm);
+        verify_zns_memo(&claim.memo, &key.public_key_hex()).unwrap();
+        assert!(prepare_with_key(
+            &free,
+            &pinned,
+            &key,
+            ZnsActionKind::Renew,
+            "alice",
+            None,
+            UA
+        )
+        .await
+        .is_err());
+
+        let unpinned = ZnsRegistrationConfig::default();
+        let claim = prepare_with_key(
+            &free,
+            &unpinned,
+            &key,
+            ZnsActionKind::Claim,
+            "alice",
+            None,
+            UA,
+        )
+        .await
+        .unwrap();
+        assert!(!claim.registry_pinned);
+        assert!(unpinned
+            .check_payment(&claim.registry_address, claim.cost_zatoshis)
+            .is_err());
+        pinned
+            .check_payment(&claim.registry_address, claim.cost_zatoshis)
+            .unwrap();
+        let elsewhere = ZnsRegistrationConfig {
+            registry_address: Some("u1attacker".to_string()),
+            max_cost_zatoshis: None,
+        };
+        assert!(prepare_with_key(
+            &free,
+            &elsewhere,
+            &key,
+            ZnsActionKind::Claim,
+            "alice",
+            None,
+            UA
+        )
+        .await
+        .is_err());
+        let cheap = ZnsRegistrationConfig {
+            registry_address: Some("u1registry".to_string()),
+            max_cost_zatoshis: Some(50_000),
+        };
+        assert!(
+            prepare_with_key(&free, &cheap, &key, ZnsActionKind::Claim, "alice", None, UA)
+                .await
+                .is_err()
+        );
+
+        let mut owned = serde_json::to_value(record("tx1", 100, CURRENT, 3)).unwrap();
+        owned["pubkey"] = key.public_key_hex().into();
+        let taken = mock_indexer(owned.clone()).await;
+        assert!(prepare_with_key(
+            &taken,
+            &pinned,
+            &key,
+            ZnsActionKind::Claim,
+            "alice",
+            None,
+            UA
+        )
+        .await
+        .is_err());
+        let renew = prepare_with_key(
+            &taken,
+            &pinned,
+            &key,
+            ZnsActionKind::Renew,
+            "alice",
+            None,
+            UA,
+        )
+        .await
+        .unwrap();
+        assert_eq!((renew.address.as_str(), renew.nonce), (CURRENT, 4));
+        assert_eq!(renew.cost_zatoshis, 10_000);
+        assert!(!renew.link_on_confirm);
+        assert!(prepare_with_key(
+            &taken,
+            &pinned,
+            &key,
+            ZnsActionKind::Renew,
+            "alice",
+            Some(UA),
+            UA
+        )
+        .await
+        .is_err());
+        let update = prepare_with_key(
+            &taken,
+            &pinned,
+            &key,
+            ZnsActionKind::Update,
+            "alice",
+            None,
+            UA,
+        )
+        .await
+        .unwrap();
+        assert!(update
+            .memo
+            .starts_with(&format!("ZNS:UPDATE:alice:{UA}:4:")));
+
+        owned["pubkey"] = ZnsSigningKey::from_seed(&[9u8; 64]).public_key_hex().into();
+        let foreign = mock_indexer(owned).await;
+        assert!(prepare_with_key(
+            &foreign,
+            &pinned,
+            &key,
+            ZnsActionKind::Update,
+            "alice",
+            None,
+            UA
+        )
+        .await
+        .is_err());
+    }
+}